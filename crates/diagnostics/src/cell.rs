@@ -1,58 +1,135 @@
-use crate::display::{dump_kv, dump_kvi};
-use holochain_state::{db, env::EnvironmentWrite, prelude::*};
-use holochain_types::{app::CellNick, cell::CellId};
-
-pub async fn dump_cell_state(
-    env: EnvironmentWrite,
-    _cell_id: CellId,
-    cell_nick: &CellNick,
-) -> anyhow::Result<()> {
+use crate::display::{kv_stats, kvi_stats, DbStats};
+use fallible_iterator::FallibleIterator;
+use holochain::core::state::{
+    dht_op_integration::{IntegrationLimboStore, IntegrationLimboValue},
+    source_chain::SourceChainBuf,
+    validation_db::{ValidationLimboStore, ValidationLimboValue},
+};
+use holochain_state::{db, env::EnvironmentRead, prelude::*};
+use holochain_types::{element::Element, prelude::*};
+use serde::Serialize;
+
+pub fn cell_db_stats(env: &EnvironmentRead) -> anyhow::Result<Vec<DbStats>> {
     use db::*;
     let g = env.guard();
     let r = g.reader()?;
 
     macro_rules! kv {
         ($name: expr, $db: ident) => {
-            let db = env.get_db(&$db)?;
-            dump_kv(&r, $name, db)?;
+            kv_stats(&r, $name, env.get_db(&$db)?)?
         };
     }
 
     macro_rules! kvi {
         ($name: expr, $db: ident) => {
-            let db = env.get_db(&$db)?;
-            dump_kvi(&r, $name, db)?;
+            kvi_stats(&r, $name, env.get_db(&$db)?)?
         };
     }
 
-    println!();
-    println!(
-        "+++++++++++++++++++++++++  cell \"{}\"  +++++++++++++++++++++++++",
-        cell_nick
-    );
-    println!();
-
-    kvi!("chain sequence", CHAIN_SEQUENCE);
-    kv!(
-        "element vault - public entries",
-        ELEMENT_VAULT_PUBLIC_ENTRIES
-    );
-    kv!(
-        "element vault - private entries",
-        ELEMENT_VAULT_PRIVATE_ENTRIES
-    );
-    kv!("element vault - headers", ELEMENT_VAULT_HEADERS);
-    kv!("metadata vault - links", META_VAULT_LINKS);
-    kv!("metadata vault - misc", META_VAULT_MISC);
-
-    kv!("element cache - entries", ELEMENT_CACHE_ENTRIES);
-    kv!("element cache - headers", ELEMENT_CACHE_HEADERS);
-    kv!("metadata cache - links", CACHE_LINKS_META);
-    kv!("metadata cache - status", CACHE_STATUS_META);
-
-    kv!("integration queue", integration_limbo);
-    kv!("integrated dht ops", INTEGRATED_DHT_OPS);
-    kv!("authored dht ops", AUTHORED_DHT_OPS);
-
-    Ok(())
+    Ok(vec![
+        kvi!("chain sequence", CHAIN_SEQUENCE),
+        kv!(
+            "element vault - public entries",
+            ELEMENT_VAULT_PUBLIC_ENTRIES
+        ),
+        kv!(
+            "element vault - private entries",
+            ELEMENT_VAULT_PRIVATE_ENTRIES
+        ),
+        kv!("element vault - headers", ELEMENT_VAULT_HEADERS),
+        kv!("metadata vault - links", META_VAULT_LINKS),
+        kv!("metadata vault - misc", META_VAULT_MISC),
+        kv!("element cache - entries", ELEMENT_CACHE_ENTRIES),
+        kv!("element cache - headers", ELEMENT_CACHE_HEADERS),
+        kv!("metadata cache - links", CACHE_LINKS_META),
+        kv!("metadata cache - status", CACHE_STATUS_META),
+        kv!("integration queue", INTEGRATION_LIMBO),
+        kv!("validation limbo", VALIDATION_LIMBO),
+        kv!("integrated dht ops", INTEGRATED_DHT_OPS),
+        kv!("authored dht ops", AUTHORED_DHT_OPS),
+    ])
+}
+
+/// Every element of the cell's source chain, from the Dna header onwards
+pub fn source_chain(env: &EnvironmentRead) -> anyhow::Result<Vec<Element>> {
+    let chain = SourceChainBuf::new(env.clone())?;
+    let mut elements = Vec::with_capacity(chain.len());
+    for i in 0..chain.len() as u32 {
+        match chain.get_at_index(i)? {
+            Some(element) => elements.push(element),
+            None => anyhow::bail!("The chain sequence has no element at index {}", i),
+        }
+    }
+    Ok(elements)
+}
+
+pub fn print_source_chain(elements: &[Element]) {
+    for element in elements {
+        let header = element.header();
+        let timestamp: Timestamp = header.timestamp().into();
+        println!(
+            "#{:<4} {:<20} {}  {}",
+            header.header_seq(),
+            format!("{:?}", header.header_type()),
+            element.header_address(),
+            timestamp,
+        );
+        if let Some((entry_hash, entry_type)) = header.entry_data() {
+            println!("      entry {} {:?}", entry_hash, entry_type);
+        }
+    }
+}
+
+/// An op waiting in one of the limbo databases
+#[derive(Serialize)]
+pub struct LimboItem<V> {
+    pub op_hash: DhtOpHash,
+    #[serde(flatten)]
+    pub value: V,
+}
+
+pub fn integration_limbo(
+    env: &EnvironmentRead,
+) -> anyhow::Result<Vec<LimboItem<IntegrationLimboValue>>> {
+    let store = IntegrationLimboStore::new(env.clone(), env.get_db(&*db::INTEGRATION_LIMBO)?);
+    let g = env.guard();
+    let r = g.reader()?;
+    let items = store
+        .iter(&r)?
+        .map(|(k, value)| {
+            Ok(LimboItem {
+                op_hash: DhtOpHash::from_raw_bytes(k.to_vec()),
+                value,
+            })
+        })
+        .collect()?;
+    Ok(items)
+}
+
+pub fn validation_limbo(
+    env: &EnvironmentRead,
+) -> anyhow::Result<Vec<LimboItem<ValidationLimboValue>>> {
+    let store = ValidationLimboStore::new(env.clone())?;
+    let g = env.guard();
+    let r = g.reader()?;
+    let items = store
+        .iter(&r)?
+        .map(|(k, value)| {
+            Ok(LimboItem {
+                op_hash: DhtOpHash::from_raw_bytes(k.to_vec()),
+                value,
+            })
+        })
+        .collect()?;
+    Ok(items)
+}
+
+pub fn print_limbo<V: std::fmt::Debug>(items: &[LimboItem<V>]) {
+    if items.is_empty() {
+        println!("(empty)");
+    }
+    for item in items {
+        println!("{}", item.op_hash);
+        println!("    {:?}", item.value);
+    }
 }
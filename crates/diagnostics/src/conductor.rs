@@ -1,15 +1,70 @@
 use crate::display::human_size;
+use anyhow::anyhow;
 use holochain::conductor::{state::ConductorState, ConductorStateDb};
-use holochain_state::{db::CONDUCTOR_STATE, env::EnvironmentWrite, prelude::*};
+use holochain_state::{db::CONDUCTOR_STATE, env::EnvironmentRead, prelude::*};
+use holochain_types::{
+    app::{AppId, CellNick},
+    cell::CellId,
+};
+use serde::Serialize;
 
-pub async fn dump_conductor_state(env: EnvironmentWrite) -> anyhow::Result<ConductorState> {
+/// The conductor state, along with its size in the database
+pub fn conductor_state(env: &EnvironmentRead) -> anyhow::Result<(ConductorState, usize)> {
     let g = env.guard();
     let r = g.reader()?;
-    let db = ConductorStateDb::new(env.get_db(&CONDUCTOR_STATE)?)?;
-    let bytes = db.get_bytes(&r, &().into())?.unwrap();
-    let state = db.get(&r, &().into())?.unwrap();
+    let db = ConductorStateDb::new(env.get_db(&CONDUCTOR_STATE)?);
+    let bytes = db
+        .get_bytes(&r, &().into())?
+        .ok_or_else(|| anyhow!("The conductor state has never been written"))?;
+    let state = db.get(&r, &().into())?.unwrap_or_default();
+    Ok((state, bytes.len()))
+}
 
-    println!("Size: {}", human_size(bytes.len()));
+pub fn print_conductor_state(state: &ConductorState, size: usize) {
+    println!("Size: {}", human_size(size));
     println!("Data: {:#?}", state);
-    Ok(state)
+}
+
+/// A Cell of an installed app
+#[derive(Clone, Debug, Serialize)]
+pub struct InstalledCellInfo {
+    pub app_id: AppId,
+    pub active: bool,
+    pub cell_id: CellId,
+    pub cell_nick: CellNick,
+}
+
+/// Every Cell of every app, active or not
+pub fn installed_cells(state: &ConductorState) -> Vec<InstalledCellInfo> {
+    let active = state.active_apps.iter().map(|app| (app, true));
+    let inactive = state.inactive_apps.iter().map(|app| (app, false));
+    active
+        .chain(inactive)
+        .flat_map(|((app_id, cells), active)| {
+            cells.iter().map(move |cell| InstalledCellInfo {
+                app_id: app_id.clone(),
+                active,
+                cell_id: cell.as_id().clone(),
+                cell_nick: cell.as_nick().clone(),
+            })
+        })
+        .collect()
+}
+
+/// Find an installed Cell by its CellId, as printed by the `list` command,
+/// or by its nick if only one app has a Cell with that nick
+pub fn find_cell(state: &ConductorState, query: &str) -> anyhow::Result<InstalledCellInfo> {
+    let cells = installed_cells(state);
+    if let Some(cell) = cells.iter().find(|c| c.cell_id.to_string() == query) {
+        return Ok(cell.clone());
+    }
+    let mut by_nick = cells.into_iter().filter(|c| c.cell_nick == query);
+    match (by_nick.next(), by_nick.next()) {
+        (Some(cell), None) => Ok(cell),
+        (Some(_), Some(_)) => Err(anyhow!(
+            "More than one app has a cell with the nick \"{}\", use its CellId instead",
+            query
+        )),
+        (None, _) => Err(anyhow!("No installed cell matches \"{}\"", query)),
+    }
 }
@@ -0,0 +1,149 @@
+use crate::display::hex;
+use anyhow::anyhow;
+use holochain_state::{
+    db::{self, DbKey, DbKind, DbName},
+    env::EnvironmentRead,
+    error::DatabaseResult,
+    exports::{IntegerStore, MultiStore, SingleStore},
+    prelude::*,
+};
+use serde::Serialize;
+
+const DB_NAMES: &[DbName] = &[
+    DbName::ElementVaultPublicEntries,
+    DbName::ElementVaultPrivateEntries,
+    DbName::ElementVaultPrivateEntriesMigrated,
+    DbName::ElementVaultHeaders,
+    DbName::MetaVaultSys,
+    DbName::MetaVaultLinks,
+    DbName::MetaVaultMisc,
+    DbName::ChainSequence,
+    DbName::ElementCacheEntries,
+    DbName::ElementCacheHeaders,
+    DbName::MetaCacheSys,
+    DbName::MetaCacheLinks,
+    DbName::MetaCacheStatus,
+    DbName::CacheAge,
+    DbName::ConductorState,
+    DbName::Wasm,
+    DbName::DnaDef,
+    DbName::EntryDef,
+    DbName::AuthoredDhtOps,
+    DbName::IntegratedDhtOps,
+    DbName::IntegrationLimbo,
+    DbName::ValidationLimbo,
+    DbName::ValidationReceipts,
+];
+
+/// Parse a [DbName] from its name, ignoring case, dashes and underscores,
+/// so `chain-sequence` and `ChainSequence` both work
+pub fn parse_db_name(name: &str) -> anyhow::Result<DbName> {
+    let normalize = |s: &str| s.replace(|c| c == '-' || c == '_', "").to_lowercase();
+    DB_NAMES
+        .iter()
+        .find(|db_name| normalize(&db_name.to_string()) == normalize(name))
+        .cloned()
+        .ok_or_else(|| {
+            let names: Vec<_> = DB_NAMES.iter().map(ToString::to_string).collect();
+            anyhow!(
+                "Unknown database \"{}\", expected one of: {}",
+                name,
+                names.join(", ")
+            )
+        })
+}
+
+/// One key-value pair of a database. Values are shown as JSON when they
+/// can be decoded from MessagePack, otherwise as hex.
+#[derive(Serialize)]
+pub struct DbItem {
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+/// Every key-value pair in one database of an environment
+pub fn dump_db(env: &EnvironmentRead, name: &DbName) -> anyhow::Result<Vec<DbItem>> {
+    let g = env.guard();
+    let r = g.reader()?;
+    let items = match name.kind() {
+        DbKind::Single => env
+            .get_db(single_db_key(name))?
+            .iter_start(&r)?
+            .map(|kv| kv.map(|(k, v)| item(k, v)))
+            .collect::<DatabaseResult<_>>()?,
+        DbKind::SingleInt => env
+            .get_db(int_db_key(name))?
+            .iter_start(&r)?
+            .map(|kv| kv.map(|(k, v)| item(k, v)))
+            .collect::<DatabaseResult<_>>()?,
+        DbKind::Multi => env
+            .get_db(multi_db_key(name))?
+            .iter_start(&r)?
+            .map(|kv| kv.map(|(k, v)| item(k, v)))
+            .collect::<DatabaseResult<_>>()?,
+    };
+    Ok(items)
+}
+
+pub fn print_db(items: &[DbItem]) -> anyhow::Result<()> {
+    if items.is_empty() {
+        println!("(empty)");
+    }
+    for item in items {
+        println!("{}", item.key);
+        println!("    {}", serde_json::to_string(&item.value)?);
+    }
+    Ok(())
+}
+
+fn item(key: &[u8], value: &[u8]) -> DbItem {
+    DbItem {
+        key: hex(key),
+        value: holochain_serialized_bytes::decode(value).unwrap_or_else(|_| hex(value).into()),
+    }
+}
+
+fn single_db_key(name: &DbName) -> &'static DbKey<SingleStore> {
+    use db::*;
+    use DbName::*;
+    match name {
+        ElementVaultPublicEntries => &*ELEMENT_VAULT_PUBLIC_ENTRIES,
+        ElementVaultPrivateEntries => &*ELEMENT_VAULT_PRIVATE_ENTRIES,
+        ElementVaultPrivateEntriesMigrated => &*ELEMENT_VAULT_PRIVATE_ENTRIES_MIGRATED,
+        ElementVaultHeaders => &*ELEMENT_VAULT_HEADERS,
+        MetaVaultLinks => &*META_VAULT_LINKS,
+        MetaVaultMisc => &*META_VAULT_MISC,
+        ElementCacheEntries => &*ELEMENT_CACHE_ENTRIES,
+        ElementCacheHeaders => &*ELEMENT_CACHE_HEADERS,
+        MetaCacheLinks => &*CACHE_LINKS_META,
+        MetaCacheStatus => &*CACHE_STATUS_META,
+        CacheAge => &*CACHE_AGE,
+        ConductorState => &*CONDUCTOR_STATE,
+        Wasm => &*WASM,
+        DnaDef => &*DNA_DEF,
+        EntryDef => &*ENTRY_DEF,
+        AuthoredDhtOps => &*AUTHORED_DHT_OPS,
+        IntegratedDhtOps => &*INTEGRATED_DHT_OPS,
+        IntegrationLimbo => &*INTEGRATION_LIMBO,
+        ValidationLimbo => &*VALIDATION_LIMBO,
+        MetaVaultSys | MetaCacheSys | ValidationReceipts | ChainSequence => {
+            unreachable!("{} is not a single-value database", name)
+        }
+    }
+}
+
+fn int_db_key(name: &DbName) -> &'static DbKey<IntegerStore> {
+    match name {
+        DbName::ChainSequence => &*db::CHAIN_SEQUENCE,
+        _ => unreachable!("{} is not an integer-keyed database", name),
+    }
+}
+
+fn multi_db_key(name: &DbName) -> &'static DbKey<MultiStore> {
+    match name {
+        DbName::MetaVaultSys => &*db::META_VAULT_SYS,
+        DbName::MetaCacheSys => &*db::CACHE_SYSTEM_META,
+        DbName::ValidationReceipts => &*db::VALIDATION_RECEIPTS,
+        _ => unreachable!("{} is not a multi-value database", name),
+    }
+}
@@ -1,4 +1,10 @@
-use holochain_state::{error::DatabaseResult, transaction::Reader};
+use holochain_state::{
+    backend::RawIter,
+    error::DatabaseResult,
+    exports::{IntegerStore, SingleStore},
+    transaction::Reader,
+};
+use serde::Serialize;
 
 const BYTE_SIZE_MARKERS: [char; 6] = [' ', 'K', 'M', 'G', 'T', 'P'];
 
@@ -17,54 +23,68 @@ pub fn human_size(size: usize) -> String {
     recurse(size as f32, 0)
 }
 
-fn dump_iter<'i>(
-    name: &str,
-    it: impl Iterator<Item = Result<(&'i [u8], Option<rkv::Value<'i>>), rkv::StoreError>>,
-) -> DatabaseResult<()> {
-    let items = it
-        .map(|kv| {
-            let (k, v) = kv.unwrap();
-            let key = k.len();
-            let val = v.map(|v| v.to_bytes().unwrap().len()).unwrap_or(0);
-            key + val
-        })
-        .collect();
-    println!("<DB \"{}\">", name);
-    println!("{}", SizeStats::new(items));
+/// Lowercase hex, for printing raw database keys
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Print anything as pretty JSON, for the `--json` flag
+pub fn print_json<T: Serialize>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
     Ok(())
 }
 
+fn size_stats(it: RawIter) -> DatabaseResult<SizeStats> {
+    let items = it
+        .map(|kv| kv.map(|(k, v)| k.len() + v.len()))
+        .collect::<DatabaseResult<_>>()?;
+    Ok(SizeStats::new(items))
+}
+
 #[allow(dead_code)]
-fn dump_iter_multi<'i>(
-    name: &str,
-    it: impl Iterator<Item = Result<(&'i [u8], rkv::Value<'i>), rkv::StoreError>>,
-) -> DatabaseResult<()> {
+fn size_stats_multi(it: RawIter) -> DatabaseResult<SizeStats> {
     let items = it
-        .map(|kv| {
-            // FIXME: we're ignoring the key here because its duplicated across items
-            let (_, v) = kv.unwrap();
-            let val = v.to_bytes().unwrap().len();
-            val
-        })
-        .collect();
-    println!("<DB \"{}\">", name);
-    println!("{}", SizeStats::new(items));
-    Ok(())
+        // FIXME: we're ignoring the key here because its duplicated across items
+        .map(|kv| kv.map(|(_, v)| v.len()))
+        .collect::<DatabaseResult<_>>()?;
+    Ok(SizeStats::new(items))
 }
 
-pub fn dump_kv(reader: &Reader, name: &str, db: rkv::SingleStore) -> DatabaseResult<()> {
-    dump_iter(name, db.iter_start(reader)?)
+pub fn kv_stats(reader: &Reader, name: &'static str, db: SingleStore) -> DatabaseResult<DbStats> {
+    Ok(DbStats {
+        name,
+        stats: size_stats(db.iter_start(reader)?)?,
+    })
 }
 
-pub fn dump_kvi(reader: &Reader, name: &str, db: rkv::IntegerStore<u32>) -> DatabaseResult<()> {
-    dump_iter(name, db.iter_start(reader)?)
+pub fn kvi_stats(reader: &Reader, name: &'static str, db: IntegerStore) -> DatabaseResult<DbStats> {
+    Ok(DbStats {
+        name,
+        stats: size_stats(db.iter_start(reader)?)?,
+    })
 }
 
 // TODO:
-// pub fn dump_kvv(reader: &Reader, name: &str, db: rkv::MultiStore) -> DatabaseResult<()> {
-//     dump_iter_multi(name, db.iter_start(reader)?)
+// pub fn kvv_stats(reader: &Reader, name: &'static str, db: MultiStore) -> DatabaseResult<DbStats> {
+//     size_stats_multi(db.iter_start(reader)?)
 // }
 
+/// The size statistics of one database
+#[derive(Serialize)]
+pub struct DbStats {
+    pub name: &'static str,
+    #[serde(flatten)]
+    pub stats: SizeStats,
+}
+
+impl std::fmt::Display for DbStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "<DB \"{}\">", self.name)?;
+        write!(f, "{}", self.stats)
+    }
+}
+
+#[derive(Serialize)]
 pub struct SizeStats {
     count: usize,
     total: usize,
@@ -6,80 +6,316 @@
 //! as a library of helper functions alongside a binary that calls into the lib,
 //! so that the binary can be freely modifiable while still accumulating a
 //! useful set of tools for querying LMDB state.
+//!
+//! Every environment is opened read-only, so it is safe to run alongside
+//! a live conductor.
 
-use cell::dump_cell_state;
-use conductor::dump_conductor_state;
-use holochain_keystore::test_keystore::spawn_test_keystore;
-use holochain_state::env::{EnvironmentKind, EnvironmentWrite};
-use std::path::PathBuf;
+use cell::{
+    cell_db_stats, integration_limbo, print_limbo, print_source_chain, source_chain,
+    validation_limbo,
+};
+use conductor::{conductor_state, find_cell, installed_cells, print_conductor_state};
+use db::{dump_db, parse_db_name, print_db};
+use display::{human_size, print_json, DbStats};
+use holochain::conductor::state::ConductorState;
+use holochain_keystore::{test_keystore::spawn_test_keystore, KeystoreSender};
+use holochain_state::env::{EnvironmentKind, EnvironmentRead, EnvironmentStats};
+use holochain_types::{app::CellNick, cell::CellId};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
 use structopt::StructOpt;
-use wasm::dump_wasm_state;
+use wasm::wasm_db_stats;
 
 mod cell;
 mod conductor;
+mod db;
 mod display;
 mod wasm;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
+    /// The conductor's environment path
     lmdb_path: PathBuf,
+
+    /// Print JSON instead of human readable output
+    #[structopt(long)]
+    json: bool,
+
+    #[structopt(subcommand)]
+    cmd: Option<Cmd>,
+}
+
+#[derive(Debug, StructOpt)]
+enum Cmd {
+    /// Size statistics of every database in the wasm, conductor and cell
+    /// environments, along with the conductor state. This is the default.
+    Summary,
+    /// List every environment, with the app and cell nick of cell environments
+    List,
+    /// Dump every key and value of a single database of a cell
+    Db {
+        /// The cell's CellId as printed by `list`, or its cell nick
+        cell: String,
+        /// The database name, e.g. `chain-sequence` or `ElementVaultHeaders`
+        db: String,
+    },
+    /// Print a cell's source chain in order, starting with the Dna header
+    Chain {
+        /// The cell's CellId as printed by `list`, or its cell nick
+        cell: String,
+    },
+    /// Print the ops waiting to be integrated into a cell's DHT shard
+    IntegrationLimbo {
+        /// The cell's CellId as printed by `list`, or its cell nick
+        cell: String,
+    },
+    /// Print the ops waiting to be validated by a cell
+    ValidationLimbo {
+        /// The cell's CellId as printed by `list`, or its cell nick
+        cell: String,
+    },
+}
+
+/// Opens environments read-only under one environment path
+struct Environments {
+    path: PathBuf,
+    // throwaway keystore that we'll never use.
+    keystore: KeystoreSender,
+}
+
+impl Environments {
+    fn open(&self, kind: EnvironmentKind) -> anyhow::Result<EnvironmentRead> {
+        Ok(EnvironmentRead::open_read_only(
+            &self.path,
+            kind,
+            self.keystore.clone(),
+        )?)
+    }
+
+    fn conductor_state(&self) -> anyhow::Result<(ConductorState, usize)> {
+        conductor_state(&self.open(EnvironmentKind::Conductor)?)
+    }
+
+    fn cell(&self, query: &str) -> anyhow::Result<EnvironmentRead> {
+        let (state, _) = self.conductor_state()?;
+        let cell = find_cell(&state, query)?;
+        self.open(EnvironmentKind::Cell(cell.cell_id))
+    }
 }
 
 async fn run() -> anyhow::Result<()> {
     let opt = Opt::from_args();
+    let envs = Environments {
+        path: opt.lmdb_path.clone(),
+        keystore: spawn_test_keystore(Vec::new()).await?,
+    };
 
-    // throwaway keystore that we'll never use.
-    let keystore = spawn_test_keystore(Vec::new()).await.unwrap();
+    match opt.cmd.unwrap_or(Cmd::Summary) {
+        Cmd::Summary => summary(&envs, opt.json),
+        Cmd::List => list(&envs, opt.json),
+        Cmd::Db { cell, db } => {
+            let db_name = parse_db_name(&db)?;
+            let items = dump_db(&envs.cell(&cell)?, &db_name)?;
+            if opt.json {
+                print_json(&items)
+            } else {
+                print_db(&items)
+            }
+        }
+        Cmd::Chain { cell } => {
+            let elements = source_chain(&envs.cell(&cell)?)?;
+            if opt.json {
+                print_json(&elements)
+            } else {
+                print_source_chain(&elements);
+                Ok(())
+            }
+        }
+        Cmd::IntegrationLimbo { cell } => {
+            let items = integration_limbo(&envs.cell(&cell)?)?;
+            if opt.json {
+                print_json(&items)
+            } else {
+                print_limbo(&items);
+                Ok(())
+            }
+        }
+        Cmd::ValidationLimbo { cell } => {
+            let items = validation_limbo(&envs.cell(&cell)?)?;
+            if opt.json {
+                print_json(&items)
+            } else {
+                print_limbo(&items);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Summary {
+    wasm: Vec<DbStats>,
+    conductor_state_size: usize,
+    conductor_state: ConductorState,
+    cells: Vec<CellSummary>,
+}
+
+#[derive(Serialize)]
+struct CellSummary {
+    cell_id: CellId,
+    cell_nick: CellNick,
+    databases: Vec<DbStats>,
+}
 
-    // set up the various environments
-    let wasm_env = EnvironmentWrite::new(
-        opt.lmdb_path.as_ref(),
-        EnvironmentKind::Wasm,
-        keystore.clone(),
-    )?;
+fn summary(envs: &Environments, json: bool) -> anyhow::Result<()> {
+    let wasm = wasm_db_stats(&envs.open(EnvironmentKind::Wasm)?)?;
+    let (conductor_state, conductor_state_size) = envs.conductor_state()?;
+    let cells = installed_cells(&conductor_state)
+        .into_iter()
+        .map(|cell| {
+            let env = envs.open(EnvironmentKind::Cell(cell.cell_id.clone()))?;
+            Ok(CellSummary {
+                databases: cell_db_stats(&env)?,
+                cell_id: cell.cell_id,
+                cell_nick: cell.cell_nick,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    if json {
+        return print_json(&Summary {
+            wasm,
+            conductor_state_size,
+            conductor_state,
+            cells,
+        });
+    }
 
     println!();
     println!("        +++++++++++++++++++++++++++++++++");
     println!("        ++++++++   WASM  STATE   ++++++++");
     println!("        +++++++++++++++++++++++++++++++++");
     println!();
-    dump_wasm_state(wasm_env).await?;
-
-    // set up the various environments
-    let conductor_env = EnvironmentWrite::new(
-        opt.lmdb_path.as_ref(),
-        EnvironmentKind::Conductor,
-        keystore.clone(),
-    )?;
+    for stats in wasm {
+        println!("{}", stats);
+    }
 
     println!();
     println!("        +++++++++++++++++++++++++++++++++");
     println!("        +++++++  CONDUCTOR STATE  +++++++");
     println!("        +++++++++++++++++++++++++++++++++");
     println!();
-    let conductor_state = dump_conductor_state(conductor_env).await?;
+    print_conductor_state(&conductor_state, conductor_state_size);
 
     println!();
     println!("        +++++++++++++++++++++++++++++++++");
     println!("        ++++++++   CELL  STATE   ++++++++");
     println!("        +++++++++++++++++++++++++++++++++");
     println!();
-
-    for (_app_id, cells) in conductor_state.active_apps {
-        for cell in cells {
-            let (cell_id, cell_nick) = cell.into_inner();
-            let cell_env = EnvironmentWrite::new(
-                opt.lmdb_path.as_ref(),
-                EnvironmentKind::Cell(cell_id.clone()),
-                keystore.clone(),
-            )?;
-            dump_cell_state(cell_env, cell_id, &cell_nick).await?;
+    for cell in cells {
+        println!();
+        println!(
+            "+++++++++++++++++++++++++  cell \"{}\"  +++++++++++++++++++++++++",
+            cell.cell_nick
+        );
+        println!();
+        for stats in cell.databases {
+            println!("{}", stats);
         }
     }
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct EnvironmentListing {
+    /// The environment's directory under the environment path
+    name: String,
+    kind: &'static str,
+    app_id: Option<String>,
+    active: Option<bool>,
+    cell_nick: Option<CellNick>,
+    /// None for directories which don't belong to the conductor
+    stats: Option<EnvironmentStats>,
+}
+
+fn list(envs: &Environments, json: bool) -> anyhow::Result<()> {
+    let (state, _) = envs.conductor_state()?;
+    let mut listings = Vec::new();
+    for (name, kind) in &[
+        ("conductor", EnvironmentKind::Conductor),
+        ("wasm", EnvironmentKind::Wasm),
+    ] {
+        listings.push(EnvironmentListing {
+            name: name.to_string(),
+            kind: *name,
+            app_id: None,
+            active: None,
+            cell_nick: None,
+            stats: Some(envs.open(kind.clone())?.stats()?),
+        });
+    }
+    for cell in installed_cells(&state) {
+        let env = envs.open(EnvironmentKind::Cell(cell.cell_id.clone()))?;
+        listings.push(EnvironmentListing {
+            name: cell.cell_id.to_string(),
+            kind: "cell",
+            app_id: Some(cell.app_id),
+            active: Some(cell.active),
+            cell_nick: Some(cell.cell_nick),
+            stats: Some(env.stats()?),
+        });
+    }
+    for name in unknown_directories(&envs.path, &listings)? {
+        listings.push(EnvironmentListing {
+            name,
+            kind: "unknown",
+            app_id: None,
+            active: None,
+            cell_nick: None,
+            stats: None,
+        });
+    }
 
+    if json {
+        return print_json(&listings);
+    }
+    for listing in listings {
+        println!("{} ({})", listing.name, listing.kind);
+        if let (Some(app_id), Some(active), Some(cell_nick)) =
+            (&listing.app_id, listing.active, &listing.cell_nick)
+        {
+            let status = if active { "active" } else { "inactive" };
+            println!(
+                "    app {} ({}), cell nick \"{}\"",
+                app_id, status, cell_nick
+            );
+        }
+        if let Some(stats) = listing.stats {
+            println!(
+                "    used {} of a {} map, {} on disk",
+                human_size(stats.used_size),
+                human_size(stats.map_size),
+                human_size(stats.disk_size as usize),
+            );
+        }
+    }
     Ok(())
 }
 
+/// Directories in the environment path which are not an environment
+/// of this conductor, e.g. those of uninstalled cells
+fn unknown_directories(path: &Path, known: &[EnvironmentListing]) -> anyhow::Result<Vec<String>> {
+    let mut unknown = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if entry.file_type()?.is_dir() && !known.iter().any(|l| l.name == name) {
+            unknown.push(name);
+        }
+    }
+    Ok(unknown)
+}
+
 #[tokio::main(threaded_scheduler)]
 async fn main() {
     if let Err(err) = run().await {
@@ -1,14 +1,14 @@
-use crate::display::dump_kv;
-use holochain_state::{db, env::EnvironmentWrite, prelude::*};
+use crate::display::{kv_stats, DbStats};
+use holochain_state::{db, env::EnvironmentRead, prelude::*};
 
-pub async fn dump_wasm_state(env: EnvironmentWrite) -> anyhow::Result<()> {
+pub fn wasm_db_stats(env: &EnvironmentRead) -> anyhow::Result<Vec<DbStats>> {
     use db::*;
     let g = env.guard();
     let r = g.reader()?;
 
-    dump_kv(&r, "wasm", env.get_db(&WASM)?)?;
-    dump_kv(&r, "dna defs", env.get_db(&DNA_DEF)?)?;
-    dump_kv(&r, "entry defs", env.get_db(&ENTRY_DEF)?)?;
-
-    Ok(())
+    Ok(vec![
+        kv_stats(&r, "wasm", env.get_db(&WASM)?)?,
+        kv_stats(&r, "dna defs", env.get_db(&DNA_DEF)?)?,
+        kv_stats(&r, "entry defs", env.get_db(&ENTRY_DEF)?)?,
+    ])
 }
@@ -23,7 +23,7 @@
 ///  e.g. the following are equivalent
 ///
 /// ```ignore
-/// #[hdk_entry(id = "foo", visibility = "private", required_validations = 6, )]
+/// #[hdk_entry(id = "foo", visibility = "private", required_validations = 6, max_size = 1024, )]
 /// pub struct Foo;
 /// ```
 ///
@@ -59,6 +59,10 @@ macro_rules! entry_def {
             pub fn required_validations() -> $crate::prelude::RequiredValidations {
                 Self::entry_def().required_validations
             }
+
+            pub fn max_size() -> Option<usize> {
+                Self::entry_def().max_size
+            }
         }
 
         impl TryFrom<&$crate::prelude::Entry> for $t {
@@ -140,8 +140,9 @@ pub struct Path(Vec<Component>);
 
 entry_def!(Path EntryDef {
     id: core::str::from_utf8(&NAME).unwrap().into(),
-    crdt_type: CrdtType,
+    crdt_type: CrdtType::default(),
     required_validations: RequiredValidations::default(),
+    max_size: None,
     visibility: EntryVisibility::Public,
 });
 
@@ -1,6 +1,7 @@
 pub mod agent_info;
 pub mod call;
 pub mod call_remote;
+pub mod close_chain;
 pub mod create;
 pub mod create_link;
 pub mod debug;
@@ -11,10 +12,12 @@ pub mod emit_signal;
 pub mod encrypt;
 pub mod entry_type_properties;
 pub mod get;
+pub mod get_agent_activity;
 pub mod get_details;
 pub mod get_link_details;
 pub mod get_links;
 pub mod hash_entry;
+pub mod open_chain;
 pub mod property;
 pub mod query;
 pub mod random_bytes;
@@ -0,0 +1,19 @@
+/// Close the source chain so the agent can move to a new version of the DNA.
+///
+/// The host runs the `migrate_agent` callback of every zome with `MigrateAgent::Close` first and
+/// only commits the `CloseChain` header pointing at the new DNA hash if all of them pass.
+/// Nothing can be committed to the chain after it is closed.
+///
+/// The agent's chain on the new DNA should then be opened with `open_chain!`.
+#[macro_export]
+macro_rules! close_chain {
+    ( $new_dna_hash:expr ) => {{
+        $crate::prelude::host_externs!(__close_chain);
+
+        $crate::host_fn!(
+            __close_chain,
+            $crate::prelude::CloseChainInput::new($new_dna_hash),
+            $crate::prelude::CloseChainOutput
+        )
+    }};
+}
@@ -0,0 +1,23 @@
+/// Returns the source chain activity of an agent, as held by the authorities for its key.
+///
+/// The query filters the headers that are returned but the chain status and the highest
+/// observed sequence number always describe the whole chain.
+///
+/// e.g. to check an agent's chain is valid before trusting its latest header:
+///
+/// ```ignore
+/// let activity = get_agent_activity!(agent, QueryFilter::new())?;
+/// if activity.status == ChainStatus::Valid { ... }
+/// ```
+#[macro_export]
+macro_rules! get_agent_activity {
+    ( $agent:expr, $query:expr ) => {{
+        $crate::prelude::host_externs!(__get_agent_activity);
+
+        $crate::host_fn!(
+            __get_agent_activity,
+            $crate::prelude::GetAgentActivityInput::new(($agent, $query)),
+            $crate::prelude::GetAgentActivityOutput
+        )
+    }};
+}
@@ -0,0 +1,18 @@
+/// Open the source chain as the continuation of the agent's chain on a previous version of the DNA.
+///
+/// The host runs the `migrate_agent` callback of every zome with `MigrateAgent::Open` first and
+/// only commits the `OpenChain` header pointing at the previous DNA hash if all of them pass.
+///
+/// @see close_chain!
+#[macro_export]
+macro_rules! open_chain {
+    ( $prev_dna_hash:expr ) => {{
+        $crate::prelude::host_externs!(__open_chain);
+
+        $crate::host_fn!(
+            __open_chain,
+            $crate::prelude::OpenChainInput::new($prev_dna_hash),
+            $crate::prelude::OpenChainOutput
+        )
+    }};
+}
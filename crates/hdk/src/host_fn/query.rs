@@ -1,6 +1,11 @@
-/// Walks the source chain in reverse (latest to oldest) filtering by header and/or entry type
+/// Walks the source chain filtering by sequence and timestamp ranges and header and/or entry types
 ///
-/// Given a header and entry type, returns a Vec<HeaderHash>
+/// Given a [QueryFilter](crate::prelude::QueryFilter), returns the matching elements in chain order,
+/// or latest to oldest if the filter is `descending`, paginated with its `offset` and `limit`.
+/// Private entries are only included if the filter sets `include_private_entries`.
+///
+/// Both are breaking changes: `query!` used to return elements latest to oldest,
+/// with private entries included whenever `include_entries` was set.
 ///
 /// @todo document this better with examples
 /// @todo do we want to return elements rather than hashes?
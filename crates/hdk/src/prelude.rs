@@ -1,5 +1,6 @@
 pub use crate::agent_info;
 pub use crate::call_remote;
+pub use crate::close_chain;
 pub use crate::create;
 pub use crate::create_cap_claim;
 pub use crate::create_cap_grant;
@@ -16,6 +17,7 @@ pub use crate::entry_defs;
 pub use crate::error::HdkError;
 pub use crate::generate_cap_secret;
 pub use crate::get;
+pub use crate::get_agent_activity;
 pub use crate::get_details;
 pub use crate::get_link_details;
 pub use crate::get_links;
@@ -29,6 +31,7 @@ pub use crate::hash_path::anchor::Anchor;
 pub use crate::hash_path::path::Path;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
+pub use crate::open_chain;
 pub use crate::query;
 pub use crate::random_bytes;
 pub use crate::sign;
@@ -42,6 +45,7 @@ pub use hdk3_derive::hdk_entry;
 pub use hdk3_derive::hdk_extern;
 pub use holo_hash::AgentPubKey;
 pub use holo_hash::AnyDhtHash;
+pub use holo_hash::DnaHash;
 pub use holo_hash::EntryHash;
 pub use holo_hash::EntryHashes;
 pub use holo_hash::HasHash;
@@ -51,11 +55,12 @@ pub use holochain_zome_types;
 pub use holochain_zome_types::agent_info::AgentInfo;
 pub use holochain_zome_types::call_remote::CallRemote;
 pub use holochain_zome_types::capability::*;
-pub use holochain_zome_types::crdt::CrdtType;
+pub use holochain_zome_types::crdt::{CrdtType, MergeCallbackResult, MergeData};
 pub use holochain_zome_types::debug_msg;
 pub use holochain_zome_types::element::{Element, ElementVec};
 pub use holochain_zome_types::entry::*;
 pub use holochain_zome_types::entry_def::*;
+pub use holochain_zome_types::genesis::GenesisSelfCheckData;
 pub use holochain_zome_types::header::*;
 pub use holochain_zome_types::init::InitCallbackResult;
 pub use holochain_zome_types::link::LinkDetails;
@@ -66,6 +71,7 @@ pub use holochain_zome_types::migrate_agent::MigrateAgent;
 pub use holochain_zome_types::migrate_agent::MigrateAgentCallbackResult;
 pub use holochain_zome_types::post_commit::PostCommitCallbackResult;
 pub use holochain_zome_types::query::ChainQueryFilter as QueryFilter;
+pub use holochain_zome_types::query::{AgentActivity, ChainStatus};
 pub use holochain_zome_types::signature::SignInput;
 pub use holochain_zome_types::signature::Signature;
 pub use holochain_zome_types::signature::VerifySignatureInput;
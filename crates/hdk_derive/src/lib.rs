@@ -17,7 +17,8 @@ impl Parse for EntryDef {
         let mut required_validations =
             holochain_zome_types::entry_def::RequiredValidations::default();
         let mut visibility = holochain_zome_types::entry_def::EntryVisibility::default();
-        let crdt_type = holochain_zome_types::crdt::CrdtType::default();
+        let mut crdt_type = holochain_zome_types::crdt::CrdtType::default();
+        let mut max_size = None;
 
         let vars = Punctuated::<syn::MetaNameValue, syn::Token![,]>::parse_terminated(input)?;
         for var in vars {
@@ -57,8 +58,29 @@ impl Parse for EntryDef {
                         };
                     }
                     "crdt_type" => {
-                        unimplemented!();
+                        match var.lit {
+                            syn::Lit::Str(s) => {
+                                crdt_type = match s.value().as_str() {
+                                    "grow_only_set" => {
+                                        holochain_zome_types::crdt::CrdtType::GrowOnlySet
+                                    }
+                                    "single_author_latest_wins" => {
+                                        holochain_zome_types::crdt::CrdtType::SingleAuthorLatestWins
+                                    }
+                                    "last_write_wins" => {
+                                        holochain_zome_types::crdt::CrdtType::LastWriteWins
+                                    }
+                                    "app_merge" => holochain_zome_types::crdt::CrdtType::AppMerge,
+                                    _ => unreachable!(),
+                                }
+                            }
+                            _ => unreachable!(),
+                        };
                     }
+                    "max_size" => match var.lit {
+                        syn::Lit::Int(i) => max_size = Some(i.base10_parse::<usize>()?),
+                        _ => unreachable!(),
+                    },
                     _ => {}
                 }
             }
@@ -68,14 +90,26 @@ impl Parse for EntryDef {
             required_validations,
             visibility,
             crdt_type,
+            max_size,
         }))
     }
 }
 
 impl quote::ToTokens for CrdtType {
     fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        let variant = syn::Ident::new(
+            match self.0 {
+                holochain_zome_types::crdt::CrdtType::GrowOnlySet => "GrowOnlySet",
+                holochain_zome_types::crdt::CrdtType::SingleAuthorLatestWins => {
+                    "SingleAuthorLatestWins"
+                }
+                holochain_zome_types::crdt::CrdtType::LastWriteWins => "LastWriteWins",
+                holochain_zome_types::crdt::CrdtType::AppMerge => "AppMerge",
+            },
+            proc_macro2::Span::call_site(),
+        );
         tokens.append_all(quote::quote! {
-            hdk3::prelude::CrdtType
+            hdk3::prelude::CrdtType::#variant
         });
     }
 }
@@ -123,6 +157,10 @@ impl quote::ToTokens for EntryDef {
         let visibility = EntryVisibility(self.0.visibility);
         let crdt_type = CrdtType(self.0.crdt_type);
         let required_validations = RequiredValidations(self.0.required_validations);
+        let max_size = match self.0.max_size {
+            Some(max_size) => quote::quote! { Some(#max_size) },
+            None => quote::quote! { None },
+        };
 
         tokens.append_all(quote::quote! {
             hdk3::prelude::EntryDef {
@@ -130,6 +168,7 @@ impl quote::ToTokens for EntryDef {
                 visibility: #visibility,
                 crdt_type: #crdt_type,
                 required_validations: #required_validations,
+                max_size: #max_size,
             }
         });
     }
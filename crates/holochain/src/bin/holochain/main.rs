@@ -47,6 +47,14 @@ struct Opt {
     useful when running a conductor for the first time"
     )]
     interactive: bool,
+
+    #[structopt(
+        long,
+        help = "Restore any LMDB environments missing from the environment path
+    from a backup directory written by the BackupConductor admin request.
+    The backup is the directory itself, not an archive of it"
+    )]
+    restore_from: Option<PathBuf>,
 }
 
 fn main() {
@@ -68,7 +76,12 @@ async fn async_main() {
     let conductor = if let Some(legacy_config_path) = opt.legacy_tryorama_config_path {
         conductor_handle_from_legacy_config_path(&legacy_config_path).await
     } else {
-        conductor_handle_from_config_path(opt.config_path.clone(), opt.interactive).await
+        conductor_handle_from_config_path(
+            opt.config_path.clone(),
+            opt.interactive,
+            opt.restore_from.clone(),
+        )
+        .await
     };
 
     info!("Conductor successfully initialized.");
@@ -107,6 +120,7 @@ async fn conductor_handle_from_legacy_config_path(legacy_config_path: &Path) ->
 async fn conductor_handle_from_config_path(
     config_path: Option<PathBuf>,
     interactive: bool,
+    restore_from: Option<PathBuf>,
 ) -> ConductorHandle {
     let config_path_default = config_path.is_none();
     let config_path: ConfigFilePath = config_path.map(Into::into).unwrap_or_default();
@@ -140,8 +154,11 @@ async fn conductor_handle_from_config_path(
     }
 
     // Initialize the Conductor
-    Conductor::builder()
-        .config(config)
+    let mut builder = Conductor::builder().config(config);
+    if let Some(backup_dir) = restore_from {
+        builder = builder.restore_from(backup_dir);
+    }
+    builder
         .build()
         .await
         .expect("Could not initialize Conductor from configuration")
@@ -1,4 +1,5 @@
 use crate::conductor::interface::error::InterfaceResult;
+use crate::core::signal::Signal;
 use holochain_serialized_bytes::prelude::*;
 
 mod admin_interface;
@@ -15,6 +16,18 @@ pub trait InterfaceApi: 'static + Send + Sync + Clone {
     type ApiRequest: TryFrom<SerializedBytes, Error = SerializedBytesError> + Send + Sync;
     /// Which response is sent to the above request
     type ApiResponse: TryInto<SerializedBytes, Error = SerializedBytesError> + Send + Sync;
+
+    /// Create the api used by a single new connection to the interface.
+    /// Apis which keep per-connection state start it fresh here.
+    fn new_connection(&self) -> Self {
+        self.clone()
+    }
+
+    /// Should this signal be sent out across this connection
+    async fn accepts_signal(&self, _signal: &Signal) -> bool {
+        true
+    }
+
     /// Handle a request on this API
     async fn handle_request(
         &self,
@@ -1,9 +1,9 @@
-use super::InterfaceApi;
+use super::{AppInterfaceBinding, AppInterfaceToken, InterfaceApi};
 use crate::conductor::api::error::{
     ConductorApiError, ConductorApiResult, ExternalApiWireError, SerializationError,
 };
 use crate::conductor::{
-    config::AdminInterfaceConfig,
+    config::{AdminInterfaceConfig, InterfaceDriver, WebsocketOptions},
     error::CreateAppError,
     interface::error::{InterfaceError, InterfaceResult},
     ConductorHandle,
@@ -11,12 +11,16 @@ use crate::conductor::{
 use holo_hash::*;
 use holochain_keystore::KeystoreSenderExt;
 use holochain_serialized_bytes::prelude::*;
+use holochain_state::env::EnvironmentStats;
 use holochain_types::{
-    app::{AppId, InstallAppDnaPayload, InstallAppPayload, InstalledApp, InstalledCell},
+    app::{
+        AppId, CreateCloneCellPayload, InstallAppDnaPayload, InstallAppPayload, InstalledApp,
+        InstalledCell,
+    },
     cell::CellId,
     dna::{DnaFile, JsonProperties},
 };
-use std::path::PathBuf;
+use std::{collections::HashMap, path::PathBuf};
 use tracing::*;
 
 /// A trait for the interface that a Conductor exposes to the outside world to use for administering the conductor.
@@ -114,6 +118,14 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let app = InstalledApp { app_id, cell_data };
                 Ok(AdminResponse::AppInstalled(app))
             }
+            CreateCloneCell(payload) => {
+                let cell = self
+                    .conductor_handle
+                    .clone()
+                    .create_clone_cell(*payload)
+                    .await?;
+                Ok(AdminResponse::CloneCellCreated(cell))
+            }
             ListDnas => {
                 let dna_list = self.conductor_handle.list_dnas().await?;
                 Ok(AdminResponse::ListDnas(dna_list))
@@ -158,19 +170,71 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 self.conductor_handle.deactivate_app(app_id.clone()).await?;
                 Ok(AdminResponse::AppDeactivated)
             }
-            AttachAppInterface { port } => {
-                let port = port.unwrap_or(0);
+            AttachAppInterface {
+                port,
+                binding,
+                options,
+                unix_socket,
+            } => {
+                let driver = match unix_socket {
+                    Some(path) => InterfaceDriver::UnixSocket { path },
+                    None => InterfaceDriver::Websocket {
+                        port: port.unwrap_or(0),
+                        options,
+                    },
+                };
                 let port = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(port)
+                    .add_app_interface(driver, binding)
                     .await?;
                 Ok(AdminResponse::AppInterfaceAttached { port })
             }
+            IssueAppInterfaceToken {
+                app_ids,
+                single_use,
+            } => {
+                let token = self
+                    .conductor_handle
+                    .issue_app_interface_token(app_ids, single_use)
+                    .await?;
+                Ok(AdminResponse::AppInterfaceTokenIssued(token))
+            }
             DumpState { cell_id } => {
                 let state = self.conductor_handle.dump_cell_state(&cell_id).await?;
                 Ok(AdminResponse::JsonState(state))
             }
+            GetEnvironmentStats => Ok(AdminResponse::EnvironmentStats(
+                self.conductor_handle.environment_stats().await?,
+            )),
+            ExportSourceChain { cell_id, path } => {
+                self.conductor_handle
+                    .export_source_chain(&cell_id, path)
+                    .await?;
+                Ok(AdminResponse::SourceChainExported)
+            }
+            ImportSourceChain { path } => {
+                let cell_id = self.conductor_handle.import_source_chain(path).await?;
+                Ok(AdminResponse::SourceChainImported(cell_id))
+            }
+            MigrateAgent {
+                agent_key,
+                from_dna,
+                to_dna,
+            } => {
+                self.conductor_handle
+                    .migrate_agent(agent_key, from_dna, to_dna)
+                    .await?;
+                Ok(AdminResponse::AgentMigrated)
+            }
+            BackupCell { cell_id, path } => {
+                self.conductor_handle.backup_cell(&cell_id, path).await?;
+                Ok(AdminResponse::CellBackedUp)
+            }
+            BackupConductor { path } => {
+                self.conductor_handle.backup_conductor(path).await?;
+                Ok(AdminResponse::ConductorBackedUp)
+            }
         }
     }
 }
@@ -226,6 +290,10 @@ pub enum AdminRequest {
     /// Triggers genesis to be run on all cells and
     /// Dnas to be stored
     InstallApp(Box<InstallAppPayload>),
+    /// Clone a Cell of an installed app with a new uuid and/or properties.
+    /// Installs the derived [Dna], runs genesis on the new Cell
+    /// and adds it to the app
+    CreateCloneCell(Box<CreateCloneCellPayload>),
     /// List all installed [Dna]s
     ListDnas,
     /// Generate a new AgentPubKey
@@ -247,12 +315,80 @@ pub enum AdminRequest {
         /// Optional port, use None to let the
         /// OS choose a free port
         port: Option<u16>,
+        /// Restrict the apps connections can access and
+        /// whether they must authenticate first
+        #[serde(default)]
+        binding: AppInterfaceBinding,
+        /// Bind address, TLS and allowed origins for the websocket
+        #[serde(default)]
+        options: WebsocketOptions,
+        /// Listen on a unix domain socket at this path instead of
+        /// a websocket port, in which case `port` and `options` are ignored
+        #[serde(default)]
+        unix_socket: Option<PathBuf>,
+    },
+    /// Issue a token which an [AppInterfaceApi] connection can
+    /// authenticate with to access the given apps
+    IssueAppInterfaceToken {
+        /// The apps the token grants access to
+        app_ids: Vec<AppId>,
+        /// Revoke the token once a connection has authenticated with it
+        single_use: bool,
     },
     /// Dump the state of a cell
     DumpState {
         /// The CellId for which to dump state
         cell_id: Box<CellId>,
     },
+    /// Get the size and usage of each LMDB environment
+    GetEnvironmentStats,
+    /// Write the full authored source chain of a cell, including private
+    /// entries, to a versioned archive file
+    ExportSourceChain {
+        /// The CellId whose source chain to export
+        cell_id: Box<CellId>,
+        /// The archive file to write
+        path: PathBuf,
+    },
+    /// Verify every signature and hash in a source chain archive and write
+    /// the chain into the cell it was exported from. The cell's DNA must be
+    /// installed and the cell must not be, so import before installing an
+    /// app with the archive's agent key. That key must be in this
+    /// conductor's keystore, or nothing is imported.
+    ImportSourceChain {
+        /// The archive file to read
+        path: PathBuf,
+    },
+    /// Move an agent to a new version of a DNA. The agent's chain on the
+    /// old DNA is closed and their chain on the new DNA is opened, once every
+    /// zome's `migrate_agent` callback in each DNA has passed. Both cells
+    /// must be installed and active.
+    MigrateAgent {
+        /// The agent to migrate
+        agent_key: AgentPubKey,
+        /// The DNA whose chain is closed
+        from_dna: DnaHash,
+        /// The DNA whose chain is opened
+        to_dna: DnaHash,
+    },
+    /// Write a consistent snapshot of a cell's environment into a
+    /// backup directory, which can be restored with `--restore-from`.
+    /// The backup is not an archive: it is a plain directory holding a
+    /// copy of the environment's LMDB directory.
+    BackupCell {
+        /// The CellId whose environment to back up
+        cell_id: Box<CellId>,
+        /// The backup directory
+        path: PathBuf,
+    },
+    /// Write a consistent snapshot of the conductor, wasm and every
+    /// installed cell environment into a backup directory.
+    /// Like [AdminRequest::BackupCell] this writes a directory, with one
+    /// subdirectory per environment, rather than a single archive file.
+    BackupConductor {
+        /// The backup directory
+        path: PathBuf,
+    },
 }
 
 /// Responses to messages received on an Admin interface
@@ -264,6 +400,8 @@ pub enum AdminResponse {
     Unimplemented(AdminRequest),
     /// hApp [Dna]s have successfully been installed
     AppInstalled(InstalledApp),
+    /// A clone of a Cell has successfully been added to an app
+    CloneCellCreated(InstalledCell),
     /// AdminInterfaces have successfully been added
     AdminInterfacesAdded(()),
     /// A list of all installed [Dna]s
@@ -274,9 +412,12 @@ pub enum AdminResponse {
     ListCellIds(Vec<CellId>),
     /// [AppInterfaceApi] successfully attached
     AppInterfaceAttached {
-        /// Port of the new [AppInterfaceApi]
+        /// Port of the new [AppInterfaceApi], or 0 if it
+        /// is listening on a unix domain socket
         port: u16,
     },
+    /// A token for authenticating an [AppInterfaceApi] connection
+    AppInterfaceTokenIssued(AppInterfaceToken),
     /// An error has ocurred in this request
     Error(ExternalApiWireError),
     /// App activated successfully
@@ -285,6 +426,19 @@ pub enum AdminResponse {
     AppDeactivated,
     /// State of a cell
     JsonState(String),
+    /// Size and usage of each LMDB environment,
+    /// keyed by "conductor", "wasm" or the CellId
+    EnvironmentStats(HashMap<String, EnvironmentStats>),
+    /// The source chain was written to the archive file
+    SourceChainExported,
+    /// The source chain was verified and imported into the given cell
+    SourceChainImported(CellId),
+    /// The agent's old chain was closed and new chain opened
+    AgentMigrated,
+    /// The cell's environment was backed up successfully
+    CellBackedUp,
+    /// Every environment was backed up successfully
+    ConductorBackedUp,
 }
 
 #[cfg(test)]
@@ -296,7 +450,10 @@ mod test {
     use holochain_types::{
         app::InstallAppDnaPayload,
         observability,
-        test_utils::{fake_agent_pubkey_1, fake_dna_file, fake_dna_zomes, write_fake_dna_file},
+        test_utils::{
+            fake_agent_pubkey_1, fake_agent_pubkey_2, fake_dna_file, fake_dna_zomes,
+            write_fake_dna_file,
+        },
     };
     use holochain_wasm_test_utils::TestWasm;
     use matches::assert_matches;
@@ -353,6 +510,23 @@ mod test {
 
         assert_matches!(res, AdminResponse::AppActivated);
 
+        let res = admin_api
+            .handle_admin_request(AdminRequest::GetEnvironmentStats)
+            .await;
+        let stats = unwrap_to::unwrap_to!(res => AdminResponse::EnvironmentStats).clone();
+        let mut env_names: Vec<_> = stats.keys().cloned().collect();
+        env_names.sort();
+        let mut expected_env_names = vec![
+            "conductor".to_string(),
+            "wasm".to_string(),
+            cell_id.to_string(),
+        ];
+        expected_env_names.sort();
+        assert_eq!(env_names, expected_env_names);
+        assert!(stats
+            .values()
+            .all(|s| s.used_size > 0 && s.used_size <= s.map_size));
+
         let res = admin_api
             .handle_admin_request(AdminRequest::ListCellIds)
             .await;
@@ -366,6 +540,275 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn create_clone_cell() -> Result<()> {
+        observability::test_run().ok();
+        let test_env = test_conductor_env();
+        let TestEnvironment {
+            env: wasm_env,
+            tmpdir: _tmpdir,
+        } = test_wasm_env();
+        let _tmpdir = test_env.tmpdir.clone();
+        let handle = Conductor::builder().test(test_env, wasm_env).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let (dna_path, _tempdir) = write_fake_dna_file(dna.clone()).await.unwrap();
+        let dna_payload = InstallAppDnaPayload::path_only(dna_path, "room".to_string());
+        let agent_key = fake_agent_pubkey_1();
+        let payload = InstallAppPayload {
+            dnas: vec![dna_payload],
+            app_id: "test".to_string(),
+            agent_key: agent_key.clone(),
+        };
+        let install_response = admin_api
+            .handle_admin_request(AdminRequest::InstallApp(Box::new(payload)))
+            .await;
+        assert_matches!(install_response, AdminResponse::AppInstalled(_));
+
+        let clone_payload = CreateCloneCellPayload {
+            app_id: "test".to_string(),
+            cell_nick: "room".to_string(),
+            uuid: Some(Uuid::new_v4().to_string()),
+            properties: None,
+            membrane_proof: None,
+        };
+
+        // A clone without any changes is rejected
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                CreateCloneCellPayload {
+                    uuid: None,
+                    ..clone_payload.clone()
+                },
+            )))
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                clone_payload.clone(),
+            )))
+            .await;
+        let clone_cell = match res {
+            AdminResponse::CloneCellCreated(cell) => cell,
+            other => panic!("unexpected response {:?}", other),
+        };
+        assert_ne!(clone_cell.as_id().dna_hash(), dna.dna_hash());
+        assert_eq!(clone_cell.as_id().agent_pubkey(), &agent_key);
+        assert_eq!(clone_cell.as_nick(), "room.0");
+
+        let dna_list = admin_api.handle_admin_request(AdminRequest::ListDnas).await;
+        assert_matches!(dna_list, AdminResponse::ListDnas(a) if a.len() == 2);
+
+        let app = handle.get_app_info(&"test".to_string()).await?.unwrap();
+        assert_eq!(app.cell_data.len(), 2);
+        assert!(app.cell_data.contains(&clone_cell));
+
+        // The same clone again is rejected before anything is installed
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                clone_payload.clone(),
+            )))
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+
+        // A clone of the clone doesn't count as a clone of the original
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                CreateCloneCellPayload {
+                    cell_nick: "room.0".to_string(),
+                    uuid: Some(Uuid::new_v4().to_string()),
+                    ..clone_payload.clone()
+                },
+            )))
+            .await;
+        assert_matches!(res, AdminResponse::CloneCellCreated(c) if c.as_nick() == "room.0.0");
+        let res = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                CreateCloneCellPayload {
+                    uuid: Some(Uuid::new_v4().to_string()),
+                    ..clone_payload.clone()
+                },
+            )))
+            .await;
+        assert_matches!(res, AdminResponse::CloneCellCreated(c) if c.as_nick() == "room.1");
+        let app = handle.get_app_info(&"test".to_string()).await?.unwrap();
+        assert_eq!(app.cell_data.len(), 4);
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ActivateApp {
+                app_id: "test".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppActivated);
+
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ListCellIds)
+            .await;
+        assert_matches!(res, AdminResponse::ListCellIds(v) if v.contains(clone_cell.as_id()));
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn install_app_checks_membrane_proof() -> Result<()> {
+        observability::test_run().ok();
+        let test_env = test_conductor_env();
+        let TestEnvironment {
+            env: wasm_env,
+            tmpdir: _tmpdir,
+        } = test_wasm_env();
+        let _tmpdir = test_env.tmpdir.clone();
+        let handle = Conductor::builder().test(test_env, wasm_env).await?;
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Membrane.into(), TestWasm::Membrane.into())],
+        );
+        let (dna_path, _tempdir) = write_fake_dna_file(dna.clone()).await.unwrap();
+        let install = |app_id: &str, membrane_proof: &[u8]| {
+            let mut dna_payload = InstallAppDnaPayload::path_only(dna_path.clone(), "".to_string());
+            dna_payload.membrane_proof = Some(SerializedBytes::from(UnsafeBytes::from(
+                membrane_proof.to_vec(),
+            )));
+            AdminRequest::InstallApp(Box::new(InstallAppPayload {
+                dnas: vec![dna_payload],
+                app_id: app_id.to_string(),
+                agent_key: fake_agent_pubkey_1(),
+            }))
+        };
+
+        // The zome's genesis_self_check refuses the proof
+        // so nothing is installed
+        let res = admin_api
+            .handle_admin_request(install("bad", b"let me in please"))
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+        assert!(handle.get_app_info(&"bad".to_string()).await?.is_none());
+
+        let res = admin_api
+            .handle_admin_request(install("good", b"let me in"))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+
+        handle.shutdown().await;
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn export_import_source_chain() -> Result<()> {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let (dna_path, _tempdir) = write_fake_dna_file(dna.clone()).await.unwrap();
+        let install = |app_id: &str, agent_key| {
+            AdminRequest::InstallApp(Box::new(InstallAppPayload {
+                dnas: vec![InstallAppDnaPayload::path_only(
+                    dna_path.clone(),
+                    "".to_string(),
+                )],
+                app_id: app_id.to_string(),
+                agent_key,
+            }))
+        };
+        let agent_key = fake_agent_pubkey_1();
+        let cell_id = CellId::new(dna.dna_hash().clone(), agent_key.clone());
+        let archive_dir = tempdir::TempDir::new("source_chain_archive").unwrap();
+        let archive_path = archive_dir.path().join("chain");
+
+        let test_env = test_conductor_env();
+        let TestEnvironment {
+            env: wasm_env,
+            tmpdir: _wasm_tmpdir,
+        } = test_wasm_env();
+        let _tmpdir = test_env.tmpdir.clone();
+        let handle = Conductor::builder().test(test_env, wasm_env).await?;
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+        let res = admin_api
+            .handle_admin_request(install("test", agent_key.clone()))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ExportSourceChain {
+                cell_id: Box::new(cell_id.clone()),
+                path: archive_path.clone(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::SourceChainExported);
+        handle.shutdown().await;
+
+        let test_env = test_conductor_env();
+        let TestEnvironment {
+            env: wasm_env,
+            tmpdir: _wasm_tmpdir,
+        } = test_wasm_env();
+        let _tmpdir = test_env.tmpdir.clone();
+        let handle = Conductor::builder().test(test_env, wasm_env).await?;
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        // The DNA must be installed first
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ImportSourceChain {
+                path: archive_path.clone(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::Error(_));
+
+        let res = admin_api
+            .handle_admin_request(install("other", fake_agent_pubkey_2()))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ImportSourceChain {
+                path: archive_path.clone(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::SourceChainImported(id) if id == cell_id);
+
+        // The imported chain is already there, so genesis is skipped
+        let res = admin_api
+            .handle_admin_request(install("test", agent_key.clone()))
+            .await;
+        assert_matches!(res, AdminResponse::AppInstalled(_));
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ActivateApp {
+                app_id: "test".to_string(),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::AppActivated);
+        let res = admin_api
+            .handle_admin_request(AdminRequest::ExportSourceChain {
+                cell_id: Box::new(cell_id.clone()),
+                path: archive_dir.path().join("imported"),
+            })
+            .await;
+        assert_matches!(res, AdminResponse::SourceChainExported);
+        assert_eq!(
+            tokio::fs::read(archive_path).await?,
+            tokio::fs::read(archive_dir.path().join("imported")).await?
+        );
+
+        handle.shutdown().await;
+        Ok(())
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn dna_read_parses() -> Result<()> {
         let uuid = Uuid::new_v4();
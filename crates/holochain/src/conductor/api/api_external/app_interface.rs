@@ -1,17 +1,28 @@
-use super::{InterfaceApi, SignalSubscription};
+use super::{InterfaceApi, SignalFilterSet, SignalSubscription};
 use crate::conductor::{
-    api::error::{ConductorApiResult, ExternalApiWireError, SerializationError},
+    api::error::{ConductorApiError, ConductorApiResult, ExternalApiWireError, SerializationError},
     state::AppInterfaceId,
 };
 use crate::conductor::{
+    error::ConductorError,
     interface::error::{InterfaceError, InterfaceResult},
     ConductorHandle,
 };
-use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::{
+    ribosome::ZomeCallInvocation,
+    signal::{Signal, SystemSignal},
+    workflow::{ZomeCallBatchResponse, ZomeCallInvocationResponse},
+};
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::app::{AppId, InstalledApp};
+use holochain_types::{
+    app::{AppId, InstalledApp},
+    cell::CellId,
+};
 use holochain_zome_types::ExternOutput;
 use holochain_zome_types::ZomeCallResponse;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
 
 /// The interface that a Conductor exposes to the outside world.
 #[async_trait::async_trait]
@@ -41,6 +52,51 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
     interface_id: AppInterfaceId,
+    /// Restrictions on what connections to this interface can access
+    binding: AppInterfaceBinding,
+    /// State belonging to a single connection to this interface
+    connection: Arc<RwLock<AppConnectionState>>,
+}
+
+/// Restrictions placed on every connection made to an App interface
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct AppInterfaceBinding {
+    /// Only these apps can be accessed through the interface.
+    /// None means any installed app can be accessed.
+    pub app_ids: Option<Vec<AppId>>,
+    /// Connections must present an [AppInterfaceToken] issued via the
+    /// admin interface before making any other request
+    pub require_token: bool,
+}
+
+/// A secret issued via the admin interface which lets an App interface
+/// connection access a set of apps
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct AppInterfaceToken(String);
+
+impl AppInterfaceToken {
+    /// Generate a new random token
+    pub fn random() -> Self {
+        use rand::Rng;
+        let bytes: [u8; 32] = rand::thread_rng().gen();
+        Self(base64::encode_config(&bytes, base64::URL_SAFE_NO_PAD))
+    }
+}
+
+/// Per connection state of an App interface
+#[derive(Debug, Default)]
+struct AppConnectionState {
+    /// The apps this connection has authenticated for, if any
+    authenticated_app_ids: Option<Vec<AppId>>,
+    /// Signal filters this connection has subscribed with, per app.
+    /// They govern the signals of whichever cells the app has when
+    /// a signal is delivered.
+    signal_subscriptions: HashMap<AppId, SignalFilterSet>,
+    /// The cells of each app this connection has looked up, so signals can
+    /// be checked without asking the conductor each time.
+    /// Cleared by the activation signals, which are sent whenever
+    /// an app's cells change.
+    app_cells: HashMap<AppId, Vec<CellId>>,
 }
 
 impl RealAppInterfaceApi {
@@ -49,6 +105,167 @@ impl RealAppInterfaceApi {
         Self {
             conductor_handle,
             interface_id,
+            binding: AppInterfaceBinding::default(),
+            connection: Default::default(),
+        }
+    }
+
+    /// Restrict what connections to this interface can access
+    pub fn with_binding(mut self, binding: AppInterfaceBinding) -> Self {
+        self.binding = binding;
+        self
+    }
+
+    /// The apps this connection can access, or None if it can access any app
+    async fn allowed_app_ids(&self) -> ConductorApiResult<Option<Vec<AppId>>> {
+        match self.connection.read().await.authenticated_app_ids.clone() {
+            Some(app_ids) => Ok(Some(app_ids)),
+            None if self.binding.require_token => Err(ConductorApiError::AppInterfaceUnauthorized(
+                "This interface requires the connection to authenticate first".into(),
+            )),
+            None => Ok(self.binding.app_ids.clone()),
+        }
+    }
+
+    #[allow(clippy::ptr_arg)]
+    async fn check_app_access(&self, app_id: &AppId) -> ConductorApiResult<()> {
+        match self.allowed_app_ids().await? {
+            Some(app_ids) if !app_ids.contains(app_id) => {
+                Err(ConductorApiError::AppInterfaceUnauthorized(format!(
+                    "This connection cannot access the app {}",
+                    app_id
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The cells the app has, or none if it isn't installed
+    #[allow(clippy::ptr_arg)]
+    async fn app_cells(&self, app_id: &AppId) -> ConductorApiResult<Vec<CellId>> {
+        if let Some(cells) = self.connection.read().await.app_cells.get(app_id) {
+            return Ok(cells.clone());
+        }
+        let cells: Vec<CellId> = match self.conductor_handle.get_app_info(app_id).await? {
+            Some(app) => app.cell_data.iter().map(|c| c.as_id().clone()).collect(),
+            None => Vec::new(),
+        };
+        self.connection
+            .write()
+            .await
+            .app_cells
+            .insert(app_id.clone(), cells.clone());
+        Ok(cells)
+    }
+
+    /// Forget the cells of any app the signal says may have changed
+    async fn invalidate_app_cells(&self, signal: &Signal) {
+        match signal {
+            Signal::System(SystemSignal::AppActivated(app_id))
+            | Signal::System(SystemSignal::AppDeactivated(app_id)) => {
+                self.connection.write().await.app_cells.remove(app_id);
+            }
+            // A cell may have been added to or removed from any app
+            Signal::System(SystemSignal::CellActivated(_))
+            | Signal::System(SystemSignal::CellDeactivated(_)) => {
+                self.connection.write().await.app_cells.clear();
+            }
+            _ => (),
+        }
+    }
+
+    /// Whether the connection can access the cell, going by the app cells
+    /// it has already looked up
+    async fn cell_accessible(&self, cell_id: &CellId) -> ConductorApiResult<bool> {
+        if let Some(app_ids) = self.allowed_app_ids().await? {
+            for app_id in app_ids {
+                if self.app_cells(&app_id).await?.contains(cell_id) {
+                    return Ok(true);
+                }
+            }
+            return Ok(false);
+        }
+        Ok(true)
+    }
+
+    async fn check_cell_access(&self, cell_id: &CellId) -> ConductorApiResult<()> {
+        if self.cell_accessible(cell_id).await? {
+            return Ok(());
+        }
+        // A request can arrive before the signal saying a cell was added
+        // to an app, so look the apps up again before refusing it
+        self.connection.write().await.app_cells.clear();
+        if self.cell_accessible(cell_id).await? {
+            return Ok(());
+        }
+        Err(ConductorApiError::AppInterfaceUnauthorized(format!(
+            "This connection cannot access the cell {:?}",
+            cell_id
+        )))
+    }
+
+    async fn authenticate(&self, token: AppInterfaceToken) -> ConductorApiResult<()> {
+        let mut app_ids = self
+            .conductor_handle
+            .authenticate_app_interface_token(&token)
+            .await?;
+        if let Some(bound_app_ids) = &self.binding.app_ids {
+            app_ids.retain(|app_id| bound_app_ids.contains(app_id));
+        }
+        self.connection.write().await.authenticated_app_ids = Some(app_ids);
+        Ok(())
+    }
+
+    async fn subscribe(&self, subscription: SignalSubscription) -> ConductorApiResult<()> {
+        let SignalSubscription { app_id, filters } = subscription;
+        self.check_app_access(&app_id).await?;
+        self.conductor_handle
+            .get_app_info(&app_id)
+            .await?
+            .ok_or(ConductorError::AppNotInstalled)?;
+        self.connection
+            .write()
+            .await
+            .signal_subscriptions
+            .insert(app_id, filters);
+        Ok(())
+    }
+
+    /// Whether every subscription governing the signal lets it through.
+    /// Signals governed by no subscription are let through.
+    async fn subscriptions_allow(&self, signal: &Signal) -> bool {
+        let subscriptions = self.connection.read().await.signal_subscriptions.clone();
+        for (app_id, filters) in subscriptions {
+            // Only look up the app's cells when its filters would block the signal
+            if filters.allows(signal) {
+                continue;
+            }
+            let governed = match signal {
+                Signal::System(SystemSignal::AppActivated(id))
+                | Signal::System(SystemSignal::AppDeactivated(id)) => *id == app_id,
+                _ => match signal.cell_id() {
+                    Some(cell_id) => self.app_has_cell(&app_id, cell_id).await,
+                    None => false,
+                },
+            };
+            if governed {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Whether the app has the cell, as of the last activation signal,
+    /// so that cells added to an app after a subscription are governed by it too.
+    /// If the app can't be looked up, the cell is assumed to be governed.
+    #[allow(clippy::ptr_arg)]
+    async fn app_has_cell(&self, app_id: &AppId, cell_id: &CellId) -> bool {
+        match self.app_cells(app_id).await {
+            Ok(cells) => cells.contains(cell_id),
+            Err(e) => {
+                tracing::warn!(?e, ?app_id, "Couldn't look up a subscribed app");
+                true
+            }
         }
     }
 }
@@ -61,18 +278,64 @@ impl AppInterfaceApi for RealAppInterfaceApi {
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse> {
         match request {
-            AppRequest::AppInfo { app_id } => Ok(AppResponse::AppInfo(
-                self.conductor_handle.get_app_info(&app_id).await?,
-            )),
-            AppRequest::SignalSubscription(_subscription) => {
-                todo!("Signal pubsub not yet implemented")
+            AppRequest::Authenticate { token } => {
+                self.authenticate(token).await?;
+                Ok(AppResponse::Authenticated)
+            }
+            AppRequest::AppInfo { app_id } => {
+                self.check_app_access(&app_id).await?;
+                Ok(AppResponse::AppInfo(
+                    self.conductor_handle.get_app_info(&app_id).await?,
+                ))
+            }
+            AppRequest::SignalSubscription(subscription) => {
+                self.subscribe(subscription).await?;
+                Ok(AppResponse::SignalSubscriptionUpdated)
             }
             AppRequest::ZomeCallInvocation(request) => {
+                self.check_cell_access(&request.cell_id).await?;
                 match self.conductor_handle.call_zome(*request).await? {
-                    Ok(ZomeCallResponse::Ok(output)) => {
-                        Ok(AppResponse::ZomeCallInvocation(Box::new(output)))
+                    Ok(ZomeCallInvocationResponse {
+                        response: ZomeCallResponse::Ok(output),
+                        post_commit_error: None,
+                    }) => Ok(AppResponse::ZomeCallInvocation(Box::new(output))),
+                    Ok(ZomeCallInvocationResponse {
+                        response: ZomeCallResponse::Ok(output),
+                        post_commit_error: Some(e),
+                    }) => Ok(AppResponse::ZomeCallPostCommitFailed {
+                        output: Box::new(output),
+                        post_commit_error: e.into(),
+                    }),
+                    Ok(ZomeCallInvocationResponse {
+                        response: ZomeCallResponse::Unauthorized,
+                        ..
+                    }) => Ok(AppResponse::ZomeCallUnauthorized),
+                    Err(e) => Ok(AppResponse::Error(e.into())),
+                }
+            }
+            AppRequest::ZomeCallBatch(invocations) => {
+                for invocation in &invocations {
+                    self.check_cell_access(&invocation.cell_id).await?;
+                }
+                match self.conductor_handle.call_zome_batch(invocations).await? {
+                    Ok(ZomeCallBatchResponse {
+                        responses,
+                        post_commit_error,
+                    }) => {
+                        let mut outputs = Vec::with_capacity(responses.len());
+                        for response in responses {
+                            match response {
+                                ZomeCallResponse::Ok(output) => outputs.push(output),
+                                ZomeCallResponse::Unauthorized => {
+                                    return Ok(AppResponse::ZomeCallUnauthorized)
+                                }
+                            }
+                        }
+                        Ok(AppResponse::ZomeCallBatch {
+                            outputs,
+                            post_commit_error: post_commit_error.map(Into::into),
+                        })
                     }
-                    Ok(ZomeCallResponse::Unauthorized) => Ok(AppResponse::ZomeCallUnauthorized),
                     Err(e) => Ok(AppResponse::Error(e.into())),
                 }
             }
@@ -85,6 +348,29 @@ impl AppInterfaceApi for RealAppInterfaceApi {
 impl InterfaceApi for RealAppInterfaceApi {
     type ApiRequest = AppRequest;
     type ApiResponse = AppResponse;
+
+    fn new_connection(&self) -> Self {
+        Self {
+            connection: Default::default(),
+            ..self.clone()
+        }
+    }
+
+    async fn accepts_signal(&self, signal: &Signal) -> bool {
+        self.invalidate_app_cells(signal).await;
+        let accessible = match signal {
+            Signal::System(SystemSignal::AppActivated(app_id))
+            | Signal::System(SystemSignal::AppDeactivated(app_id)) => {
+                self.check_app_access(app_id).await.is_ok()
+            }
+            _ => match signal.cell_id() {
+                Some(cell_id) => self.cell_accessible(cell_id).await.unwrap_or(false),
+                None => self.allowed_app_ids().await.is_ok(),
+            },
+        };
+        accessible && self.subscriptions_allow(signal).await
+    }
+
     async fn handle_request(
         &self,
         request: Result<Self::ApiRequest, SerializedBytesError>,
@@ -107,6 +393,14 @@ impl InterfaceApi for RealAppInterfaceApi {
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename = "snake-case", tag = "type", content = "data")]
 pub enum AppRequest {
+    /// Authenticate this connection with a token issued via the admin interface.
+    /// Required before any other request if the interface demands it,
+    /// and restricts the connection to the apps the token was issued for.
+    Authenticate {
+        /// The token issued via the admin interface
+        token: AppInterfaceToken,
+    },
+
     /// Get info about the App
     AppInfo {
         /// The AppId for which to get information
@@ -119,6 +413,10 @@ pub enum AppRequest {
     /// Call a zome function
     ZomeCallInvocation(Box<ZomeCallInvocation>),
 
+    /// Call several zome functions on the same cell, in order,
+    /// committing what they commit only if every call succeeds
+    ZomeCallBatch(Vec<ZomeCallInvocation>),
+
     /// Update signal subscriptions
     SignalSubscription(SignalSubscription),
 }
@@ -130,12 +428,33 @@ pub enum AppResponse {
     /// There has been an error in the request
     Error(ExternalApiWireError),
 
+    /// The connection has been authenticated
+    Authenticated,
+
     /// The response to an AppInfo request
     AppInfo(Option<InstalledApp>),
 
     /// The response to a zome call
     ZomeCallInvocation(Box<ExternOutput>),
 
+    /// The response to a zome call whose post_commit callback failed.
+    /// The call's commits are kept.
+    ZomeCallPostCommitFailed {
+        /// The output of the call
+        output: Box<ExternOutput>,
+        /// The error of the post_commit callback
+        post_commit_error: ExternalApiWireError,
+    },
+
+    /// The responses to a batch of zome calls
+    ZomeCallBatch {
+        /// The output of each call, in the order they were called
+        outputs: Vec<ExternOutput>,
+        /// The error of the first post_commit callback that failed.
+        /// The batch's commits are kept even if this is set.
+        post_commit_error: Option<ExternalApiWireError>,
+    },
+
     /// The response to a SignalSubscription message
     SignalSubscriptionUpdated,
 
@@ -1,5 +1,7 @@
+use crate::core::signal::{Signal, SignalKind};
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::{app::AppId, cell::CellId};
+use holochain_zome_types::zome::ZomeName;
 use std::collections::HashMap;
 
 /// Declares updated Signal subscription settings for an App.
@@ -8,9 +10,9 @@ use std::collections::HashMap;
 #[cfg_attr(test, derive(PartialEq))]
 pub struct SignalSubscription {
     /// The app for which to manage subscription
-    app_id: AppId,
+    pub app_id: AppId,
     /// Fine-grained per-cell filters
-    filters: SignalFilterSet,
+    pub filters: SignalFilterSet,
 }
 
 /// Associate a SignalFilter with each Cell in an App.
@@ -19,6 +21,9 @@ pub struct SignalSubscription {
 ///
 /// An empty Exclude filter means "allow all signals" (subscribe to all).
 /// An empty Include filter means "block all signals" (unsubscribe from all).
+///
+/// Signals which concern no single Cell, such as app activation,
+/// are treated as coming from an unlisted Cell.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[cfg_attr(test, derive(PartialEq))]
 pub enum SignalFilterSet {
@@ -47,12 +52,44 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Whether this filter set lets the signal through
+    pub fn allows(&self, signal: &Signal) -> bool {
+        let filter = signal
+            .cell_id()
+            .and_then(|cell_id| self.filters().get(cell_id));
+        let matched = filter.map(|f| f.matches(signal)).unwrap_or(false);
+        match self {
+            SignalFilterSet::Include(_) => matched,
+            SignalFilterSet::Exclude(_) => !matched,
+        }
+    }
+
+    fn filters(&self) -> &HashMap<CellId, SignalFilter> {
+        match self {
+            SignalFilterSet::Include(filters) | SignalFilterSet::Exclude(filters) => filters,
+        }
+    }
 }
 
-/// Specifies fine-grained filter controls for the signals
+/// Specifies fine-grained filter controls for the signals.
+/// A signal matches the filter if it meets every criterion which is set.
+/// The zome and type tag criteria only apply to app-defined signals,
+/// so setting either means no system signal matches.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[cfg_attr(test, derive(PartialEq))]
-pub struct SignalFilter;
+pub struct SignalFilter {
+    /// Only match signals emitted by these zomes
+    #[serde(default)]
+    pub zome_names: Option<Vec<ZomeName>>,
+    /// Only match signals of these kinds
+    #[serde(default)]
+    pub kinds: Option<Vec<SignalKind>>,
+    /// Only match app signals with these type tags.
+    /// See [Signal::type_tag]
+    #[serde(default)]
+    pub type_tags: Option<Vec<String>>,
+}
 
 impl Default for SignalFilter {
     fn default() -> Self {
@@ -64,6 +101,96 @@ impl Default for SignalFilter {
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        SignalFilter {
+            zome_names: None,
+            kinds: None,
+            type_tags: None,
+        }
+    }
+
+    /// Whether the signal meets every criterion of this filter
+    pub fn matches(&self, signal: &Signal) -> bool {
+        if let Some(zome_names) = &self.zome_names {
+            match signal.zome_name() {
+                Some(zome_name) if zome_names.contains(zome_name) => (),
+                _ => return false,
+            }
+        }
+        if let Some(kinds) = &self.kinds {
+            if !kinds.contains(&signal.kind()) {
+                return false;
+            }
+        }
+        if let Some(type_tags) = &self.type_tags {
+            match signal.type_tag() {
+                Some(type_tag) if type_tags.contains(&type_tag) => (),
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::signal::SystemSignal;
+    use ::fixt::prelude::*;
+    use holochain_types::fixt::CellIdFixturator;
+    use maplit::hashmap;
+
+    #[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+    #[serde(tag = "type")]
+    enum TaggedPayload {
+        Ping,
+        Pong,
+    }
+
+    fn app_signal(cell_id: &CellId, zome: &str, payload: TaggedPayload) -> Signal {
+        Signal::App(
+            cell_id.clone(),
+            zome.into(),
+            SerializedBytes::try_from(payload).unwrap(),
+        )
+    }
+
+    #[test]
+    fn empty_filter_sets() {
+        let cell_id = fixt!(CellId);
+        let signal = app_signal(&cell_id, "foo", TaggedPayload::Ping);
+        assert!(SignalFilterSet::allow_all().allows(&signal));
+        assert!(!SignalFilterSet::block_all().allows(&signal));
+    }
+
+    #[test]
+    fn include_filters_by_criteria() {
+        let cell_id = fixt!(CellId);
+        let other_cell_id = fixt!(CellId);
+        let filters = SignalFilterSet::Include(hashmap! {
+            cell_id.clone() => SignalFilter {
+                zome_names: Some(vec!["foo".into()]),
+                type_tags: Some(vec!["Ping".into()]),
+                ..Default::default()
+            },
+        });
+        assert!(filters.allows(&app_signal(&cell_id, "foo", TaggedPayload::Ping)));
+        assert!(!filters.allows(&app_signal(&cell_id, "foo", TaggedPayload::Pong)));
+        assert!(!filters.allows(&app_signal(&cell_id, "bar", TaggedPayload::Ping)));
+        assert!(!filters.allows(&app_signal(&other_cell_id, "foo", TaggedPayload::Ping)));
+        assert!(!filters.allows(&SystemSignal::CellActivated(cell_id).into()));
+    }
+
+    #[test]
+    fn exclude_filters_by_kind() {
+        let cell_id = fixt!(CellId);
+        let filters = SignalFilterSet::Exclude(hashmap! {
+            cell_id.clone() => SignalFilter {
+                kinds: Some(vec![SignalKind::CellActivated, SignalKind::CellDeactivated]),
+                ..Default::default()
+            },
+        });
+        assert!(filters.allows(&app_signal(&cell_id, "foo", TaggedPayload::Ping)));
+        assert!(!filters.allows(&SystemSignal::CellActivated(cell_id.clone()).into()));
+        assert!(filters.allows(&SystemSignal::AppActivated("app".into()).into()));
     }
 }
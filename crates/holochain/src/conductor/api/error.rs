@@ -33,6 +33,15 @@ pub enum ConductorApiError {
         invocation_cell_id: CellId,
     },
 
+    /// The zome calls in a batch were not all on the same cell.
+    #[error("Every zome call in a batch must be on the same Cell.\nBatch CellId: {batch_cell_id:?}\nInvocation CellId: {invocation_cell_id:?}")]
+    ZomeCallBatchCellMismatch {
+        /// The CellId of the first call in the batch
+        batch_cell_id: CellId,
+        /// The CellId of a call on a different cell
+        invocation_cell_id: CellId,
+    },
+
     /// Conductor threw an error during API call.
     #[error("Conductor returned an error while using a ConductorApi: {0:?}")]
     ConductorError(#[from] ConductorError),
@@ -81,6 +90,10 @@ pub enum ConductorApiError {
 
     #[error(transparent)]
     SourceChainError(#[from] SourceChainError),
+
+    /// The app interface connection is not allowed to make this request
+    #[error("Unauthorized app interface request: {0}")]
+    AppInterfaceUnauthorized(String),
 }
 
 /// All the serialization errors that can occur
@@ -117,6 +130,8 @@ pub enum ExternalApiWireError {
     RibosomeError(String),
     /// Error activating app
     ActivateApp(String),
+    /// The connection is not authorized to make this request
+    Unauthorized(String),
 }
 
 impl ExternalApiWireError {
@@ -132,6 +147,12 @@ impl From<ConductorApiError> for ExternalApiWireError {
     fn from(err: ConductorApiError) -> Self {
         match err {
             ConductorApiError::DnaReadError(e) => ExternalApiWireError::DnaReadError(e),
+            ConductorApiError::AppInterfaceUnauthorized(e) => ExternalApiWireError::Unauthorized(e),
+            ConductorApiError::ConductorError(ConductorError::AppInterfaceTokenInvalid) => {
+                ExternalApiWireError::Unauthorized(
+                    ConductorError::AppInterfaceTokenInvalid.to_string(),
+                )
+            }
             e => ExternalApiWireError::internal(e),
         }
     }
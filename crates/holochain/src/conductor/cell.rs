@@ -4,12 +4,17 @@
 //! Elements can be added. A constructed Cell is guaranteed to have a valid
 //! SourceChain which has already undergone Genesis.
 
-use super::{interface::SignalBroadcaster, manager::ManagedTaskAdd};
+use super::{config::CacheConfig, interface::SignalBroadcaster, manager::ManagedTaskAdd};
 use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::CellConductorApiT;
+use crate::conductor::entry_def_store::EntryDefBufferKey;
 use crate::conductor::handle::ConductorHandle;
-use crate::core::queue_consumer::{spawn_queue_consumer_tasks, InitialQueueTriggers};
+use crate::core::check_warrant;
+use crate::core::queue_consumer::{
+    spawn_queue_consumer_tasks, InitialQueueTriggers, OneshotWriter,
+};
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::signal::SystemSignal;
 use holochain_zome_types::zome::FunctionName;
 
 use crate::{
@@ -17,16 +22,26 @@ use crate::{
     core::ribosome::{guest_callback::init::InitResult, wasm_ribosome::WasmRibosome},
     core::{
         state::{
-            dht_op_integration::IntegratedDhtOpsBuf,
+            dht_op_integration::{AuthoredDhtOpsKey, AuthoredDhtOpsValue, IntegratedDhtOpsBuf},
             element_buf::ElementBuf,
-            metadata::{LinkMetaKey, MetadataBuf, MetadataBufT},
+            metadata::{ChainItemKey, LinkMetaKey, MetadataBuf, MetadataBufT},
+            private_entries::{migrate_private_entries, PrivateEntryKey},
             source_chain::SourceChainBuf,
+            validation_receipts_db::{SignedValidationReceipt, ValidationReceiptsBuf},
         },
         workflow::{
-            call_zome_workflow, error::WorkflowError, genesis_workflow::genesis_workflow,
-            incoming_dht_ops_workflow::incoming_dht_ops_workflow, initialize_zomes_workflow,
-            CallZomeWorkflowArgs, CallZomeWorkspace, GenesisWorkflowArgs, GenesisWorkspace,
-            InitializeZomesWorkflowArgs, ZomeCallInvocationResult,
+            call_zome_batch_workflow, call_zome_workflow,
+            error::WorkflowError,
+            genesis_workflow::genesis_workflow,
+            incoming_dht_ops_workflow::incoming_dht_ops_workflow,
+            initialize_zomes_workflow,
+            migrate_agent_workflow::{
+                prepare_migration, MigrateAgentWorkflowArgs, MigrateAgentWorkspace,
+                PreparedMigration,
+            },
+            CallZomeBatchWorkflowArgs, CallZomeWorkflowArgs, CallZomeWorkspace,
+            GenesisWorkflowArgs, GenesisWorkspace, InitializeZomesWorkflowArgs,
+            ZomeCallBatchResult, ZomeCallInvocationResult,
         },
     },
 };
@@ -35,11 +50,14 @@ use fallible_iterator::FallibleIterator;
 use futures::future::FutureExt;
 use hash_type::AnyDht;
 use holo_hash::*;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pCellT;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_state::{
-    db::GetDb,
+    buffer::{BufferedStore, KvStore, KvStoreT},
+    db::{GetDb, AUTHORED_DHT_OPS},
     env::{EnvironmentWrite, ReadManager},
+    error::DatabaseError,
 };
 use holochain_types::{
     autonomic::AutonomicProcess,
@@ -47,15 +65,18 @@ use holochain_types::{
     element::{GetElementResponse, WireElement},
     link::{GetLinksResponse, WireLinkMetaKey},
     metadata::{MetadataSet, TimedHeaderHash},
+    warrant::Warrant,
     Timestamp,
 };
 use holochain_zome_types::capability::CapSecret;
 use holochain_zome_types::header::{CreateLink, DeleteLink};
+use holochain_zome_types::migrate_agent::MigrateAgent;
+use holochain_zome_types::query::{AgentActivity, ChainQueryFilter, ChainStatus};
 use holochain_zome_types::signature::Signature;
 use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::ExternInput;
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashMap},
     convert::TryInto,
     hash::{Hash, Hasher},
 };
@@ -118,9 +139,14 @@ impl Cell {
         mut holochain_p2p_cell: holochain_p2p::HolochainP2pCell,
         managed_task_add_sender: sync::mpsc::Sender<ManagedTaskAdd>,
         managed_task_stop_broadcaster: sync::broadcast::Sender<()>,
+        cache_config: CacheConfig,
     ) -> CellResult<Self> {
         let conductor_api = CellConductorApi::new(conductor_handle.clone(), id.clone());
 
+        // load the private entry key, and encrypt any private entries
+        // written before they were encrypted at rest
+        migrate_private_entries(&env).await?;
+
         // check if genesis has been run
         let has_genesis = {
             // check if genesis ran on source chain buf
@@ -135,6 +161,7 @@ impl Cell {
                 conductor_api.clone(),
                 managed_task_add_sender,
                 managed_task_stop_broadcaster,
+                cache_config,
             )
             .await;
 
@@ -174,12 +201,21 @@ impl Cell {
 
         let conductor_api = CellConductorApi::new(conductor_handle, id.clone());
 
+        // genesis may commit private entries
+        PrivateEntryKey::for_env(&cell_env).await?;
+
         // run genesis
         let workspace = GenesisWorkspace::new(cell_env.clone().into())
             .await
             .map_err(ConductorApiError::from)
             .map_err(Box::new)?;
-        let args = GenesisWorkflowArgs::new(dna_file, id.agent_pubkey().clone(), membrane_proof);
+        let ribosome = WasmRibosome::new(dna_file.clone());
+        let args = GenesisWorkflowArgs::new(
+            dna_file,
+            id.agent_pubkey().clone(),
+            membrane_proof,
+            ribosome,
+        );
 
         genesis_workflow(workspace, cell_env.clone().into(), conductor_api, args)
             .await
@@ -259,6 +295,22 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_publish"))
                 .await;
             }
+            PublishWarrant {
+                span: _span,
+                respond,
+                warrant,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_publish_warrant(warrant)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_publish_warrant"))
+                .await;
+            }
             GetValidationPackage {
                 span: _span,
                 respond,
@@ -324,6 +376,23 @@ impl Cell {
                 .instrument(debug_span!("cell_handle_get_links"))
                 .await;
             }
+            GetAgentActivity {
+                span: _span,
+                respond,
+                agent,
+                query,
+                options,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_get_agent_activity(agent, query, options)
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_get_agent_activity"))
+                .await;
+            }
             ValidationReceiptReceived {
                 span: _span,
                 respond,
@@ -409,6 +478,36 @@ impl Cell {
         Ok(())
     }
 
+    #[instrument(skip(self, warrant))]
+    /// we are receiving a warrant against an agent whose activity we hold
+    async fn handle_publish_warrant(&self, warrant: Warrant) -> CellResult<()> {
+        // Only hold warrants we can prove for ourselves
+        match check_warrant(&warrant).await {
+            Ok(true) => (),
+            Ok(false) => {
+                warn!(
+                    msg = "Dropping a warrant that doesn't prove its claim",
+                    ?warrant
+                );
+                return Ok(());
+            }
+            Err(e) => {
+                warn!(
+                    msg = "Dropping a warrant that couldn't be checked",
+                    ?warrant,
+                    ?e
+                );
+                return Ok(());
+            }
+        }
+
+        let mut meta_vault = MetadataBuf::vault(self.env.clone().into())?;
+        meta_vault.register_warrant(warrant)?;
+        let writer: OneshotWriter = self.env.clone().into();
+        writer.with_writer(|writer| Ok(meta_vault.flush_to_txn_ref(writer)?))?;
+        Ok(())
+    }
+
     /// a remote node is attempting to retreive a validation package
     async fn handle_get_validation_package(&self) -> CellResult<()> {
         unimplemented!()
@@ -559,9 +658,122 @@ impl Cell {
         })
     }
 
+    #[instrument(skip(self, _options))]
+    /// a remote agent is asking for the activity of an agent we are
+    /// an authority for
+    fn handle_get_agent_activity(
+        &self,
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        _options: holochain_p2p::event::GetActivityOptions,
+    ) -> CellResult<AgentActivity> {
+        // Get the vaults
+        let env_ref = self.env.guard();
+        let reader = env_ref.reader()?;
+        let element_vault = ElementBuf::vault(self.env.clone().into(), false)?;
+        let meta_vault = MetadataBuf::vault(self.env.clone().into())?;
+        let element_rejected = ElementBuf::rejected(self.env.clone().into())?;
+        let meta_rejected = MetadataBuf::rejected(self.env.clone().into())?;
+
+        let key = ChainItemKey::Agent(agent.clone());
+        let valid = meta_vault
+            .get_activity(&reader, key.clone())?
+            .collect::<Vec<_>>()?;
+        let rejected = meta_rejected
+            .get_activity(&reader, key)?
+            .collect::<Vec<_>>()?;
+
+        // Only valid headers are returned but rejected headers
+        // still count towards the highest observed sequence number
+        let mut headers = Vec::with_capacity(valid.len());
+        let mut highest_observed = None;
+        for activity in valid {
+            if let Some(header) = element_vault.get_header(&activity.header_hash)? {
+                highest_observed = highest_observed.max(Some(header.header().header_seq()));
+                if query.check(header.header()) {
+                    headers.push(header);
+                }
+            }
+        }
+        for activity in rejected.iter() {
+            if let Some(header) = element_rejected.get_header(&activity.header_hash)? {
+                highest_observed = highest_observed.max(Some(header.header().header_seq()));
+            }
+        }
+        headers.sort_by_key(|h| h.header().header_seq());
+        let headers = query.paginate(headers);
+
+        // A forked agent has its later ops rejected so
+        // a fork takes precedence over other rejections
+        let forked = meta_vault.get_warrants(&reader, agent)?.next()?.is_some();
+        let status = if forked {
+            ChainStatus::Forked
+        } else if !rejected.is_empty() {
+            ChainStatus::Invalid
+        } else if highest_observed.is_some() {
+            ChainStatus::Valid
+        } else {
+            ChainStatus::Empty
+        };
+
+        Ok(AgentActivity {
+            headers,
+            status,
+            highest_observed,
+        })
+    }
+
+    #[instrument(skip(self, receipt))]
     /// a remote agent is sending us a validation receipt.
-    async fn handle_validation_receipt(&self, _receipt: SerializedBytes) -> CellResult<()> {
-        unimplemented!()
+    async fn handle_validation_receipt(&self, receipt: SerializedBytes) -> CellResult<()> {
+        let receipt: SignedValidationReceipt = receipt.try_into()?;
+        let SignedValidationReceipt {
+            receipt: content,
+            validator_signature,
+        } = &receipt;
+        if !content
+            .validator
+            .verify_signature(validator_signature, content.clone())
+            .await
+            .map_err(DatabaseError::from)?
+        {
+            warn!(
+                msg = "Dropping validation receipt with a bad signature",
+                ?receipt
+            );
+            return Ok(());
+        }
+
+        let op_hash = receipt.receipt.dht_op_hash.clone();
+        let mut receipts = ValidationReceiptsBuf::new(&self.env)?;
+        receipts.add_if_unique(receipt.clone())?;
+        let authored_dht_ops: KvStore<AuthoredDhtOpsKey, AuthoredDhtOpsValue> =
+            KvStore::new(self.env.get_db(&*AUTHORED_DHT_OPS)?);
+        let writer: OneshotWriter = self.env.clone().into();
+        writer.with_writer(|writer| {
+            receipts.flush_to_txn_ref(writer)?;
+            // Update the receipt count of our authored op so we stop
+            // publishing it once enough validators have it. This happens in
+            // the same transaction as adding the receipt, so receipts arriving
+            // at once can't overwrite each other's count.
+            if let Some(mut authored) = authored_dht_ops.get(&*writer, &op_hash)? {
+                let receipt_count = receipts.count_valid(&*writer, &op_hash)? as u32;
+                if receipt_count != authored.receipt_count {
+                    authored.receipt_count = receipt_count;
+                    authored_dht_ops.put(writer, &op_hash, &authored)?;
+                }
+            }
+            Ok(())
+        })?;
+
+        self.signal_broadcaster()
+            .await
+            .send_system(SystemSignal::ValidationReceiptReceived {
+                cell_id: self.id.clone(),
+                op_hash: receipt.receipt.dht_op_hash,
+                validator: receipt.receipt.validator,
+            });
+        Ok(())
     }
 
     #[instrument(skip(self, dht_arc, since, until))]
@@ -646,7 +858,17 @@ impl Cell {
         // double ? because
         // - ConductorApiResult
         // - ZomeCallInvocationResult
-        Ok(self.call_zome(invocation).await??.try_into()?)
+        let response = self.call_zome(invocation).await??;
+        // The remote agent only sees the response; the callback failing
+        // is this cell's concern
+        if let Some(e) = response.post_commit_error {
+            warn!(
+                msg = "post_commit failed after a remote call",
+                ?zome_name,
+                ?e
+            );
+        }
+        Ok(response.response.try_into()?)
     }
 
     /// Function called by the Conductor
@@ -671,7 +893,38 @@ impl Cell {
             self.holochain_p2p_cell.clone(),
             keystore,
             self.signal_broadcaster().await,
-            arc.clone().into(),
+            arc.clone(),
+            args,
+            self.queue_triggers.produce_dht_ops.clone(),
+        )
+        .await
+        .map_err(Box::new)?)
+    }
+
+    /// Call several zome functions on this cell against a single workspace,
+    /// committing everything they commit or nothing
+    #[instrument(skip(self, invocations))]
+    pub async fn call_zome_batch(
+        &self,
+        invocations: Vec<ZomeCallInvocation>,
+    ) -> CellResult<ZomeCallBatchResult> {
+        // Check if init has run if not run it
+        self.check_or_run_zome_init().await?;
+
+        let arc = self.env();
+        let keystore = arc.keystore().clone();
+        let workspace = CallZomeWorkspace::new(arc.clone().into())?;
+
+        let args = CallZomeBatchWorkflowArgs {
+            ribosome: self.get_ribosome().await?,
+            invocations,
+        };
+        Ok(call_zome_batch_workflow(
+            workspace,
+            self.holochain_p2p_cell.clone(),
+            keystore,
+            self.signal_broadcaster().await,
+            arc.clone(),
             args,
             self.queue_triggers.produce_dht_ops.clone(),
         )
@@ -684,8 +937,6 @@ impl Cell {
         // If not run it
         let env = self.env.clone();
         let keystore = env.keystore().clone();
-        let id = self.id.clone();
-        let conductor_api = self.conductor_api.clone();
         // Create the workspace
         let workspace = CallZomeWorkspace::new(self.env().clone().into())
             .map_err(WorkflowError::from)
@@ -697,15 +948,9 @@ impl Cell {
         }
         trace!("running init");
 
-        // get the dna
-        let dna_file = conductor_api
-            .get_dna(id.dna_hash())
-            .await
-            .ok_or(CellError::DnaMissing)?;
-        let dna_def = dna_file.dna().clone();
-
         // Get the ribosome
-        let ribosome = WasmRibosome::new(dna_file);
+        let ribosome = self.get_ribosome().await?;
+        let dna_def = ribosome.dna_file.dna().clone();
 
         // Run the workflow
         let args = InitializeZomesWorkflowArgs { dna_def, ribosome };
@@ -726,6 +971,28 @@ impl Cell {
         Ok(())
     }
 
+    /// Prepare to close or open this Cell's source chain to migrate the agent
+    /// between DNAs, if every zome's migrate_agent callback passes.
+    /// `dna_hash` is the DNA migrated to when closing and from when opening.
+    /// Nothing is written until the returned migration is flushed.
+    pub async fn prepare_migration(
+        &self,
+        migrate_agent: MigrateAgent,
+        dna_hash: DnaHash,
+    ) -> CellResult<PreparedMigration> {
+        let workspace = MigrateAgentWorkspace::new(self.env.clone().into())
+            .map_err(WorkflowError::from)
+            .map_err(Box::new)?;
+        let args =
+            MigrateAgentWorkflowArgs::new(self.get_ribosome().await?, migrate_agent, dna_hash);
+        Ok(prepare_migration(workspace, args).await.map_err(Box::new)?)
+    }
+
+    /// Produce the DhtOps of a migration header once it has been written
+    pub(crate) fn migration_committed(&self) {
+        self.queue_triggers.produce_dht_ops.clone().trigger();
+    }
+
     /// Delete all data associated with this Cell by deleting the associated
     /// LMDB environment. Completely reverses Cell creation.
     pub async fn destroy(self) -> CellResult<()> {
@@ -739,13 +1006,27 @@ impl Cell {
         Ok(())
     }
 
-    /// Instantiate a Ribosome for use by this Cell's workflows
+    /// Instantiate a Ribosome for use by this Cell's workflows,
+    /// with the entry defs the conductor stored when the DNA was installed
     // TODO: reevaluate once Workflows are fully implemented (after B-01567)
     pub(crate) async fn get_ribosome(&self) -> CellResult<WasmRibosome> {
-        match self.conductor_api.get_dna(self.dna_hash()).await {
-            Some(dna) => Ok(WasmRibosome::new(dna)),
-            None => Err(CellError::DnaMissing),
+        let dna = self
+            .conductor_api
+            .get_dna(self.dna_hash())
+            .await
+            .ok_or(CellError::DnaMissing)?;
+        // Each zome's entry defs are stored at consecutive positions
+        let mut entry_defs = HashMap::new();
+        for (_, zome) in dna.dna().zomes.iter() {
+            for position in 0..=u8::MAX {
+                let key = EntryDefBufferKey::new(zome.clone(), position.into());
+                match self.conductor_api.get_entry_def(&key).await {
+                    Some(entry_def) => entry_defs.insert(key, entry_def),
+                    None => break,
+                };
+            }
         }
+        Ok(WasmRibosome::new(dna).with_entry_defs(entry_defs))
     }
 
     /// Accessor for the LMDB environment backing this Cell
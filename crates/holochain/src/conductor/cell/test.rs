@@ -1,33 +1,48 @@
 use crate::{
-    conductor::manager::spawn_task_manager,
-    core::workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
-    fixt::{DnaFileFixturator, SignatureFixturator},
+    conductor::manager::{spawn_task_manager, TaskManagerRunHandle},
+    core::{
+        state::{
+            element_buf::ElementBuf,
+            metadata::{MetadataBuf, MetadataBufT},
+        },
+        workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
+    },
+    fixt::{CreateLinkFixturator, DnaFileFixturator, SignatureFixturator},
 };
 use ::fixt::prelude::*;
 use holo_hash::HasHash;
 use holochain_p2p::actor::HolochainP2pRefToCell;
-use holochain_state::test_utils::{test_cell_env, TestEnvironment};
+use holochain_state::{
+    buffer::BufferedStore,
+    env::EnvironmentWrite,
+    test_utils::{test_cell_env, TestEnvironment},
+};
 use holochain_types::{
     dht_op::{DhtOp, DhtOpHashed},
+    element::{SignedHeader, SignedHeaderHashed},
     test_utils::{fake_agent_pubkey_2, fake_cell_id},
+    warrant::Warrant,
     HeaderHashed, Timestamp,
 };
-use holochain_zome_types::header;
+use holochain_zome_types::{
+    header,
+    query::{ChainQueryFilter, ChainStatus},
+};
 use std::sync::Arc;
 use tokio::sync;
 
-#[tokio::test(threaded_scheduler)]
-async fn test_cell_handle_publish() {
-    let TestEnvironment {
-        env,
-        tmpdir: _tmpdir,
-    } = test_cell_env();
+/// Run genesis for a cell on this environment and start it
+async fn create_test_cell(
+    env: EnvironmentWrite,
+) -> (
+    super::Cell,
+    sync::broadcast::Sender<()>,
+    TaskManagerRunHandle,
+) {
     let (holochain_p2p, _p2p_evt) = holochain_p2p::spawn_holochain_p2p().await.unwrap();
     let cell_id = fake_cell_id(1);
-    let dna = cell_id.dna_hash().clone();
-    let agent = cell_id.agent_pubkey().clone();
-
-    let holochain_p2p_cell = holochain_p2p.to_cell(dna.clone(), agent.clone());
+    let holochain_p2p_cell =
+        holochain_p2p.to_cell(cell_id.dna_hash().clone(), cell_id.agent_pubkey().clone());
 
     let mut mock_handler = crate::conductor::handle::MockConductorHandleT::new();
     mock_handler
@@ -46,13 +61,27 @@ async fn test_cell_handle_publish() {
     let cell = super::Cell::create(
         cell_id,
         mock_handler,
-        env.clone(),
+        env,
         holochain_p2p_cell,
         add_task_sender,
         stop_tx.clone(),
+        Default::default(),
     )
     .await
     .unwrap();
+    (cell, stop_tx, shutdown)
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_handle_publish() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let cell_id = fake_cell_id(1);
+    let dna = cell_id.dna_hash().clone();
+    let agent = cell_id.agent_pubkey().clone();
+    let (cell, stop_tx, shutdown) = create_test_cell(env).await;
 
     let sig = fixt!(Signature);
     let header = header::Header::Dna(header::Dna {
@@ -81,3 +110,85 @@ async fn test_cell_handle_publish() {
     stop_tx.send(()).unwrap();
     shutdown.await.unwrap();
 }
+
+#[tokio::test(threaded_scheduler)]
+async fn test_cell_handle_get_agent_activity() {
+    let TestEnvironment {
+        env,
+        tmpdir: _tmpdir,
+    } = test_cell_env();
+    let (cell, stop_tx, shutdown) = create_test_cell(env.clone()).await;
+    let agent = fake_agent_pubkey_2();
+    let activity = || {
+        cell.handle_get_agent_activity(agent.clone(), ChainQueryFilter::new(), Default::default())
+            .unwrap()
+    };
+    let activity_header = |header_seq| {
+        let mut header = fixt!(CreateLink);
+        header.author = agent.clone();
+        header.header_seq = header_seq;
+        header::Header::CreateLink(header)
+    };
+    let signed = |header: header::Header| {
+        SignedHeaderHashed::with_presigned(
+            HeaderHashed::from_content_sync(header),
+            fixt!(Signature),
+        )
+    };
+
+    let empty = activity();
+    assert_eq!(empty.status, ChainStatus::Empty);
+    assert_eq!(empty.highest_observed, None);
+
+    // Valid activity is returned
+    let mut element_vault = ElementBuf::vault(env.clone().into(), false).unwrap();
+    let mut meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+    for header_seq in 1..3 {
+        let header = activity_header(header_seq);
+        meta_vault.register_activity(&header).unwrap();
+        element_vault.put(signed(header), None).unwrap();
+    }
+    env.with_commit(|writer| {
+        element_vault.flush_to_txn_ref(writer)?;
+        meta_vault.flush_to_txn_ref(writer)
+    })
+    .unwrap();
+    let valid = activity();
+    assert_eq!(valid.status, ChainStatus::Valid);
+    assert_eq!(valid.highest_observed, Some(2));
+    assert_eq!(valid.headers.len(), 2);
+
+    // Rejected activity makes the chain invalid and is only
+    // counted towards the highest observed sequence number
+    let mut element_rejected = ElementBuf::rejected(env.clone().into()).unwrap();
+    let mut meta_rejected = MetadataBuf::rejected(env.clone().into()).unwrap();
+    let rejected = activity_header(3);
+    meta_rejected.register_activity(&rejected).unwrap();
+    element_rejected.put(signed(rejected), None).unwrap();
+    env.with_commit(|writer| {
+        element_rejected.flush_to_txn_ref(writer)?;
+        meta_rejected.flush_to_txn_ref(writer)
+    })
+    .unwrap();
+    let invalid = activity();
+    assert_eq!(invalid.status, ChainStatus::Invalid);
+    assert_eq!(invalid.highest_observed, Some(3));
+    assert_eq!(invalid.headers, valid.headers);
+
+    // A warrant for a fork takes precedence over the rejection
+    let mut meta_vault = MetadataBuf::vault(env.clone().into()).unwrap();
+    meta_vault
+        .register_warrant(Warrant::chain_fork(
+            SignedHeader(activity_header(2), fixt!(Signature)),
+            SignedHeader(activity_header(2), fixt!(Signature)),
+        ))
+        .unwrap();
+    env.with_commit(|writer| meta_vault.flush_to_txn_ref(writer))
+        .unwrap();
+    let forked = activity();
+    assert_eq!(forked.status, ChainStatus::Forked);
+    assert_eq!(forked.highest_observed, Some(3));
+
+    stop_tx.send(()).unwrap();
+    shutdown.await.unwrap();
+}
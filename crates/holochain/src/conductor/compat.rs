@@ -1,4 +1,5 @@
 use super::{
+    api::AppInterfaceBinding,
     config::{AdminInterfaceConfig, ConductorConfig, DpkiConfig, InterfaceDriver},
     error::ConductorError,
     state::AppInterfaceConfig,
@@ -118,12 +119,12 @@ pub async fn load_conductor_from_legacy_config(
 
     for i in app_interfaces {
         let AppInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver,
             signal_subscriptions: _,
         } = i;
         conductor
             .clone()
-            .add_app_interface(port)
+            .add_app_interface(driver, AppInterfaceBinding::default())
             .await
             .map_err(Box::new)?;
     }
@@ -149,7 +150,7 @@ fn convert_dpki(legacy: legacy::DpkiConfig) -> DpkiConfig {
 
 fn convert_interface_driver(legacy: legacy::InterfaceDriver) -> Option<InterfaceDriver> {
     match legacy {
-        legacy::InterfaceDriver::Websocket { port } => Some(InterfaceDriver::Websocket { port }),
+        legacy::InterfaceDriver::Websocket { port } => Some(InterfaceDriver::websocket(port)),
         _ => None,
     }
 }
@@ -306,7 +307,7 @@ pub mod tests {
         assert_matches!(
             config.admin_interfaces.unwrap()[0],
             AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 2222 },
+                driver: InterfaceDriver::websocket(2222),
             }
         );
         assert!(config.dpki.is_some());
@@ -384,9 +385,12 @@ pub mod tests {
             .returning(|| Ok(vec![]));
         handle
             .expect_add_app_interface()
-            .with(predicate::eq(1111))
+            .with(
+                predicate::eq(InterfaceDriver::websocket(1111)),
+                predicate::eq(AppInterfaceBinding::default()),
+            )
             .times(1)
-            .returning(|port| Ok(port));
+            .returning(|_, _| Ok(1111));
 
         let builder = Conductor::builder().with_mock_handle(handle);
         let _ = load_conductor_from_legacy_config(legacy_config, builder)
@@ -9,8 +9,11 @@
 //! However, there's no reason we can't have multiple Conductors in a single process, simulating multiple
 //! users in a testing environment.
 use super::{
-    api::{CellConductorApi, CellConductorApiT, RealAdminInterfaceApi, RealAppInterfaceApi},
-    config::{AdminInterfaceConfig, InterfaceDriver},
+    api::{
+        AppInterfaceBinding, AppInterfaceToken, CellConductorApi, CellConductorApiT,
+        RealAdminInterfaceApi, RealAppInterfaceApi,
+    },
+    config::{AdminInterfaceConfig, CacheConfig, DurabilityMode, InterfaceDriver},
     dna_store::{DnaDefBuf, DnaStore, RealDnaStore},
     entry_def_store::{get_entry_defs, EntryDefBuf, EntryDefBufferKey},
     error::{ConductorError, CreateAppError},
@@ -18,7 +21,7 @@ use super::{
     interface::{
         error::InterfaceResult,
         websocket::{
-            spawn_admin_interface_task, spawn_app_interface_task, spawn_websocket_listener,
+            spawn_admin_interface_task, spawn_app_interface_task, spawn_interface_listener,
             SIGNAL_BUFFER_SIZE,
         },
         SignalBroadcaster,
@@ -37,8 +40,15 @@ use crate::{
         api::error::ConductorApiResult, cell::Cell, config::ConductorConfig,
         dna_store::MockDnaStore, error::ConductorResult, handle::ConductorHandle,
     },
-    core::signal::Signal,
-    core::state::{source_chain::SourceChainBuf, wasm::WasmBuf},
+    core::signal::{Signal, SystemSignal},
+    core::state::{
+        cache_access,
+        private_entries::PrivateEntryKey,
+        source_chain::{ChainArchive, SourceChainBuf},
+        wasm::WasmBuf,
+        workspace::Workspace,
+    },
+    core::workflow::error::WorkflowError,
 };
 use holochain_keystore::{
     lair_keystore::spawn_lair_keystore, test_keystore::spawn_test_keystore, KeystoreSender,
@@ -48,7 +58,7 @@ use holochain_state::{
     buffer::BufferedStore,
     buffer::{KvStore, KvStoreT},
     db,
-    env::{EnvironmentKind, EnvironmentWrite, ReadManager},
+    env::{restore_environments, EnvironmentKind, EnvironmentStats, EnvironmentWrite, ReadManager},
     exports::SingleStore,
     fresh_reader,
     prelude::*,
@@ -58,14 +68,16 @@ use holochain_types::{
     cell::CellId,
     dna::{wasm::DnaWasmHashed, DnaFile},
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tracing::*;
 
 pub use builder::*;
 use futures::future::{self, TryFutureExt};
-use holo_hash::DnaHash;
+use holo_hash::{AgentPubKey, DnaHash};
+use holochain_zome_types::migrate_agent::MigrateAgent;
 
 #[cfg(test)]
 use super::handle::MockConductorHandleT;
@@ -90,6 +102,13 @@ where
     _state: CellState,
 }
 
+/// The apps an [AppInterfaceToken] grants access to
+struct IssuedAppInterfaceToken {
+    app_ids: Vec<AppId>,
+    /// Revoke the token after the first connection authenticates with it
+    single_use: bool,
+}
+
 pub type StopBroadcaster = tokio::sync::broadcast::Sender<()>;
 pub type StopReceiver = tokio::sync::broadcast::Receiver<()>;
 
@@ -124,6 +143,10 @@ where
     app_interface_signal_broadcasters:
         HashMap<AppInterfaceId, tokio::sync::broadcast::Sender<Signal>>,
 
+    /// Tokens issued for authenticating app interface connections.
+    /// These are intentionally not persisted and do not survive a restart.
+    app_interface_tokens: HashMap<AppInterfaceToken, IssuedAppInterfaceToken>,
+
     /// Channel on which to send info about tasks we want to manage
     managed_task_add_sender: mpsc::Sender<ManagedTaskAdd>,
 
@@ -143,6 +166,12 @@ where
     /// The root environment directory where all environments are created
     root_env_dir: EnvironmentRootPath,
 
+    /// The durability mode for new Cell environments
+    cell_durability: DurabilityMode,
+
+    /// The bounds of each Cell's cache
+    cache_config: CacheConfig,
+
     /// Handle to the network actor.
     holochain_p2p: holochain_p2p::HolochainP2pRef,
 }
@@ -208,7 +237,25 @@ where
             .map(|_| ())
             .unwrap_or_else(|e| {
                 error!(?e, "Couldn't broadcast stop signal to managed tasks!");
-            })
+            });
+        self.close_environments();
+    }
+
+    /// Stop the periodic sync of every environment and flush them to disk,
+    /// so nothing committed is lost regardless of the durability mode
+    fn close_environments(&self) {
+        let envs = std::iter::once(&self.env)
+            .chain(std::iter::once(&self.wasm_env))
+            .chain(self.cells.values().map(|item| item.cell.env()));
+        for env in envs {
+            if let Err(e) = env.close() {
+                error!(?e, path = ?env.path(), "Couldn't flush environment to disk");
+            }
+        }
+        for (cell_id, item) in self.cells.iter() {
+            PrivateEntryKey::forget(cell_id);
+            cache_access::forget(item.cell.env());
+        }
     }
 
     pub(super) fn take_shutdown_handle(&mut self) -> Option<TaskManagerRunHandle> {
@@ -233,18 +280,15 @@ where
             let admin_api = admin_api.clone();
             let stop_tx = stop_tx.clone();
             async move {
-                match driver {
-                    InterfaceDriver::Websocket { port } => {
-                        let listener = spawn_websocket_listener(port).await?;
-                        let port = listener.local_addr().port().unwrap_or(port);
-                        let handle: ManagedTaskHandle = spawn_admin_interface_task(
-                            listener,
-                            admin_api.clone(),
-                            stop_tx.subscribe(),
-                        )?;
-                        InterfaceResult::Ok((port, handle))
-                    }
-                }
+                let port = match &driver {
+                    InterfaceDriver::Websocket { port, .. } => Some(*port),
+                    InterfaceDriver::UnixSocket { .. } => None,
+                };
+                let listener = spawn_interface_listener(driver).await?;
+                let port = port.map(|port| listener.local_addr().port().unwrap_or(port));
+                let handle: ManagedTaskHandle =
+                    spawn_admin_interface_task(listener, admin_api.clone(), stop_tx.subscribe())?;
+                InterfaceResult::Ok((port, handle))
             }
         };
 
@@ -270,7 +314,8 @@ where
 
             // Now that tasks are spawned, register them with the TaskManager
             for (port, handle) in handles {
-                ports.push(port);
+                // unix socket interfaces have no port to record
+                ports.extend(port);
                 self.manage_task(ManagedTaskAdd::new(
                     handle,
                     Box::new(|result| {
@@ -291,17 +336,22 @@ where
 
     pub(super) async fn add_app_interface_via_handle(
         &mut self,
-        port: u16,
+        driver: InterfaceDriver,
+        binding: AppInterfaceBinding,
         handle: ConductorHandle,
     ) -> ConductorResult<u16> {
-        let interface_id: AppInterfaceId = format!("interface-{}", port).into();
-        let app_api = RealAppInterfaceApi::new(handle, interface_id.clone());
+        let interface_id: AppInterfaceId = match &driver {
+            InterfaceDriver::Websocket { port, .. } => format!("interface-{}", port),
+            InterfaceDriver::UnixSocket { path } => format!("interface-{}", path.display()),
+        }
+        .into();
+        let app_api = RealAppInterfaceApi::new(handle, interface_id.clone()).with_binding(binding);
         // This receiver is thrown away because we can produce infinite new
         // receivers from the Sender
         let (signal_broadcaster, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
         let stop_rx = self.managed_task_stop_broadcaster.subscribe();
         let (port, task) =
-            spawn_app_interface_task(port, app_api, signal_broadcaster.clone(), stop_rx)
+            spawn_app_interface_task(driver, app_api, signal_broadcaster.clone(), stop_rx)
                 .await
                 .map_err(Box::new)?;
         // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
@@ -311,6 +361,37 @@ where
         Ok(port)
     }
 
+    pub(super) fn issue_app_interface_token(
+        &mut self,
+        app_ids: Vec<AppId>,
+        single_use: bool,
+    ) -> AppInterfaceToken {
+        let token = AppInterfaceToken::random();
+        self.app_interface_tokens.insert(
+            token.clone(),
+            IssuedAppInterfaceToken {
+                app_ids,
+                single_use,
+            },
+        );
+        token
+    }
+
+    pub(super) fn authenticate_app_interface_token(
+        &mut self,
+        token: &AppInterfaceToken,
+    ) -> ConductorResult<Vec<AppId>> {
+        let issued = self
+            .app_interface_tokens
+            .get(token)
+            .ok_or(ConductorError::AppInterfaceTokenInvalid)?;
+        let app_ids = issued.app_ids.clone();
+        if issued.single_use {
+            self.app_interface_tokens.remove(token);
+        }
+        Ok(app_ids)
+    }
+
     pub(super) fn signal_broadcaster(&self) -> SignalBroadcaster {
         SignalBroadcaster::new(
             self.app_interface_signal_broadcasters
@@ -320,17 +401,36 @@ where
         )
     }
 
+    /// Send a system signal across every attached app interface.
+    /// Peers are only known of when they join through this conductor,
+    /// since every network space is currently local to the conductor.
+    pub(super) fn send_system_signal(&self, signal: SystemSignal) {
+        self.signal_broadcaster().send_system(signal)
+    }
+
     /// Perform Genesis on the source chains for each of the specified CellIds.
     ///
     /// If genesis fails for any cell, this entire function fails, and all other
     /// partial or complete successes are rolled back.
+    /// Remove the environment of a Cell which was never added to an app
+    pub(super) async fn remove_cell_env(&self, cell_id: CellId) -> ConductorResult<()> {
+        let env = EnvironmentWrite::new(
+            &std::path::PathBuf::from(self.root_env_dir.clone()),
+            EnvironmentKind::Cell(cell_id),
+            self.keystore.clone(),
+        )?;
+        env.remove().await?;
+        Ok(())
+    }
+
     pub(super) async fn genesis_cells(
         &self,
         cell_ids_with_proofs: Vec<(CellId, Option<MembraneProof>)>,
         conductor_handle: ConductorHandle,
     ) -> ConductorResult<()> {
         let root_env_dir = std::path::PathBuf::from(self.root_env_dir.clone());
-        let keystore = self.keystore.clone();
+
+        let cell_durability = self.cell_durability;
 
         let cells_tasks = cell_ids_with_proofs.into_iter().map(|(cell_id, proof)| {
             let root_env_dir = root_env_dir.clone();
@@ -338,10 +438,11 @@ where
             let conductor_handle = conductor_handle.clone();
             let cell_id_inner = cell_id.clone();
             tokio::spawn(async move {
-                let env = EnvironmentWrite::new(
+                let env = EnvironmentWrite::new_with_durability(
                     &root_env_dir,
                     EnvironmentKind::Cell(cell_id_inner.clone()),
                     keystore.clone(),
+                    cell_durability,
                 )?;
                 Cell::genesis(cell_id_inner, conductor_handle, env, proof).await
             })
@@ -359,12 +460,7 @@ where
         // If there were errors, cleanup and return the errors
         if !errors.is_empty() {
             for cell_id in success {
-                let env = EnvironmentWrite::new(
-                    &root_env_dir,
-                    EnvironmentKind::Cell(cell_id),
-                    keystore.clone(),
-                )?;
-                env.remove().await?;
+                self.remove_cell_env(cell_id).await?;
             }
 
             // match needed to avoid Debug requirement on unwrap_err
@@ -430,10 +526,11 @@ where
                                     cell_id.agent_pubkey().clone(),
                                 );
 
-                                let env = EnvironmentWrite::new_cell(
+                                let env = EnvironmentWrite::new_with_durability(
                                     &dir,
-                                    cell_id.clone(),
+                                    EnvironmentKind::Cell(cell_id.clone()),
                                     keystore.clone(),
+                                    self.cell_durability,
                                 )?;
                                 Cell::create(
                                     cell_id.clone(),
@@ -442,6 +539,7 @@ where
                                     holochain_p2p_cell,
                                     self.managed_task_add_sender.clone(),
                                     self.managed_task_stop_broadcaster.clone(),
+                                    self.cache_config,
                                 )
                                 .await
                             },
@@ -495,13 +593,47 @@ where
     ) -> ConductorResult<()> {
         trace!(?app);
         self.update_state(move |mut state| {
-            state.inactive_apps.insert(app.app_id, app.cell_data);
+            state
+                .inactive_apps
+                .insert(app.app_id.clone(), app.cell_data.clone());
             Ok(state)
         })
         .await?;
         Ok(())
     }
 
+    /// Add a cloned cell to an installed app in the database,
+    /// returning whether the app is active
+    pub(super) async fn add_clone_cell_to_app_in_db(
+        &mut self,
+        app_id: AppId,
+        cell: InstalledCell,
+    ) -> ConductorResult<bool> {
+        let state = self
+            .update_state({
+                let app_id = app_id.clone();
+                move |mut state| {
+                    let cell_data = match state.active_apps.get_mut(&app_id) {
+                        Some(cell_data) => cell_data,
+                        None => state
+                            .inactive_apps
+                            .get_mut(&app_id)
+                            .ok_or(ConductorError::AppNotInstalled)?,
+                    };
+                    if cell_data.iter().any(|c| c.as_id() == cell.as_id()) {
+                        return Err(ConductorError::CellAlreadyInApp(
+                            cell.as_id().clone(),
+                            app_id.clone(),
+                        ));
+                    }
+                    cell_data.push(cell.clone());
+                    Ok(state)
+                }
+            })
+            .await?;
+        Ok(state.active_apps.contains_key(&app_id))
+    }
+
     /// Activate an app in the database
     pub(super) async fn activate_app_in_db(&mut self, app_id: AppId) -> ConductorResult<()> {
         self.update_state(move |mut state| {
@@ -509,7 +641,7 @@ where
                 .inactive_apps
                 .remove(&app_id)
                 .ok_or(ConductorError::AppNotInstalled)?;
-            state.active_apps.insert(app_id, cell_data);
+            state.active_apps.insert(app_id.clone(), cell_data);
             Ok(state)
         })
         .await?;
@@ -529,7 +661,7 @@ where
                         .active_apps
                         .remove(&app_id)
                         .ok_or(ConductorError::AppNotActive)?;
-                    state.inactive_apps.insert(app_id, cell_ids);
+                    state.inactive_apps.insert(app_id.clone(), cell_ids);
                     Ok(state)
                 }
             })
@@ -547,16 +679,38 @@ where
     /// Add fully constructed cells to the cell map in the Conductor
     pub(super) fn add_cells(&mut self, cells: Vec<Cell>) {
         for cell in cells {
+            let cell_id = cell.id().clone();
             self.cells.insert(
-                cell.id().clone(),
+                cell_id.clone(),
                 CellItem {
                     cell,
                     _state: CellState { _active: false },
                 },
             );
+            self.send_system_signal(SystemSignal::CellActivated(cell_id.clone()));
+            for peer_id in self.local_peer_cell_ids(&cell_id) {
+                self.send_system_signal(SystemSignal::LocalPeerJoined {
+                    cell_id: peer_id.clone(),
+                    agent: cell_id.agent_pubkey().clone(),
+                });
+                self.send_system_signal(SystemSignal::LocalPeerJoined {
+                    cell_id: cell_id.clone(),
+                    agent: peer_id.agent_pubkey().clone(),
+                });
+            }
         }
     }
 
+    /// The other cells on this conductor which share a network space with this one.
+    /// Remote peers are not known here.
+    fn local_peer_cell_ids(&self, cell_id: &CellId) -> Vec<CellId> {
+        self.cells
+            .keys()
+            .filter(|id| id.dna_hash() == cell_id.dna_hash() && *id != cell_id)
+            .cloned()
+            .collect()
+    }
+
     pub(super) fn initialize_cell_workflows(&mut self) {
         for cell in self.cells.values_mut() {
             cell.cell.initialize_workflows();
@@ -610,7 +764,23 @@ where
     /// Remove cells from the cell map in the Conductor
     pub(super) fn remove_cells(&mut self, cell_ids: Vec<CellId>) {
         for cell_id in cell_ids {
-            self.cells.remove(&cell_id);
+            let item = match self.cells.remove(&cell_id) {
+                Some(item) => item,
+                None => continue,
+            };
+            // Opening the cell again resumes the periodic sync
+            if let Err(e) = item.cell.env().close() {
+                error!(?e, ?cell_id, "Couldn't flush environment to disk");
+            }
+            PrivateEntryKey::forget(&cell_id);
+            cache_access::forget(item.cell.env());
+            self.send_system_signal(SystemSignal::CellDeactivated(cell_id.clone()));
+            for peer_id in self.local_peer_cell_ids(&cell_id) {
+                self.send_system_signal(SystemSignal::LocalPeerLeft {
+                    cell_id: peer_id,
+                    agent: cell_id.agent_pubkey().clone(),
+                });
+            }
         }
     }
 
@@ -642,17 +812,14 @@ where
         if dna_def_buf.get(dna.dna_hash()).await?.is_none() {
             dna_def_buf.put(dna.dna().clone()).await?;
         }
-        {
-            let env = environ.guard();
-            // write the wasm db
-            env.with_commit(|writer| wasm_buf.flush_to_txn(writer))?;
+        // write the wasm db
+        environ.with_commit(|writer| wasm_buf.flush_to_txn_ref(writer))?;
 
-            // write the dna_def db
-            env.with_commit(|writer| dna_def_buf.flush_to_txn(writer))?;
+        // write the dna_def db
+        environ.with_commit(|writer| dna_def_buf.flush_to_txn_ref(writer))?;
 
-            // write the entry_def db
-            env.with_commit(|writer| entry_def_buf.flush_to_txn(writer))?;
-        }
+        // write the entry_def db
+        environ.with_commit(|writer| entry_def_buf.flush_to_txn_ref(writer))?;
         Ok(zome_defs)
     }
 
@@ -667,6 +834,199 @@ where
         Ok(source_chain.dump_as_json().await?)
     }
 
+    /// Migrate an agent from one DNA to another by closing their chain on
+    /// `from_dna` and opening it on `to_dna`. Both Cells must be running.
+    ///
+    /// The callbacks of both DNAs run and both headers are validated before
+    /// anything is written. One header is then committed from within the
+    /// transaction writing the other, so if it can't be written the other is
+    /// rolled back and nothing changes. If the outer transaction fails after
+    /// the inner one was committed, [ConductorError::MigrationIncomplete] says
+    /// which chain was written.
+    pub(super) async fn migrate_agent(
+        &self,
+        agent_key: AgentPubKey,
+        from_dna: DnaHash,
+        to_dna: DnaHash,
+    ) -> ConductorResult<()> {
+        if from_dna == to_dna {
+            return Err(ConductorError::MigrateToSameDna(from_dna));
+        }
+        // Both Cells belong to the same agent, so this orders them by CellId.
+        // Write locks are always taken in that order, so two migrations in
+        // opposite directions can't each hold the lock the other is waiting for.
+        let close_first = from_dna < to_dna;
+        let from_cell = self.cell_by_id(&CellId::new(from_dna.clone(), agent_key.clone()))?;
+        let to_cell = self.cell_by_id(&CellId::new(to_dna.clone(), agent_key))?;
+        let mut close = from_cell
+            .prepare_migration(MigrateAgent::Close, to_dna)
+            .await?;
+        let mut open = to_cell
+            .prepare_migration(MigrateAgent::Open, from_dna)
+            .await?;
+        let (outer_cell, inner_cell) = if close_first {
+            (from_cell, to_cell)
+        } else {
+            (to_cell, from_cell)
+        };
+        // The outer transaction may be retried if its map has to grow,
+        // but the inner one must only be written once
+        let mut inner_written = false;
+        outer_cell
+            .env()
+            .with_commit::<ConductorError, _, _>(|outer_writer| {
+                let (outer, inner) = if close_first {
+                    (&mut close, &mut open)
+                } else {
+                    (&mut open, &mut close)
+                };
+                outer
+                    .flush_to_txn_ref(outer_writer)
+                    .map_err(WorkflowError::from)?;
+                if !inner_written {
+                    inner_cell
+                        .env()
+                        .with_commit::<ConductorError, _, _>(|inner_writer| {
+                            Ok(inner
+                                .flush_to_txn_ref(inner_writer)
+                                .map_err(WorkflowError::from)?)
+                        })?;
+                    inner_written = true;
+                }
+                Ok(())
+            })
+            .map_err(|e| {
+                if inner_written {
+                    ConductorError::MigrationIncomplete(
+                        inner_cell.id().clone(),
+                        outer_cell.id().clone(),
+                        Box::new(e),
+                    )
+                } else {
+                    e
+                }
+            })?;
+        from_cell.migration_committed();
+        to_cell.migration_committed();
+        Ok(())
+    }
+
+    /// Write a consistent snapshot of an installed Cell's environment
+    /// into `backup_dir`
+    pub(super) async fn backup_cell(
+        &self,
+        cell_id: &CellId,
+        backup_dir: PathBuf,
+    ) -> ConductorResult<()> {
+        let state = self.get_state().await?;
+        let env = self.installed_cell_env(cell_id, &state)?;
+        backup_environments(vec![env], backup_dir).await
+    }
+
+    /// Write the full authored source chain of an installed Cell,
+    /// including private entries, to an archive file at `path`
+    pub(super) async fn export_source_chain(
+        &self,
+        cell_id: &CellId,
+        path: PathBuf,
+    ) -> ConductorResult<()> {
+        let state = self.get_state().await?;
+        let env = self.installed_cell_env(cell_id, &state)?;
+        PrivateEntryKey::for_env(&env).await?;
+        let archive = SourceChainBuf::new(env.into())?.export_archive()?;
+        tokio::fs::write(path, archive.to_bytes()?).await?;
+        Ok(())
+    }
+
+    /// Verify a source chain archive and write it into the environment of
+    /// the Cell it was exported from. The Cell's DNA must be installed and
+    /// its source chain must be empty, so the chain should be imported before
+    /// installing an app with the archive's agent key; genesis is then
+    /// skipped for that Cell.
+    /// The agent key must be in this conductor's keystore, because the
+    /// imported private entries are encrypted with a key derived from it.
+    /// Nothing is written if it is missing.
+    pub(super) async fn import_source_chain(&self, path: PathBuf) -> ConductorResult<CellId> {
+        let archive = ChainArchive::from_bytes(&tokio::fs::read(path).await?)?;
+        let cell_id = CellId::new(archive.dna_hash.clone(), archive.agent_pubkey.clone());
+        if self.dna_store.get(cell_id.dna_hash()).is_none() {
+            return Err(ConductorError::DnaMissing(cell_id.dna_hash().clone()));
+        }
+        PrivateEntryKey::for_cell(&cell_id, &self.keystore)
+            .await
+            .map_err(|e| {
+                ConductorError::ImportAgentKeyMissing(cell_id.agent_pubkey().clone(), e)
+            })?;
+        let env = EnvironmentWrite::new_with_durability(
+            &PathBuf::from(self.root_env_dir.clone()),
+            EnvironmentKind::Cell(cell_id.clone()),
+            self.keystore.clone(),
+            self.cell_durability,
+        )?;
+        PrivateEntryKey::for_env(&env).await?;
+        let mut source_chain = SourceChainBuf::new(env.clone().into())?;
+        source_chain.import_archive(archive).await?;
+        env.with_commit(|writer| source_chain.flush_to_txn_ref(writer))?;
+        Ok(cell_id)
+    }
+
+    /// Write a consistent snapshot of the conductor and wasm environments
+    /// and the environment of every installed Cell into `backup_dir`
+    pub(super) async fn backup_conductor(&self, backup_dir: PathBuf) -> ConductorResult<()> {
+        let state = self.get_state().await?;
+        let cell_ids: HashSet<&CellId> = state
+            .active_apps
+            .values()
+            .chain(state.inactive_apps.values())
+            .flatten()
+            .map(|c| c.as_id())
+            .collect();
+        let mut envs = vec![self.env.clone(), self.wasm_env.clone()];
+        for cell_id in cell_ids {
+            envs.push(self.installed_cell_env(cell_id, &state)?);
+        }
+        backup_environments(envs, backup_dir).await
+    }
+
+    /// The environment of an installed Cell, opening it if the Cell
+    /// is not running
+    fn installed_cell_env(
+        &self,
+        cell_id: &CellId,
+        state: &ConductorState,
+    ) -> ConductorResult<EnvironmentWrite> {
+        if let Some(item) = self.cells.get(cell_id) {
+            return Ok(item.cell.env().clone());
+        }
+        let installed = state
+            .active_apps
+            .values()
+            .chain(state.inactive_apps.values())
+            .flatten()
+            .any(|c| c.as_id() == cell_id);
+        if !installed {
+            return Err(ConductorError::CellMissing(cell_id.clone()));
+        }
+        Ok(EnvironmentWrite::new_with_durability(
+            &PathBuf::from(self.root_env_dir.clone()),
+            EnvironmentKind::Cell(cell_id.clone()),
+            self.keystore.clone(),
+            self.cell_durability,
+        )?)
+    }
+
+    /// Size and usage of the conductor, wasm and every cell environment,
+    /// keyed by "conductor", "wasm" or the CellId
+    pub(super) fn environment_stats(&self) -> ConductorResult<HashMap<String, EnvironmentStats>> {
+        let mut stats = HashMap::new();
+        stats.insert("conductor".to_string(), self.env.stats()?);
+        stats.insert("wasm".to_string(), self.wasm_env.stats()?);
+        for (cell_id, item) in self.cells.iter() {
+            stats.insert(cell_id.to_string(), item.cell.env().stats()?);
+        }
+        Ok(stats)
+    }
+
     #[cfg(test)]
     pub(super) async fn get_state_from_handle(&self) -> ConductorResult<ConductorState> {
         self.get_state().await
@@ -687,6 +1047,8 @@ where
         dna_store: DS,
         keystore: KeystoreSender,
         root_env_dir: EnvironmentRootPath,
+        cell_durability: DurabilityMode,
+        cache_config: CacheConfig,
         holochain_p2p: holochain_p2p::HolochainP2pRef,
     ) -> ConductorResult<Self> {
         let db: SingleStore = env.get_db(&db::CONDUCTOR_STATE)?;
@@ -700,6 +1062,7 @@ where
             cells: HashMap::new(),
             shutting_down: false,
             app_interface_signal_broadcasters: HashMap::new(),
+            app_interface_tokens: HashMap::new(),
             managed_task_add_sender: task_tx,
             managed_task_stop_broadcaster: stop_tx,
             task_manager_run_handle,
@@ -707,6 +1070,8 @@ where
             dna_store,
             keystore,
             root_env_dir,
+            cell_durability,
+            cache_config,
             holochain_p2p,
         })
     }
@@ -717,13 +1082,14 @@ where
         Ok(self.state_db.get(&reader, &UnitDbKey)?.unwrap_or_default())
     }
 
-    async fn update_state<F: Send>(&self, f: F) -> ConductorResult<ConductorState>
+    /// Update the state in one transaction. The closure is run again if the
+    /// transaction had to be retried, so it must not move out of its captures.
+    async fn update_state<F: Send>(&self, mut f: F) -> ConductorResult<ConductorState>
     where
-        F: FnOnce(ConductorState) -> ConductorResult<ConductorState>,
+        F: FnMut(ConductorState) -> ConductorResult<ConductorState>,
     {
         self.check_running()?;
-        let guard = self.env.guard();
-        let new_state = guard.with_commit(|txn| {
+        let new_state = self.env.with_commit(|txn| {
             let state: ConductorState = self.state_db.get(txn, &UnitDbKey)?.unwrap_or_default();
             let new_state = f(state)?;
             self.state_db.put(txn, &UnitDbKey, &new_state)?;
@@ -760,6 +1126,7 @@ mod builder {
         config: ConductorConfig,
         dna_store: DS,
         keystore: Option<KeystoreSender>,
+        restore_from: Option<PathBuf>,
         #[cfg(test)]
         state: Option<ConductorState>,
         #[cfg(test)]
@@ -793,6 +1160,14 @@ mod builder {
             self
         }
 
+        /// Before opening any environment, restore those missing from the
+        /// environment path from a backup written by the `BackupConductor`
+        /// or `BackupCell` admin requests
+        pub fn restore_from(mut self, backup_dir: PathBuf) -> Self {
+            self.restore_from = Some(backup_dir);
+            self
+        }
+
         /// Initialize a "production" Conductor
         pub async fn build(self) -> ConductorResult<ConductorHandle> {
             cfg_if::cfg_if! {
@@ -824,14 +1199,26 @@ mod builder {
             };
             let env_path = self.config.environment_path.clone();
 
-            let environment = EnvironmentWrite::new(
+            if let Some(backup_dir) = &self.restore_from {
+                let restored = restore_environments(backup_dir, env_path.as_ref())?;
+                info!(?backup_dir, ?restored, "Restored environments from backup");
+            }
+
+            let durability = self.config.durability;
+
+            let environment = EnvironmentWrite::new_with_durability(
                 env_path.as_ref(),
                 EnvironmentKind::Conductor,
                 keystore.clone(),
+                durability.conductor,
             )?;
 
-            let wasm_environment =
-                EnvironmentWrite::new(env_path.as_ref(), EnvironmentKind::Wasm, keystore.clone())?;
+            let wasm_environment = EnvironmentWrite::new_with_durability(
+                env_path.as_ref(),
+                EnvironmentKind::Wasm,
+                keystore.clone(),
+                durability.wasm,
+            )?;
 
             #[cfg(test)]
             let state = self.state;
@@ -848,6 +1235,8 @@ mod builder {
                 dna_store,
                 keystore,
                 env_path,
+                durability.cell,
+                config.cache,
                 holochain_p2p,
             )
             .await?;
@@ -924,7 +1313,7 @@ mod builder {
             conductor: Conductor<DS>,
         ) -> ConductorResult<Conductor<DS>> {
             if let Some(state) = state {
-                conductor.update_state(move |_| Ok(state)).await?;
+                conductor.update_state(move |_| Ok(state.clone())).await?;
             }
             Ok(conductor)
         }
@@ -947,6 +1336,8 @@ mod builder {
                 self.dna_store,
                 keystore,
                 tmpdir.path().to_path_buf().into(),
+                self.config.durability.cell,
+                self.config.cache,
                 holochain_p2p,
             )
             .await?;
@@ -959,6 +1350,22 @@ mod builder {
     }
 }
 
+/// Back up environments on a blocking thread, since doing so copies
+/// their data files
+async fn backup_environments(
+    envs: Vec<EnvironmentWrite>,
+    backup_dir: PathBuf,
+) -> ConductorResult<()> {
+    tokio::task::spawn_blocking(move || {
+        for env in envs {
+            let dest = env.backup_into(&backup_dir)?;
+            debug!(?dest, "Backed up environment");
+        }
+        ConductorResult::Ok(())
+    })
+    .await?
+}
+
 async fn p2p_event_task(
     mut p2p_evt: holochain_p2p::event::HolochainP2pEventReceiver,
     handle: ConductorHandle,
@@ -1003,6 +1410,8 @@ pub mod tests {
             dna_store,
             keystore,
             tmpdir.path().to_path_buf().into(),
+            DurabilityMode::default(),
+            CacheConfig::default(),
             holochain_p2p,
         )
         .await
@@ -1017,7 +1426,7 @@ pub mod tests {
             .update_state(|mut state| {
                 state
                     .inactive_apps
-                    .insert("fake app".to_string(), vec![installed_cell]);
+                    .insert("fake app".to_string(), vec![installed_cell.clone()]);
                 Ok(state)
             })
             .await
@@ -4,7 +4,9 @@
 use serde::{Deserialize, Serialize};
 
 mod admin_interface_config;
+mod cache_config;
 mod dpki_config;
+mod durability_config;
 mod network_config;
 mod passphrase_service_config;
 //mod logger_config;
@@ -14,9 +16,11 @@ use super::{
     paths::EnvironmentRootPath,
 };
 
-pub use crate::conductor::interface::InterfaceDriver;
+pub use crate::conductor::interface::{InterfaceDriver, InterfaceTlsConfig, WebsocketOptions};
 pub use admin_interface_config::AdminInterfaceConfig;
+pub use cache_config::CacheConfig;
 pub use dpki_config::DpkiConfig;
+pub use durability_config::{DurabilityConfig, DurabilityMode};
 //pub use logger_config::LoggerConfig;
 pub use network_config::NetworkConfig;
 pub use passphrase_service_config::PassphraseServiceConfig;
@@ -71,6 +75,16 @@ pub struct ConductorConfig {
 
     /// Setup admin interfaces to control this conductor through a websocket connection
     pub admin_interfaces: Option<Vec<AdminInterfaceConfig>>,
+
+    /// How durably each kind of LMDB environment writes to disk.
+    /// If omitted, all environments write asynchronously.
+    #[serde(default)]
+    pub durability: DurabilityConfig,
+
+    /// How much network data each Cell keeps in its cache.
+    /// If omitted, the cache is bounded to 256 MiB and one week per Cell.
+    #[serde(default)]
+    pub cache: CacheConfig,
     //
     //
     // /// Which signals to emit
@@ -149,6 +163,8 @@ pub mod tests {
                 passphrase_service: Some(PassphraseServiceConfig::Cmd),
                 admin_interfaces: None,
                 use_dangerous_test_keystore: false,
+                durability: DurabilityConfig::default(),
+                cache: CacheConfig::default(),
             }
         );
     }
@@ -178,6 +194,15 @@ pub mod tests {
     driver.type = "websocket"
     driver.port = 1234
 
+    [durability]
+    conductor.type = "sync"
+    cell.type = "periodic"
+    cell.interval_ms = 500
+
+    [cache]
+    max_bytes = 1048576
+    prune_interval_secs = 60
+
     "#;
         let result: ConductorResult<ConductorConfig> = config_from_toml(toml);
         assert_eq!(
@@ -196,9 +221,19 @@ pub mod tests {
                 }),
                 passphrase_service: Some(PassphraseServiceConfig::Cmd),
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::websocket(1234)
                 }]),
                 use_dangerous_test_keystore: true,
+                durability: DurabilityConfig {
+                    conductor: DurabilityMode::Sync,
+                    wasm: DurabilityMode::Async,
+                    cell: DurabilityMode::Periodic { interval_ms: 500 },
+                },
+                cache: CacheConfig {
+                    max_bytes: 1048576,
+                    max_age_secs: 7 * 24 * 60 * 60,
+                    prune_interval_secs: 60,
+                },
             }
         );
     }
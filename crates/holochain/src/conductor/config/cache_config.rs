@@ -0,0 +1,52 @@
+use serde::{self, Deserialize, Serialize};
+use std::time::Duration;
+
+/// How much of the network data cached by each Cell is kept.
+///
+/// The cache is pruned periodically. Items are first dropped once they have
+/// gone unused for `max_age_secs`, then least recently used first until the
+/// cache fits in `max_bytes`. An item which is never used counts as used by
+/// the first prune pass which sees it. Authored and integrated data is never pruned.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct CacheConfig {
+    /// The most bytes of keys and values each Cell's cache may hold.
+    /// If omitted, defaults to 256 MiB.
+    #[serde(default = "default_max_bytes")]
+    pub max_bytes: usize,
+    /// How long an unused item is kept in the cache, in seconds.
+    /// If omitted, defaults to one week.
+    #[serde(default = "default_max_age_secs")]
+    pub max_age_secs: u64,
+    /// How often each Cell's cache is pruned, in seconds
+    #[serde(default = "default_prune_interval_secs")]
+    pub prune_interval_secs: u64,
+}
+
+fn default_max_bytes() -> usize {
+    256 * 1024 * 1024
+}
+
+fn default_max_age_secs() -> u64 {
+    7 * 24 * 60 * 60
+}
+
+fn default_prune_interval_secs() -> u64 {
+    5 * 60
+}
+
+impl CacheConfig {
+    /// The time between prune passes
+    pub fn prune_interval(&self) -> Duration {
+        Duration::from_secs(self.prune_interval_secs)
+    }
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: default_max_bytes(),
+            max_age_secs: default_max_age_secs(),
+            prune_interval_secs: default_prune_interval_secs(),
+        }
+    }
+}
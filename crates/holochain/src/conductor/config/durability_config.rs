@@ -0,0 +1,19 @@
+use serde::{self, Deserialize, Serialize};
+
+pub use holochain_state::env::DurabilityMode;
+
+/// The [DurabilityMode] of each kind of LMDB environment.
+/// Every kind defaults to `async`, which is fastest but can lose
+/// recent commits in a crash.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct DurabilityConfig {
+    /// For the environment holding the conductor's own state
+    #[serde(default)]
+    pub conductor: DurabilityMode,
+    /// For the environment holding wasm and DNA definitions
+    #[serde(default)]
+    pub wasm: DurabilityMode,
+    /// For the environment of each Cell
+    #[serde(default)]
+    pub cell: DurabilityMode,
+}
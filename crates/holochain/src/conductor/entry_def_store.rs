@@ -221,14 +221,16 @@ mod tests {
         let post_def = EntryDef {
             id: "post".into(),
             visibility: EntryVisibility::Public,
-            crdt_type: CrdtType,
+            crdt_type: CrdtType::GrowOnlySet,
             required_validations: 5.into(),
+            max_size: None,
         };
         let comment_def = EntryDef {
             id: "comment".into(),
             visibility: EntryVisibility::Private,
-            crdt_type: CrdtType,
+            crdt_type: CrdtType::GrowOnlySet,
             required_validations: 5.into(),
+            max_size: None,
         };
         let dna_wasm = DnaWasmHashed::from_content(TestWasm::EntryDefs.into())
             .await
@@ -1,7 +1,14 @@
 use super::{entry_def_store::error::EntryDefStoreError, interface::error::InterfaceError};
-use crate::{conductor::cell::error::CellError, core::workflow::error::WorkflowError};
+use crate::{
+    conductor::cell::error::CellError,
+    core::{state::source_chain::SourceChainError, workflow::error::WorkflowError},
+};
+use holo_hash::{AgentPubKey, DnaHash};
 use holochain_state::error::DatabaseError;
-use holochain_types::{app::AppId, cell::CellId};
+use holochain_types::{
+    app::{AppId, CellNick},
+    cell::CellId,
+};
 use std::path::PathBuf;
 use thiserror::Error;
 
@@ -48,6 +55,9 @@ pub enum ConductorError {
     #[error("Error while performing IO for the Conductor: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error(transparent)]
+    JoinError(#[from] tokio::task::JoinError),
+
     #[error("Error while trying to send a task to the task manager: {0}")]
     SubmitTaskError(String),
 
@@ -79,6 +89,30 @@ pub enum ConductorError {
     #[error("Tried to deactivate an app that was not active")]
     AppNotActive,
 
+    #[error("The app {0} has no cell with the nick {1}")]
+    CellNickMissing(AppId, CellNick),
+
+    #[error("Dna was referenced, but is missing from the conductor. DnaHash: {0}")]
+    DnaMissing(DnaHash),
+
+    #[error("Cloning a cell requires a new uuid or new properties, otherwise the clone is the same cell")]
+    CloneCellUnchanged,
+
+    #[error("The cell {0:?} is already part of the app {1}")]
+    CellAlreadyInApp(CellId, AppId),
+
+    #[error("The app interface token is invalid or has already been used")]
+    AppInterfaceTokenInvalid,
+
+    #[error("An agent can't migrate from a DNA to itself: {0}")]
+    MigrateToSameDna(DnaHash),
+
+    #[error("A migration was written to the chain of {0:?} but not to the chain of {1:?}, so the agent is only half migrated: {2}")]
+    MigrationIncomplete(CellId, CellId, Box<ConductorError>),
+
+    #[error("The agent key {0} of an imported source chain must be in this conductor's keystore to encrypt its private entries: {1}")]
+    ImportAgentKeyMissing(AgentPubKey, DatabaseError),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -87,6 +121,9 @@ pub enum ConductorError {
 
     #[error(transparent)]
     KeystoreError(#[from] holochain_keystore::KeystoreError),
+
+    #[error(transparent)]
+    SourceChainError(#[from] SourceChainError),
 }
 
 #[derive(Error, Debug)]
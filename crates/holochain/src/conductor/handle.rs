@@ -46,25 +46,32 @@
 //! code which interacted with the Conductor would also have to be highly generic.
 
 use super::{
-    api::error::ConductorApiResult,
-    config::AdminInterfaceConfig,
+    api::{
+        error::{ConductorApiError, ConductorApiResult},
+        AppInterfaceBinding, AppInterfaceToken,
+    },
+    config::{AdminInterfaceConfig, InterfaceDriver},
     dna_store::DnaStore,
     entry_def_store::EntryDefBufferKey,
-    error::{ConductorResult, CreateAppError},
+    error::{ConductorError, ConductorResult, CreateAppError},
     interface::SignalBroadcaster,
     manager::TaskManagerRunHandle,
     Cell, Conductor,
 };
 use crate::core::ribosome::ZomeCallInvocation;
-use crate::core::workflow::ZomeCallInvocationResult;
+use crate::core::signal::SystemSignal;
+use crate::core::workflow::{ZomeCallBatchResponse, ZomeCallBatchResult, ZomeCallInvocationResult};
 use derive_more::From;
+use holochain_state::env::EnvironmentStats;
 use holochain_types::{
-    app::{AppId, InstalledApp, InstalledCell, MembraneProof},
+    app::{AppId, CreateCloneCellPayload, InstalledApp, InstalledCell, MembraneProof},
     autonomic::AutonomicCue,
     cell::CellId,
     dna::DnaFile,
     prelude::*,
 };
+use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use tracing::*;
@@ -100,8 +107,28 @@ pub trait ConductorHandleT: Send + Sync {
         configs: Vec<AdminInterfaceConfig>,
     ) -> ConductorResult<()>;
 
-    /// Add an app interface
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16>;
+    /// Add an app interface, returning the port it is listening on,
+    /// or 0 for unix socket interfaces
+    async fn add_app_interface(
+        self: Arc<Self>,
+        driver: InterfaceDriver,
+        binding: AppInterfaceBinding,
+    ) -> ConductorResult<u16>;
+
+    /// Issue a token which an app interface connection can authenticate
+    /// with to access the given apps
+    async fn issue_app_interface_token(
+        &self,
+        app_ids: Vec<AppId>,
+        single_use: bool,
+    ) -> ConductorResult<AppInterfaceToken>;
+
+    /// Check a token presented by an app interface connection and return
+    /// the apps it grants access to. Single use tokens are revoked here.
+    async fn authenticate_app_interface_token(
+        &self,
+        token: &AppInterfaceToken,
+    ) -> ConductorResult<Vec<AppId>>;
 
     /// Install a [Dna] in this Conductor
     async fn install_dna(&self, dna: DnaFile) -> ConductorResult<()>;
@@ -131,6 +158,12 @@ pub trait ConductorHandleT: Send + Sync {
         invocation: ZomeCallInvocation,
     ) -> ConductorApiResult<ZomeCallInvocationResult>;
 
+    /// Invoke several zome functions on one Cell, committing all or nothing
+    async fn call_zome_batch(
+        &self,
+        invocations: Vec<ZomeCallInvocation>,
+    ) -> ConductorApiResult<ZomeCallBatchResult>;
+
     /// Cue the autonomic system to perform some action early (experimental)
     async fn autonomic_cue(&self, cue: AutonomicCue, cell_id: &CellId) -> ConductorApiResult<()>;
 
@@ -162,6 +195,14 @@ pub trait ConductorHandleT: Send + Sync {
         cell_data_with_proofs: Vec<(InstalledCell, Option<MembraneProof>)>,
     ) -> ConductorResult<()>;
 
+    /// Clone a Cell of an installed App with a new uuid and/or properties,
+    /// run genesis on the new source chain and add the new Cell to the App.
+    /// If the App is active the new Cell is created straight away.
+    async fn create_clone_cell(
+        self: Arc<Self>,
+        payload: CreateCloneCellPayload,
+    ) -> ConductorResult<InstalledCell>;
+
     /// Setup the cells from the database
     /// Only creates any cells that are not already created
     async fn setup_cells(self: Arc<Self>) -> ConductorResult<Vec<CreateAppError>>;
@@ -181,6 +222,31 @@ pub trait ConductorHandleT: Send + Sync {
     #[allow(clippy::ptr_arg)]
     async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String>;
 
+    /// Size and usage of each LMDB environment,
+    /// keyed by "conductor", "wasm" or the CellId
+    async fn environment_stats(&self) -> ConductorResult<HashMap<String, EnvironmentStats>>;
+
+    /// Write a Cell's full authored source chain to an archive file
+    async fn export_source_chain(&self, cell_id: &CellId, path: PathBuf) -> ConductorResult<()>;
+
+    /// Verify a source chain archive and write it into the empty source
+    /// chain of the Cell it was exported from
+    async fn import_source_chain(&self, path: PathBuf) -> ConductorResult<CellId>;
+
+    /// Close an agent's chain on one DNA and open it on another
+    async fn migrate_agent(
+        &self,
+        agent_key: AgentPubKey,
+        from_dna: DnaHash,
+        to_dna: DnaHash,
+    ) -> ConductorResult<()>;
+
+    /// Write a consistent snapshot of a Cell's environment into `backup_dir`
+    async fn backup_cell(&self, cell_id: &CellId, backup_dir: PathBuf) -> ConductorResult<()>;
+
+    /// Write a consistent snapshot of every environment into `backup_dir`
+    async fn backup_conductor(&self, backup_dir: PathBuf) -> ConductorResult<()>;
+
     /// Access the broadcast Sender which will send a Signal across every
     /// attached app interface
     async fn signal_broadcaster(&self) -> SignalBroadcaster;
@@ -231,9 +297,40 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .await
     }
 
-    async fn add_app_interface(self: Arc<Self>, port: u16) -> ConductorResult<u16> {
+    async fn add_app_interface(
+        self: Arc<Self>,
+        driver: InterfaceDriver,
+        binding: AppInterfaceBinding,
+    ) -> ConductorResult<u16> {
+        let mut lock = self.conductor.write().await;
+        lock.add_app_interface_via_handle(driver, binding, self.clone())
+            .await
+    }
+
+    async fn issue_app_interface_token(
+        &self,
+        app_ids: Vec<AppId>,
+        single_use: bool,
+    ) -> ConductorResult<AppInterfaceToken> {
         let mut lock = self.conductor.write().await;
-        lock.add_app_interface_via_handle(port, self.clone()).await
+        let state = lock.get_state().await?;
+        if app_ids
+            .iter()
+            .any(|app_id| state.get_app_info(app_id).is_none())
+        {
+            return Err(ConductorError::AppNotInstalled);
+        }
+        Ok(lock.issue_app_interface_token(app_ids, single_use))
+    }
+
+    async fn authenticate_app_interface_token(
+        &self,
+        token: &AppInterfaceToken,
+    ) -> ConductorResult<Vec<AppId>> {
+        self.conductor
+            .write()
+            .await
+            .authenticate_app_interface_token(token)
     }
 
     async fn install_dna(&self, dna: DnaFile) -> ConductorResult<()> {
@@ -295,6 +392,32 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         Ok(cell.call_zome(invocation).await?)
     }
 
+    async fn call_zome_batch(
+        &self,
+        invocations: Vec<ZomeCallInvocation>,
+    ) -> ConductorApiResult<ZomeCallBatchResult> {
+        let cell_id = match invocations.first() {
+            Some(invocation) => invocation.cell_id.clone(),
+            None => {
+                return Ok(Ok(ZomeCallBatchResponse {
+                    responses: Vec::new(),
+                    post_commit_error: None,
+                }))
+            }
+        };
+        // The calls share a workspace so they must all be on the same cell
+        if let Some(invocation) = invocations.iter().find(|i| i.cell_id != cell_id) {
+            return Err(ConductorApiError::ZomeCallBatchCellMismatch {
+                batch_cell_id: cell_id,
+                invocation_cell_id: invocation.cell_id.clone(),
+            });
+        }
+        let lock = self.conductor.read().await;
+        debug!(cell_id = ?cell_id);
+        let cell: &Cell = lock.cell_by_id(&cell_id)?;
+        Ok(cell.call_zome_batch(invocations).await?)
+    }
+
     async fn autonomic_cue(&self, cue: AutonomicCue, cell_id: &CellId) -> ConductorApiResult<()> {
         let lock = self.conductor.write().await;
         let cell = lock.cell_by_id(cell_id)?;
@@ -353,6 +476,95 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
             .await
     }
 
+    async fn create_clone_cell(
+        self: Arc<Self>,
+        payload: CreateCloneCellPayload,
+    ) -> ConductorResult<InstalledCell> {
+        if payload.uuid.is_none() && payload.properties.is_none() {
+            return Err(ConductorError::CloneCellUnchanged);
+        }
+        let app = self
+            .get_app_info(&payload.app_id)
+            .await?
+            .ok_or(ConductorError::AppNotInstalled)?;
+        let (base_cell_id, _) = app
+            .cell_data
+            .iter()
+            .find(|c| c.as_nick() == &payload.cell_nick)
+            .cloned()
+            .ok_or_else(|| {
+                ConductorError::CellNickMissing(payload.app_id.clone(), payload.cell_nick.clone())
+            })?
+            .into_inner();
+        let clone_index = app
+            .cell_data
+            .iter()
+            .filter_map(|c| payload.clone_index(c.as_nick()))
+            .max()
+            .map_or(0, |i| i + 1);
+
+        // Derive the new Dna from the installed one
+        let mut dna = self
+            .get_dna(base_cell_id.dna_hash())
+            .await
+            .ok_or_else(|| ConductorError::DnaMissing(base_cell_id.dna_hash().clone()))?;
+        if let Some(uuid) = payload.uuid.clone() {
+            dna = dna.with_uuid(uuid).await?;
+        }
+        if let Some(properties) = payload.properties.clone() {
+            dna = dna
+                .with_properties(SerializedBytes::try_from(properties)?)
+                .await?;
+        }
+        let cell_id = CellId::new(dna.dna_hash().clone(), base_cell_id.agent_pubkey().clone());
+        // Check before anything is installed, so a duplicate leaves nothing behind
+        if app.cell_data.iter().any(|c| c.as_id() == &cell_id) {
+            return Err(ConductorError::CellAlreadyInApp(cell_id, payload.app_id));
+        }
+        let cell = InstalledCell::new(cell_id.clone(), payload.clone_nick(clone_index));
+        self.install_dna(dna).await?;
+
+        // Call genesis
+        self.conductor
+            .read()
+            .await
+            .genesis_cells(
+                vec![(cell_id, payload.membrane_proof.clone())],
+                self.clone(),
+            )
+            .await?;
+
+        // Update the db
+        let added = self
+            .conductor
+            .write()
+            .await
+            .add_clone_cell_to_app_in_db(payload.app_id.clone(), cell.clone())
+            .await;
+        let is_active = match added {
+            Ok(is_active) => is_active,
+            // The same clone was added since the check above,
+            // and the environment belongs to it
+            Err(e @ ConductorError::CellAlreadyInApp(..)) => return Err(e),
+            Err(e) => {
+                self.conductor
+                    .read()
+                    .await
+                    .remove_cell_env(cell.into_id())
+                    .await?;
+                return Err(e);
+            }
+        };
+
+        // Create the cell if the app is already running
+        if is_active {
+            if let Some(error) = self.clone().setup_cells().await?.into_iter().next() {
+                return Err(error.into());
+            }
+        }
+        Ok(cell)
+    }
+
     async fn setup_cells(self: Arc<Self>) -> ConductorResult<Vec<CreateAppError>> {
         let cells = {
             let lock = self.conductor.read().await;
@@ -382,24 +594,17 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
     }
 
     async fn activate_app(&self, app_id: AppId) -> ConductorResult<()> {
-        self.conductor
-            .write()
-            .await
-            .activate_app_in_db(app_id)
-            .await
+        let mut lock = self.conductor.write().await;
+        lock.activate_app_in_db(app_id.clone()).await?;
+        lock.send_system_signal(SystemSignal::AppActivated(app_id));
+        Ok(())
     }
 
     async fn deactivate_app(&self, app_id: AppId) -> ConductorResult<()> {
-        let cell_ids_to_remove = self
-            .conductor
-            .write()
-            .await
-            .deactivate_app_in_db(app_id)
-            .await?;
-        self.conductor
-            .write()
-            .await
-            .remove_cells(cell_ids_to_remove);
+        let mut lock = self.conductor.write().await;
+        let cell_ids_to_remove = lock.deactivate_app_in_db(app_id.clone()).await?;
+        lock.remove_cells(cell_ids_to_remove);
+        lock.send_system_signal(SystemSignal::AppDeactivated(app_id));
         Ok(())
     }
 
@@ -411,6 +616,51 @@ impl<DS: DnaStore + 'static> ConductorHandleT for ConductorHandleImpl<DS> {
         self.conductor.read().await.dump_cell_state(cell_id).await
     }
 
+    async fn environment_stats(&self) -> ConductorResult<HashMap<String, EnvironmentStats>> {
+        self.conductor.read().await.environment_stats()
+    }
+
+    async fn export_source_chain(&self, cell_id: &CellId, path: PathBuf) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .export_source_chain(cell_id, path)
+            .await
+    }
+
+    async fn import_source_chain(&self, path: PathBuf) -> ConductorResult<CellId> {
+        self.conductor.read().await.import_source_chain(path).await
+    }
+
+    async fn migrate_agent(
+        &self,
+        agent_key: AgentPubKey,
+        from_dna: DnaHash,
+        to_dna: DnaHash,
+    ) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .migrate_agent(agent_key, from_dna, to_dna)
+            .await
+    }
+
+    async fn backup_cell(&self, cell_id: &CellId, backup_dir: PathBuf) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .backup_cell(cell_id, backup_dir)
+            .await
+    }
+
+    async fn backup_conductor(&self, backup_dir: PathBuf) -> ConductorResult<()> {
+        self.conductor
+            .read()
+            .await
+            .backup_conductor(backup_dir)
+            .await
+    }
+
     async fn signal_broadcaster(&self) -> SignalBroadcaster {
         self.conductor.read().await.signal_broadcaster()
     }
@@ -2,13 +2,19 @@
 //! and dispatch them to the appropriate handlers within Holochain.
 //! They also allow emitting responses and one-way Signals.
 //!
-//! Currently the only InterfaceDriver is a Websocket-based one, whose
-//! implementation can be found in the `websocket` module here.
+//! Both InterfaceDrivers speak the Websocket protocol, over either TCP or a
+//! unix domain socket. The implementation can be found in the `websocket`
+//! module here.
 
-use crate::{conductor::api::*, core::signal::Signal};
+use crate::{
+    conductor::api::*,
+    core::signal::{Signal, SystemSignal},
+};
 use error::{InterfaceError, InterfaceResult};
 use serde::{Deserialize, Serialize};
 use std::convert::TryInto;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::PathBuf;
 use tokio::sync::broadcast;
 
 #[allow(missing_docs)]
@@ -31,6 +37,16 @@ impl SignalBroadcaster {
         Ok(())
     }
 
+    /// send a system signal to the connected clients.
+    /// System signals are best effort, so a failure to send,
+    /// i.e. because nobody is listening, is not an error.
+    pub fn send_system(&mut self, signal: SystemSignal) {
+        let sig = Signal::from(signal);
+        for tx in self.0.iter_mut() {
+            tx.send(sig.clone()).ok();
+        }
+    }
+
     /// internal constructor
     pub fn new(senders: Vec<broadcast::Sender<Signal>>) -> Self {
         Self(senders)
@@ -56,5 +72,64 @@ pub enum InterfaceDriver {
     Websocket {
         /// The port on which to establish the WebsocketListener
         port: u16,
+        /// How the WebsocketListener is exposed beyond its port
+        #[serde(default)]
+        options: WebsocketOptions,
     },
+    /// An interface speaking the same Websocket protocol over a
+    /// unix domain socket. Access is controlled by the filesystem
+    /// permissions of the socket file and its directory.
+    UnixSocket {
+        /// The path at which to create the socket file
+        path: PathBuf,
+    },
+}
+
+impl InterfaceDriver {
+    /// A plain "ws" interface on localhost
+    pub fn websocket(port: u16) -> Self {
+        InterfaceDriver::Websocket {
+            port,
+            options: WebsocketOptions::default(),
+        }
+    }
+}
+
+/// Options for exposing a websocket interface
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct WebsocketOptions {
+    /// The address to bind to. [default = 127.0.0.1]
+    #[serde(default = "default_bind_address")]
+    pub bind_address: IpAddr,
+    /// Serve the interface as "wss" with this certificate and key. [default = None]
+    #[serde(default)]
+    pub tls: Option<InterfaceTlsConfig>,
+    /// Only accept browser connections from these origins.
+    /// Clients which send no Origin header are always accepted.
+    /// [default = None, accept any origin]
+    #[serde(default)]
+    pub allowed_origins: Option<Vec<String>>,
+}
+
+impl Default for WebsocketOptions {
+    fn default() -> Self {
+        Self {
+            bind_address: default_bind_address(),
+            tls: None,
+            allowed_origins: None,
+        }
+    }
+}
+
+fn default_bind_address() -> IpAddr {
+    Ipv4Addr::LOCALHOST.into()
+}
+
+/// Files used to serve an interface over TLS
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
+pub struct InterfaceTlsConfig {
+    /// Path to the PEM encoded certificate chain
+    pub cert_path: PathBuf,
+    /// Path to the PEM encoded PKCS #8 private key
+    pub key_path: PathBuf,
 }
@@ -1,5 +1,6 @@
 //! Module for establishing Websocket-based Interfaces,
 //! i.e. those configured with `InterfaceDriver::Websocket`
+//! or `InterfaceDriver::UnixSocket`
 
 use super::error::{InterfaceError, InterfaceResult};
 use crate::conductor::{
@@ -9,11 +10,15 @@ use crate::conductor::{
 };
 use crate::core::signal::Signal;
 use holochain_serialized_bytes::SerializedBytes;
+#[cfg(unix)]
+use holochain_websocket::websocket_bind_unix;
 use holochain_websocket::{
-    websocket_bind, WebsocketConfig, WebsocketListener, WebsocketMessage, WebsocketReceiver,
-    WebsocketSender,
+    websocket_bind, TlsIdentity, WebsocketConfig, WebsocketListener, WebsocketMessage,
+    WebsocketReceiver, WebsocketSender,
 };
 use std::convert::TryFrom;
+use std::net::SocketAddr;
+use std::path::Path;
 
 use std::sync::Arc;
 use tokio::stream::StreamExt;
@@ -28,17 +33,72 @@ use url2::url2;
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 
 /// Create a WebsocketListener to be used in interfaces
-pub async fn spawn_websocket_listener(port: u16) -> InterfaceResult<WebsocketListener> {
+pub async fn spawn_websocket_listener(
+    port: u16,
+    options: WebsocketOptions,
+) -> InterfaceResult<WebsocketListener> {
     trace!("Initializing Admin interface");
+    let WebsocketOptions {
+        bind_address,
+        tls,
+        allowed_origins,
+    } = options;
+    let mut config = WebsocketConfig::default();
+    if let Some(InterfaceTlsConfig {
+        cert_path,
+        key_path,
+    }) = tls
+    {
+        config = config.tls_identity(TlsIdentity {
+            cert_pem: tokio::fs::read(cert_path).await?,
+            key_pem: tokio::fs::read(key_path).await?,
+        });
+    }
+    if let Some(allowed_origins) = allowed_origins {
+        config = config.allowed_origins(allowed_origins);
+    }
     let listener = websocket_bind(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+        url2!(
+            "{}://{}",
+            config.scheme,
+            SocketAddr::new(bind_address, port)
+        ),
+        Arc::new(config),
     )
     .await?;
     trace!("LISTENING AT: {}", listener.local_addr());
     Ok(listener)
 }
 
+/// Create a WebsocketListener listening on a unix domain socket
+#[cfg(unix)]
+pub async fn spawn_unix_socket_listener(path: &Path) -> InterfaceResult<WebsocketListener> {
+    let listener = websocket_bind_unix(path, Arc::new(WebsocketConfig::default())).await?;
+    trace!("LISTENING AT: {}", listener.local_addr());
+    Ok(listener)
+}
+
+/// Unix domain sockets are only supported on unix
+#[cfg(not(unix))]
+pub async fn spawn_unix_socket_listener(path: &Path) -> InterfaceResult<WebsocketListener> {
+    Err(InterfaceError::Other(format!(
+        "Unix socket interfaces are not supported on this platform: {}",
+        path.display()
+    )))
+}
+
+/// Create the WebsocketListener for any kind of interface
+pub async fn spawn_interface_listener(
+    driver: InterfaceDriver,
+) -> InterfaceResult<WebsocketListener> {
+    match driver {
+        InterfaceDriver::Websocket { port, options } => {
+            spawn_websocket_listener(port, options).await
+        }
+        InterfaceDriver::UnixSocket { path } => spawn_unix_socket_listener(&path).await,
+    }
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
 /// from the external client
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
@@ -60,7 +120,7 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
                         Ok((tx_to_iface, rx_from_iface)) => {
                             send_sockets.push(tx_to_iface);
                             listener_handles.push(tokio::task::spawn(recv_incoming_admin_msgs(
-                                api.clone(),
+                                api.new_connection(),
                                 rx_from_iface,
                             )));
                         }
@@ -98,24 +158,25 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
+/// from Cells via a broadcast channel.
+/// Returns the port the interface is listening on, or 0 for unix socket interfaces.
 pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
+    driver: InterfaceDriver,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
 ) -> InterfaceResult<(u16, ManagedTaskHandle)> {
     trace!("Initializing App interface");
-    let mut listener = websocket_bind(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
-    trace!("LISTENING AT: {}", listener.local_addr());
-    let port = listener
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let is_unix_socket = matches!(driver, InterfaceDriver::UnixSocket { .. });
+    let mut listener = spawn_interface_listener(driver).await?;
+    let port = if is_unix_socket {
+        0
+    } else {
+        listener
+            .local_addr()
+            .port()
+            .ok_or(InterfaceError::PortError)?
+    };
     let task = tokio::task::spawn(async move {
         let mut listener_handles = Vec::new();
 
@@ -123,7 +184,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
             |tx_to_iface: WebsocketSender, rx_from_iface: WebsocketReceiver| {
                 let rx_from_cell = signal_broadcaster.subscribe();
                 listener_handles.push(tokio::task::spawn(recv_incoming_msgs_and_outgoing_signals(
-                    api.clone(),
+                    api.new_connection(),
                     rx_from_iface,
                     rx_from_cell,
                     tx_to_iface,
@@ -198,10 +259,11 @@ async fn recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
             // tx and rx together in a new spawned task
             signal = rx_from_cell.next() => {
                 if let Some(signal) = signal {
-                    let bytes = SerializedBytes::try_from(
-                        signal.map_err(InterfaceError::SignalReceive)?,
-                    )?;
-                    tx_to_iface.signal(bytes).await?;
+                    let signal = signal.map_err(InterfaceError::SignalReceive)?;
+                    if api.accepts_signal(&signal).await {
+                        let bytes = SerializedBytes::try_from(signal)?;
+                        tx_to_iface.signal(bytes).await?;
+                    }
                 } else {
                     debug!("Closing interface: signal stream empty");
                     break;
@@ -250,6 +312,7 @@ pub mod test {
         state::ConductorState,
         Conductor, ConductorHandle,
     };
+    use crate::core::signal::SystemSignal;
     use crate::core::state::source_chain::SourceChainBuf;
     use crate::fixt::WasmRibosomeFixturator;
     use futures::future::FutureExt;
@@ -460,6 +523,7 @@ pub mod test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(1)
             .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
 
         let (_tmpdir, app_api, handle) = setup_app(vec![(installed_cell, None)], dna_store).await;
         let mut request = Box::new(
@@ -493,6 +557,90 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn app_interface_call_zome_batch() {
+        use crate::conductor::api::AppInterfaceApi;
+        use crate::core::ribosome::ZomeCallInvocation;
+        use holochain_types::test_utils::fake_agent_pubkey_2;
+        observability::test_run().ok();
+        let dna = fake_dna_zomes(
+            &Uuid::new_v4().to_string(),
+            vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+        );
+        let alice_cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let bob_cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_2()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
+        let (_tmpdir, app_api, handle) = setup_app(
+            vec![
+                (
+                    InstalledCell::new(alice_cell_id.clone(), "alice".into()),
+                    None,
+                ),
+                (InstalledCell::new(bob_cell_id.clone(), "bob".into()), None),
+            ],
+            dna_store,
+        )
+        .await;
+
+        let foo = |cell_id: &CellId, provenance| ZomeCallInvocation {
+            cell_id: cell_id.clone(),
+            zome_name: TestWasm::Foo.into(),
+            cap: None,
+            fn_name: "foo".into(),
+            payload: ExternInput::new(().try_into().unwrap()),
+            provenance,
+        };
+
+        let response = app_api
+            .handle_app_request(AppRequest::ZomeCallBatch(Vec::new()))
+            .await;
+        assert_matches!(
+            response,
+            AppResponse::ZomeCallBatch { outputs, post_commit_error: None } if outputs.is_empty()
+        );
+
+        let response = app_api
+            .handle_app_request(AppRequest::ZomeCallBatch(vec![
+                foo(&alice_cell_id, fake_agent_pubkey_1()),
+                foo(&alice_cell_id, fake_agent_pubkey_1()),
+            ]))
+            .await;
+        assert_matches!(
+            response,
+            AppResponse::ZomeCallBatch { outputs, post_commit_error: None } if outputs.len() == 2
+        );
+
+        // Every call of a batch must be on the same cell
+        let response = app_api
+            .handle_app_request(AppRequest::ZomeCallBatch(vec![
+                foo(&alice_cell_id, fake_agent_pubkey_1()),
+                foo(&bob_cell_id, fake_agent_pubkey_2()),
+            ]))
+            .await;
+        assert_matches!(
+            response,
+            AppResponse::Error(ExternalApiWireError::InternalError(e)) if e.contains("same Cell")
+        );
+
+        // Bob holds no grant for alice's cell
+        let response = app_api
+            .handle_app_request(AppRequest::ZomeCallBatch(vec![
+                foo(&alice_cell_id, fake_agent_pubkey_1()),
+                foo(&alice_cell_id, fake_agent_pubkey_2()),
+            ]))
+            .await;
+        assert_matches!(response, AppResponse::ZomeCallUnauthorized);
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn activate_app() {
         observability::test_run().ok();
@@ -524,6 +672,7 @@ pub mod test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(1)
             .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
         let (_tmpdir, conductor_handle) =
             setup_admin_fake_cells(cell_ids_with_proofs, dna_store).await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
@@ -615,11 +764,16 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            binding: Default::default(),
+            options: Default::default(),
+            unix_socket: None,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
-            assert_matches!(response, AdminResponse::AppInterfaceAttached{ .. });
+            assert_matches!(response, AdminResponse::AppInterfaceAttached { .. });
             async { Ok(()) }.boxed()
         };
         let respond = Box::new(respond);
@@ -629,6 +783,380 @@ pub mod test {
         shutdown.await.unwrap();
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn app_interface_requires_token() {
+        observability::test_run().ok();
+        let uuid = Uuid::new_v4();
+        let dna = fake_dna_zomes(
+            &uuid.to_string(),
+            vec![("zomey".into(), TestWasm::Foo.into())],
+        );
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
+
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id, None)], dna_store).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let app_api = RealAppInterfaceApi::new(conductor_handle.clone(), "test-interface".into())
+            .with_binding(AppInterfaceBinding {
+                app_ids: Some(vec!["test app".to_string()]),
+                require_token: true,
+            });
+        let app_info = |app_id: &str| AppRequest::AppInfo {
+            app_id: app_id.to_string(),
+        };
+
+        // Requests before authenticating are rejected
+        let connection = app_api.new_connection();
+        let response = connection.handle_app_request(app_info("test app")).await;
+        assert_matches!(
+            response,
+            AppResponse::Error(ExternalApiWireError::Unauthorized(_))
+        );
+
+        let token = conductor_handle
+            .issue_app_interface_token(vec!["test app".to_string()], true)
+            .await
+            .unwrap();
+        let response = connection
+            .handle_app_request(AppRequest::Authenticate {
+                token: token.clone(),
+            })
+            .await;
+        assert_matches!(response, AppResponse::Authenticated);
+        let response = connection.handle_app_request(app_info("test app")).await;
+        assert_matches!(response, AppResponse::AppInfo(Some(_)));
+
+        // Apps outside of the token are rejected
+        let response = connection.handle_app_request(app_info("other app")).await;
+        assert_matches!(
+            response,
+            AppResponse::Error(ExternalApiWireError::Unauthorized(_))
+        );
+
+        // A single use token can't authenticate a second connection
+        let response = app_api
+            .new_connection()
+            .handle_app_request(AppRequest::Authenticate { token })
+            .await;
+        assert_matches!(
+            response,
+            AppResponse::Error(ExternalApiWireError::Unauthorized(_))
+        );
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn app_lifecycle_emits_system_signals() {
+        observability::test_run().ok();
+        let dna = fake_dna_file(&Uuid::new_v4().to_string());
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+
+        let port = conductor_handle
+            .clone()
+            .add_app_interface(InterfaceDriver::websocket(0), Default::default())
+            .await
+            .unwrap();
+        let (mut client, mut rx) = holochain_websocket::websocket_connect(
+            url2!("ws://127.0.0.1:{}", port),
+            Arc::new(WebsocketConfig::default()),
+        )
+        .await
+        .unwrap();
+        // Once a request is answered the connection is subscribed to signals
+        let _: AppResponse = client
+            .request(AppRequest::AppInfo {
+                app_id: "test app".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let conductor_handle = activate(conductor_handle).await;
+        conductor_handle
+            .deactivate_app("test app".to_string())
+            .await
+            .unwrap();
+
+        let mut signals = Vec::new();
+        while signals.len() < 4 {
+            match rx.next().await {
+                Some(WebsocketMessage::Signal(bytes)) => {
+                    signals.push(Signal::try_from(bytes).unwrap())
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert_eq!(
+            signals,
+            vec![
+                SystemSignal::AppActivated("test app".to_string()).into(),
+                SystemSignal::CellActivated(cell_id.clone()).into(),
+                SystemSignal::CellDeactivated(cell_id).into(),
+                SystemSignal::AppDeactivated("test app".to_string()).into(),
+            ]
+        );
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn signal_subscription_filters_signals() {
+        use crate::conductor::api::{SignalFilter, SignalFilterSet, SignalSubscription};
+        use crate::core::signal::SignalKind;
+        observability::test_run().ok();
+        let dna = fake_dna_file(&Uuid::new_v4().to_string());
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().returning(move |_| Some(dna.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(1)
+            .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+
+        let port = conductor_handle
+            .clone()
+            .add_app_interface(InterfaceDriver::websocket(0), Default::default())
+            .await
+            .unwrap();
+        let (mut client, mut rx) = holochain_websocket::websocket_connect(
+            url2!("ws://127.0.0.1:{}", port),
+            Arc::new(WebsocketConfig::default()),
+        )
+        .await
+        .unwrap();
+        let mut filters = HashMap::new();
+        filters.insert(
+            cell_id.clone(),
+            SignalFilter {
+                kinds: Some(vec![SignalKind::CellActivated, SignalKind::CellDeactivated]),
+                ..Default::default()
+            },
+        );
+        let response: AppResponse = client
+            .request(AppRequest::SignalSubscription(SignalSubscription {
+                app_id: "test app".to_string(),
+                filters: SignalFilterSet::Exclude(filters),
+            }))
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+        let conductor_handle = activate(conductor_handle).await;
+        conductor_handle
+            .deactivate_app("test app".to_string())
+            .await
+            .unwrap();
+
+        let mut signals = Vec::new();
+        while signals.len() < 2 {
+            match rx.next().await {
+                Some(WebsocketMessage::Signal(bytes)) => {
+                    signals.push(Signal::try_from(bytes).unwrap())
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert_eq!(
+            signals,
+            vec![
+                SystemSignal::AppActivated("test app".to_string()).into(),
+                SystemSignal::AppDeactivated("test app".to_string()).into(),
+            ]
+        );
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn signal_subscription_governs_cells_added_later() {
+        use crate::conductor::api::{SignalFilterSet, SignalSubscription};
+        use holochain_types::{
+            app::CreateCloneCellPayload,
+            test_utils::{fake_agent_pubkey_2, write_fake_dna_file},
+        };
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+        let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
+        let mut dna_tmpdirs = Vec::new();
+        for (app_id, agent_key) in vec![
+            ("test app", fake_agent_pubkey_1()),
+            ("other app", fake_agent_pubkey_2()),
+        ] {
+            let dna = fake_dna_zomes(
+                &Uuid::new_v4().to_string(),
+                vec![(TestWasm::Foo.into(), TestWasm::Foo.into())],
+            );
+            let (dna_path, dna_tmpdir) = write_fake_dna_file(dna).await.unwrap();
+            dna_tmpdirs.push(dna_tmpdir);
+            let payload = InstallAppPayload {
+                dnas: vec![InstallAppDnaPayload::path_only(
+                    dna_path,
+                    "room".to_string(),
+                )],
+                app_id: app_id.to_string(),
+                agent_key,
+            };
+            let response = admin_api
+                .handle_admin_request(AdminRequest::InstallApp(Box::new(payload)))
+                .await;
+            assert_matches!(response, AdminResponse::AppInstalled(_));
+        }
+        let conductor_handle = activate(conductor_handle).await;
+
+        let port = conductor_handle
+            .clone()
+            .add_app_interface(InterfaceDriver::websocket(0), Default::default())
+            .await
+            .unwrap();
+        let (mut client, mut rx) = holochain_websocket::websocket_connect(
+            url2!("ws://127.0.0.1:{}", port),
+            Arc::new(WebsocketConfig::default()),
+        )
+        .await
+        .unwrap();
+        let response: AppResponse = client
+            .request(AppRequest::SignalSubscription(SignalSubscription {
+                app_id: "test app".to_string(),
+                filters: SignalFilterSet::block_all(),
+            }))
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::SignalSubscriptionUpdated);
+
+        // The clone joins the app after the subscription, so its
+        // activation is blocked all the same
+        let response = admin_api
+            .handle_admin_request(AdminRequest::CreateCloneCell(Box::new(
+                CreateCloneCellPayload {
+                    app_id: "test app".to_string(),
+                    cell_nick: "room".to_string(),
+                    uuid: Some(Uuid::new_v4().to_string()),
+                    properties: None,
+                    membrane_proof: None,
+                },
+            )))
+            .await;
+        assert_matches!(response, AdminResponse::CloneCellCreated(_));
+
+        conductor_handle
+            .activate_app("other app".to_string())
+            .await
+            .unwrap();
+        assert!(conductor_handle
+            .clone()
+            .setup_cells()
+            .await
+            .unwrap()
+            .is_empty());
+        let other_cell_id = conductor_handle
+            .get_app_info(&"other app".to_string())
+            .await
+            .unwrap()
+            .unwrap()
+            .cell_data[0]
+            .as_id()
+            .clone();
+
+        let mut signals = Vec::new();
+        while signals.len() < 2 {
+            match rx.next().await {
+                Some(WebsocketMessage::Signal(bytes)) => {
+                    signals.push(Signal::try_from(bytes).unwrap())
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+        assert!(signals.contains(&SystemSignal::AppActivated("other app".to_string()).into()));
+        assert!(signals.contains(&SystemSignal::CellActivated(other_cell_id).into()));
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn app_interface_serves_tls() {
+        observability::test_run().ok();
+        let (_tmpdir, conductor_handle) = setup_admin().await;
+        let shutdown = conductor_handle.take_shutdown_handle().await.unwrap();
+
+        let cert_dir = TempDir::new("holochain-test-tls").unwrap();
+        let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_pem = cert.serialize_pem().unwrap();
+        let tls = InterfaceTlsConfig {
+            cert_path: cert_dir.path().join("cert.pem"),
+            key_path: cert_dir.path().join("key.pem"),
+        };
+        std::fs::write(&tls.cert_path, &cert_pem).unwrap();
+        std::fs::write(&tls.key_path, cert.serialize_private_key_pem()).unwrap();
+
+        let driver = InterfaceDriver::Websocket {
+            port: 0,
+            options: WebsocketOptions {
+                tls: Some(tls),
+                ..Default::default()
+            },
+        };
+        let port = conductor_handle
+            .clone()
+            .add_app_interface(driver, Default::default())
+            .await
+            .unwrap();
+        let (mut client, _rx) = holochain_websocket::websocket_connect(
+            url2!("wss://localhost:{}", port),
+            Arc::new(WebsocketConfig::default().tls_root_certificate(cert_pem.into_bytes())),
+        )
+        .await
+        .unwrap();
+        let response: AppResponse = client
+            .request(AppRequest::AppInfo {
+                app_id: "test app".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_matches!(response, AppResponse::AppInfo(None));
+
+        conductor_handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn dump_state() {
         observability::test_run().ok();
@@ -649,6 +1177,7 @@ pub mod test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(1)
             .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
 
         let (_tmpdir, conductor_handle) =
             setup_admin_fake_cells(vec![(cell_id.clone(), None)], dna_store).await;
@@ -24,15 +24,15 @@
 //!
 //! Implicitly, every workflow also writes to its own source queue, i.e. to
 //! remove the item it has just processed.
+//!
+//! The one consumer outside this table prunes the cache databases. It works
+//! on an interval rather than being triggered.
 
 use std::sync::{Arc, Once};
 
 use derive_more::{Constructor, Display, From};
 use futures::future::Either;
-use holochain_state::{
-    env::{EnvironmentWrite, WriteManager},
-    prelude::Writer,
-};
+use holochain_state::{env::EnvironmentWrite, prelude::Writer};
 use tokio::sync::{self, mpsc};
 
 // TODO: move these to workflow mod
@@ -44,10 +44,12 @@ mod app_validation_consumer;
 use app_validation_consumer::*;
 mod produce_dht_ops_consumer;
 use produce_dht_ops_consumer::*;
+mod prune_cache_consumer;
 mod publish_dht_ops_consumer;
 use super::state::workspace::WorkspaceError;
-use crate::conductor::{api::CellConductorApiT, manager::ManagedTaskAdd};
+use crate::conductor::{api::CellConductorApiT, config::CacheConfig, manager::ManagedTaskAdd};
 use holochain_p2p::HolochainP2pCell;
+use prune_cache_consumer::*;
 use publish_dht_ops_consumer::*;
 
 /// Spawns several long-running tasks which are responsible for processing work
@@ -61,10 +63,15 @@ pub async fn spawn_queue_consumer_tasks(
     conductor_api: impl CellConductorApiT + 'static,
     mut task_sender: sync::mpsc::Sender<ManagedTaskAdd>,
     stop: sync::broadcast::Sender<()>,
+    cache_config: CacheConfig,
 ) -> InitialQueueTriggers {
     // Publish
-    let (tx_publish, handle) =
-        spawn_publish_dht_ops_consumer(env.clone(), stop.subscribe(), cell_network.clone());
+    let (tx_publish, handle) = spawn_publish_dht_ops_consumer(
+        env.clone(),
+        stop.subscribe(),
+        cell_network.clone(),
+        conductor_api.clone(),
+    );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
         .await
@@ -73,8 +80,12 @@ pub async fn spawn_queue_consumer_tasks(
     let (create_tx_sys, get_tx_sys) = tokio::sync::oneshot::channel();
 
     // Integration
-    let (tx_integration, handle) =
-        spawn_integrate_dht_ops_consumer(env.clone(), stop.subscribe(), get_tx_sys);
+    let (tx_integration, handle) = spawn_integrate_dht_ops_consumer(
+        env.clone(),
+        stop.subscribe(),
+        get_tx_sys,
+        conductor_api.clone(),
+    );
     task_sender
         .send(ManagedTaskAdd::dont_handle(handle))
         .await
@@ -117,6 +128,13 @@ pub async fn spawn_queue_consumer_tasks(
         .await
         .expect("Failed to manage workflow handle");
 
+    // Prune cache
+    let handle = spawn_prune_cache_consumer(env.clone(), stop.subscribe(), cache_config);
+    task_sender
+        .send(ManagedTaskAdd::dont_handle(handle))
+        .await
+        .expect("Failed to manage workflow handle");
+
     InitialQueueTriggers::new(tx_sys, tx_produce, tx_publish, tx_app, tx_integration)
 }
 
@@ -232,12 +250,13 @@ pub struct OneshotWriter(EnvironmentWrite);
 
 impl OneshotWriter {
     /// Create the writer and pass it into a closure.
-    pub fn with_writer<F>(self, f: F) -> Result<(), WorkspaceError>
+    /// The closure may be run more than once if the environment's map
+    /// had to grow, so it should flush with `flush_to_txn_ref`.
+    pub fn with_writer<F>(self, mut f: F) -> Result<(), WorkspaceError>
     where
-        F: FnOnce(&mut Writer) -> Result<(), WorkspaceError> + Send,
+        F: FnMut(&mut Writer) -> Result<(), WorkspaceError> + Send,
     {
-        let env_ref = self.0.guard();
-        env_ref.with_commit::<WorkspaceError, (), _>(|w| {
+        self.0.with_commit::<WorkspaceError, (), _>(|w| {
             f(w)?;
             Ok(())
         })?;
@@ -3,7 +3,7 @@
 use super::*;
 
 use crate::{
-    conductor::manager::ManagedTaskResult,
+    conductor::{api::CellConductorApiT, manager::ManagedTaskResult},
     core::workflow::integrate_dht_ops_workflow::{
         integrate_dht_ops_workflow, IntegrateDhtOpsWorkspace,
     },
@@ -14,11 +14,12 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for DhtOpIntegration workflow
-#[instrument(skip(env, stop, trigger_sys))]
+#[instrument(skip(env, stop, trigger_sys, conductor_api))]
 pub fn spawn_integrate_dht_ops_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
     trigger_sys: sync::oneshot::Receiver<TriggerSender>,
+    conductor_api: impl CellConductorApiT + 'static,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
     let mut trigger_self = tx.clone();
@@ -36,10 +37,15 @@ pub fn spawn_integrate_dht_ops_consumer(
             // Run the workflow
             let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into())
                 .expect("Could not create Workspace");
-            if let WorkComplete::Incomplete =
-                integrate_dht_ops_workflow(workspace, env.clone().into(), &mut trigger_sys)
-                    .await
-                    .expect("Error running Workflow")
+            if let WorkComplete::Incomplete = integrate_dht_ops_workflow(
+                workspace,
+                env.clone().into(),
+                &mut trigger_sys,
+                conductor_api.cell_id(),
+                conductor_api.signal_broadcaster().await,
+            )
+            .await
+            .expect("Error running Workflow")
             {
                 trigger_self.trigger()
             };
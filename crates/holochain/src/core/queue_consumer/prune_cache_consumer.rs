@@ -0,0 +1,44 @@
+//! The consumer which prunes the cache. Unlike the other consumers,
+//! it isn't triggered by new work but runs on an interval.
+
+use super::*;
+
+use crate::{
+    conductor::{config::CacheConfig, manager::ManagedTaskResult},
+    core::workflow::prune_cache_workflow::prune_cache_workflow,
+};
+use holochain_state::env::EnvironmentWrite;
+
+use tokio::task::JoinHandle;
+use tracing::*;
+
+/// Spawn the task which prunes the cache every [CacheConfig::prune_interval]
+#[instrument(skip(env, stop))]
+pub fn spawn_prune_cache_consumer(
+    env: EnvironmentWrite,
+    mut stop: sync::broadcast::Receiver<()>,
+    config: CacheConfig,
+) -> JoinHandle<ManagedTaskResult> {
+    tokio::spawn(async move {
+        loop {
+            // Wait for the next pass
+            let tick = tokio::time::delay_for(config.prune_interval());
+            let kill = stop.recv();
+            tokio::pin!(tick);
+            tokio::pin!(kill);
+            if let Either::Right(_) = futures::future::select(tick, kill).await {
+                tracing::warn!(
+                    "Cell is shutting down: stopping prune_cache_workflow queue consumer."
+                );
+                break;
+            }
+
+            // A failed pass leaves the cache as it was, so just try again later
+            match prune_cache_workflow(env.clone(), &config).await {
+                Ok(summary) => debug!(?summary, "Pruned the cache"),
+                Err(err) => error!(?err, "Could not prune the cache"),
+            }
+        }
+        Ok(())
+    })
+}
@@ -3,7 +3,7 @@
 use super::*;
 
 use crate::{
-    conductor::manager::ManagedTaskResult,
+    conductor::{api::CellConductorApiT, manager::ManagedTaskResult},
     core::workflow::publish_dht_ops_workflow::{publish_dht_ops_workflow, PublishDhtOpsWorkspace},
 };
 use holochain_state::env::EnvironmentWrite;
@@ -12,11 +12,12 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for Publish workflow
-#[instrument(skip(env, stop, cell_network))]
+#[instrument(skip(env, stop, cell_network, conductor_api))]
 pub fn spawn_publish_dht_ops_consumer(
     env: EnvironmentWrite,
     mut stop: sync::broadcast::Receiver<()>,
     mut cell_network: HolochainP2pCell,
+    conductor_api: impl CellConductorApiT + 'static,
 ) -> (TriggerSender, JoinHandle<ManagedTaskResult>) {
     let (tx, mut rx) = TriggerSender::new();
     let mut trigger_self = tx.clone();
@@ -33,10 +34,15 @@ pub fn spawn_publish_dht_ops_consumer(
             // Run the workflow
             let workspace = PublishDhtOpsWorkspace::new(env.clone().into())
                 .expect("Could not create Workspace");
-            if let WorkComplete::Incomplete =
-                publish_dht_ops_workflow(workspace, env.clone().into(), &mut cell_network)
-                    .await
-                    .expect("Error running Workflow")
+            if let WorkComplete::Incomplete = publish_dht_ops_workflow(
+                workspace,
+                env.clone().into(),
+                &mut cell_network,
+                conductor_api.signal_broadcaster().await,
+                &conductor_api,
+            )
+            .await
+            .expect("Error running Workflow")
             {
                 trigger_self.trigger()
             };
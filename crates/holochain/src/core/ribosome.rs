@@ -12,10 +12,15 @@ pub mod guest_callback;
 pub mod host_fn;
 pub mod wasm_ribosome;
 
+use crate::conductor::entry_def_store::EntryDefBufferKey;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::guest_callback::merge::MergeInvocation;
+use crate::core::ribosome::guest_callback::merge::MergeResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
@@ -37,7 +42,8 @@ use ::fixt::prelude::*;
 use derive_more::Constructor;
 use error::RibosomeResult;
 use guest_callback::{
-    entry_defs::EntryDefsHostAccess, init::InitHostAccess, migrate_agent::MigrateAgentHostAccess,
+    entry_defs::EntryDefsHostAccess, genesis_self_check::GenesisSelfCheckHostAccess,
+    init::InitHostAccess, merge::MergeHostAccess, migrate_agent::MigrateAgentHostAccess,
     post_commit::PostCommitHostAccess, validate::ValidateHostAccess,
     validation_package::ValidationPackageHostAccess,
 };
@@ -53,6 +59,7 @@ use holochain_types::fixt::CapSecretFixturator;
 use holochain_types::fixt::CellIdFixturator;
 use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::capability::CapGrant;
+use holochain_zome_types::entry_def::EntryDef;
 use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::ExternOutput;
@@ -90,6 +97,8 @@ pub enum HostAccess {
     ValidateCreateLink(ValidateLinkHostAccess),
     Init(InitHostAccess),
     EntryDefs(EntryDefsHostAccess),
+    GenesisSelfCheck(GenesisSelfCheckHostAccess),
+    Merge(MergeHostAccess),
     MigrateAgent(MigrateAgentHostAccess),
     ValidationPackage(ValidationPackageHostAccess),
     PostCommit(PostCommitHostAccess),
@@ -105,6 +114,10 @@ impl From<&HostAccess> for HostFnAccess {
             }
             HostAccess::Init(init_host_access) => init_host_access.into(),
             HostAccess::EntryDefs(entry_defs_host_access) => entry_defs_host_access.into(),
+            HostAccess::GenesisSelfCheck(genesis_self_check_host_access) => {
+                genesis_self_check_host_access.into()
+            }
+            HostAccess::Merge(merge_host_access) => merge_host_access.into(),
             HostAccess::MigrateAgent(migrate_agent_host_access) => migrate_agent_host_access.into(),
             HostAccess::ValidationPackage(validation_package_host_access) => {
                 validation_package_host_access.into()
@@ -160,7 +173,8 @@ impl HostAccess {
     /// Get the signal broadcaster, panics if none was provided
     pub fn signal_tx(&mut self) -> &mut SignalBroadcaster {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. }) => signal_tx,
+            Self::ZomeCall(ZomeCallHostAccess { signal_tx, .. })
+            | Self::PostCommit(PostCommitHostAccess { signal_tx, .. }) => signal_tx,
             _ => panic!(
                 "Gave access to a host function that uses the signal broadcaster without providing one"
             ),
@@ -170,7 +184,8 @@ impl HostAccess {
     /// Get the associated CellId, panics if not applicable
     pub fn cell_id(&self) -> &CellId {
         match self {
-            Self::ZomeCall(ZomeCallHostAccess { cell_id, .. }) => cell_id,
+            Self::ZomeCall(ZomeCallHostAccess { cell_id, .. })
+            | Self::PostCommit(PostCommitHostAccess { cell_id, .. }) => cell_id,
             _ => panic!("Gave access to a host function that references a CellId"),
         }
     }
@@ -411,6 +426,10 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
 
     fn zomes_to_invoke(&self, zomes_to_invoke: ZomesToInvoke) -> Vec<ZomeName>;
 
+    /// The entry def the conductor stored for an entry type when the DNA
+    /// was installed. `None` if the ribosome wasn't given the stored defs.
+    fn stored_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef>;
+
     fn zome_name_to_id(&self, zome_name: &ZomeName) -> RibosomeResult<ZomeId>;
 
     fn maybe_call<I: Invocation + 'static>(
@@ -435,12 +454,24 @@ pub trait RibosomeT: Sized + std::fmt::Debug {
         // self.instance().exports().filter(|e| !e.is_callback())
     }
 
+    fn run_genesis_self_check(
+        &self,
+        access: GenesisSelfCheckHostAccess,
+        invocation: GenesisSelfCheckInvocation,
+    ) -> RibosomeResult<GenesisSelfCheckResult>;
+
     fn run_init(
         &self,
         access: InitHostAccess,
         invocation: InitInvocation,
     ) -> RibosomeResult<InitResult>;
 
+    fn run_merge(
+        &self,
+        access: MergeHostAccess,
+        invocation: MergeInvocation,
+    ) -> RibosomeResult<MergeResult>;
+
     fn run_migrate_agent(
         &self,
         access: MigrateAgentHostAccess,
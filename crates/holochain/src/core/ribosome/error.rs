@@ -4,11 +4,13 @@
 use crate::{
     conductor::interface::error::InterfaceError,
     core::state::{cascade::error::CascadeError, source_chain::SourceChainError},
+    core::sys_validate::SysValidationError,
 };
 use holo_hash::AnyDhtHash;
 use holochain_serialized_bytes::prelude::SerializedBytesError;
 use holochain_types::dna::error::DnaError;
 use holochain_wasmer_host::prelude::WasmError;
+use holochain_zome_types::header::HeaderHashes;
 use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::zome::ZomeName;
 use thiserror::Error;
@@ -49,6 +51,25 @@ pub enum RibosomeError {
     #[error("A mandatory element is missing, dht hash: {0}")]
     ElementDeps(AnyDhtHash),
 
+    /// The post_commit callback failed after the zome call's commits were
+    /// already written, so the caller can react to the partial success
+    #[error("The post_commit callback of zome {0} failed for headers {1:?}: {2}")]
+    PostCommitFailed(ZomeName, HeaderHashes, String),
+
+    /// A zome's migrate_agent callback refused to let the agent
+    /// open or close their chain
+    #[error("The migrate_agent callback of zome {0} refused the migration: {1}")]
+    MigrateAgentFailed(ZomeName, String),
+
+    /// An entry def uses AppMerge but its zome couldn't resolve the updates
+    #[error("The merge callback of zome {0} couldn't resolve the updates: {1}")]
+    MergeFailed(ZomeName, String),
+
+    /// A zome asked to sign data which only the conductor may sign
+    /// with an agent key, such as the context private entry keys are derived from
+    #[error("Zomes can't sign data in a context reserved by the conductor")]
+    ReservedSigningContext,
+
     /// ident
     #[error("Unspecified ring error")]
     RingUnspecified,
@@ -81,6 +102,10 @@ pub enum RibosomeError {
     #[error(transparent)]
     JoinError(#[from] JoinError),
 
+    /// An entry or link failed the checks made before it is committed
+    #[error(transparent)]
+    SysValidationError(#[from] Box<SysValidationError>),
+
     /// ident
     #[error(transparent)]
     P2pError(#[from] holochain_p2p::HolochainP2pError),
@@ -1,5 +1,7 @@
 pub mod entry_defs;
+pub mod genesis_self_check;
 pub mod init;
+pub mod merge;
 pub mod migrate_agent;
 pub mod post_commit;
 pub mod validate;
@@ -275,14 +275,16 @@ mod slow_tests {
                     EntryDef {
                         id: "post".into(),
                         visibility: EntryVisibility::Public,
-                        crdt_type: CrdtType,
+                        crdt_type: CrdtType::GrowOnlySet,
                         required_validations: 5.into(),
+                        max_size: None,
                     },
                     EntryDef {
                         id: "comment".into(),
                         visibility: EntryVisibility::Private,
-                        crdt_type: CrdtType,
+                        crdt_type: CrdtType::GrowOnlySet,
                         required_validations: 5.into(),
+                        max_size: None,
                     },
                 ]
                 .into();
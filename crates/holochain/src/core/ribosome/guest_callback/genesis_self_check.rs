@@ -0,0 +1,187 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::{HostAccess, ZomesToInvoke};
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::dna::zome::{HostFnAccess, Permission};
+use holochain_zome_types::genesis::GenesisSelfCheckData;
+use holochain_zome_types::validate::ValidateCallbackResult;
+use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::ExternInput;
+
+#[derive(Debug, Clone, Constructor)]
+pub struct GenesisSelfCheckInvocation {
+    pub payload: GenesisSelfCheckData,
+}
+
+/// Genesis runs before the agent has a source chain or is on the network,
+/// so the self check only has access to the DNA itself
+#[derive(Clone, Constructor)]
+pub struct GenesisSelfCheckHostAccess;
+
+impl From<GenesisSelfCheckHostAccess> for HostAccess {
+    fn from(genesis_self_check_host_access: GenesisSelfCheckHostAccess) -> Self {
+        Self::GenesisSelfCheck(genesis_self_check_host_access)
+    }
+}
+
+impl From<&GenesisSelfCheckHostAccess> for HostFnAccess {
+    fn from(_: &GenesisSelfCheckHostAccess) -> Self {
+        let mut access = Self::none();
+        access.dna_bindings = Permission::Allow;
+        access
+    }
+}
+
+impl Invocation for GenesisSelfCheckInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::All
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["genesis_self_check".into()].into()
+    }
+    fn host_input(self) -> Result<ExternInput, SerializedBytesError> {
+        Ok(ExternInput::new((&self.payload).try_into()?))
+    }
+}
+
+impl TryFrom<GenesisSelfCheckInvocation> for ExternInput {
+    type Error = SerializedBytesError;
+    fn try_from(invocation: GenesisSelfCheckInvocation) -> Result<Self, Self::Error> {
+        Ok(Self::new((&invocation.payload).try_into()?))
+    }
+}
+
+/// the aggregate result of _all_ genesis_self_check callbacks
+#[derive(PartialEq, Debug)]
+pub enum GenesisSelfCheckResult {
+    /// all callbacks accepted the agent
+    Valid,
+    /// some callback rejected the agent
+    /// ZomeName is the first zome that rejected the agent
+    /// String is a human-readable reason for the rejection
+    Invalid(ZomeName, String),
+}
+
+impl From<Vec<(ZomeName, ValidateCallbackResult)>> for GenesisSelfCheckResult {
+    fn from(callback_results: Vec<(ZomeName, ValidateCallbackResult)>) -> Self {
+        callback_results
+            .into_iter()
+            .fold(Self::Valid, |acc, (zome_name, x)| match (acc, x) {
+                // the first invalid result sticks
+                (acc @ Self::Invalid(_, _), _) => acc,
+                (Self::Valid, ValidateCallbackResult::Invalid(reason)) => {
+                    Self::Invalid(zome_name, reason)
+                }
+                // there is nothing to fetch dependencies from before genesis
+                (Self::Valid, ValidateCallbackResult::UnresolvedDependencies(deps)) => {
+                    Self::Invalid(
+                        zome_name,
+                        format!("genesis_self_check can't depend on DHT data: {:?}", deps),
+                    )
+                }
+                (Self::Valid, ValidateCallbackResult::Valid) => Self::Valid,
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::GenesisSelfCheckResult;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::GenesisSelfCheckHostAccessFixturator;
+    use crate::fixt::GenesisSelfCheckInvocationFixturator;
+    use crate::fixt::ZomeNameFixturator;
+    use ::fixt::prelude::*;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_types::dna::zome::{HostFnAccess, Permission};
+    use holochain_zome_types::validate::ValidateCallbackResult;
+    use holochain_zome_types::ExternInput;
+    use matches::assert_matches;
+
+    #[test]
+    fn genesis_self_check_callback_result_fold() {
+        let mut rng = thread_rng();
+
+        let result_valid = || GenesisSelfCheckResult::Valid;
+        let result_invalid = || GenesisSelfCheckResult::Invalid("".into(), "".into());
+
+        let cb_valid = || {
+            (
+                ZomeNameFixturator::new(Empty).next().unwrap(),
+                ValidateCallbackResult::Valid,
+            )
+        };
+        let cb_invalid = || {
+            (
+                ZomeNameFixturator::new(Empty).next().unwrap(),
+                ValidateCallbackResult::Invalid("".into()),
+            )
+        };
+        let cb_unresolved = || {
+            (
+                ZomeNameFixturator::new(Empty).next().unwrap(),
+                ValidateCallbackResult::UnresolvedDependencies(vec![]),
+            )
+        };
+
+        for (mut results, expected) in vec![
+            (vec![], result_valid()),
+            (vec![cb_valid()], result_valid()),
+            (vec![cb_invalid()], result_invalid()),
+            (vec![cb_valid(), cb_invalid()], result_invalid()),
+        ] {
+            // order of the results should not change the final result
+            results.shuffle(&mut rng);
+
+            // number of times a callback result appears should not change the final result
+            let number_of_extras = rng.gen_range(0, 5);
+            for _ in 0..number_of_extras {
+                let maybe_extra = results.choose(&mut rng).cloned();
+                if let Some(extra) = maybe_extra {
+                    results.push(extra);
+                }
+            }
+
+            assert_eq!(expected, results.into(),);
+        }
+
+        assert_matches!(
+            GenesisSelfCheckResult::from(vec![cb_unresolved()]),
+            GenesisSelfCheckResult::Invalid(_, _)
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn genesis_self_check_access() {
+        let access = HostFnAccess::from(
+            &GenesisSelfCheckHostAccessFixturator::new(Empty)
+                .next()
+                .unwrap(),
+        );
+        let mut expected = HostFnAccess::none();
+        expected.dna_bindings = Permission::Allow;
+        assert_eq!(access, expected);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn genesis_self_check_invocation() {
+        let invocation = GenesisSelfCheckInvocationFixturator::new(Empty)
+            .next()
+            .unwrap();
+        assert_eq!(ZomesToInvoke::All, invocation.zomes());
+        assert_eq!(
+            vec!["genesis_self_check"],
+            invocation
+                .fn_components()
+                .into_iter()
+                .collect::<Vec<String>>(),
+        );
+        assert_eq!(
+            ExternInput::new(SerializedBytes::try_from(&invocation.payload).unwrap()),
+            invocation.host_input().unwrap(),
+        );
+    }
+}
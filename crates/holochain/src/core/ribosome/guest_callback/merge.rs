@@ -0,0 +1,162 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::{HostAccess, ZomesToInvoke};
+use derive_more::Constructor;
+use holo_hash::HeaderHash;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::dna::zome::{HostFnAccess, Permission};
+use holochain_zome_types::crdt::MergeCallbackResult;
+use holochain_zome_types::crdt::MergeData;
+use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::ExternInput;
+
+#[derive(Debug, Clone, Constructor)]
+pub struct MergeInvocation {
+    /// The zome that defines the entry
+    pub zome_name: ZomeName,
+    /// The id of the entry def, so a zome can implement `merge_{entry_def_id}`
+    pub entry_def_id: String,
+    pub payload: MergeData,
+}
+
+/// Every agent must resolve the same updates to the same element,
+/// so a merge can only look at the data it is given
+#[derive(Clone, Constructor)]
+pub struct MergeHostAccess;
+
+impl From<MergeHostAccess> for HostAccess {
+    fn from(merge_host_access: MergeHostAccess) -> Self {
+        Self::Merge(merge_host_access)
+    }
+}
+
+impl From<&MergeHostAccess> for HostFnAccess {
+    fn from(_: &MergeHostAccess) -> Self {
+        let mut access = Self::none();
+        access.dna_bindings = Permission::Allow;
+        access
+    }
+}
+
+impl Invocation for MergeInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::One(self.zome_name.to_owned())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["merge".into(), self.entry_def_id.clone()].into()
+    }
+    fn host_input(self) -> Result<ExternInput, SerializedBytesError> {
+        Ok(ExternInput::new((&self.payload).try_into()?))
+    }
+}
+
+impl TryFrom<MergeInvocation> for ExternInput {
+    type Error = SerializedBytesError;
+    fn try_from(invocation: MergeInvocation) -> Result<Self, Self::Error> {
+        Ok(Self::new((&invocation.payload).try_into()?))
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum MergeResult {
+    /// The header hash of the element the get resolves to
+    Resolved(HeaderHash),
+    /// ZomeName is the zome that failed to merge
+    /// String is a human-readable reason for the failure
+    Fail(ZomeName, String),
+    /// The entry def uses AppMerge but has no merge callback
+    NotImplemented,
+}
+
+impl From<Vec<(ZomeName, MergeCallbackResult)>> for MergeResult {
+    fn from(callback_results: Vec<(ZomeName, MergeCallbackResult)>) -> Self {
+        callback_results
+            .into_iter()
+            .fold(Self::NotImplemented, |acc, (zome_name, x)| match (acc, x) {
+                // failure anywhere overrides everything
+                (acc @ Self::Fail(_, _), _) => acc,
+                (_, MergeCallbackResult::Fail(reason)) => Self::Fail(zome_name, reason),
+                // the most specific callback is called first so its answer sticks
+                (acc @ Self::Resolved(_), MergeCallbackResult::Resolved(_)) => acc,
+                (Self::NotImplemented, MergeCallbackResult::Resolved(hash)) => Self::Resolved(hash),
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::MergeResult;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::MergeHostAccessFixturator;
+    use crate::fixt::MergeInvocationFixturator;
+    use crate::fixt::ZomeNameFixturator;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::HeaderHashFixturator;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_types::dna::zome::{HostFnAccess, Permission};
+    use holochain_zome_types::crdt::MergeCallbackResult;
+    use holochain_zome_types::ExternInput;
+
+    #[test]
+    fn merge_callback_result_fold() {
+        let zome_name = ZomeNameFixturator::new(Empty).next().unwrap();
+        let hash_a = fixt!(HeaderHash);
+        let hash_b = fixt!(HeaderHash);
+
+        let resolved = |hash| (zome_name.clone(), MergeCallbackResult::Resolved(hash));
+        let fail = || (zome_name.clone(), MergeCallbackResult::Fail("".into()));
+
+        for (results, expected) in vec![
+            (vec![], MergeResult::NotImplemented),
+            (
+                vec![resolved(hash_a.clone())],
+                MergeResult::Resolved(hash_a.clone()),
+            ),
+            (
+                vec![resolved(hash_a.clone()), resolved(hash_b.clone())],
+                MergeResult::Resolved(hash_a.clone()),
+            ),
+            (
+                vec![resolved(hash_a.clone()), fail()],
+                MergeResult::Fail(zome_name.clone(), "".into()),
+            ),
+            (
+                vec![fail(), resolved(hash_b.clone())],
+                MergeResult::Fail(zome_name.clone(), "".into()),
+            ),
+        ] {
+            assert_eq!(expected, results.into());
+        }
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn merge_access() {
+        let access = HostFnAccess::from(&MergeHostAccessFixturator::new(Empty).next().unwrap());
+        let mut expected = HostFnAccess::none();
+        expected.dna_bindings = Permission::Allow;
+        assert_eq!(access, expected);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn merge_invocation() {
+        let mut invocation = MergeInvocationFixturator::new(Empty).next().unwrap();
+        invocation.entry_def_id = "post".into();
+        assert_eq!(
+            ZomesToInvoke::One(invocation.zome_name.clone()),
+            invocation.zomes()
+        );
+        assert_eq!(
+            vec!["merge_post", "merge"],
+            invocation
+                .fn_components()
+                .into_iter()
+                .collect::<Vec<String>>(),
+        );
+        assert_eq!(
+            ExternInput::new(SerializedBytes::try_from(&invocation.payload).unwrap()),
+            invocation.host_input().unwrap(),
+        );
+    }
+}
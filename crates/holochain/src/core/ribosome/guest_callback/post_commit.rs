@@ -1,3 +1,4 @@
+use crate::conductor::interface::SignalBroadcaster;
 use crate::core::ribosome::FnComponents;
 use crate::core::ribosome::HostAccess;
 use crate::core::ribosome::Invocation;
@@ -7,6 +8,7 @@ use derive_more::Constructor;
 use holochain_keystore::KeystoreSender;
 use holochain_p2p::HolochainP2pCell;
 use holochain_serialized_bytes::prelude::*;
+use holochain_types::cell::CellId;
 use holochain_types::dna::zome::HostFnAccess;
 use holochain_zome_types::header::HeaderHashes;
 use holochain_zome_types::post_commit::PostCommitCallbackResult;
@@ -30,6 +32,8 @@ pub struct PostCommitHostAccess {
     pub workspace: CallZomeWorkspaceLock,
     pub keystore: KeystoreSender,
     pub network: HolochainP2pCell,
+    pub signal_tx: SignalBroadcaster,
+    pub cell_id: CellId,
 }
 
 impl From<PostCommitHostAccess> for HostAccess {
@@ -67,6 +67,7 @@ impl Invocation for ValidateInvocation {
             Header::Create(_) => fns.push("create".into()),
             Header::Update(_) => fns.push("update".into()),
             Header::Delete(_) => fns.push("delete".into()),
+            Header::AgentValidationPkg(_) => fns.push("agent_validation_pkg".into()),
             _ => (),
         }
         match self.element.entry().as_option() {
@@ -285,10 +286,14 @@ mod slow_tests {
     use crate::fixt::*;
     use ::fixt::prelude::*;
     use holo_hash::fixt::AgentPubKeyFixturator;
+    use holochain_serialized_bytes::prelude::*;
+    use holochain_types::element::{Element, SignedHeaderHashed};
     use holochain_types::fixt::*;
+    use holochain_types::HeaderHashed;
     use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::CreateOutput;
     use holochain_zome_types::Entry;
+    use holochain_zome_types::Header;
     use std::sync::Arc;
 
     #[tokio::test(threaded_scheduler)]
@@ -365,6 +370,42 @@ mod slow_tests {
         assert_eq!(result, ValidateResult::Invalid("esoteric edge case".into()));
     }
 
+    #[tokio::test(threaded_scheduler)]
+    async fn test_validate_agent_validation_pkg() {
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![TestWasm::Membrane]))
+            .next()
+            .unwrap();
+        let element = |membrane_proof: &[u8]| {
+            let mut pkg = fixt!(AgentValidationPkg);
+            pkg.membrane_proof = Some(SerializedBytes::from(UnsafeBytes::from(
+                membrane_proof.to_vec(),
+            )));
+            Element::new(
+                SignedHeaderHashed::with_presigned(
+                    HeaderHashed::from_content_sync(Header::AgentValidationPkg(pkg)),
+                    fixt!(Signature),
+                ),
+                None,
+            )
+        };
+        let validate = |element| {
+            let mut validate_invocation = ValidateInvocationFixturator::new(fixt::Empty)
+                .next()
+                .unwrap();
+            validate_invocation.zomes_to_invoke = ZomesToInvoke::One(TestWasm::Membrane.into());
+            validate_invocation.element = Arc::new(element);
+            ribosome
+                .run_validate(fixt!(ValidateHostAccess), validate_invocation)
+                .unwrap()
+        };
+
+        assert_eq!(validate(element(b"let me in")), ValidateResult::Valid);
+        assert_eq!(
+            validate(element(b"let me in please")),
+            ValidateResult::Invalid("not a valid membrane proof".into())
+        );
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn pass_validate_test<'a>() {
         // test workspace boilerplate
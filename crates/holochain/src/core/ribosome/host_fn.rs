@@ -4,6 +4,7 @@ pub mod call_remote;
 pub mod capability_claims;
 pub mod capability_grants;
 pub mod capability_info;
+pub mod close_chain;
 pub mod create;
 pub mod create_link;
 pub mod debug;
@@ -14,10 +15,12 @@ pub mod emit_signal;
 pub mod encrypt;
 pub mod entry_type_properties;
 pub mod get;
+pub mod get_agent_activity;
 pub mod get_details;
 pub mod get_link_details;
 pub mod get_links;
 pub mod hash_entry;
+pub mod open_chain;
 pub mod property;
 pub mod query;
 pub mod random_bytes;
@@ -63,6 +63,7 @@ pub mod wasm_test {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::WhoAmI.into()].into(),
+            limits: Default::default(),
         };
         let dna_file = DnaFile::new(dna_def, vec![TestWasm::WhoAmI.into()])
             .await
@@ -111,6 +112,7 @@ pub mod wasm_test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(2)
             .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
 
         let (_tmpdir, _app_api, handle) = setup_app(
             vec![(alice_installed_cell, None), (bob_installed_cell, None)],
@@ -149,7 +151,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         match output {
             ZomeCallResponse::Ok(guest_output) => {
@@ -41,6 +41,7 @@ pub mod wasm_test {
         // test workspace boilerplate
         let test_env = holochain_state::test_utils::test_cell_env();
         let env = test_env.env();
+        crate::test_utils::load_private_entry_key(&env).await;
         let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
 
         crate::core::workflow::fake_genesis(&mut workspace.source_chain)
@@ -60,6 +61,7 @@ pub mod wasm_test {
         // test workspace boilerplate
         let test_env = holochain_state::test_utils::test_cell_env();
         let env = test_env.env();
+        crate::test_utils::load_private_entry_key(&env).await;
         let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
 
         crate::core::workflow::fake_genesis(&mut workspace.source_chain)
@@ -105,6 +107,7 @@ pub mod wasm_test {
                 uuid: "c2f5ccfb-42b4-4927-a32c-60a642265c5a".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::Capability.into()].into(),
+                limits: Default::default(),
             },
             vec![TestWasm::Capability.into()],
         )
@@ -154,6 +157,7 @@ pub mod wasm_test {
             .expect_add_entry_defs::<Vec<_>>()
             .times(2)
             .return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
 
         let (_tmpdir, _app_api, handle) = setup_app(
             vec![(alice_installed_cell, None), (bob_installed_cell, None)],
@@ -187,7 +191,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // the _outer_ invocation response is to try_cap_claim for alice
         // the _inner_ invocation response is needs_cap_claim and should be unauthorized
@@ -214,7 +219,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         let original_grant_hash: HeaderHash = match output.clone() {
             ZomeCallResponse::Ok(guest_output) => guest_output.into_inner().try_into().unwrap(),
@@ -238,7 +244,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // the _outer_ invocation response is to try_cap_claim for alice
         // the _inner_ invocation response is needs_cap_claim and should be unauthorized
@@ -268,7 +275,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         let new_grant_header_hash: HeaderHash = match output.clone() {
             ZomeCallResponse::Ok(guest_output) => guest_output.into_inner().try_into().unwrap(),
@@ -286,7 +294,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         let new_secret: CapSecret = match output.clone() {
             ZomeCallResponse::Ok(guest_output) => {
@@ -320,7 +329,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // the _outer_ invocation response is to try_cap_claim for alice
         // the _inner_ invocation response is needs_cap_claim and should be unauthorized
@@ -349,7 +359,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // the _outer_ invocation response is to try_cap_claim for alice
         // the _inner_ invocation response is needs_cap_claim and should be unauthorized
@@ -379,7 +390,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         let output = handle
             .call_zome(ZomeCallInvocation {
@@ -396,7 +408,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // the _outer_ invocation response is to try_cap_claim for alice
         // the _inner_ invocation response is needs_cap_claim and should be unauthorized
@@ -425,7 +438,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // the _outer_ invocation response is to try_cap_claim for alice
         // the _inner_ invocation response is needs_cap_claim and should be unauthorized
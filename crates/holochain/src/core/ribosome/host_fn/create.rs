@@ -5,13 +5,14 @@ use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use crate::core::{
+    sys_validate::check_entry_size,
     workflow::{
         call_zome_workflow::CallZomeWorkspace, integrate_dht_ops_workflow::integrate_to_cache,
     },
     SourceChainError,
 };
 use holo_hash::HasHash;
-use holochain_zome_types::entry_def::{EntryDefId, EntryVisibility};
+use holochain_zome_types::entry_def::{EntryDef, EntryDefId};
 use holochain_zome_types::header::builder;
 use holochain_zome_types::header::AppEntryType;
 use holochain_zome_types::header::EntryType;
@@ -40,10 +41,13 @@ pub fn create<'a>(
     // extract the entry defs for a zome
     let entry_type = match entry_def_id {
         EntryDefId::App(entry_def_id) => {
-            let (header_entry_def_id, entry_visibility) =
-                extract_entry_def(ribosome, call_context.clone(), entry_def_id.into())?;
+            let (header_entry_def_id, entry_def) =
+                extract_entry_def(ribosome.clone(), call_context.clone(), entry_def_id.into())?;
+            // reject oversized entries before they reach the source chain
+            check_entry_size(&entry, ribosome.dna_file().dna().max_entry_size(&entry_def))
+                .map_err(Box::new)?;
             let app_entry_type =
-                AppEntryType::new(header_entry_def_id, header_zome_id, entry_visibility);
+                AppEntryType::new(header_entry_def_id, header_zome_id, entry_def.visibility);
             EntryType::App(app_entry_type)
         }
         EntryDefId::CapGrant => EntryType::CapGrant,
@@ -87,7 +91,7 @@ pub fn extract_entry_def(
     ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     entry_def_id: EntryDefId,
-) -> RibosomeResult<(holochain_zome_types::header::EntryDefIndex, EntryVisibility)> {
+) -> RibosomeResult<(holochain_zome_types::header::EntryDefIndex, EntryDef)> {
     let app_entry_type = match ribosome
         .run_entry_defs((&call_context.host_access).into(), EntryDefsInvocation)?
     {
@@ -100,7 +104,7 @@ pub fn extract_entry_def(
                     // build an app entry type from the entry def at the found position
                     Some(index) => Some((
                         holochain_zome_types::header::EntryDefIndex::from(index as u8),
-                        entry_defs[index].visibility,
+                        entry_defs[index].clone(),
                     )),
                     None => None,
                 },
@@ -128,18 +132,21 @@ pub mod wasm_test {
     use crate::core::state::source_chain::ChainInvalidReason;
     use crate::core::state::source_chain::SourceChainError;
     use crate::core::state::source_chain::SourceChainResult;
+    use crate::core::sys_validate::{SysValidationError, ValidationOutcome};
     use crate::core::workflow::call_zome_workflow::CallZomeWorkspace;
     use crate::fixt::CallContextFixturator;
     use crate::fixt::EntryFixturator;
     use crate::fixt::WasmRibosomeFixturator;
     use crate::fixt::ZomeCallHostAccessFixturator;
+    use crate::test_utils::host_fn_api::LimitedCallData;
     use crate::test_utils::setup_app;
     use ::fixt::prelude::*;
     use hdk3::prelude::*;
     use holo_hash::{AnyDhtHash, EntryHash};
     use holochain_types::{
-        app::InstalledCell, cell::CellId, dna::DnaDef, dna::DnaFile, fixt::AppEntry, observability,
-        test_utils::fake_agent_pubkey_1, test_utils::fake_agent_pubkey_2,
+        app::InstalledCell, cell::CellId, dna::DnaDef, dna::DnaFile, dna::DnaLimits,
+        fixt::AppEntry, observability, test_utils::fake_agent_pubkey_1,
+        test_utils::fake_agent_pubkey_2,
     };
     use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::entry_def::EntryDefId;
@@ -148,6 +155,7 @@ pub mod wasm_test {
     use holochain_zome_types::Entry;
     use holochain_zome_types::GetOutput;
     use holochain_zome_types::{entry::EntryError, ExternInput};
+    use matches::assert_matches;
     use std::sync::Arc;
     use test_wasm_common::TestBytes;
     use test_wasm_common::TestInt;
@@ -234,6 +242,41 @@ pub mod wasm_test {
         assert_eq!(chain_head, output.into_inner(),);
     }
 
+    #[tokio::test(threaded_scheduler)]
+    /// oversized entries are rejected before they reach the source chain
+    async fn create_oversized_entry_test() {
+        let call_data = LimitedCallData::new(
+            TestWasm::Create,
+            DnaLimits {
+                max_entry_size: 4,
+                ..Default::default()
+            },
+        )
+        .await;
+        let entry = Entry::app(().try_into().unwrap()).unwrap();
+        let big_entry = Entry::app(TestBytes(vec![0; 8]).try_into().unwrap()).unwrap();
+
+        let output = create(
+            call_data.ribosome.clone(),
+            call_data.call_context.clone(),
+            CreateInput::new((EntryDefId::App("post".into()), big_entry)),
+        );
+        assert_matches!(
+            output,
+            Err(RibosomeError::SysValidationError(e))
+                if matches!(*e, SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(_, 4)))
+        );
+        call_data.assert_chain_unchanged().await;
+
+        // entries under the limit are still committed
+        create(
+            call_data.ribosome,
+            call_data.call_context,
+            CreateInput::new((EntryDefId::App("post".into()), entry)),
+        )
+        .unwrap();
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn ribosome_create_entry_test<'a>() {
         holochain_types::observability::test_run().ok();
@@ -290,6 +333,7 @@ pub mod wasm_test {
                 uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::MultipleCalls.into()].into(),
+                limits: Default::default(),
             },
             vec![TestWasm::MultipleCalls.into()],
         )
@@ -364,7 +408,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
         assert_eq!(
             output,
             ZomeCallResponse::Ok(
@@ -386,7 +431,8 @@ pub mod wasm_test {
             })
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .response;
 
         // check the vals
         let mut expected = vec![];
@@ -2,6 +2,7 @@ use crate::core::ribosome::error::RibosomeResult;
 use crate::core::workflow::integrate_dht_ops_workflow::integrate_to_cache;
 use crate::core::{
     ribosome::{CallContext, RibosomeT},
+    sys_validate::check_tag_size,
     workflow::CallZomeWorkspace,
     SourceChainResult,
 };
@@ -21,6 +22,9 @@ pub fn create_link<'a>(
     // extract the zome position
     let zome_id = ribosome.zome_name_to_id(&call_context.zome_name)?;
 
+    // reject oversized tags before they reach the source chain
+    check_tag_size(&tag, ribosome.dna_file().dna().limits.max_tag_size).map_err(Box::new)?;
+
     // Construct the link add
     let header_builder = builder::CreateLink::new(base_address, target_address, zome_id, tag);
 
@@ -52,3 +56,51 @@ pub fn create_link<'a>(
 }
 
 // we rely on the tests for get_links and get_link_details
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::create_link;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::sys_validate::{SysValidationError, ValidationOutcome};
+    use crate::test_utils::host_fn_api::LimitedCallData;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::EntryHashFixturator;
+    use holochain_types::dna::DnaLimits;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::{link::LinkTag, CreateLinkInput};
+    use matches::assert_matches;
+
+    #[tokio::test(threaded_scheduler)]
+    /// oversized tags are rejected before they reach the source chain
+    async fn create_link_oversized_tag_test() {
+        let call_data = LimitedCallData::new(
+            TestWasm::Link,
+            DnaLimits {
+                max_tag_size: 4,
+                ..Default::default()
+            },
+        )
+        .await;
+
+        let output = create_link(
+            call_data.ribosome.clone(),
+            call_data.call_context.clone(),
+            CreateLinkInput::new((fixt!(EntryHash), fixt!(EntryHash), LinkTag::new(vec![0; 8]))),
+        );
+        assert_matches!(
+            output,
+            Err(RibosomeError::SysValidationError(e))
+                if matches!(*e, SysValidationError::ValidationOutcome(ValidationOutcome::TagTooLarge(8, 4)))
+        );
+        call_data.assert_chain_unchanged().await;
+
+        // tags under the limit are still committed
+        create_link(
+            call_data.ribosome,
+            call_data.call_context,
+            CreateLinkInput::new((fixt!(EntryHash), fixt!(EntryHash), LinkTag::new(vec![0; 2]))),
+        )
+        .unwrap();
+    }
+}
@@ -1,34 +1,338 @@
-use crate::core::ribosome::error::RibosomeResult;
+use crate::conductor::entry_def_store::EntryDefBufferKey;
+use crate::core::ribosome::error::{RibosomeError, RibosomeResult};
+use crate::core::ribosome::guest_callback::entry_defs::{EntryDefsInvocation, EntryDefsResult};
+use crate::core::ribosome::guest_callback::merge::{MergeHostAccess, MergeInvocation, MergeResult};
 use crate::core::ribosome::{CallContext, RibosomeT};
+use crate::core::state::cascade::{error::CascadeResult, Cascade};
+use holo_hash::hash_type::AnyDht;
+use holo_hash::{EntryHash, HeaderHash};
+use holochain_p2p::actor::GetOptions;
+use holochain_zome_types::crdt::{CrdtType, MergeData};
+use holochain_zome_types::element::Element;
+use holochain_zome_types::entry_def::{EntryDef, EntryDefId};
+use holochain_zome_types::header::EntryType;
+use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::GetInput;
 use holochain_zome_types::GetOutput;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn get<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: GetInput,
 ) -> RibosomeResult<GetOutput> {
     let (hash, options) = input.into_inner();
+    let options: GetOptions = options.into();
 
     // Get the network from the context
     let network = call_context.host_access.network().clone();
 
     // timeouts must be handled by the network
     tokio_safe_block_on::tokio_safe_block_forever_on(async move {
-        let maybe_element = call_context
-            .host_access
-            .workspace()
-            .write()
-            .await
-            .cascade(network)
-            .dht_get(hash, options.into())
-            .await?;
+        let mut workspace = call_context.host_access.workspace().write().await;
+        let mut cascade = workspace.cascade(network);
+        let maybe_element = cascade.dht_get(hash.clone(), options.clone()).await?;
+
+        // Only a get by entry hash can resolve to an update
+        let original = match (maybe_element, *hash.hash_type()) {
+            (Some(original), AnyDht::Entry) => original,
+            (maybe_element, _) => return Ok(GetOutput::new(maybe_element)),
+        };
+        let (zome_name, entry_def) = match app_entry_def(&ribosome, &call_context, &original)? {
+            Some(def) if def.1.crdt_type != CrdtType::GrowOnlySet => def,
+            _ => return Ok(GetOutput::new(Some(original))),
+        };
+        let updates = get_updates_transitively(&mut cascade, hash.into(), options).await?;
+        drop(workspace);
 
-        Ok(GetOutput::new(maybe_element))
+        let resolved = resolve_updates(&ribosome, zome_name, entry_def, original, updates)?;
+        Ok(GetOutput::new(Some(resolved)))
     })
 }
 
+/// Every live update to an entry, the updates to those updates' entries
+/// and so on, oldest first. The original entry must already have been
+/// fetched so the cascade has its update metadata.
+async fn get_updates_transitively(
+    cascade: &mut Cascade<'_>,
+    entry_hash: EntryHash,
+    options: GetOptions,
+) -> CascadeResult<Vec<Element>> {
+    let mut seen_entries: HashSet<EntryHash> = std::iter::once(entry_hash.clone()).collect();
+    let mut seen_headers: HashSet<HeaderHash> = HashSet::new();
+    let mut updates = Vec::new();
+    let mut to_visit = cascade
+        .dht_get_entry_updates(entry_hash, options.clone())
+        .await?;
+    while let Some(update) = to_visit.pop() {
+        if !seen_headers.insert(update.header_address().clone()) {
+            continue;
+        }
+        if let Some((new_entry, _)) = update.header().entry_data() {
+            if seen_entries.insert(new_entry.clone()) {
+                // Fetch the updated entry so its own updates are in the cache
+                cascade
+                    .dht_get(new_entry.clone().into(), options.clone())
+                    .await?;
+                to_visit.extend(
+                    cascade
+                        .dht_get_entry_updates(new_entry.clone(), options.clone())
+                        .await?,
+                );
+            }
+        }
+        updates.push(update);
+    }
+    updates.sort_by_key(|e| (e.header().timestamp(), e.header_address().clone()));
+    Ok(updates)
+}
+
+/// The zome and entry def of the app entry this element creates, if any.
+/// The conductor stores every entry def when the DNA is installed, so the
+/// entry_defs callback only runs for a ribosome without the stored defs.
+fn app_entry_def(
+    ribosome: &Arc<impl RibosomeT>,
+    call_context: &CallContext,
+    element: &Element,
+) -> RibosomeResult<Option<(ZomeName, EntryDef)>> {
+    let app_entry_type = match element.header().entry_data() {
+        Some((_, EntryType::App(app_entry_type))) => app_entry_type.clone(),
+        _ => return Ok(None),
+    };
+    let (zome_name, zome) = match ribosome
+        .dna_file()
+        .dna()
+        .zomes
+        .get(u8::from(app_entry_type.zome_id()) as usize)
+    {
+        Some((zome_name, zome)) => (zome_name.clone(), zome.clone()),
+        None => return Ok(None),
+    };
+    let key = EntryDefBufferKey::new(zome, app_entry_type.id());
+    if let Some(entry_def) = ribosome.stored_entry_def(&key) {
+        return Ok(Some((zome_name, entry_def)));
+    }
+    match ribosome.run_entry_defs((&call_context.host_access).into(), EntryDefsInvocation)? {
+        EntryDefsResult::Defs(defs) => Ok(defs
+            .get(&zome_name)
+            .cloned()
+            .and_then(|defs| defs.into_iter().nth(u8::from(app_entry_type.id()) as usize))
+            .map(|entry_def| (zome_name, entry_def))),
+        _ => Ok(None),
+    }
+}
+
+/// Pick the element a get on the original entry returns
+/// according to the entry def's [CrdtType]
+fn resolve_updates(
+    ribosome: &Arc<impl RibosomeT>,
+    zome_name: ZomeName,
+    entry_def: EntryDef,
+    original: Element,
+    updates: Vec<Element>,
+) -> RibosomeResult<Element> {
+    if let Some(resolved) = entry_def.crdt_type.resolve(&original, &updates) {
+        return Ok(resolved.clone());
+    }
+    let entry_def_id = match entry_def.id {
+        EntryDefId::App(entry_def_id) => entry_def_id,
+        other => {
+            return Err(RibosomeError::MergeFailed(
+                zome_name,
+                format!("{:?} is not an app entry def, so it can't be merged", other),
+            ))
+        }
+    };
+    let invocation = MergeInvocation::new(
+        zome_name.clone(),
+        entry_def_id,
+        MergeData { original, updates },
+    );
+    let payload = invocation.payload.clone();
+    match ribosome.run_merge(MergeHostAccess, invocation)? {
+        MergeResult::Resolved(header_hash) => std::iter::once(payload.original)
+            .chain(payload.updates)
+            .find(|element| element.header_address() == &header_hash)
+            .ok_or_else(|| {
+                RibosomeError::MergeFailed(
+                    zome_name,
+                    format!("{} is not the original or one of its updates", header_hash),
+                )
+            }),
+        MergeResult::Fail(zome_name, reason) => Err(RibosomeError::MergeFailed(zome_name, reason)),
+        MergeResult::NotImplemented => Err(RibosomeError::MergeFailed(
+            zome_name,
+            "the entry def uses AppMerge but there is no merge callback".into(),
+        )),
+    }
+}
+
 // we are relying on the create tests to show the commit/get round trip
 // @see commit_entry.rs
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::conductor::dna_store::MockDnaStore;
+    use crate::conductor::entry_def_store::get_entry_defs;
+    use crate::conductor::interface::websocket::test::setup_app;
+    use crate::core::ribosome::ZomeCallInvocation;
+    use crate::core::ribosome::ZomeCallResponse;
+    use crate::test_utils::wait_for_integration;
+    use hdk3::prelude::*;
+    use holochain_types::app::InstalledCell;
+    use holochain_types::cell::CellId;
+    use holochain_types::dna::DnaDef;
+    use holochain_types::dna::DnaFile;
+    use holochain_types::test_utils::fake_agent_pubkey_1;
+    use holochain_types::test_utils::fake_agent_pubkey_2;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::ExternInput;
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    /// A replica of the TestWasm::Crdt note entry
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+    struct Note(String);
+
+    #[derive(Serialize, Deserialize, SerializedBytes)]
+    struct UpdateNote(HeaderHash, Note);
+
+    #[tokio::test(threaded_scheduler)]
+    /// a get on an entry resolves to the latest update of its updates
+    async fn get_resolves_updates_of_updates() {
+        holochain_types::observability::test_run().ok();
+        let dna_def = DnaDef {
+            name: "get_resolves_updates_of_updates".to_string(),
+            uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
+            properties: SerializedBytes::try_from(()).unwrap(),
+            zomes: vec![TestWasm::Crdt.into()].into(),
+            limits: Default::default(),
+        };
+        let dna_file = DnaFile::new(dna_def, vec![TestWasm::Crdt.into()])
+            .await
+            .unwrap();
+
+        let alice_agent_id = fake_agent_pubkey_1();
+        let alice_cell_id = CellId::new(dna_file.dna_hash().to_owned(), alice_agent_id.clone());
+        let alice_installed_cell = InstalledCell::new(alice_cell_id.clone(), "alice_handle".into());
+        let bob_agent_id = fake_agent_pubkey_2();
+        let bob_cell_id = CellId::new(dna_file.dna_hash().to_owned(), bob_agent_id.clone());
+        let bob_installed_cell = InstalledCell::new(bob_cell_id.clone(), "bob_handle".into());
+
+        // The entry defs the conductor stores when the DNA is installed,
+        // so the get is resolved without calling the entry_defs callback
+        let entry_defs: HashMap<_, _> = get_entry_defs(dna_file.clone())
+            .unwrap()
+            .into_iter()
+            .collect();
+        let mut dna_store = MockDnaStore::new();
+        dna_store.expect_get().return_const(Some(dna_file.clone()));
+        dna_store
+            .expect_add_dnas::<Vec<_>>()
+            .times(2)
+            .return_const(());
+        dna_store
+            .expect_add_entry_defs::<Vec<_>>()
+            .times(2)
+            .return_const(());
+        dna_store
+            .expect_get_entry_def()
+            .returning(move |key| entry_defs.get(key).cloned());
+
+        let (_tmpdir, _app_api, handle) = setup_app(
+            vec![(alice_installed_cell, None), (bob_installed_cell, None)],
+            dna_store,
+        )
+        .await;
+
+        let call = |cell_id: &CellId, fn_name: &str, payload: SerializedBytes| {
+            let handle = handle.clone();
+            let invocation = ZomeCallInvocation {
+                cell_id: cell_id.clone(),
+                zome_name: TestWasm::Crdt.into(),
+                cap: None,
+                fn_name: fn_name.into(),
+                payload: ExternInput::new(payload),
+                provenance: cell_id.agent_pubkey().clone(),
+            };
+            async move {
+                match handle
+                    .call_zome(invocation)
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .response
+                {
+                    ZomeCallResponse::Ok(guest_output) => guest_output.into_inner(),
+                    _ => unreachable!(),
+                }
+            }
+        };
+
+        // Alice updates the note, then updates the update
+        let original = Note("original".into());
+        let original_header: HeaderHash = call(
+            &alice_cell_id,
+            "create",
+            original.clone().try_into().unwrap(),
+        )
+        .await
+        .try_into()
+        .unwrap();
+        let first_header: HeaderHash = call(
+            &alice_cell_id,
+            "update",
+            UpdateNote(original_header, Note("first".into()))
+                .try_into()
+                .unwrap(),
+        )
+        .await
+        .try_into()
+        .unwrap();
+        let _: HeaderHash = call(
+            &alice_cell_id,
+            "update",
+            UpdateNote(first_header, Note("second".into()))
+                .try_into()
+                .unwrap(),
+        )
+        .await
+        .try_into()
+        .unwrap();
+
+        // 14 ops for genesis, 2 for alice's init, 3 for the create
+        // and 4 for each update
+        let bob_env = handle.get_cell_env(&bob_cell_id).await.unwrap();
+        wait_for_integration(
+            &bob_env,
+            14 + 2 + 3 + 4 + 4,
+            100,
+            Duration::from_millis(100),
+        )
+        .await;
+
+        // The second update only updates the first, but it's the latest write
+        let original_hash = holochain_types::entry::EntryHashed::from_content_sync(
+            Entry::app(original.try_into().unwrap()).unwrap(),
+        )
+        .into_hash();
+        let output: GetOutput = call(&bob_cell_id, "read", original_hash.try_into().unwrap())
+            .await
+            .try_into()
+            .unwrap();
+        let note: Option<Note> = output
+            .into_inner()
+            .expect("the note was not found")
+            .entry()
+            .to_app_option()
+            .unwrap();
+        assert_eq!(note, Some(Note("second".into())));
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::{CallContext, RibosomeT};
+use holochain_p2p::actor::GetActivityOptions;
+use holochain_zome_types::GetAgentActivityInput;
+use holochain_zome_types::GetAgentActivityOutput;
+use std::sync::Arc;
+
+/// Ask the authorities for an agent's key for the agent's source chain activity
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn get_agent_activity<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: GetAgentActivityInput,
+) -> RibosomeResult<GetAgentActivityOutput> {
+    let (agent, query) = input.into_inner();
+
+    // Get the network from the context
+    let network = call_context.host_access.network().clone();
+
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let activity = call_context
+            .host_access
+            .workspace()
+            .write()
+            .await
+            .cascade(network)
+            .get_agent_activity(agent, query, GetActivityOptions::default())
+            .await?;
+
+        Ok(GetAgentActivityOutput::new(activity))
+    })
+}
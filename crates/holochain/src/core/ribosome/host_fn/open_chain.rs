@@ -0,0 +1,31 @@
+use crate::core::ribosome::error::RibosomeResult;
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::RibosomeT;
+use crate::core::workflow::migrate_agent_workflow::{
+    put_migration_header, run_migrate_agent_callbacks,
+};
+use holochain_zome_types::migrate_agent::MigrateAgent;
+use holochain_zome_types::OpenChainInput;
+use holochain_zome_types::OpenChainOutput;
+use std::sync::Arc;
+
+/// Open the source chain as the continuation of the agent's chain on the DNA in the input.
+/// Every zome's `migrate_agent` callback must pass first.
+#[allow(clippy::extra_unused_lifetimes)]
+pub fn open_chain<'a>(
+    ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: OpenChainInput,
+) -> RibosomeResult<OpenChainOutput> {
+    let dna_hash = input.into_inner();
+    let workspace_lock = call_context.host_access().workspace().clone();
+
+    run_migrate_agent_callbacks(&*ribosome, workspace_lock.clone(), MigrateAgent::Open)?;
+
+    // handle timeouts at the source chain layer
+    tokio_safe_block_on::tokio_safe_block_forever_on(async move {
+        let header_hash =
+            put_migration_header(workspace_lock, MigrateAgent::Open, dna_hash).await?;
+        Ok(OpenChainOutput::new(header_hash))
+    })
+}
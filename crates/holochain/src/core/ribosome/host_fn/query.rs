@@ -1,9 +1,13 @@
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use crate::core::state::source_chain::{SourceChainBuf, SourceChainResult};
 use holochain_zome_types::element::{Element, ElementVec};
+use holochain_zome_types::entry_def::EntryVisibility;
+use holochain_zome_types::query::ChainQueryFilter;
 use holochain_zome_types::QueryInput;
 use holochain_zome_types::QueryOutput;
+use std::ops::Bound;
 use std::sync::Arc;
 
 pub fn query(
@@ -12,17 +16,85 @@ pub fn query(
     input: QueryInput,
 ) -> RibosomeResult<QueryOutput> {
     tokio_safe_block_on::tokio_safe_block_forever_on(async move {
-        let elements: Vec<Element> = call_context
-            .host_access
-            .workspace()
-            .write()
-            .await
-            .source_chain
-            .query(input.inner_ref())?;
+        let workspace = call_context.host_access.workspace().read().await;
+        let elements = query_chain(&workspace.source_chain, &input.into_inner())?;
         Ok(QueryOutput::new(ElementVec(elements)))
     })
 }
 
+/// Walk the part of the chain within the sequence range in the order of the query,
+/// loading one header at a time and stopping as soon as the limit is reached
+fn query_chain(
+    chain: &SourceChainBuf,
+    query: &ChainQueryFilter,
+) -> SourceChainResult<Vec<Element>> {
+    let (first, last) = match sequence_indices(query, chain.len() as u32) {
+        Some(indices) => indices,
+        None => return Ok(Vec::new()),
+    };
+    let indices: Box<dyn Iterator<Item = u32>> = if query.descending {
+        Box::new((first..=last).rev())
+    } else {
+        Box::new(first..=last)
+    };
+
+    let mut elements = Vec::new();
+    let mut skip = query.offset;
+    for i in indices {
+        if query.limit.map(|limit| elements.len() >= limit as usize) == Some(true) {
+            break;
+        }
+        let shh = match chain.sequence().get(i)? {
+            Some(header_hash) => chain.get_header(&header_hash)?,
+            None => None,
+        };
+        let shh = match shh {
+            Some(shh) if query.check(shh.header()) => shh,
+            _ => continue,
+        };
+        if skip > 0 {
+            skip -= 1;
+            continue;
+        }
+        let entry = match shh.header().entry_data() {
+            Some((entry_hash, entry_type)) if query.include_entries => {
+                match entry_type.visibility() {
+                    EntryVisibility::Private if !query.include_private_entries => None,
+                    _ => chain.get_entry(entry_hash)?.map(|e| e.into_content()),
+                }
+            }
+            _ => None,
+        };
+        elements.push(Element::new(shh, entry));
+    }
+    Ok(elements)
+}
+
+/// The first and last sequence numbers on a chain of this length
+/// that the query's sequence range includes
+fn sequence_indices(query: &ChainQueryFilter, len: u32) -> Option<(u32, u32)> {
+    let (start, end) = query
+        .sequence_range
+        .clone()
+        .unwrap_or((Bound::Unbounded, Bound::Unbounded));
+    let first = match start {
+        Bound::Included(start) => start,
+        Bound::Excluded(start) => start.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let last = match end {
+        Bound::Included(end) => end,
+        Bound::Excluded(end) => end.checked_sub(1)?,
+        Bound::Unbounded => u32::MAX,
+    }
+    .min(len.checked_sub(1)?);
+    if first > last {
+        None
+    } else {
+        Some((first, last))
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "slow_tests")]
 pub mod slow_tests {
@@ -31,6 +103,8 @@ pub mod slow_tests {
     use ::fixt::prelude::*;
     use hdk3::prelude::*;
     use holochain_state::test_utils::TestEnvironment;
+    use holochain_types::fixt::CapSecretFixturator;
+    use holochain_types::test_utils::fake_agent_pubkey_2;
     use query::ChainQueryFilter;
 
     use holochain_wasm_test_utils::TestWasm;
@@ -81,4 +155,103 @@ pub mod slow_tests {
 
         assert_eq!(elements.0.len(), 5);
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn query_ranges_and_pages() {
+        let (_test_env, host_access) = setup().await;
+
+        for s in vec!["a", "b"] {
+            let _hash: EntryHash = crate::call_test_ribosome!(
+                host_access,
+                TestWasm::Query,
+                "add_path",
+                TestString::from(s.to_string())
+            );
+        }
+
+        let all: ElementVec = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Query,
+            "query",
+            ChainQueryFilter::new()
+        );
+        // elements are in chain order by default
+        assert!(all
+            .0
+            .windows(2)
+            .all(|w| w[0].header().header_seq() < w[1].header().header_seq()));
+
+        let tail: ElementVec = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Query,
+            "query",
+            ChainQueryFilter::new().sequence_range(3..)
+        );
+        assert_eq!(tail.0, all.0[3..].to_vec());
+
+        let page: ElementVec = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Query,
+            "query",
+            ChainQueryFilter::new().descending(true).offset(1).limit(2)
+        );
+        assert_eq!(
+            page.0,
+            all.0
+                .iter()
+                .rev()
+                .skip(1)
+                .take(2)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        let since = all.0[3].header().timestamp();
+        let recent: ElementVec = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Query,
+            "query",
+            ChainQueryFilter::new().timestamp_range(since..)
+        );
+        assert!(recent.0.len() >= all.0.len() - 3);
+        assert_eq!(
+            recent.0,
+            all.0
+                .iter()
+                .filter(|element| element.header().timestamp() >= since)
+                .cloned()
+                .collect::<Vec<_>>()
+        );
+
+        // cap claims are private entries
+        let claim = CapClaim::new("claim".into(), fake_agent_pubkey_2(), fixt!(CapSecret));
+        host_access
+            .workspace
+            .write()
+            .await
+            .source_chain
+            .put_cap_claim(claim.clone())
+            .await
+            .unwrap();
+        let claims = ChainQueryFilter::new()
+            .entry_type(EntryType::CapClaim)
+            .include_entries(true);
+
+        let public: ElementVec =
+            crate::call_test_ribosome!(host_access, TestWasm::Query, "query", claims.clone());
+        assert_eq!(public.0.len(), 1);
+        assert_eq!(public.0[0].entry().as_option(), None);
+
+        let private: ElementVec = crate::call_test_ribosome!(
+            host_access,
+            TestWasm::Query,
+            "query",
+            claims.include_private_entries(true)
+        );
+        assert_eq!(private.0.len(), 1);
+        assert_eq!(
+            private.0[0].entry().as_option(),
+            Some(&Entry::CapClaim(claim))
+        );
+    }
 }
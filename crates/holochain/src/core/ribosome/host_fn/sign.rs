@@ -1,6 +1,8 @@
+use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
+use crate::core::state::private_entries::is_private_entry_key_context;
 use holochain_keystore::keystore_actor::KeystoreSenderExt;
 use holochain_zome_types::SignInput;
 use holochain_zome_types::SignOutput;
@@ -11,6 +13,11 @@ pub fn sign(
     call_context: Arc<CallContext>,
     input: SignInput,
 ) -> RibosomeResult<SignOutput> {
+    // Signatures are deterministic, so signing this context would hand the
+    // zome the key the cell's private entries are encrypted with
+    if is_private_entry_key_context(input.inner_ref().data().bytes()) {
+        return Err(RibosomeError::ReservedSigningContext);
+    }
     Ok(SignOutput::new(
         tokio_safe_block_on::tokio_safe_block_forever_on(async move {
             call_context
@@ -33,6 +40,30 @@ pub mod wasm_test {
     use hdk3::prelude::*;
     use holochain_wasm_test_utils::TestWasm;
 
+    #[tokio::test(threaded_scheduler)]
+    /// zomes can't sign the context private entry keys are derived from
+    async fn sign_refuses_private_entry_key_context() {
+        use crate::core::ribosome::error::RibosomeError;
+        use crate::fixt::CallContextFixturator;
+        use crate::fixt::WasmRibosomeFixturator;
+        use std::sync::Arc;
+
+        let ribosome = WasmRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+            .next()
+            .unwrap();
+        let call_context = CallContextFixturator::new(fixt::Unpredictable)
+            .next()
+            .unwrap();
+        let mut data = b"holochain private entry key".to_vec();
+        data.extend_from_slice(&[0xdb; 36]);
+        let input = hdk3::prelude::holochain_zome_types::zome_io::SignInput::new(
+            SignInput::new_raw(fake_agent_pubkey_1(), data),
+        );
+
+        let result = super::sign(Arc::new(ribosome), Arc::new(call_context), input);
+        assert!(matches!(result, Err(RibosomeError::ReservedSigningContext)));
+    }
+
     #[tokio::test(threaded_scheduler)]
     async fn ribosome_sign_test() {
         let test_env = holochain_state::test_utils::test_cell_env();
@@ -3,6 +3,7 @@ use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::CallContext;
 use crate::core::{
     ribosome::RibosomeT,
+    sys_validate::check_entry_size,
     workflow::{integrate_dht_ops_workflow::integrate_to_cache, CallZomeWorkspace},
     SourceChainError,
 };
@@ -35,10 +36,13 @@ pub fn update<'a>(
     // extract the entry defs for a zome
     let entry_type = match entry_def_id {
         EntryDefId::App(entry_def_id) => {
-            let (header_entry_def_id, entry_visibility) =
-                extract_entry_def(ribosome, call_context.clone(), entry_def_id.into())?;
+            let (header_entry_def_id, entry_def) =
+                extract_entry_def(ribosome.clone(), call_context.clone(), entry_def_id.into())?;
+            // reject oversized entries before they reach the source chain
+            check_entry_size(&entry, ribosome.dna_file().dna().max_entry_size(&entry_def))
+                .map_err(Box::new)?;
             let app_entry_type =
-                AppEntryType::new(header_entry_def_id, header_zome_id, entry_visibility);
+                AppEntryType::new(header_entry_def_id, header_zome_id, entry_def.visibility);
             EntryType::App(app_entry_type)
         }
         EntryDefId::CapGrant => EntryType::CapGrant,
@@ -85,3 +89,47 @@ pub fn update<'a>(
 }
 
 // relying on tests for get_details
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use super::update;
+    use crate::core::ribosome::error::RibosomeError;
+    use crate::core::sys_validate::{SysValidationError, ValidationOutcome};
+    use crate::test_utils::host_fn_api::LimitedCallData;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::HeaderHashFixturator;
+    use holochain_types::dna::DnaLimits;
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::{entry_def::EntryDefId, Entry, UpdateInput};
+    use matches::assert_matches;
+    use std::convert::TryInto;
+    use test_wasm_common::TestBytes;
+
+    #[tokio::test(threaded_scheduler)]
+    /// oversized entries are rejected before the original is even looked up
+    async fn update_oversized_entry_test() {
+        let call_data = LimitedCallData::new(
+            TestWasm::Create,
+            DnaLimits {
+                max_entry_size: 4,
+                ..Default::default()
+            },
+        )
+        .await;
+        let big_entry = Entry::app(TestBytes(vec![0; 8]).try_into().unwrap()).unwrap();
+
+        // The original doesn't exist, so any later check would fail differently
+        let output = update(
+            call_data.ribosome,
+            call_data.call_context,
+            UpdateInput::new((EntryDefId::App("post".into()), big_entry, fixt!(HeaderHash))),
+        );
+        assert_matches!(
+            output,
+            Err(RibosomeError::SysValidationError(e))
+                if matches!(*e, SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(_, 4)))
+        );
+        call_data.assert_chain_unchanged().await;
+    }
+}
@@ -1,17 +1,23 @@
 use super::{
     guest_callback::{
-        entry_defs::EntryDefsHostAccess, init::InitHostAccess,
-        migrate_agent::MigrateAgentHostAccess, post_commit::PostCommitHostAccess,
-        validate::ValidateHostAccess, validation_package::ValidationPackageHostAccess,
+        entry_defs::EntryDefsHostAccess, genesis_self_check::GenesisSelfCheckHostAccess,
+        init::InitHostAccess, merge::MergeHostAccess, migrate_agent::MigrateAgentHostAccess,
+        post_commit::PostCommitHostAccess, validate::ValidateHostAccess,
+        validation_package::ValidationPackageHostAccess,
     },
     HostAccess, ZomeCallHostAccess,
 };
+use crate::conductor::entry_def_store::EntryDefBufferKey;
 use crate::core::ribosome::error::RibosomeError;
 use crate::core::ribosome::error::RibosomeResult;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsResult;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
 use crate::core::ribosome::guest_callback::init::InitResult;
+use crate::core::ribosome::guest_callback::merge::MergeInvocation;
+use crate::core::ribosome::guest_callback::merge::MergeResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
@@ -30,6 +36,7 @@ use crate::core::ribosome::host_fn::call_remote::call_remote;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::close_chain::close_chain;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::debug::debug;
@@ -39,10 +46,12 @@ use crate::core::ribosome::host_fn::delete_link::delete_link;
 use crate::core::ribosome::host_fn::emit_signal::emit_signal;
 use crate::core::ribosome::host_fn::encrypt::encrypt;
 use crate::core::ribosome::host_fn::get::get;
+use crate::core::ribosome::host_fn::get_agent_activity::get_agent_activity;
 use crate::core::ribosome::host_fn::get_details::get_details;
 use crate::core::ribosome::host_fn::get_link_details::get_link_details;
 use crate::core::ribosome::host_fn::get_links::get_links;
 use crate::core::ribosome::host_fn::hash_entry::hash_entry;
+use crate::core::ribosome::host_fn::open_chain::open_chain;
 use crate::core::ribosome::host_fn::property::property;
 use crate::core::ribosome::host_fn::query::query;
 use crate::core::ribosome::host_fn::random_bytes::random_bytes;
@@ -66,6 +75,8 @@ use holochain_types::dna::{
     DnaFile,
 };
 use holochain_wasmer_host::prelude::*;
+use holochain_zome_types::crdt::MergeCallbackResult;
+use holochain_zome_types::entry_def::EntryDef;
 use holochain_zome_types::entry_def::EntryDefsCallbackResult;
 use holochain_zome_types::init::InitCallbackResult;
 use holochain_zome_types::migrate_agent::MigrateAgentCallbackResult;
@@ -78,6 +89,7 @@ use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::CallbackResult;
 use holochain_zome_types::ZomeCallResponse;
 use holochain_zome_types::{header::ZomeId, ExternOutput};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 /// Path to the wasm cache path
@@ -92,12 +104,24 @@ pub struct WasmRibosome {
     //      - is already in the wasm cache, and only include the DnaDef portion
     //      - here in the ribosome.
     pub dna_file: DnaFile,
+    /// The entry defs the conductor stored for the DNA's zomes, so host
+    /// functions can look them up without calling the entry_defs callback
+    pub entry_defs: Arc<HashMap<EntryDefBufferKey, EntryDef>>,
 }
 
 impl WasmRibosome {
     /// Create a new instance
     pub fn new(dna_file: DnaFile) -> Self {
-        Self { dna_file }
+        Self {
+            dna_file,
+            entry_defs: Arc::new(HashMap::new()),
+        }
+    }
+
+    /// Use the entry defs the conductor stored for this DNA
+    pub fn with_entry_defs(mut self, entry_defs: HashMap<EntryDefBufferKey, EntryDef>) -> Self {
+        self.entry_defs = Arc::new(entry_defs);
+        self
     }
 
     pub fn module(&self, call_context: CallContext) -> RibosomeResult<Module> {
@@ -276,6 +300,10 @@ impl WasmRibosome {
                 func!(invoke_host_function!(get_link_details)),
             );
             ns.insert("__query", func!(invoke_host_function!(query)));
+            ns.insert(
+                "__get_agent_activity",
+                func!(invoke_host_function!(get_agent_activity)),
+            );
         } else {
             ns.insert("__get", func!(invoke_host_function!(unreachable)));
             ns.insert("__get_details", func!(invoke_host_function!(unreachable)));
@@ -285,6 +313,10 @@ impl WasmRibosome {
                 func!(invoke_host_function!(unreachable)),
             );
             ns.insert("__query", func!(invoke_host_function!(unreachable)));
+            ns.insert(
+                "__get_agent_activity",
+                func!(invoke_host_function!(unreachable)),
+            );
         }
 
         if let HostFnAccess {
@@ -310,6 +342,8 @@ impl WasmRibosome {
             ns.insert("__update", func!(invoke_host_function!(update)));
             ns.insert("__delete", func!(invoke_host_function!(delete)));
             ns.insert("__schedule", func!(invoke_host_function!(schedule)));
+            ns.insert("__open_chain", func!(invoke_host_function!(open_chain)));
+            ns.insert("__close_chain", func!(invoke_host_function!(close_chain)));
         } else {
             ns.insert("__call", func!(invoke_host_function!(unreachable)));
             ns.insert("__create", func!(invoke_host_function!(unreachable)));
@@ -319,6 +353,8 @@ impl WasmRibosome {
             ns.insert("__update", func!(invoke_host_function!(unreachable)));
             ns.insert("__delete", func!(invoke_host_function!(unreachable)));
             ns.insert("__schedule", func!(invoke_host_function!(unreachable)));
+            ns.insert("__open_chain", func!(invoke_host_function!(unreachable)));
+            ns.insert("__close_chain", func!(invoke_host_function!(unreachable)));
         }
         imports.register("env", ns);
 
@@ -364,6 +400,10 @@ impl RibosomeT for WasmRibosome {
         }
     }
 
+    fn stored_entry_def(&self, key: &EntryDefBufferKey) -> Option<EntryDef> {
+        self.entry_defs.get(key).cloned()
+    }
+
     fn zome_name_to_id(&self, zome_name: &ZomeName) -> RibosomeResult<ZomeId> {
         match self
             .dna_file()
@@ -474,6 +514,22 @@ impl RibosomeT for WasmRibosome {
         do_callback!(self, access, invocation, InitCallbackResult)
     }
 
+    fn run_merge(
+        &self,
+        access: MergeHostAccess,
+        invocation: MergeInvocation,
+    ) -> RibosomeResult<MergeResult> {
+        do_callback!(self, access, invocation, MergeCallbackResult)
+    }
+
+    fn run_genesis_self_check(
+        &self,
+        access: GenesisSelfCheckHostAccess,
+        invocation: GenesisSelfCheckInvocation,
+    ) -> RibosomeResult<GenesisSelfCheckResult> {
+        do_callback!(self, access, invocation, ValidateCallbackResult)
+    }
+
     fn run_entry_defs(
         &self,
         access: EntryDefsHostAccess,
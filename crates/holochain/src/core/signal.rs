@@ -3,33 +3,192 @@
 //! - App-defined signals are produced via the `emit_signal!` host function.
 //! - System-defined signals are produced in various places in the system
 
+use holo_hash::{AgentPubKey, DhtOpHash, HeaderHash};
 use holochain_serialized_bytes::prelude::*;
-use holochain_types::{cell::CellId, impl_from};
+use holochain_types::{app::AppId, cell::CellId, impl_from};
+use holochain_zome_types::zome::ZomeName;
 
 /// A Signal is some information emitted from within Holochain out through
 /// an Interface
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum Signal {
-    /// Signal from a Cell, generated by `emit_signal!`
-    App(CellId, SerializedBytes),
+    /// Signal from a Cell, generated by `emit_signal!` in the given zome
+    App(CellId, ZomeName, SerializedBytes),
     /// System-defined signals
     System(SystemSignal),
 }
 
+impl Signal {
+    /// The Cell this signal concerns, if any
+    pub fn cell_id(&self) -> Option<&CellId> {
+        match self {
+            Signal::App(cell_id, _, _) => Some(cell_id),
+            Signal::System(signal) => signal.cell_id(),
+        }
+    }
+
+    /// The zome which emitted this signal, if it is app-defined
+    pub fn zome_name(&self) -> Option<&ZomeName> {
+        match self {
+            Signal::App(_, zome_name, _) => Some(zome_name),
+            Signal::System(_) => None,
+        }
+    }
+
+    /// The kind of this signal, for filtering
+    pub fn kind(&self) -> SignalKind {
+        match self {
+            Signal::App(..) => SignalKind::App,
+            Signal::System(signal) => signal.kind(),
+        }
+    }
+
+    /// The app-defined type tag of an app signal.
+    /// This is the `type` field of the payload, if the payload is a map
+    /// with a string `type` field, as produced by serializing an enum
+    /// with `#[serde(tag = "type")]`.
+    pub fn type_tag(&self) -> Option<String> {
+        #[derive(Deserialize)]
+        struct TypeTag {
+            #[serde(rename = "type")]
+            type_tag: String,
+        }
+        match self {
+            Signal::App(_, _, bytes) => {
+                let tag: Result<TypeTag, _> = holochain_serialized_bytes::decode(bytes.bytes());
+                tag.ok().map(|t| t.type_tag)
+            }
+            Signal::System(_) => None,
+        }
+    }
+}
+
+/// The kinds of [Signal], without their contents
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum SignalKind {
+    /// An app-defined signal
+    App,
+    /// [SystemSignal::OpPublished]
+    OpPublished,
+    /// [SystemSignal::OpValidated]
+    OpValidated,
+    /// [SystemSignal::OpRejected]
+    OpRejected,
+    /// [SystemSignal::ValidationReceiptReceived]
+    ValidationReceiptReceived,
+    /// [SystemSignal::LocalPeerJoined]
+    LocalPeerJoined,
+    /// [SystemSignal::LocalPeerLeft]
+    LocalPeerLeft,
+    /// [SystemSignal::CellActivated]
+    CellActivated,
+    /// [SystemSignal::CellDeactivated]
+    CellDeactivated,
+    /// [SystemSignal::AppActivated]
+    AppActivated,
+    /// [SystemSignal::AppDeactivated]
+    AppDeactivated,
+}
+
 /// A Signal which originates from within the Holochain system, as opposed to
 /// from within a Cell
-///
-/// TODO, decide what these will be. For instance, maybe there is a
-/// DataAvailable signal for doing async network requests
 #[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
 pub enum SystemSignal {
-    /// Since we have no real system signals, we use a test signal for testing
-    /// TODO: replace instances of this with something real
-    Test(String),
+    /// A DhtOp produced from one of this Cell's own headers
+    /// was published to the network
+    OpPublished {
+        /// The Cell which published the op
+        cell_id: CellId,
+        /// The published op
+        op_hash: DhtOpHash,
+    },
+    /// A DhtOp produced from one of this Cell's own headers
+    /// was integrated as valid
+    OpValidated {
+        /// The Cell which authored the header
+        cell_id: CellId,
+        /// The validated op
+        op_hash: DhtOpHash,
+        /// The header the op was produced from
+        header_hash: HeaderHash,
+    },
+    /// A DhtOp produced from one of this Cell's own headers
+    /// was integrated as rejected
+    OpRejected {
+        /// The Cell which authored the header
+        cell_id: CellId,
+        /// The rejected op
+        op_hash: DhtOpHash,
+        /// The header the op was produced from
+        header_hash: HeaderHash,
+    },
+    /// A remote validator sent this Cell a receipt for one of its ops
+    ValidationReceiptReceived {
+        /// The Cell which received the receipt
+        cell_id: CellId,
+        /// The op the receipt is for
+        op_hash: DhtOpHash,
+        /// The agent which validated the op
+        validator: AgentPubKey,
+    },
+    /// Another Cell of the same Dna was started on this conductor.
+    /// Remote agents joining the network space are not reported.
+    LocalPeerJoined {
+        /// The Cell whose space was joined
+        cell_id: CellId,
+        /// The agent of the Cell which was started
+        agent: AgentPubKey,
+    },
+    /// Another Cell of the same Dna was stopped on this conductor.
+    /// Remote agents leaving the network space are not reported.
+    LocalPeerLeft {
+        /// The Cell whose space was left
+        cell_id: CellId,
+        /// The agent of the Cell which was stopped
+        agent: AgentPubKey,
+    },
+    /// A Cell was started by the conductor
+    CellActivated(CellId),
+    /// A Cell was stopped by the conductor
+    CellDeactivated(CellId),
+    /// An App was activated
+    AppActivated(AppId),
+    /// An App was deactivated
+    AppDeactivated(AppId),
 }
 
-pub fn test_signal(s: &str) -> Signal {
-    SystemSignal::Test(s.to_string()).into()
+impl SystemSignal {
+    /// The Cell this signal concerns, if any.
+    /// App activation signals concern no single Cell.
+    pub fn cell_id(&self) -> Option<&CellId> {
+        match self {
+            SystemSignal::OpPublished { cell_id, .. }
+            | SystemSignal::OpValidated { cell_id, .. }
+            | SystemSignal::OpRejected { cell_id, .. }
+            | SystemSignal::ValidationReceiptReceived { cell_id, .. }
+            | SystemSignal::LocalPeerJoined { cell_id, .. }
+            | SystemSignal::LocalPeerLeft { cell_id, .. }
+            | SystemSignal::CellActivated(cell_id)
+            | SystemSignal::CellDeactivated(cell_id) => Some(cell_id),
+            SystemSignal::AppActivated(_) | SystemSignal::AppDeactivated(_) => None,
+        }
+    }
+
+    /// The kind of this signal, for filtering
+    pub fn kind(&self) -> SignalKind {
+        match self {
+            SystemSignal::OpPublished { .. } => SignalKind::OpPublished,
+            SystemSignal::OpValidated { .. } => SignalKind::OpValidated,
+            SystemSignal::OpRejected { .. } => SignalKind::OpRejected,
+            SystemSignal::ValidationReceiptReceived { .. } => SignalKind::ValidationReceiptReceived,
+            SystemSignal::LocalPeerJoined { .. } => SignalKind::LocalPeerJoined,
+            SystemSignal::LocalPeerLeft { .. } => SignalKind::LocalPeerLeft,
+            SystemSignal::CellActivated(_) => SignalKind::CellActivated,
+            SystemSignal::CellDeactivated(_) => SignalKind::CellDeactivated,
+            SystemSignal::AppActivated(_) => SignalKind::AppActivated,
+            SystemSignal::AppDeactivated(_) => SignalKind::AppDeactivated,
+        }
+    }
 }
 
 impl_from! {
@@ -32,6 +32,7 @@
 //! source: https://textik.com/#d7907793784e17e9
 //! ```
 
+pub mod cache_access;
 #[allow(missing_docs)]
 pub mod cascade;
 #[allow(missing_docs)]
@@ -40,6 +41,7 @@ pub mod dht_op_integration;
 #[allow(missing_docs)]
 pub mod element_buf;
 pub mod metadata;
+pub mod private_entries;
 #[allow(missing_docs)]
 pub mod source_chain;
 pub mod validation_db;
@@ -0,0 +1,60 @@
+//! Records when the [Cascade](super::cascade::Cascade) last used each basis
+//! hash of a cell's cache, so the cache can be pruned least recently used first.
+//!
+//! Reads of the cache happen in read-only transactions, so uses are collected
+//! in memory and written to the [CACHE_AGE](holochain_state::db::CACHE_AGE)
+//! database by the next pass of the prune cache workflow.
+
+use holochain_state::env::EnvironmentRead;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+lazy_static::lazy_static! {
+    /// When each basis was last used, in seconds since the epoch, by environment path
+    static ref LAST_USED: Mutex<HashMap<PathBuf, HashMap<Vec<u8>, u64>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Record that the cached data about this basis was used just now
+pub fn touch<B: AsRef<[u8]>>(env: &EnvironmentRead, basis: B) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    touch_at(env, basis, now)
+}
+
+/// Record that the cached data about this basis was used at `when`,
+/// in seconds since the epoch
+pub fn touch_at<B: AsRef<[u8]>>(env: &EnvironmentRead, basis: B, when: u64) {
+    let mut last_used = LAST_USED.lock();
+    let used = last_used
+        .entry(env.path().clone())
+        .or_default()
+        .entry(basis.as_ref().to_vec())
+        .or_insert(when);
+    *used = (*used).max(when);
+}
+
+/// Take every use recorded for this environment since the last call
+pub fn take(env: &EnvironmentRead) -> HashMap<Vec<u8>, u64> {
+    LAST_USED.lock().remove(env.path()).unwrap_or_default()
+}
+
+/// Put back uses taken by a prune pass which failed,
+/// so the next pass still writes them
+pub fn restore(env: &EnvironmentRead, used: HashMap<Vec<u8>, u64>) {
+    let mut last_used = LAST_USED.lock();
+    let recorded = last_used.entry(env.path().clone()).or_default();
+    for (basis, when) in used {
+        let last = recorded.entry(basis).or_insert(when);
+        *last = (*last).max(when);
+    }
+}
+
+/// Drop every use recorded for an environment which is being closed
+pub fn forget(env: &EnvironmentRead) {
+    LAST_USED.lock().remove(env.path());
+}
@@ -5,6 +5,7 @@
 //! the appropriate validation has been run.
 
 use super::{
+    cache_access,
     element_buf::ElementBuf,
     metadata::{LinkMetaKey, MetadataBuf, MetadataBufT},
 };
@@ -14,10 +15,10 @@ use crate::core::workflow::{
 };
 use error::CascadeResult;
 use fallible_iterator::FallibleIterator;
-use holo_hash::{hash_type::AnyDht, AnyDhtHash, EntryHash, HeaderHash};
+use holo_hash::{hash_type::AnyDht, AgentPubKey, AnyDhtHash, EntryHash, HeaderHash};
 use holochain_p2p::HolochainP2pCellT;
 use holochain_p2p::{
-    actor::{GetLinksOptions, GetMetaOptions, GetOptions},
+    actor::{GetActivityOptions, GetLinksOptions, GetMetaOptions, GetOptions},
     HolochainP2pCell,
 };
 use holochain_state::{error::DatabaseResult, fresh_reader, prelude::*};
@@ -38,11 +39,12 @@ use holochain_zome_types::{
     header::{Delete, Update},
     link::Link,
     metadata::{Details, ElementDetails, EntryDetails},
+    query::{AgentActivity, ChainQueryFilter, ChainStatus},
     Header,
 };
 use std::convert::TryFrom;
 use std::{
-    collections::{BTreeMap, BTreeSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     convert::TryInto,
 };
 use tracing::*;
@@ -275,6 +277,13 @@ where
         }
     }
 
+    /// Record that the cached data about this basis is being used
+    fn touch_cache<B: AsRef<[u8]>>(&self, basis: B) {
+        if let (Some(_), Some(env)) = (self.cache_data.as_ref(), self.env.as_ref()) {
+            cache_access::touch(env, basis);
+        }
+    }
+
     async fn update_stores(&mut self, element: Element) -> CascadeResult<()> {
         let cache_data = ok_or_return!(self.cache_data.as_mut());
         let op_lights = produce_op_lights_from_elements(vec![&element]).await?;
@@ -631,6 +640,7 @@ where
         options: GetOptions,
     ) -> CascadeResult<Option<EntryDetails>> {
         debug!("in get entry details");
+        self.touch_cache(&entry_hash);
         // Update the cache from the network
         self.fetch_element_via_entry(entry_hash.clone(), options.clone())
             .await?;
@@ -648,6 +658,7 @@ where
         options: GetOptions,
     ) -> CascadeResult<Option<Element>> {
         debug!("in get entry");
+        self.touch_cache(&entry_hash);
         // Update the cache from the network
         self.fetch_element_via_entry(entry_hash.clone(), options.clone())
             .await?;
@@ -673,16 +684,20 @@ where
                                 Ok(None)
                             }
                         })
-                        .min()?
-                        .expect("Status is live but no headers?");
-
-                    // We have an oldest live header now get the element
-                    CascadeResult::Ok(
-                        self.get_element_local_raw(&oldest_live_header.header_hash)?
-                            .map(Search::Found)
-                            // It's not local so check the network
-                            .unwrap_or(Search::Continue(oldest_live_header.header_hash)),
-                    )
+                        .min()?;
+
+                    match oldest_live_header {
+                        // We have an oldest live header now get the element
+                        Some(oldest_live_header) => CascadeResult::Ok(
+                            self.get_element_local_raw(&oldest_live_header.header_hash)?
+                                .map(Search::Found)
+                                // It's not local so check the network
+                                .unwrap_or(Search::Continue(oldest_live_header.header_hash)),
+                        ),
+                        // The headers are gone from the cache so
+                        // there's nothing to go on
+                        None => CascadeResult::Ok(Search::NotInCascade),
+                    }
                 }
                 EntryDhtStatus::Dead
                 | EntryDhtStatus::Pending
@@ -704,6 +719,35 @@ where
         }
     }
 
+    #[instrument(skip(self, options))]
+    /// Returns every live [Element] that updates this [EntryHash], oldest first.
+    /// Call after [Cascade::dht_get_entry] so the cache already has
+    /// the latest update metadata from the authorities.
+    pub async fn dht_get_entry_updates(
+        &mut self,
+        entry_hash: EntryHash,
+        options: GetOptions,
+    ) -> CascadeResult<Vec<Element>> {
+        let cache_data = ok_or_return!(self.cache_data.as_ref(), vec![]);
+        let env = ok_or_return!(self.env.as_ref(), vec![]);
+        let updates = fresh_reader!(env, |r| cache_data
+            .meta
+            .get_updates(&r, entry_hash.into())?
+            .collect::<BTreeSet<_>>())?;
+
+        let mut elements = Vec::with_capacity(updates.len());
+        for update in updates {
+            // Deleted updates are not live
+            if let Some(element) = self
+                .dht_get_header(update.header_hash, options.clone())
+                .await?
+            {
+                elements.push(element);
+            }
+        }
+        Ok(elements)
+    }
+
     #[instrument(skip(self, options))]
     pub async fn get_header_details(
         &mut self,
@@ -711,6 +755,7 @@ where
         options: GetOptions,
     ) -> CascadeResult<Option<ElementDetails>> {
         debug!("in get header details");
+        self.touch_cache(&header_hash);
         // Network
         self.fetch_element_via_header(header_hash.clone(), options)
             .await?;
@@ -733,6 +778,7 @@ where
         let integrated_data = ok_or_return!(self.integrated_data.as_ref(), None);
         let env = ok_or_return!(self.env.as_ref(), None);
         debug!("in get header");
+        cache_access::touch(env, &header_hash);
         let found_local_delete = fresh_reader!(env, |r| {
             let in_cache = || {
                 DatabaseResult::Ok({
@@ -789,6 +835,7 @@ where
         hash: EntryHash,
         options: GetOptions,
     ) -> CascadeResult<Option<EntryHashed>> {
+        self.touch_cache(&hash);
         match self.get_entry_local_raw(&hash)? {
             Some(e) => Ok(Some(e)),
             None => {
@@ -809,6 +856,7 @@ where
         hash: HeaderHash,
         options: GetOptions,
     ) -> CascadeResult<Option<SignedHeaderHashed>> {
+        self.touch_cache(&hash);
         match self.get_header_local_raw_with_sig(&hash)? {
             Some(h) => Ok(Some(h)),
             None => {
@@ -831,6 +879,7 @@ where
         hash: AnyDhtHash,
         options: GetOptions,
     ) -> CascadeResult<Option<Element>> {
+        self.touch_cache(&hash);
         match *hash.hash_type() {
             AnyDht::Entry => {
                 let hash = hash.into();
@@ -899,6 +948,7 @@ where
         key: &'link LinkMetaKey<'link>,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<Link>> {
+        self.touch_cache(key.base());
         // Update the cache from the network
         self.fetch_links(key.into(), options).await?;
 
@@ -915,6 +965,23 @@ where
         })
     }
 
+    #[instrument(skip(self, options))]
+    /// Get the activity of an agent from the authorities for its key.
+    /// Activity is not cached because only the authorities
+    /// can say what state an agent's chain is in.
+    pub async fn get_agent_activity(
+        &mut self,
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: GetActivityOptions,
+    ) -> CascadeResult<AgentActivity> {
+        let network = ok_or_return!(self.network.as_mut(), AgentActivity::default());
+        let results = network
+            .get_agent_activity(agent, query.clone(), options)
+            .await?;
+        Ok(merge_agent_activity(&query, results))
+    }
+
     #[instrument(skip(self, key, options))]
     /// Return all CreateLink headers
     /// and DeleteLink headers ordered by time.
@@ -923,6 +990,7 @@ where
         key: &'link LinkMetaKey<'link>,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<(CreateLink, Vec<DeleteLink>)>> {
+        self.touch_cache(key.base());
         // Update the cache from the network
         self.fetch_links(key.into(), options).await?;
 
@@ -968,6 +1036,43 @@ where
     }
 }
 
+/// Merge the activity returned by several authorities for the same agent.
+/// The chain takes the worst status any authority reports and the highest
+/// sequence number any of them has seen, and the headers are deduplicated.
+/// Each authority has already applied the query's offset, so only the
+/// order and limit are applied again.
+fn merge_agent_activity(query: &ChainQueryFilter, responses: Vec<AgentActivity>) -> AgentActivity {
+    fn severity(status: ChainStatus) -> u8 {
+        match status {
+            ChainStatus::Empty => 0,
+            ChainStatus::Valid => 1,
+            ChainStatus::Invalid => 2,
+            ChainStatus::Forked => 3,
+        }
+    }
+    let mut merged = AgentActivity::default();
+    let mut seen = HashSet::new();
+    for response in responses {
+        if severity(response.status) > severity(merged.status) {
+            merged.status = response.status;
+        }
+        merged.highest_observed = merged.highest_observed.max(response.highest_observed);
+        for header in response.headers {
+            if seen.insert(header.header_address().clone()) {
+                merged.headers.push(header);
+            }
+        }
+    }
+    merged.headers.sort_by_key(|h| h.header().header_seq());
+    if query.descending {
+        merged.headers.reverse();
+    }
+    if let Some(limit) = query.limit {
+        merged.headers.truncate(limit as usize);
+    }
+    merged
+}
+
 impl<'a, M: MetadataBufT> From<&'a DbPairMut<'a, M>> for DbPair<'a, M> {
     fn from(n: &'a DbPairMut<'a, M>) -> Self {
         Self {
@@ -173,6 +173,7 @@ async fn get_from_another_agent() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -348,6 +349,7 @@ async fn get_links_from_another_agent() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
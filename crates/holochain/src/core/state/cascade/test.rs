@@ -1,11 +1,11 @@
-use super::Cascade;
+use super::{merge_agent_activity, Cascade};
 use crate::core::state::{
     element_buf::ElementBuf,
     metadata::{LinkMetaKey, MockMetadataBuf},
     source_chain::{SourceChainBuf, SourceChainResult},
 };
 use crate::{
-    fixt::{LinkMetaValFixturator, ZomeIdFixturator},
+    fixt::{CreateLinkFixturator, LinkMetaValFixturator, ZomeIdFixturator},
     test_utils::test_network,
 };
 use ::fixt::prelude::*;
@@ -23,6 +23,7 @@ use holochain_types::{
     HeaderHashed,
 };
 use holochain_zome_types::link::LinkTag;
+use holochain_zome_types::query::{AgentActivity, ChainQueryFilter, ChainStatus};
 use holochain_zome_types::{header, Entry, Header};
 use mockall::*;
 
@@ -507,3 +508,56 @@ async fn links_notauth_cache() -> DatabaseResult<()> {
     // this is implied by the mock not expecting calls
     Ok(())
 }
+
+fn activity_header(header_seq: u32) -> SignedHeaderHashed {
+    let mut header = fixt!(CreateLink);
+    header.author = fake_agent_pubkey_1();
+    header.header_seq = header_seq;
+    SignedHeaderHashed::with_presigned(
+        HeaderHashed::from_content_sync(header.into()),
+        fixt!(Signature),
+    )
+}
+
+#[test]
+fn agent_activity_is_merged_across_authorities() {
+    let h1 = activity_header(1);
+    let h2 = activity_header(2);
+    let h3 = activity_header(3);
+    let responses = vec![
+        AgentActivity {
+            headers: vec![h2.clone(), h3.clone()],
+            status: ChainStatus::Valid,
+            highest_observed: Some(3),
+        },
+        AgentActivity {
+            headers: vec![h1.clone(), h2.clone()],
+            status: ChainStatus::Forked,
+            highest_observed: Some(2),
+        },
+        AgentActivity {
+            headers: vec![h1.clone()],
+            status: ChainStatus::Invalid,
+            highest_observed: Some(4),
+        },
+        AgentActivity::default(),
+    ];
+
+    // The worst status and highest sequence number win
+    // and each header is only returned once
+    let merged = merge_agent_activity(&ChainQueryFilter::new(), responses.clone());
+    assert_eq!(merged.status, ChainStatus::Forked);
+    assert_eq!(merged.highest_observed, Some(4));
+    assert_eq!(merged.headers, vec![h1.clone(), h2.clone(), h3.clone()]);
+
+    // The order and limit still apply
+    let query = ChainQueryFilter::new().descending(true).limit(2);
+    let merged = merge_agent_activity(&query, responses);
+    assert_eq!(merged.headers, vec![h3, h2]);
+
+    // Nothing to merge is an empty chain
+    assert_eq!(
+        merge_agent_activity(&ChainQueryFilter::new(), vec![]),
+        AgentActivity::default()
+    );
+}
@@ -7,6 +7,9 @@
 /// using the ElementBuf for caching non-authored data, or for situations where
 /// it is known that private entries should be protected, such as when handling
 /// a get_entry request from the network.
+///
+/// Private entries are encrypted at rest, see [private_entries](super::private_entries).
+use crate::core::state::private_entries::PrivateEntryBuf;
 use crate::core::state::source_chain::SourceChainResult;
 use holo_hash::{EntryHash, HasHash, HeaderHash};
 use holochain_state::{
@@ -39,7 +42,7 @@ where
     P: PrefixType,
 {
     public_entries: EntryCas<P>,
-    private_entries: Option<EntryCas<P>>,
+    private_entries: Option<PrivateEntryBuf<P>>,
     headers: HeaderCas<P>,
 }
 
@@ -88,7 +91,7 @@ where
         headers_store: SingleStore,
     ) -> DatabaseResult<Self> {
         let private_entries = if let Some(store) = private_entries_store {
-            Some(CasBufFreshSync::new(env.clone(), store))
+            Some(PrivateEntryBuf::new(env.clone(), store)?)
         } else {
             None
         };
@@ -204,7 +207,7 @@ where
                     EntryVisibility::Public => self.public_entries.put(entry),
                     EntryVisibility::Private => {
                         if let Some(db) = self.private_entries.as_mut() {
-                            db.put(entry);
+                            db.put(entry)?;
                         } else {
                             error!("Attempted ElementBuf::put on a private entry with a disabled private DB: {}", entry.as_hash());
                         }
@@ -231,7 +234,7 @@ where
             EntryVisibility::Public => self.public_entries.put(entry),
             EntryVisibility::Private => {
                 if let Some(db) = self.private_entries.as_mut() {
-                    db.put(entry);
+                    db.put(entry)?;
                 } else {
                     error!("Attempted ElementBuf::put on a private entry with a disabled private DB: {}", entry.as_hash());
                 }
@@ -269,7 +272,7 @@ where
         &self.public_entries
     }
 
-    pub fn private_entries(&self) -> Option<&EntryCas<P>> {
+    pub fn private_entries(&self) -> Option<&PrivateEntryBuf<P>> {
         self.private_entries.as_ref()
     }
 
@@ -313,8 +316,8 @@ impl<P: PrefixType> BufferedStore for ElementBuf<P> {
 #[cfg(test)]
 mod tests {
 
-    use super::ElementBuf;
     use crate::test_utils::fake_unique_element;
+    use crate::test_utils::{fake_unique_element, load_private_entry_key};
     use holo_hash::*;
     use holochain_keystore::test_keystore::spawn_test_keystore;
     use holochain_keystore::AgentPubKeyExt;
@@ -326,6 +329,7 @@ mod tests {
         let keystore = spawn_test_keystore().await?;
         let test_env = test_cell_env();
         let arc = test_env.env();
+        load_private_entry_key(&arc).await;
         let env = arc.guard();
 
         let agent_key = AgentPubKey::new_from_pure_entropy(&keystore).await?;
@@ -6,7 +6,7 @@
 
 use fallible_iterator::FallibleIterator;
 use holo_hash::HasHash;
-use holo_hash::{AgentPubKey, AnyDhtHash, EntryHash, HeaderHash};
+use holo_hash::{AgentPubKey, AnyDhtHash, EntryHash, HeaderHash, HOLO_HASH_SERIALIZED_LEN};
 use holochain_serialized_bytes::prelude::*;
 use holochain_state::{
     buffer::{KvBufUsed, KvvBufUsed},
@@ -20,7 +20,7 @@ use holochain_state::{
 };
 use holochain_types::metadata::{EntryDhtStatus, TimedHeaderHash};
 use holochain_types::{header::NewEntryHeader, link::WireLinkMetaKey};
-use holochain_types::{HeaderHashed, Timestamp};
+use holochain_types::{warrant::Warrant, HeaderHashed, Timestamp};
 use holochain_zome_types::header::{self, CreateLink, DeleteLink, ZomeId};
 use holochain_zome_types::{link::LinkTag, Header};
 use std::fmt::Debug;
@@ -151,6 +151,16 @@ where
         key: ChainItemKey,
     ) -> DatabaseResult<Box<dyn FallibleIterator<Item = TimedHeaderHash, Error = DatabaseError> + '_>>;
 
+    /// Registers a [Warrant] against the agent it accuses
+    fn register_warrant(&mut self, warrant: Warrant) -> DatabaseResult<()>;
+
+    /// Returns all the [Warrant]s held against an agent
+    fn get_warrants<'r, R: Readable>(
+        &'r self,
+        reader: &'r R,
+        agent: AgentPubKey,
+    ) -> DatabaseResult<Box<dyn FallibleIterator<Item = Warrant, Error = DatabaseError> + '_>>;
+
     /// Returns all the hashes of [Update] headers registered on an [Entry]
     fn get_updates<'r, R: Readable>(
         &'r self,
@@ -235,6 +245,67 @@ impl MetadataBuf<IntegratedPrefix> {
         let misc_meta = env.get_db(&*CACHE_STATUS_META)?;
         Self::new(env, system_meta, links_meta, misc_meta)
     }
+
+    /// The basis hash a key of the cache's misc metadata database is about,
+    /// so everything cached about one entry, header or agent can be pruned together
+    pub(crate) fn cache_misc_basis(key: &[u8]) -> Option<Vec<u8>> {
+        // Skip the integrated prefix
+        let (misc_prefix, rest) = key.get(1..)?.split_first()?;
+        match *misc_prefix {
+            EntryStatusPrefix::PREFIX => {
+                let hash: EntryHash = holochain_serialized_bytes::decode(rest).ok()?;
+                Some(hash.into_inner())
+            }
+            StoreElementPrefix::PREFIX => {
+                let hash: HeaderHash = holochain_serialized_bytes::decode(rest).ok()?;
+                Some(hash.into_inner())
+            }
+            ChainItemPrefix::PREFIX | WarrantPrefix::PREFIX => {
+                rest.get(..HOLO_HASH_SERIALIZED_LEN).map(<[u8]>::to_vec)
+            }
+            _ => None,
+        }
+    }
+
+    /// Delete everything the cache's misc metadata database holds about this basis
+    pub(crate) fn evict_cache_misc(
+        misc_meta: SingleStore,
+        writer: &mut Writer,
+        basis: &[u8],
+    ) -> DatabaseResult<()> {
+        let entry_status: PrefixBytesKey =
+            MiscMetaKey::entry_status(&EntryHash::from_raw_bytes(basis.to_vec())).into();
+        misc_meta.delete(writer, entry_status)?;
+        let store_element: PrefixBytesKey =
+            MiscMetaKey::store_element(&HeaderHash::from_raw_bytes(basis.to_vec())).into();
+        misc_meta.delete(writer, store_element)?;
+        for misc_prefix in &[ChainItemPrefix::PREFIX, WarrantPrefix::PREFIX] {
+            let mut prefix = vec![IntegratedPrefix::PREFIX, *misc_prefix];
+            prefix.extend_from_slice(basis);
+            delete_prefixed(misc_meta, writer, &prefix)?;
+        }
+        Ok(())
+    }
+}
+
+/// Delete every key of a database which starts with `prefix`
+pub(crate) fn delete_prefixed(
+    store: SingleStore,
+    writer: &mut Writer,
+    prefix: &[u8],
+) -> DatabaseResult<()> {
+    let mut keys = Vec::new();
+    for kv in store.iter_from(writer, prefix)? {
+        let (k, _) = kv?;
+        if !k.starts_with(prefix) {
+            break;
+        }
+        keys.push(k.to_vec());
+    }
+    for k in keys {
+        store.delete(writer, k)?;
+    }
+    Ok(())
 }
 
 impl MetadataBuf<PendingPrefix> {
@@ -531,6 +602,24 @@ where
         ))
     }
 
+    fn register_warrant(&mut self, warrant: Warrant) -> DatabaseResult<()> {
+        let key = MiscMetaKey::warrant(warrant.author(), Some(warrant.header_seq())).into();
+        self.misc_meta.put(key, MiscMetaValue::Warrant(warrant))
+    }
+
+    fn get_warrants<'r, R: Readable>(
+        &'r self,
+        r: &'r R,
+        agent: AgentPubKey,
+    ) -> DatabaseResult<Box<dyn FallibleIterator<Item = Warrant, Error = DatabaseError> + '_>> {
+        let k = MiscMetaKey::warrant(&agent, None).into();
+        Ok(Box::new(
+            self.misc_meta
+                .iter_all_key_matches(r, k)?
+                .map(|(_, v)| Ok(MiscMetaValue::warrant(v))),
+        ))
+    }
+
     fn get_updates<'r, R: Readable>(
         &'r self,
         r: &'r R,
@@ -30,6 +30,12 @@ impl PrefixType for ChainItemPrefix {
     const PREFIX: u8 = 0x2;
 }
 
+#[derive(PartialOrd, Clone, Ord, PartialEq, Eq, Debug)]
+pub struct WarrantPrefix;
+impl PrefixType for WarrantPrefix {
+    const PREFIX: u8 = 0x3;
+}
+
 impl<P: PrefixType> MiscMetaKey<P> {
     /// Create a new prefix bytes key
     pub fn new<I: IntoIterator<Item = u8>>(bytes: I) -> Self {
@@ -44,7 +50,7 @@ impl<P: PrefixType> MiscMetaKey<P> {
     }
 }
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 /// Values for the misc kv
 /// Matches the key
 pub enum MiscMetaValue {
@@ -55,6 +61,8 @@ pub enum MiscMetaValue {
     /// There is a header at this key.
     /// We store the timestamp so headers can be ordered.
     ChainItem(Timestamp),
+    /// A warrant against the agent at this key
+    Warrant(Warrant),
 }
 
 impl MiscMetaKey<EntryStatusPrefix> {
@@ -83,6 +91,19 @@ impl MiscMetaKey<ChainItemPrefix> {
     }
 }
 
+impl MiscMetaKey<WarrantPrefix> {
+    /// Create a key for the warrants against an agent,
+    /// optionally narrowed to one position of their chain
+    pub fn warrant(agent: &AgentPubKey, header_seq: Option<u32>) -> MiscMetaKey<WarrantPrefix> {
+        let key = match header_seq {
+            Some(seq) => ChainItemKey::AgentSequence(agent.clone(), seq),
+            None => ChainItemKey::Agent(agent.clone()),
+        };
+        let bytes: BytesKey = (&key).into();
+        MiscMetaKey::new(bytes.0.into_iter())
+    }
+}
+
 impl<PM, PB> From<MiscMetaKey<PM>> for PrefixBytesKey<PB>
 where
     PM: PrefixType,
@@ -121,6 +142,13 @@ impl MiscMetaValue {
         }
     }
 
+    pub fn warrant(self) -> Warrant {
+        match self {
+            MiscMetaValue::Warrant(w) => w,
+            _ => unreachable!("Tried to go from {:?} to {:?}", self, "warrant"),
+        }
+    }
+
     pub fn new_store_element() -> Self {
         Self::StoreElement
     }
@@ -44,6 +44,11 @@ mock! {
             &self,
             key: ChainItemKey,
         ) -> DatabaseResult<Box<dyn FallibleIterator<Item = TimedHeaderHash, Error = DatabaseError>>>;
+        fn sync_register_warrant(&mut self, warrant: Warrant) -> DatabaseResult<()>;
+        fn get_warrants(
+            &self,
+            agent: AgentPubKey,
+        ) -> DatabaseResult<Box<dyn FallibleIterator<Item = Warrant, Error = DatabaseError>>>;
         fn get_updates(
             &self,
             hash: AnyDhtHash,
@@ -121,6 +126,18 @@ impl MetadataBufT for MockMetadataBuf {
         self.get_activity(key)
     }
 
+    fn register_warrant(&mut self, warrant: Warrant) -> DatabaseResult<()> {
+        self.sync_register_warrant(warrant)
+    }
+
+    fn get_warrants<'r, R: Readable>(
+        &'r self,
+        _reader: &'r R,
+        agent: AgentPubKey,
+    ) -> DatabaseResult<Box<dyn FallibleIterator<Item = Warrant, Error = DatabaseError> + '_>> {
+        self.get_warrants(agent)
+    }
+
     fn get_updates<'r, R: Readable>(
         &'r self,
         _reader: &'r R,
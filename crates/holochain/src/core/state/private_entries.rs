@@ -0,0 +1,372 @@
+//! Private entries, including capability grants and their secrets,
+//! are encrypted at rest.
+//!
+//! Each cell has its own [PrivateEntryKey], derived by signing a fixed context
+//! with the cell's agent key in the keystore, so the key is never written to disk.
+//! Zomes can't sign that context (see [is_private_entry_key_context]), so they
+//! can't derive the key either. The key is loaded when the cell starts up.
+//! [PrivateEntryBuf] encrypts entries when they are put and decrypts them when
+//! they are read, so readers of an [ElementBuf](super::element_buf::ElementBuf)
+//! only ever see plaintext.
+//!
+//! Environments written before private entries were encrypted hold plaintext.
+//! It can still be read, and [migrate_private_entries] encrypts it in place.
+
+use holo_hash::{EntryHash, HasHash};
+use holochain_keystore::{AgentPubKeyExt, KeystoreSender};
+use holochain_state::{
+    buffer::{BufferedStore, KvBufUsed, KvStore},
+    db::{GetDb, ELEMENT_VAULT_PRIVATE_ENTRIES, ELEMENT_VAULT_PRIVATE_ENTRIES_MIGRATED},
+    env::{EnvironmentKind, EnvironmentRead, EnvironmentWrite},
+    error::{DatabaseError, DatabaseResult},
+    exports::SingleStore,
+    fresh_reader,
+    prelude::*,
+};
+use holochain_types::{cell::CellId, entry::EntryHashed};
+use holochain_zome_types::Entry;
+use parking_lot::RwLock;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tracing::*;
+
+/// The agent key signs this, along with the dna hash, to derive a [PrivateEntryKey].
+/// Changing it makes every private entry that is already stored unreadable.
+const PRIVATE_ENTRY_KEY_CONTEXT: &[u8] = b"holochain private entry key";
+
+lazy_static::lazy_static! {
+    /// The keystore can only be reached asynchronously but an ElementBuf
+    /// is constructed synchronously, so keys are loaded once per cell
+    static ref PRIVATE_ENTRY_KEYS: RwLock<HashMap<CellId, PrivateEntryKey>> =
+        RwLock::new(HashMap::new());
+}
+
+/// A private entry as it is stored in the database
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PrivateEntryVal {
+    /// Encrypted with the cell's [PrivateEntryKey]
+    Encrypted {
+        /// Unique to this value
+        nonce: Vec<u8>,
+        /// The encoded entry followed by the authentication tag
+        ciphertext: Vec<u8>,
+    },
+    /// Written before private entries were encrypted
+    Plaintext(Entry),
+}
+
+/// The symmetric key a cell's private entries are encrypted with
+#[derive(Clone)]
+pub struct PrivateEntryKey(Arc<LessSafeKey>);
+
+impl PrivateEntryKey {
+    /// Get the key for the cell this environment belongs to,
+    /// deriving it with the keystore the first time.
+    /// This must be called before the cell's private entries are used.
+    pub async fn for_env(env: &EnvironmentRead) -> DatabaseResult<Self> {
+        Self::for_cell(cell_id(env)?, env.keystore()).await
+    }
+
+    /// Get the key for a cell, deriving it with `keystore` the first time.
+    /// This fails if the cell's agent key is not in the keystore.
+    pub async fn for_cell(cell_id: &CellId, keystore: &KeystoreSender) -> DatabaseResult<Self> {
+        if let Some(key) = PRIVATE_ENTRY_KEYS.read().get(cell_id) {
+            return Ok(key.clone());
+        }
+        let mut context = PRIVATE_ENTRY_KEY_CONTEXT.to_vec();
+        context.extend_from_slice(cell_id.dna_hash().get_full_bytes());
+        let signature = cell_id.agent_pubkey().sign_raw(keystore, &context).await?;
+        let key_bytes = holo_hash::encode::blake2b_256(&signature.0);
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &key_bytes).map_err(crypto_error)?;
+        let key = Self(Arc::new(LessSafeKey::new(key)));
+        PRIVATE_ENTRY_KEYS
+            .write()
+            .insert(cell_id.clone(), key.clone());
+        Ok(key)
+    }
+
+    /// Drop the key of a cell that has stopped,
+    /// so it is derived again if the cell starts up again
+    pub fn forget(cell_id: &CellId) {
+        PRIVATE_ENTRY_KEYS.write().remove(cell_id);
+    }
+
+    /// The key for the cell this environment belongs to,
+    /// if [PrivateEntryKey::for_env] has already loaded it
+    fn loaded(env: &EnvironmentRead) -> DatabaseResult<Option<Self>> {
+        Ok(PRIVATE_ENTRY_KEYS.read().get(cell_id(env)?).cloned())
+    }
+
+    /// Encrypt an entry, binding it to `aad` so it can't be moved to another key
+    fn encrypt(&self, entry: &Entry, aad: &[u8]) -> DatabaseResult<PrivateEntryVal> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(crypto_error)?;
+        let mut ciphertext = holochain_serialized_bytes::encode(entry)?;
+        self.0
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad),
+                &mut ciphertext,
+            )
+            .map_err(crypto_error)?;
+        Ok(PrivateEntryVal::Encrypted {
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    fn decrypt(&self, val: PrivateEntryVal, aad: &[u8]) -> DatabaseResult<Entry> {
+        match val {
+            PrivateEntryVal::Encrypted {
+                nonce,
+                mut ciphertext,
+            } => {
+                let nonce = Nonce::try_assume_unique_for_key(&nonce).map_err(crypto_error)?;
+                let plaintext = self
+                    .0
+                    .open_in_place(nonce, Aad::from(aad), &mut ciphertext)
+                    .map_err(crypto_error)?;
+                Ok(holochain_serialized_bytes::decode(&*plaintext)?)
+            }
+            PrivateEntryVal::Plaintext(entry) => Ok(entry),
+        }
+    }
+}
+
+/// Whether signing this data with an agent key would reveal the
+/// [PrivateEntryKey] of one of the agent's cells.
+/// Nothing but this module may sign such data.
+pub fn is_private_entry_key_context(data: &[u8]) -> bool {
+    data.starts_with(PRIVATE_ENTRY_KEY_CONTEXT)
+}
+
+fn cell_id(env: &EnvironmentRead) -> DatabaseResult<&CellId> {
+    match env.kind() {
+        EnvironmentKind::Cell(cell_id) => Ok(cell_id),
+        _ => Err(DatabaseError::NoPrivateDb(
+            "only cell environments have private entries".into(),
+        )),
+    }
+}
+
+fn crypto_error(_: ring::error::Unspecified) -> DatabaseError {
+    DatabaseError::PrivateEntryCrypto("ring error".into())
+}
+
+/// A buffer of private entries, keyed by their hash like a CAS,
+/// which encrypts them with the cell's [PrivateEntryKey]
+pub struct PrivateEntryBuf<P = IntegratedPrefix>
+where
+    P: PrefixType,
+{
+    env: EnvironmentRead,
+    key: Option<PrivateEntryKey>,
+    inner: KvBufUsed<PrefixHashKey<P>, PrivateEntryVal>,
+}
+
+impl<P: PrefixType> PrivateEntryBuf<P> {
+    /// Create a new PrivateEntryBuf for the cell of this environment.
+    /// Encrypted entries can only be read or written once the cell's
+    /// [PrivateEntryKey] is loaded.
+    pub fn new(env: EnvironmentRead, db: SingleStore) -> DatabaseResult<Self> {
+        Ok(Self {
+            key: PrivateEntryKey::loaded(&env)?,
+            env,
+            inner: KvBufUsed::new(db),
+        })
+    }
+
+    fn key(&self) -> DatabaseResult<&PrivateEntryKey> {
+        self.key
+            .as_ref()
+            .ok_or(DatabaseError::PrivateEntryKeyNotLoaded)
+    }
+
+    fn decrypt(&self, val: PrivateEntryVal, aad: &[u8]) -> DatabaseResult<Entry> {
+        match val {
+            PrivateEntryVal::Plaintext(entry) => Ok(entry),
+            val => self.key()?.decrypt(val, aad),
+        }
+    }
+
+    /// Get and decrypt an entry
+    pub fn get(&self, hash: &EntryHash) -> DatabaseResult<Option<EntryHashed>> {
+        let k = PrefixHashKey::new(hash);
+        match fresh_reader!(self.env, |r| self.inner.get(&r, &k))? {
+            Some(val) => Ok(Some(EntryHashed::with_pre_hashed(
+                self.decrypt(val, hash.as_ref())?,
+                hash.clone(),
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Check if an entry is stored at this hash, without decrypting it
+    pub fn contains(&self, hash: &EntryHash) -> DatabaseResult<bool> {
+        let k = PrefixHashKey::new(hash);
+        fresh_reader!(self.env, |r| self.inner.contains(&r, &k))
+    }
+
+    /// Encrypt an entry and put it in the scratch space
+    pub fn put(&mut self, entry: EntryHashed) -> DatabaseResult<()> {
+        let val = self
+            .key()?
+            .encrypt(entry.as_content(), entry.as_hash().as_ref())?;
+        self.inner.put(PrefixHashKey::new(entry.as_hash()), val)
+    }
+
+    /// Delete an entry from the scratch space
+    pub fn delete(&mut self, hash: EntryHash) {
+        // This expect seems valid as it means the hashing is broken
+        self.inner
+            .delete(PrefixHashKey::new(&hash))
+            .expect("Hash key is empty");
+    }
+
+    /// Removes a delete if there was one previously added
+    pub fn cancel_delete(&mut self, hash: EntryHash) {
+        // This expect seems valid as it means the hashing is broken
+        self.inner
+            .cancel_delete(PrefixHashKey::new(&hash))
+            .expect("Hash key is empty");
+    }
+
+    /// Iterate over and decrypt every entry, taking the scratch space into account
+    pub fn iter_fail<'r, R: Readable>(
+        &'r self,
+        r: &'r R,
+    ) -> DatabaseResult<impl FallibleIterator<Item = EntryHashed, Error = DatabaseError> + 'r> {
+        Ok(self.inner.iter(r)?.map(move |(k, val)| {
+            let k: PrefixHashKey<P> = PrefixHashKey::from_key_bytes_or_friendly_panic(k);
+            let hash = EntryHash::from_raw_bytes(k.as_hash_bytes().to_vec());
+            let entry = self.decrypt(val, k.as_hash_bytes())?;
+            Ok(EntryHashed::with_pre_hashed(entry, hash))
+        }))
+    }
+
+    // TODO: This should be cfg test but can't because it's in a different crate
+    /// Clear all scratch and db, useful for tests
+    pub fn clear_all(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.inner.clear_all(writer)
+    }
+}
+
+impl<P: PrefixType> BufferedStore for PrivateEntryBuf<P> {
+    type Error = DatabaseError;
+
+    fn is_clean(&self) -> bool {
+        self.inner.is_clean()
+    }
+
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
+        self.inner.flush_to_txn_ref(writer)
+    }
+}
+
+/// Encrypt every private entry that was stored in plaintext,
+/// for environments created before private entries were encrypted.
+/// This is recorded in the environment so later calls skip the scan.
+/// Returns how many entries were encrypted.
+pub async fn migrate_private_entries(env: &EnvironmentWrite) -> DatabaseResult<usize> {
+    let migrated: KvStore<UnitDbKey, bool> =
+        KvStore::new(env.get_db(&*ELEMENT_VAULT_PRIVATE_ENTRIES_MIGRATED)?);
+    if fresh_reader!(env, |r| migrated.get(&r, &UnitDbKey))?.unwrap_or(false) {
+        return Ok(0);
+    }
+    let key = PrivateEntryKey::for_env(env).await?;
+    let db = env.get_db(&*ELEMENT_VAULT_PRIVATE_ENTRIES)?;
+    // The prefix is part of the key bytes so this covers
+    // the integrated, pending and rejected entries alike
+    let mut buf: KvBufUsed<PrefixHashKey, PrivateEntryVal> = KvBufUsed::new(db);
+    let plaintext = fresh_reader!(env, |r| buf
+        .iter(&r)?
+        .filter_map(|(k, val)| {
+            Ok(match val {
+                PrivateEntryVal::Plaintext(entry) => Some((
+                    PrefixHashKey::<IntegratedPrefix>::from_key_bytes_or_friendly_panic(k),
+                    entry,
+                )),
+                PrivateEntryVal::Encrypted { .. } => None,
+            })
+        })
+        .collect::<Vec<_>>())?;
+    let count = plaintext.len();
+    for (k, entry) in plaintext {
+        let val = key.encrypt(&entry, k.as_hash_bytes())?;
+        buf.put(k, val)?;
+    }
+    env.with_commit(|writer| {
+        buf.flush_to_txn_ref(writer)?;
+        migrated.put(writer, &UnitDbKey, &true)
+    })?;
+    if count > 0 {
+        info!(count, "Encrypted private entries stored in plaintext");
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{fake_unique_element, load_private_entry_key};
+    use holo_hash::AgentPubKey;
+    use holochain_keystore::test_keystore::spawn_test_keystore;
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_zome_types::entry_def::EntryVisibility;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn private_entries_are_encrypted_at_rest() -> anyhow::Result<()> {
+        let keystore = spawn_test_keystore().await?;
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        load_private_entry_key(&env).await;
+
+        let agent_key = AgentPubKey::new_from_pure_entropy(&keystore).await?;
+        let (_, entry) =
+            fake_unique_element(&keystore, agent_key, EntryVisibility::Private).await?;
+        let db = env.get_db(&*ELEMENT_VAULT_PRIVATE_ENTRIES)?;
+
+        // An entry written in plaintext before encryption
+        let mut raw: KvBufUsed<PrefixHashKey, PrivateEntryVal> = KvBufUsed::new(db);
+        raw.put(
+            PrefixHashKey::new(entry.as_hash()),
+            PrivateEntryVal::Plaintext(entry.as_content().clone()),
+        )?;
+        env.with_commit(|writer| raw.flush_to_txn_ref(writer))?;
+
+        // Plaintext can still be read before the migration
+        let buf: PrivateEntryBuf = PrivateEntryBuf::new(env.clone().into(), db)?;
+        assert_eq!(buf.get(entry.as_hash())?, Some(entry.clone()));
+
+        assert_eq!(migrate_private_entries(&env).await?, 1);
+        assert_eq!(migrate_private_entries(&env).await?, 0);
+
+        // The stored value no longer contains the entry
+        let raw: KvBufUsed<PrefixHashKey, PrivateEntryVal> = KvBufUsed::new(db);
+        let stored = fresh_reader!(env, |r| raw.get(&r, &PrefixHashKey::new(entry.as_hash())))?;
+        match stored {
+            Some(PrivateEntryVal::Encrypted { ciphertext, .. }) => {
+                let plaintext = holochain_serialized_bytes::encode(entry.as_content())?;
+                assert_ne!(ciphertext[..plaintext.len()], plaintext[..]);
+            }
+            other => panic!("Expected an encrypted entry but got {:?}", other),
+        }
+
+        // And is decrypted transparently
+        let buf: PrivateEntryBuf = PrivateEntryBuf::new(env.clone().into(), db)?;
+        assert_eq!(buf.get(entry.as_hash())?, Some(entry.clone()));
+
+        // The environment is only scanned once
+        let mut raw: KvBufUsed<PrefixHashKey, PrivateEntryVal> = KvBufUsed::new(db);
+        raw.put(
+            PrefixHashKey::new(entry.as_hash()),
+            PrivateEntryVal::Plaintext(entry.as_content().clone()),
+        )?;
+        env.with_commit(|writer| raw.flush_to_txn_ref(writer))?;
+        assert_eq!(migrate_private_entries(&env).await?, 0);
+        Ok(())
+    }
+}
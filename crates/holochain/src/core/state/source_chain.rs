@@ -3,6 +3,7 @@
 //! which would return Option in the SourceChainBuf, like getting the source chain head, or the AgentPubKey,
 //! cannot fail, so the function return types reflect that.
 
+pub use archive::*;
 pub use error::*;
 use fallible_iterator::FallibleIterator;
 use holo_hash::*;
@@ -10,15 +11,14 @@ use holochain_state::{buffer::BufferedStore, error::DatabaseResult, fresh_reader
 use holochain_types::{prelude::*, EntryHashed};
 use holochain_zome_types::{
     capability::{CapAccess, CapGrant, CapSecret, GrantedFunction},
-    element::Element,
     entry::{CapClaimEntry, Entry},
     header::{builder, EntryType, Header, HeaderBuilder, HeaderBuilderCommon, HeaderInner},
-    query::ChainQueryFilter,
 };
 use shrinkwraprs::Shrinkwrap;
 pub use source_chain_buffer::*;
 use std::collections::HashSet;
 
+mod archive;
 mod error;
 mod source_chain_buffer;
 
@@ -286,23 +286,6 @@ impl SourceChain {
     //         }
     //     }
     // }
-
-    /// Query Headers in the source chain.
-    /// This returns a Vec rather than an iterator because it is intended to be
-    /// used by the `query` host function, which crosses the wasm boundary
-    pub fn query(&self, query: &ChainQueryFilter) -> SourceChainResult<Vec<Element>> {
-        let include_entries = query.include_entries;
-        self.iter_back()
-            .filter(|shh| Ok(query.check(shh.header())))
-            .map(|shh| {
-                let entry = match shh.header().entry_hash() {
-                    Some(eh) if include_entries => self.0.get_entry(eh)?,
-                    _ => None,
-                };
-                Ok(Element::new(shh, entry.map(|e| e.into_content())))
-            })
-            .collect()
-    }
 }
 
 impl From<SourceChainBuf> for SourceChain {
@@ -336,6 +319,7 @@ pub mod tests {
     async fn test_get_cap_grant() -> SourceChainResult<()> {
         let test_env = test_cell_env();
         let env = test_env.env();
+        crate::test_utils::load_private_entry_key(&env).await;
         let secret = Some(CapSecretFixturator::new(Unpredictable).next().unwrap());
         let access = CapAccess::from(secret.unwrap());
 
@@ -0,0 +1,264 @@
+//! A portable, self-verifying file format for an agent's authored source chain,
+//! used to move a chain between conductors or to archive it offline.
+//!
+//! The archive holds every signed header in chain order, along with its public
+//! or private entry. Nothing in it is trusted on import: every signature, the
+//! `prev_header` hash chain, the header sequence numbers and every entry hash
+//! are checked before anything is written to the new environment.
+
+use super::{SourceChainBuf, SourceChainError, SourceChainResult};
+use holo_hash::{AgentPubKey, DnaHash, HasHash, HeaderHash};
+use holochain_keystore::KeystoreError;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::{
+    element::{SignedHeaderHashed, SignedHeaderHashedExt},
+    EntryHashed,
+};
+use holochain_zome_types::{element::SignedHeader, Entry, Header};
+
+/// The version of the archive format written by this conductor.
+/// Archives with any other version are refused on import.
+pub const CHAIN_ARCHIVE_VERSION: u32 = 1;
+
+/// An exported source chain
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct ChainArchive {
+    /// The format version, see [CHAIN_ARCHIVE_VERSION]
+    pub version: u32,
+    /// The DNA of the cell the chain was exported from
+    pub dna_hash: DnaHash,
+    /// The author of every header in the chain
+    pub agent_pubkey: AgentPubKey,
+    /// The hash of the last header, checked against the last element on import
+    pub chain_head: HeaderHash,
+    /// Every element of the chain, starting with the Dna header
+    pub elements: Vec<ArchivedElement>,
+}
+
+/// A signed header and its entry, if it has one
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedElement {
+    /// The header and the author's signature of it
+    pub signed_header: SignedHeader,
+    /// The public or private entry the header refers to
+    pub entry: Option<Entry>,
+}
+
+impl ChainArchive {
+    /// Serialize the archive into the bytes of an archive file
+    pub fn to_bytes(&self) -> SourceChainResult<Vec<u8>> {
+        let sb = SerializedBytes::try_from(self.clone())?;
+        Ok(UnsafeBytes::from(sb).into())
+    }
+
+    /// Deserialize an archive file, refusing unknown format versions
+    pub fn from_bytes(bytes: &[u8]) -> SourceChainResult<Self> {
+        let sb: SerializedBytes = UnsafeBytes::from(bytes.to_vec()).into();
+        let archive = Self::try_from(sb)?;
+        if archive.version != CHAIN_ARCHIVE_VERSION {
+            return Err(SourceChainError::ArchiveVersion(archive.version));
+        }
+        Ok(archive)
+    }
+
+    /// Check the integrity of the whole archive, returning the verified
+    /// elements in chain order
+    pub async fn verify(self) -> SourceChainResult<Vec<(SignedHeaderHashed, Option<EntryHashed>)>> {
+        let invalid = |reason: String| Err(SourceChainError::ArchiveInvalid(reason));
+        let mut verified = Vec::with_capacity(self.elements.len());
+        let mut prev: Option<HeaderHash> = None;
+
+        for (seq, element) in self.elements.into_iter().enumerate() {
+            let ArchivedElement {
+                signed_header,
+                entry,
+            } = element;
+            let signed_header = SignedHeaderHashed::from_content_sync(signed_header);
+            let header = signed_header.header();
+            if header.author() != &self.agent_pubkey {
+                return invalid(format!("header {} has another author", seq));
+            }
+            if header.header_seq() as usize != seq {
+                return invalid(format!(
+                    "header {} has sequence number {}",
+                    seq,
+                    header.header_seq()
+                ));
+            }
+            if header.prev_header() != prev.as_ref() {
+                return invalid(format!(
+                    "header {} does not follow the previous header",
+                    seq
+                ));
+            }
+            if let (0, Header::Dna(dna)) = (seq, header) {
+                if dna.hash != self.dna_hash {
+                    return invalid("the Dna header is for another DNA".into());
+                }
+            } else if seq == 0 {
+                return invalid("the chain does not begin with a Dna header".into());
+            }
+            match signed_header.validate().await {
+                Err(KeystoreError::InvalidSignature(..)) => {
+                    return Err(SourceChainError::InvalidSignature)
+                }
+                result => result?,
+            }
+
+            let entry = match (entry, header.entry_data()) {
+                (None, None) => None,
+                (None, Some(_)) => {
+                    return invalid(format!(
+                        "header {} refers to an entry the archive does not hold",
+                        seq
+                    ))
+                }
+                (Some(entry), Some((entry_hash, _))) => {
+                    let entry = EntryHashed::from_content_sync(entry);
+                    if entry.as_hash() != entry_hash {
+                        return invalid(format!(
+                            "the entry of header {} does not match its hash",
+                            seq
+                        ));
+                    }
+                    Some(entry)
+                }
+                (Some(_), None) => {
+                    return invalid(format!("header {} has an entry but refers to none", seq))
+                }
+            };
+
+            prev = Some(signed_header.header_address().clone());
+            verified.push((signed_header, entry));
+        }
+
+        if prev.as_ref() != Some(&self.chain_head) {
+            return invalid("the last header is not the chain head".into());
+        }
+        Ok(verified)
+    }
+}
+
+impl SourceChainBuf {
+    /// Export every element of this chain, including private entries
+    pub fn export_archive(&self) -> SourceChainResult<ChainArchive> {
+        let chain_head = self
+            .chain_head()
+            .ok_or(SourceChainError::ChainEmpty)?
+            .clone();
+        let agent_pubkey = self.agent_pubkey()?.ok_or(SourceChainError::ChainEmpty)?;
+        let mut dna_hash = None;
+        let mut elements = Vec::with_capacity(self.len());
+        for i in 0..self.len() as u32 {
+            let element = self
+                .get_at_index(i)?
+                .ok_or_else(|| SourceChainError::ElementMissing(format!("at index {}", i)))?;
+            let (signed_header, entry) = element.into_inner();
+            if let Header::Dna(dna) = signed_header.header() {
+                dna_hash = Some(dna.hash.clone());
+            }
+            elements.push(ArchivedElement {
+                signed_header: signed_header.into_inner().0,
+                entry: entry.into_option(),
+            });
+        }
+        Ok(ChainArchive {
+            version: CHAIN_ARCHIVE_VERSION,
+            dna_hash: dna_hash.ok_or(SourceChainError::ChainEmpty)?,
+            agent_pubkey,
+            chain_head,
+            elements,
+        })
+    }
+
+    /// Verify an archive and write its elements into this chain, which must
+    /// be empty. The imported elements are published like newly authored ones.
+    pub async fn import_archive(&mut self, archive: ChainArchive) -> SourceChainResult<()> {
+        if !self.is_empty() {
+            return Err(SourceChainError::ArchiveInvalid(
+                "the destination source chain is not empty".into(),
+            ));
+        }
+        for (signed_header, entry) in archive.verify().await? {
+            self.put_verified(signed_header, entry)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::state::source_chain::SourceChain;
+    use crate::core::workflow::genesis_workflow::tests::fake_genesis;
+    use holochain_state::{prelude::*, test_utils::test_cell_env};
+
+    async fn exported_chain() -> ChainArchive {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut source_chain = SourceChain::new(env.clone().into()).unwrap();
+        fake_genesis(&mut source_chain).await.unwrap();
+        source_chain.export_archive().unwrap()
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn archive_round_trips_into_an_empty_chain() {
+        let archive = exported_chain().await;
+        let bytes = archive.to_bytes().unwrap();
+        let archive = ChainArchive::from_bytes(&bytes).unwrap();
+
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut chain = SourceChainBuf::new(env.clone().into()).unwrap();
+            chain.import_archive(archive.clone()).await.unwrap();
+            env.guard()
+                .with_commit(|writer| chain.flush_to_txn(writer))
+                .unwrap();
+        }
+        let chain = SourceChainBuf::new(env.clone().into()).unwrap();
+        assert_eq!(chain.chain_head(), Some(&archive.chain_head));
+        assert_eq!(chain.export_archive().unwrap(), archive);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn tampered_archive_is_refused() {
+        let mut archive = exported_chain().await;
+        archive.elements[2].entry = Some(Entry::Agent(
+            holochain_types::test_utils::fake_agent_pubkey_2(),
+        ));
+        assert!(matches!(
+            archive.clone().verify().await,
+            Err(SourceChainError::ArchiveInvalid(_))
+        ));
+
+        let mut archive = exported_chain().await;
+        archive.elements.remove(1);
+        assert!(matches!(
+            archive.verify().await,
+            Err(SourceChainError::ArchiveInvalid(_))
+        ));
+
+        let mut archive = exported_chain().await;
+        archive.version += 1;
+        assert!(matches!(
+            ChainArchive::from_bytes(&archive.to_bytes().unwrap()),
+            Err(SourceChainError::ArchiveVersion(_))
+        ));
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn archive_missing_an_entry_is_refused() {
+        let mut archive = exported_chain().await;
+        assert!(archive.elements[2].entry.take().is_some());
+
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut chain = SourceChainBuf::new(env.clone().into()).unwrap();
+        assert!(matches!(
+            chain.import_archive(archive).await,
+            Err(SourceChainError::ArchiveInvalid(_))
+        ));
+        assert!(chain.is_empty());
+    }
+}
@@ -66,6 +66,12 @@ pub enum SourceChainError {
     /// Element signature doesn't validate against the header
     #[error("Element associated with header {0} was not found on the source chain")]
     ElementMissing(String),
+
+    #[error("Source chain archive has unsupported format version {0}")]
+    ArchiveVersion(u32),
+
+    #[error("Source chain archive failed verification: {0}")]
+    ArchiveInvalid(String),
 }
 
 // serde_json::Error does not implement PartialEq - why is that a requirement??
@@ -158,6 +158,19 @@ impl SourceChainBuf {
         Ok(header_address)
     }
 
+    /// Add an Element whose signature and place in the chain were
+    /// already checked, e.g. one imported from a [super::ChainArchive]
+    pub(super) fn put_verified(
+        &mut self,
+        signed_header: SignedHeaderHashed,
+        maybe_entry: Option<EntryHashed>,
+    ) -> SourceChainResult<()> {
+        self.sequence
+            .put_header(signed_header.header_address().clone())?;
+        self.elements.put(signed_header, maybe_entry)?;
+        Ok(())
+    }
+
     pub fn headers(&self) -> &HeaderCas<IntegratedPrefix> {
         &self.elements.headers()
     }
@@ -11,6 +11,7 @@ use holochain_state::{
     prelude::{Readable, Writer},
 };
 use holochain_zome_types::signature::Signature;
+use std::collections::HashSet;
 
 /// The result of a DhtOp Validation.
 #[derive(
@@ -109,20 +110,21 @@ impl ValidationReceiptsBuf {
     }
 
     /// Get the current valid receipt count for a given hash.
+    /// Each validator is only counted once.
     pub fn count_valid<'r, R: Readable>(
         &'r self,
         r: &'r R,
         dht_op_hash: &DhtOpHash,
     ) -> DatabaseResult<usize> {
-        let mut count = 0;
+        let mut validators = HashSet::new();
 
         let mut iter = self.list_receipts(r, dht_op_hash)?;
         while let Some(v) = iter.next()? {
             if v.receipt.validation_result == ValidationResult::Valid {
-                count += 1;
+                validators.insert(v.receipt.validator);
             }
         }
-        Ok(count)
+        Ok(validators.len())
     }
 
     /// Add this receipt if it isn't already in the database.
@@ -16,14 +16,24 @@ use fallible_iterator::FallibleIterator;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pCell;
 use holochain_state::{env::EnvironmentWrite, error::DatabaseResult, fresh_reader};
-use holochain_types::{dht_op::DhtOp, header::NewEntryHeaderRef, Entry};
-use holochain_zome_types::{element::ElementEntry, signature::Signature};
+use holochain_types::{
+    dht_op::DhtOp,
+    dna::{DnaDef, RateLimit},
+    header::NewEntryHeaderRef,
+    warrant::Warrant,
+    Entry,
+};
+use holochain_zome_types::{
+    element::{ElementEntry, SignedHeader},
+    signature::Signature,
+};
 use holochain_zome_types::{
     entry_def::{EntryDef, EntryVisibility},
     header::{AppEntryType, EntryType, Update},
     link::LinkTag,
     Header,
 };
+use std::collections::BTreeSet;
 use std::convert::TryInto;
 
 pub use crate::core::state::source_chain::{SourceChainError, SourceChainResult};
@@ -40,14 +50,7 @@ mod error;
 #[cfg(test)]
 mod tests;
 
-/// 16mb limit on Entries due to websocket limits.
-/// Consider splitting large entries up.
-pub const MAX_ENTRY_SIZE: usize = 16_000_000;
-
-/// 400b limit on LinkTags.
-/// Tags are used as keys to the database to allow
-/// fast lookup so they need to be small.
-pub const MAX_TAG_SIZE: usize = 400;
+pub use holochain_types::dna::{MAX_ENTRY_SIZE, MAX_TAG_SIZE};
 
 /////////////
 // TODO: These checks are old and should probably be removed when
@@ -105,6 +108,7 @@ pub async fn sys_validate_element(
 ///   Then, ensure the previous header timestamp sequence /
 ///   ordering is correct, and the previous header is strictly the previous
 ///   header by sequence.
+/// - The previous header is not a CloseChain.
 /// - @TODO - The agent was valid in DPKI at time of signing.
 pub fn sys_validate_header(
     header: &HeaderHashed,
@@ -155,6 +159,14 @@ pub fn sys_validate_header(
                 prev_header.header_seq(),
             )));
         }
+
+        // nothing can be written after the chain was closed
+        if let Header::CloseChain(close) = prev_header.as_content() {
+            return Err(SourceChainError::InvalidPreviousHeader(format!(
+                "chain was closed for migration to dna: {}",
+                close.new_dna_hash,
+            )));
+        }
     }
 
     // - @TODO - The agent was valid in DPKI at time of signing.
@@ -225,53 +237,158 @@ pub async fn check_valid_if_dna(
 }
 
 /// Check if there are other headers at this
-/// sequence number
+/// sequence number.
+/// If there are, the author has forked their chain and this
+/// fails with a [Warrant] holding both signed headers as proof.
 pub async fn check_chain_rollback(
+    signature: &Signature,
     header: &Header,
     workspace: &SysValidationWorkspace,
 ) -> SysValidationResult<()> {
     let header_hash = HeaderHash::with_data_sync(header);
     let k = ChainItemKey::AgentSequence(header.author().clone(), header.header_seq());
     let env = workspace.meta_vault.env();
-    // Check there are no conflicting chain items
+    // Find any conflicting chain items
     // at any valid or potentially valid stores.
-    let count = fresh_reader!(env, |r| {
-        let vault_count = workspace
+    let conflicts = fresh_reader!(env, |r| {
+        let mut conflicts = workspace
             .meta_vault
             .get_activity(&r, k.clone())?
             .filter(|thh| Ok(thh.header_hash != header_hash))
-            .count()?;
-        let pending_count = workspace
+            .map(|thh| Ok(thh.header_hash))
+            .collect::<Vec<_>>()?;
+        let pending = workspace
             .meta_pending
             .get_activity(&r, k.clone())?
             .filter(|thh| Ok(thh.header_hash != header_hash))
-            .count()?;
-        DatabaseResult::Ok(vault_count + pending_count)
+            .map(|thh| Ok(thh.header_hash))
+            .collect::<Vec<_>>()?;
+        conflicts.extend(pending);
+        DatabaseResult::Ok(conflicts)
     })?;
 
-    // Ok or log warning
-    if count == 0 {
-        return Ok(());
-    } else {
-        let s = tracing::warn_span!("agent_activity");
-        let _g = s.enter();
-        // TODO: implement real rollback detection once we know what that looks like
-        tracing::error!(
-            "Chain rollback detected at position {} for agent {:?} from header {:?}
-            There were {} headers at this position",
+    for conflict in conflicts.iter() {
+        let other = match workspace.element_vault.get_header(conflict)? {
+            Some(other) => Some(other),
+            None => workspace.element_pending.get_header(conflict)?,
+        };
+        if let Some(other) = other {
+            let (other, _) = other.into_inner();
+            let this = SignedHeader(header.clone(), signature.clone());
+            return Err(
+                ValidationOutcome::ChainFork(Box::new(Warrant::chain_fork(this, other))).into(),
+            );
+        }
+    }
+    if !conflicts.is_empty() {
+        // We only hold the activity of the other headers so can't prove the fork yet.
+        // Whichever of them arrives next will be checked against this one.
+        tracing::warn!(
+            "Chain fork at position {} for agent {:?} from header {:?} could not be proven. There were {} other headers at this position",
             header.header_seq(),
             header.author(),
             header,
-            count,
+            conflicts.len(),
         );
     }
     Ok(())
 }
 
-/// Placeholder for future spam check.
-/// Check header timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_header: &Header) -> SysValidationResult<()> {
-    Ok(())
+/// Check the author of a header has no warrant against them from at or
+/// before the header's position in their chain. Headers from before the
+/// fork a warrant proves are still valid, but nothing from the fork
+/// onwards can be trusted.
+pub async fn check_not_warranted(
+    header: &Header,
+    meta_vault: &impl MetadataBufT,
+) -> SysValidationResult<()> {
+    let author = header.author();
+    let warranted = fresh_reader!(meta_vault.env(), |r| {
+        meta_vault
+            .get_warrants(&r, author.clone())?
+            .any(|warrant| Ok(warrant.header_seq() <= header.header_seq()))
+    })?;
+    if warranted {
+        Err(ValidationOutcome::AuthorWarranted(author.clone()).into())
+    } else {
+        Ok(())
+    }
+}
+
+/// Check a warrant from another node proves what it claims.
+/// Both headers must be well formed and signed by the author.
+pub async fn check_warrant(warrant: &Warrant) -> SysValidationResult<bool> {
+    if !warrant.is_well_formed() {
+        return Ok(false);
+    }
+    match warrant {
+        Warrant::ChainFork(a, b) => Ok(verify_header_signature(a.signature(), a.header()).await?
+            && verify_header_signature(b.signature(), b.header()).await?),
+    }
+}
+
+/// Check the author of a header hasn't published more headers than
+/// the DNA's [RateLimit] allows, using the activity we hold for them.
+/// Only the agent activity authority holds an author's full activity,
+/// so this is only checked for [DhtOp::RegisterAgentActivity]; every
+/// other op's header is also registered there, so all headers are covered.
+pub async fn check_spam(
+    header: &Header,
+    rate_limit: Option<RateLimit>,
+    workspace: &SysValidationWorkspace,
+) -> SysValidationResult<()> {
+    let rate_limit = match rate_limit {
+        Some(rate_limit) => rate_limit,
+        None => return Ok(()),
+    };
+    let k = ChainItemKey::Agent(header.author().clone());
+    let env = workspace.meta_vault.env();
+    // Valid and potentially valid activity both count
+    // and the same header may be in both stores
+    let recent = fresh_reader!(env, |r| {
+        let mut recent = workspace
+            .meta_vault
+            .get_activity(&r, k.clone())?
+            .collect::<BTreeSet<_>>()?;
+        recent.extend(
+            workspace
+                .meta_pending
+                .get_activity(&r, k.clone())?
+                .collect::<Vec<_>>()?,
+        );
+        DatabaseResult::Ok(recent)
+    })?;
+    check_rate_limit(
+        header,
+        recent.into_iter().map(|thh| thh.timestamp),
+        rate_limit,
+    )
+}
+
+/// Check there are fewer than the rate limit's `max_headers` timestamps
+/// in the window leading up to this header.
+/// The header's own timestamp is not counted.
+pub fn check_rate_limit(
+    header: &Header,
+    recent: impl IntoIterator<Item = Timestamp>,
+    rate_limit: RateLimit,
+) -> SysValidationResult<()> {
+    let end = Timestamp::from(header.timestamp());
+    let start: Timestamp = (chrono::DateTime::<chrono::Utc>::from(end)
+        - chrono::Duration::milliseconds(rate_limit.window_ms as i64))
+    .into();
+    let count = recent
+        .into_iter()
+        .filter(|t| *t >= start && *t < end)
+        .count();
+    if count < rate_limit.max_headers as usize {
+        Ok(())
+    } else {
+        Err(
+            ValidationOutcome::RateLimitExceeded(rate_limit.max_headers, rate_limit.window_ms)
+                .into(),
+        )
+    }
 }
 
 /// Check previous header timestamp is before this header
@@ -295,6 +412,16 @@ pub fn check_prev_seq(header: &Header, prev_header: &Header) -> SysValidationRes
     }
 }
 
+/// Check the previous header didn't close the chain
+pub fn check_prev_not_closed(prev_header: &Header) -> SysValidationResult<()> {
+    match prev_header {
+        Header::CloseChain(_) => {
+            Err(PrevHeaderError::ChainClosed).map_err(|e| ValidationOutcome::from(e).into())
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Check the entry variant matches the variant in the headers entry type
 pub fn check_entry_type(entry_type: &EntryType, entry: &Entry) -> SysValidationResult<()> {
     match (entry_type, entry) {
@@ -306,6 +433,14 @@ pub fn check_entry_type(entry_type: &EntryType, entry: &Entry) -> SysValidationR
     }
 }
 
+/// Get the [DnaDef] of the cell which is validating
+pub async fn get_dna_def(conductor_api: &impl CellConductorApiT) -> SysValidationResult<DnaDef> {
+    let dna_file = { conductor_api.get_this_dna().await };
+    let dna_file =
+        dna_file.ok_or_else(|| SysValidationError::DnaMissing(conductor_api.cell_id().clone()))?;
+    Ok(dna_file.dna().clone())
+}
+
 /// Check the AppEntryType is valid for the zome.
 /// Check the EntryDefId and ZomeId are in range.
 pub async fn check_app_entry_type(
@@ -380,15 +515,16 @@ pub fn check_new_entry_header(header: &Header) -> SysValidationResult<()> {
     }
 }
 
-/// Check the entry size is under the MAX_ENTRY_SIZE
-pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
+/// Check the entry size is under the limit for its entry def,
+/// as given by [DnaDef::max_entry_size]
+pub fn check_entry_size(entry: &Entry, max_size: usize) -> SysValidationResult<()> {
     match entry {
         Entry::App(bytes) => {
             let size = std::mem::size_of_val(&bytes.bytes()[..]);
-            if size < MAX_ENTRY_SIZE {
+            if size < max_size {
                 Ok(())
             } else {
-                Err(ValidationOutcome::EntryTooLarge(size, MAX_ENTRY_SIZE).into())
+                Err(ValidationOutcome::EntryTooLarge(size, max_size).into())
             }
         }
         // Other entry types are small
@@ -396,13 +532,13 @@ pub fn check_entry_size(entry: &Entry) -> SysValidationResult<()> {
     }
 }
 
-/// Check the link tag size is under the MAX_TAG_SIZE
-pub fn check_tag_size(tag: &LinkTag) -> SysValidationResult<()> {
+/// Check the link tag size is under the DNA's limit
+pub fn check_tag_size(tag: &LinkTag, max_size: usize) -> SysValidationResult<()> {
     let size = std::mem::size_of_val(&tag.0[..]);
-    if size < MAX_TAG_SIZE {
+    if size < max_size {
         Ok(())
     } else {
-        Err(ValidationOutcome::TagTooLarge(size, MAX_TAG_SIZE).into())
+        Err(ValidationOutcome::TagTooLarge(size, max_size).into())
     }
 }
 
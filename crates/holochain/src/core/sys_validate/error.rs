@@ -3,10 +3,10 @@ use crate::{
     conductor::entry_def_store::error::EntryDefStoreError,
     core::state::cascade::error::CascadeError, core::workflow::error::WorkflowError,
 };
-use holo_hash::{AnyDhtHash, HeaderHash};
+use holo_hash::{AgentPubKey, AnyDhtHash, HeaderHash};
 use holochain_keystore::KeystoreError;
 use holochain_state::error::DatabaseError;
-use holochain_types::cell::CellId;
+use holochain_types::{cell::CellId, warrant::Warrant};
 use holochain_zome_types::signature::Signature;
 use holochain_zome_types::{
     header::{AppEntryType, EntryType},
@@ -51,19 +51,25 @@ pub type SysValidationResult<T> = Result<T, SysValidationError>;
 /// failed validation.
 #[derive(Error, Debug)]
 pub enum ValidationOutcome {
+    #[error("The author {0:?} has a warrant against them")]
+    AuthorWarranted(AgentPubKey),
+    #[error("The author forked their chain at position {}", .0.header_seq())]
+    ChainFork(Box<Warrant>),
     #[error("The dependency {0:?} was not found on the DHT")]
     DepMissingFromDht(AnyDhtHash),
     #[error("The app entry type {0:?} entry def id was out of range")]
     EntryDefId(AppEntryType),
     #[error("The entry has a different hash to the header's entry hash")]
     EntryHash,
-    #[error("The entry size {0} was bigger then the MAX_ENTRY_SIZE {1}")]
+    #[error("The entry size {0} was bigger than the limit {1}")]
     EntryTooLarge(usize, usize),
     #[error("The entry has a different type to the header's entry type")]
     EntryType,
     #[error("The app entry type {0:?} visibility didn't match the zome")]
     EntryVisibility(AppEntryType),
-    #[error("The link tag size {0} was bigger then the MAX_TAG_SIZE {1}")]
+    #[error("The author published more than {0} headers in {1}ms")]
+    RateLimitExceeded(u32, u64),
+    #[error("The link tag size {0} was bigger than the limit {1}")]
     TagTooLarge(usize, usize),
     #[error("The header {0:?} was expected to be a link add header")]
     NotCreateLink(HeaderHash),
@@ -94,6 +100,8 @@ impl ValidationOutcome {
 
 #[derive(Error, Debug)]
 pub enum PrevHeaderError {
+    #[error("The previous header closed the chain so nothing can follow it")]
+    ChainClosed,
     #[error("Root of source chain must be Dna")]
     InvalidRoot,
     #[error("Previous header sequence number {1} is not {0} - 1")]
@@ -4,10 +4,10 @@ use ::fixt::prelude::*;
 use error::SysValidationError;
 use holo_hash::fixt::*;
 use holochain_keystore::AgentPubKeyExt;
-use holochain_serialized_bytes::SerializedBytes;
+use holochain_serialized_bytes::{SerializedBytes, UnsafeBytes};
 use holochain_state::{env::EnvironmentRead, test_utils::test_cell_env};
 use holochain_types::{
-    dna::{DnaDef, DnaFile},
+    dna::{DnaDef, DnaFile, RateLimit},
     element::{SignedHeaderHashed, SignedHeaderHashedExt},
     fixt::*,
     observability,
@@ -138,6 +138,88 @@ async fn verify_header_signature_test() {
     );
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn check_warrant_test() {
+    let keystore = holochain_state::test_utils::test_keystore();
+    let author = fake_agent_pubkey_1();
+    let mut a = fixt!(CreateLink);
+    a.author = author.clone();
+    a.header_seq = 5;
+    let mut b = a.clone();
+    b.base_address = fixt!(EntryHash);
+    let a = Header::CreateLink(a);
+    let b = Header::CreateLink(b);
+    let a = SignedHeader(a.clone(), author.sign(&keystore, &a).await.unwrap());
+    let b = SignedHeader(b.clone(), author.sign(&keystore, &b).await.unwrap());
+
+    // Every node builds the same warrant for a fork
+    let warrant = Warrant::chain_fork(a.clone(), b.clone());
+    assert_eq!(warrant, Warrant::chain_fork(b.clone(), a.clone()));
+    assert_eq!(warrant.author(), &author);
+    assert_eq!(warrant.header_seq(), 5);
+    assert_matches!(check_warrant(&warrant).await, Ok(true));
+
+    // The same header twice is not a fork
+    let warrant = Warrant::chain_fork(a.clone(), a.clone());
+    assert_matches!(check_warrant(&warrant).await, Ok(false));
+
+    // Both headers must be signed by the author
+    let forged = SignedHeader(b.header().clone(), Signature(vec![1; 64]));
+    let warrant = Warrant::chain_fork(a, forged);
+    assert_matches!(check_warrant(&warrant).await, Ok(false));
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn check_not_warranted_test() {
+    let env: EnvironmentRead = test_cell_env().env.into();
+    let author = fake_agent_pubkey_1();
+    let mut forked = fixt!(CreateLink);
+    forked.author = author.clone();
+    forked.header_seq = 5;
+    let forked = Header::CreateLink(forked);
+    let signature = Signature(vec![0; 64]);
+    let warrant = Warrant::chain_fork(
+        SignedHeader(forked.clone(), signature.clone()),
+        SignedHeader(forked, signature),
+    );
+    let mut metadata = meta_mock!();
+    metadata.expect_env().return_const(env);
+    metadata.expect_get_warrants().returning(move |agent| {
+        let warrants = if agent == author {
+            vec![warrant.clone()]
+        } else {
+            vec![]
+        };
+        Ok(Box::new(fallible_iterator::convert(
+            warrants.into_iter().map(Ok),
+        )))
+    });
+
+    // Headers from before the fork are still valid
+    let mut header = fixt!(CreateLink);
+    header.author = fake_agent_pubkey_1();
+    header.header_seq = 4;
+    assert_matches!(
+        check_not_warranted(&header.clone().into(), &metadata).await,
+        Ok(())
+    );
+
+    // Nothing from the fork onwards is
+    for seq in &[5, 6] {
+        header.header_seq = *seq;
+        assert_matches!(
+            check_not_warranted(&header.clone().into(), &metadata).await,
+            Err(SysValidationError::ValidationOutcome(
+                ValidationOutcome::AuthorWarranted(_)
+            ))
+        );
+    }
+
+    // Other authors are unaffected
+    header.author = fixt!(AgentPubKey);
+    assert_matches!(check_not_warranted(&header.into(), &metadata).await, Ok(()));
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn check_previous_header() {
     let mut header = fixt!(CreateLink);
@@ -255,6 +337,17 @@ async fn check_previous_seq() {
     );
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn check_prev_not_closed_test() {
+    assert_matches!(check_prev_not_closed(&fixt!(OpenChain).into()), Ok(()));
+    assert_matches!(
+        check_prev_not_closed(&fixt!(CloseChain).into()),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::PrevHeaderError(PrevHeaderError::ChainClosed)
+        ))
+    );
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn check_entry_type_test() {
     let entry_fixt = EntryFixturator::new(Predictable);
@@ -310,15 +403,28 @@ async fn check_entry_hash_test() {
 
 #[tokio::test(threaded_scheduler)]
 async fn check_entry_size_test() {
-    // let tiny = Entry::App(SerializedBytes::from(UnsafeBytes::from(vec![0; 1])));
-    // let bytes = (0..16_000_000).map(|_| 0u8).into_iter().collect::<Vec<_>>();
-    // let huge = Entry::App(SerializedBytes::from(UnsafeBytes::from(bytes)));
-    // assert_matches!(check_entry_size(&tiny), Ok(()));
-
-    // assert_matches!(
-    //     check_entry_size(&huge),
-    //     Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(_, _)))
-    // );
+    let tiny = Entry::App(SerializedBytes::from(UnsafeBytes::from(vec![0; 1])));
+    let big = Entry::App(SerializedBytes::from(UnsafeBytes::from(vec![0; 100])));
+    let mut dna_def = fixt!(DnaDef);
+    dna_def.limits.max_entry_size = 50;
+    let mut entry_def = fixt!(EntryDef);
+
+    entry_def.max_size = None;
+    assert_matches!(
+        check_entry_size(&tiny, dna_def.max_entry_size(&entry_def)),
+        Ok(())
+    );
+    assert_matches!(
+        check_entry_size(&big, dna_def.max_entry_size(&entry_def)),
+        Err(SysValidationError::ValidationOutcome(ValidationOutcome::EntryTooLarge(100, 50)))
+    );
+
+    // The entry def's limit overrides the DNA's
+    entry_def.max_size = Some(200);
+    assert_matches!(
+        check_entry_size(&big, dna_def.max_entry_size(&entry_def)),
+        Ok(())
+    );
 }
 
 #[tokio::test(threaded_scheduler)]
@@ -361,14 +467,45 @@ async fn check_link_tag_size_test() {
     let tiny = LinkTag(vec![0; 1]);
     let bytes = (0..401).map(|_| 0u8).into_iter().collect::<Vec<_>>();
     let huge = LinkTag(bytes);
-    assert_matches!(check_tag_size(&tiny), Ok(()));
+    assert_matches!(check_tag_size(&tiny, MAX_TAG_SIZE), Ok(()));
 
     assert_matches!(
-        check_tag_size(&huge),
+        check_tag_size(&huge, MAX_TAG_SIZE),
         Err(SysValidationError::ValidationOutcome(ValidationOutcome::TagTooLarge(_, _)))
     );
 }
 
+#[tokio::test(threaded_scheduler)]
+async fn check_rate_limit_test() {
+    let rate_limit = RateLimit {
+        max_headers: 2,
+        window_ms: 1000,
+    };
+    let mut header = fixt!(CreateLink);
+    header.timestamp = Timestamp(100, 0).into();
+    let header: Header = header.into();
+
+    // Headers outside the window and the header itself don't count,
+    // so one header in the window leaves room for this one
+    let recent = vec![
+        Timestamp(98, 0),
+        Timestamp(98, 999_999_999),
+        Timestamp(99, 500_000_000),
+        Timestamp(100, 0),
+    ];
+    assert_matches!(check_rate_limit(&header, recent, rate_limit), Ok(()));
+
+    // Two headers in the window plus this one is too many
+
+    let recent = vec![Timestamp(99, 0), Timestamp(99, 500_000_000)];
+    assert_matches!(
+        check_rate_limit(&header, recent, rate_limit),
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimitExceeded(2, 1000)
+        ))
+    );
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn check_app_entry_type_test() {
     observability::test_run().ok();
@@ -379,6 +516,7 @@ async fn check_app_entry_type_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::EntryDefs.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::EntryDefs.into()],
     )
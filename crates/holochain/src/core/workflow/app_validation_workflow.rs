@@ -81,7 +81,7 @@ pub async fn app_validation_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
     trigger_integration.trigger();
@@ -262,7 +262,10 @@ fn get_element(op: DhtOp) -> AppValidationOutcome<Element> {
     match op {
         DhtOp::RegisterAgentActivity(_, _) => Outcome::accepted(),
         DhtOp::StoreElement(s, h, e) => match h {
-            Header::Delete(_) | Header::CreateLink(_) | Header::DeleteLink(_) => Ok(Element::new(
+            Header::Delete(_)
+            | Header::CreateLink(_)
+            | Header::DeleteLink(_)
+            | Header::AgentValidationPkg(_) => Ok(Element::new(
                 SignedHeaderHashed::with_presigned(HeaderHashed::from_content_sync(h), s),
                 None,
             )),
@@ -620,13 +623,18 @@ impl Workspace for AppValidationWorkspace {
 
         // Need to flush the call zome workspace because of the cache.
         // TODO: If cache becomes a separate env then remove this
-        if let Some(czws) = self
-            .call_zome_workspace_lock
-            .take()
-            .and_then(|o| Arc::try_unwrap(o.into_inner()).ok())
-        {
-            let mut czws: CallZomeWorkspace = czws.into_inner();
-            czws.flush_to_txn_ref(writer)?;
+        if let Some(lock) = self.call_zome_workspace_lock.take() {
+            match Arc::try_unwrap(lock.into_inner()) {
+                Ok(czws) => {
+                    let mut czws: CallZomeWorkspace = czws.into_inner();
+                    let result = czws.flush_to_txn_ref(writer);
+                    // Put the workspace back so a retried transaction flushes it again
+                    self.call_zome_workspace_lock = Some(czws.into());
+                    result?;
+                }
+                // Something still holds the workspace, so it isn't flushed
+                Err(shared) => self.call_zome_workspace_lock = Some(shared.into()),
+            }
         }
         Ok(())
     }
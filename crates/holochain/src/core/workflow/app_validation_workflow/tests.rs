@@ -39,6 +39,7 @@ async fn app_validation_workflow_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Validate.into(), TestWasm::ValidateLink.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::Validate.into(), TestWasm::ValidateLink.into()],
     )
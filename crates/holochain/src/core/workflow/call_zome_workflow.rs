@@ -3,12 +3,16 @@ use super::{
     error::{WorkflowError, WorkflowResult},
 };
 use crate::conductor::interface::SignalBroadcaster;
+use crate::core::ribosome::guest_callback::post_commit::{
+    PostCommitHostAccess, PostCommitInvocation, PostCommitResult,
+};
 use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::ribosome::{error::RibosomeError, ZomesToInvoke};
 use crate::core::ribosome::{error::RibosomeResult, RibosomeT, ZomeCallHostAccess};
 use crate::core::state::source_chain::SourceChainError;
 use crate::core::state::workspace::Workspace;
 use crate::core::{
+    check_rate_limit,
     queue_consumer::{OneshotWriter, TriggerSender},
     state::{
         cascade::Cascade, element_buf::ElementBuf, metadata::MetadataBuf,
@@ -19,12 +23,17 @@ use crate::core::{
 pub use call_zome_workspace_lock::CallZomeWorkspaceLock;
 use either::Either;
 use fallible_iterator::FallibleIterator;
+use holo_hash::{AgentPubKey, HeaderHash};
 use holochain_keystore::KeystoreSender;
 use holochain_p2p::HolochainP2pCell;
+use holochain_state::env::EnvironmentWrite;
 use holochain_state::prelude::*;
+use holochain_types::dna::RateLimit;
 use holochain_types::element::Element;
+use holochain_types::Timestamp;
 use holochain_zome_types::entry::GetOptions;
 use holochain_zome_types::header::Header;
+use holochain_zome_types::zome::ZomeName;
 use holochain_zome_types::ZomeCallResponse;
 use std::sync::Arc;
 use tracing::instrument;
@@ -33,7 +42,59 @@ pub mod call_zome_workspace_lock;
 
 /// Placeholder for the return value of a zome invocation
 /// TODO: do we want this to be the same as ZomeCallInvocationRESPONSE?
-pub type ZomeCallInvocationResult = RibosomeResult<ZomeCallResponse>;
+pub type ZomeCallInvocationResult = RibosomeResult<ZomeCallInvocationResponse>;
+
+/// The response of a zome call which didn't error
+#[derive(Debug)]
+pub struct ZomeCallInvocationResponse {
+    /// What the zome function returned
+    pub response: ZomeCallResponse,
+    /// The error of the zome's post_commit callback, if it failed.
+    /// The callback runs after the call's commits were kept, so these stay
+    /// on the chain and only what the callback committed is discarded.
+    pub post_commit_error: Option<RibosomeError>,
+}
+
+/// The result of a batch of zome calls. The batch stops at the first call
+/// that errors, and then nothing the batch committed is kept.
+pub type ZomeCallBatchResult = RibosomeResult<ZomeCallBatchResponse>;
+
+/// The responses of a batch of zome calls which didn't error
+#[derive(Debug)]
+pub struct ZomeCallBatchResponse {
+    /// The response of each call, in the order they were called.
+    /// The batch stops at the first call that is unauthorized, which is
+    /// the last response, and nothing the batch committed is kept.
+    pub responses: Vec<ZomeCallResponse>,
+    /// The first post_commit callback that failed, if any.
+    /// The callbacks run after the batch's commits were kept, so these stay
+    /// on the chain and only what the failing callback committed is discarded.
+    pub post_commit_error: Option<RibosomeError>,
+}
+
+/// What came of a zome's post_commit callback. The zome call's own
+/// commits are already kept, whatever the outcome.
+#[derive(Debug)]
+pub enum PostCommitOutcome {
+    /// The callback succeeded and everything it committed was kept
+    Kept(Vec<HeaderHash>),
+    /// The callback failed, so nothing it committed was kept
+    Failed(RibosomeError),
+    /// The callback succeeded but something it committed didn't validate,
+    /// so nothing it committed was kept
+    Invalid(SourceChainError),
+}
+
+impl PostCommitOutcome {
+    /// The error to return to the caller of the zome call, if any
+    pub fn into_result(self) -> RibosomeResult<()> {
+        match self {
+            PostCommitOutcome::Kept(_) => Ok(()),
+            PostCommitOutcome::Failed(e) => Err(e),
+            PostCommitOutcome::Invalid(e) => Err(e.into()),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct CallZomeWorkflowArgs<Ribosome: RibosomeT> {
@@ -41,20 +102,32 @@ pub struct CallZomeWorkflowArgs<Ribosome: RibosomeT> {
     pub invocation: ZomeCallInvocation,
 }
 
-#[instrument(skip(workspace, network, keystore, writer, args, trigger_produce_dht_ops))]
+#[instrument(skip(workspace, network, keystore, env, args, trigger_produce_dht_ops))]
 pub async fn call_zome_workflow<'env, Ribosome: RibosomeT>(
     workspace: CallZomeWorkspace,
     network: HolochainP2pCell,
     keystore: KeystoreSender,
     signal_tx: SignalBroadcaster,
-    writer: OneshotWriter,
+    env: EnvironmentWrite,
     args: CallZomeWorkflowArgs<Ribosome>,
     mut trigger_produce_dht_ops: TriggerSender,
 ) -> WorkflowResult<ZomeCallInvocationResult> {
+    let CallZomeWorkflowArgs {
+        ribosome,
+        invocation,
+    } = args;
+    let zome_name = invocation.zome_name.clone();
+    let cell_id = invocation.cell_id.clone();
     let workspace_lock = CallZomeWorkspaceLock::new(workspace);
-    let result =
-        call_zome_workflow_inner(workspace_lock.clone(), network, keystore, signal_tx, args)
-            .await?;
+    let (result, committed) = call_zome_workflow_inner(
+        workspace_lock.clone(),
+        network.clone(),
+        keystore.clone(),
+        signal_tx.clone(),
+        &ribosome,
+        invocation,
+    )
+    .await?;
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
@@ -62,26 +135,205 @@ pub async fn call_zome_workflow<'env, Ribosome: RibosomeT>(
     {
         let mut guard = workspace_lock.write().await;
         let workspace = &mut guard;
+        let writer: OneshotWriter = env.clone().into();
         writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
     }
 
+    // Let the zome react to what it just committed
+    let response = match result {
+        Ok(response) => response,
+        Err(e) => return Ok(Err(e)),
+    };
+    let post_commit_error = if committed.is_empty() {
+        None
+    } else {
+        let host_access = PostCommitHostAccess::new(
+            CallZomeWorkspaceLock::new(CallZomeWorkspace::new(env.clone().into())?),
+            keystore,
+            network,
+            signal_tx,
+            cell_id,
+        );
+        call_post_commit(&env, &ribosome, zome_name, committed, host_access)
+            .await?
+            .into_result()
+            .err()
+    };
+
     trigger_produce_dht_ops.trigger();
 
-    Ok(result)
+    Ok(Ok(ZomeCallInvocationResponse {
+        response,
+        post_commit_error,
+    }))
 }
 
-async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
-    workspace_lock: CallZomeWorkspaceLock,
+#[derive(Debug)]
+pub struct CallZomeBatchWorkflowArgs<Ribosome: RibosomeT> {
+    pub ribosome: Ribosome,
+    /// Every invocation must be on the cell the workspace belongs to
+    pub invocations: Vec<ZomeCallInvocation>,
+}
+
+/// Run a batch of zome calls against a single workspace, like one zome call
+/// that calls several functions. Each call sees what the calls before it
+/// committed and is validated like a single zome call, but the workspace is
+/// only flushed once every call has succeeded, so a failure anywhere in the
+/// batch leaves the chain untouched. As with a single call the flush fails if
+/// the chain head moved while the batch was running.
+/// The post_commit callbacks run after the flush, so their failures are
+/// returned alongside the responses instead of failing the batch.
+#[instrument(skip(workspace, network, keystore, env, args, trigger_produce_dht_ops))]
+pub async fn call_zome_batch_workflow<'env, Ribosome: RibosomeT>(
+    workspace: CallZomeWorkspace,
     network: HolochainP2pCell,
     keystore: KeystoreSender,
     signal_tx: SignalBroadcaster,
-    args: CallZomeWorkflowArgs<Ribosome>,
-) -> WorkflowResult<ZomeCallInvocationResult> {
-    let CallZomeWorkflowArgs {
+    env: EnvironmentWrite,
+    args: CallZomeBatchWorkflowArgs<Ribosome>,
+    mut trigger_produce_dht_ops: TriggerSender,
+) -> WorkflowResult<ZomeCallBatchResult> {
+    let CallZomeBatchWorkflowArgs {
         ribosome,
-        invocation,
+        invocations,
     } = args;
+    let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+    let mut responses = Vec::with_capacity(invocations.len());
+    let mut all_committed = Vec::new();
+    for invocation in invocations {
+        let zome_name = invocation.zome_name.clone();
+        let cell_id = invocation.cell_id.clone();
+        let (result, committed) = call_zome_workflow_inner(
+            workspace_lock.clone(),
+            network.clone(),
+            keystore.clone(),
+            signal_tx.clone(),
+            &ribosome,
+            invocation,
+        )
+        .await?;
+        match result {
+            Ok(ZomeCallResponse::Ok(output)) => responses.push(ZomeCallResponse::Ok(output)),
+            // Dropping the workspace discards everything the batch committed
+            Ok(ZomeCallResponse::Unauthorized) => {
+                responses.push(ZomeCallResponse::Unauthorized);
+                return Ok(Ok(ZomeCallBatchResponse {
+                    responses,
+                    post_commit_error: None,
+                }));
+            }
+            Err(e) => return Ok(Err(e)),
+        }
+        if !committed.is_empty() {
+            all_committed.push((zome_name, cell_id, committed));
+        }
+    }
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // commit the workspace
+    {
+        let mut guard = workspace_lock.write().await;
+        let workspace = &mut guard;
+        let writer: OneshotWriter = env.clone().into();
+        writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+    }
+
+    // Let each zome react to what its call committed. The batch's commits
+    // are kept whatever the callbacks do, so every callback runs.
+    let mut post_commit_error = None;
+    for (zome_name, cell_id, committed) in all_committed {
+        let host_access = PostCommitHostAccess::new(
+            CallZomeWorkspaceLock::new(CallZomeWorkspace::new(env.clone().into())?),
+            keystore.clone(),
+            network.clone(),
+            signal_tx.clone(),
+            cell_id,
+        );
+        if let Err(e) = call_post_commit(&env, &ribosome, zome_name, committed, host_access)
+            .await?
+            .into_result()
+        {
+            post_commit_error.get_or_insert(e);
+        }
+    }
+
+    trigger_produce_dht_ops.trigger();
+
+    Ok(Ok(ZomeCallBatchResponse {
+        responses,
+        post_commit_error,
+    }))
+}
+
+/// Run the post_commit callback of a zome with the headers a zome call
+/// committed. Anything the callback commits is validated like the zome
+/// call's own commits and only kept if the callback succeeds and all of it
+/// is valid. The zome call's commits are already flushed so a failure here
+/// can't undo them, and is only an error if the workspace itself failed.
+async fn call_post_commit<Ribosome: RibosomeT>(
+    env: &EnvironmentWrite,
+    ribosome: &Ribosome,
+    zome_name: ZomeName,
+    committed: Vec<HeaderHash>,
+    host_access: PostCommitHostAccess,
+) -> WorkflowResult<PostCommitOutcome> {
+    let workspace_lock = host_access.workspace.clone();
+    let network = host_access.network.clone();
+    let agent_key = host_access.cell_id.agent_pubkey().clone();
+    let chain_head_start = workspace_lock
+        .read()
+        .await
+        .source_chain
+        .chain_head()?
+        .clone();
+
+    let invocation = PostCommitInvocation::new(zome_name.clone(), committed.into());
+    match ribosome.run_post_commit(host_access, invocation) {
+        Ok(PostCommitResult::Success) => (),
+        Ok(PostCommitResult::Fail(headers, reason)) => {
+            return Ok(PostCommitOutcome::Failed(RibosomeError::PostCommitFailed(
+                zome_name, headers, reason,
+            )))
+        }
+        Err(e) => return Ok(PostCommitOutcome::Failed(e)),
+    }
+
+    // Dropping the workspace discards whatever didn't validate
+    let kept = match validate_new_elements(
+        workspace_lock.clone(),
+        chain_head_start,
+        &zome_name,
+        &agent_key,
+        ribosome,
+        network,
+    )
+    .await
+    {
+        Ok(kept) => kept,
+        Err(WorkflowError::SourceChainError(e)) => return Ok(PostCommitOutcome::Invalid(e)),
+        Err(WorkflowError::RibosomeError(e)) => return Ok(PostCommitOutcome::Failed(e)),
+        Err(e) => return Err(e),
+    };
 
+    let mut guard = workspace_lock.write().await;
+    let workspace = &mut guard;
+    let writer: OneshotWriter = env.clone().into();
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+    Ok(PostCommitOutcome::Kept(kept))
+}
+
+/// Call the zome function and validate everything it committed.
+/// Returns the result of the call and the hashes of the committed headers
+/// in the order they were committed.
+async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
+    workspace_lock: CallZomeWorkspaceLock,
+    network: HolochainP2pCell,
+    keystore: KeystoreSender,
+    signal_tx: SignalBroadcaster,
+    ribosome: &Ribosome,
+    invocation: ZomeCallInvocation,
+) -> WorkflowResult<(RibosomeResult<ZomeCallResponse>, Vec<HeaderHash>)> {
     let zome_name = invocation.zome_name.clone();
 
     // Get the current head
@@ -108,13 +360,64 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
     };
     tracing::trace!(line = line!());
 
-    let to_app_validate = {
+    let committed = validate_new_elements(
+        workspace_lock,
+        chain_head_start,
+        &zome_name,
+        &agent_key,
+        ribosome,
+        network,
+    )
+    .await?;
+
+    Ok((result, committed))
+}
+
+/// Check none of the new elements break the DNA's [RateLimit], so the call
+/// fails here instead of publishing ops our authorities would reject.
+/// The new elements are newest first, as they come off the source chain.
+fn check_commit_rate(
+    source_chain: &SourceChain,
+    new_elements: &[Element],
+    rate_limit: RateLimit,
+) -> WorkflowResult<()> {
+    let oldest = match new_elements.last() {
+        Some(element) => Timestamp::from(element.header().timestamp()),
+        None => return Ok(()),
+    };
+    let cutoff: Timestamp = (chrono::DateTime::<chrono::Utc>::from(oldest)
+        - chrono::Duration::milliseconds(rate_limit.window_ms as i64))
+    .into();
+    // Every header that can fall in the window of a new header
+    let recent = source_chain
+        .iter_back()
+        .map(|shh| Ok(Timestamp::from(shh.header().timestamp())))
+        .take_while(|timestamp| Ok(*timestamp >= cutoff))
+        .collect::<Vec<_>>()?;
+    for element in new_elements {
+        check_rate_limit(element.header(), recent.iter().cloned(), rate_limit)?;
+    }
+    Ok(())
+}
+
+/// Sys and app validate every element committed since `chain_head_start`.
+/// Returns the hashes of their headers in the order they were committed.
+async fn validate_new_elements<Ribosome: RibosomeT>(
+    workspace_lock: CallZomeWorkspaceLock,
+    chain_head_start: HeaderHash,
+    zome_name: &ZomeName,
+    agent_key: &AgentPubKey,
+    ribosome: &Ribosome,
+    network: HolochainP2pCell,
+) -> WorkflowResult<Vec<HeaderHash>> {
+    let (to_app_validate, mut committed) = {
         let workspace = workspace_lock.read().await;
         // Get the new head
         let chain_head_end = workspace.source_chain.chain_head()?;
 
         // collect all the elements we need to validate in wasm
         let mut to_app_validate: Vec<Element> = vec![];
+        let mut committed = vec![];
 
         // Has there been changes?
         if chain_head_start != *chain_head_end {
@@ -145,13 +448,17 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
                     None => None,
                 };
                 if let Some(ref chain_element) = chain_element {
-                    sys_validate_element(&agent_key, chain_element, prev_chain_element.as_ref())
+                    sys_validate_element(agent_key, chain_element, prev_chain_element.as_ref())
                         .await?;
+                    committed.push(chain_element.header_address().clone());
                     to_app_validate.push(chain_element.to_owned());
                 }
             }
         }
-        to_app_validate
+        if let Some(rate_limit) = ribosome.dna_file().dna().limits.rate_limit {
+            check_commit_rate(&workspace.source_chain, &to_app_validate, rate_limit)?;
+        }
+        (to_app_validate, committed)
     };
 
     {
@@ -197,7 +504,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
                             link_add,
                             base,
                             target,
-                            &ribosome,
+                            ribosome,
                             workspace_lock.clone(),
                             network.clone(),
                         )?,
@@ -207,7 +514,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
                     app_validation_workflow::run_delete_link_validation_callback(
                         zome_name.clone(),
                         delete_link.clone(),
-                        &ribosome,
+                        ribosome,
                         workspace_lock.clone(),
                         network.clone(),
                     )?,
@@ -217,7 +524,7 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
                     Either::Right(app_validation_workflow::run_validation_callback(
                         ZomesToInvoke::One(zome_name.clone()),
                         element,
-                        &ribosome,
+                        ribosome,
                         workspace_lock.clone(),
                         network.clone(),
                     )?)
@@ -253,7 +560,9 @@ async fn call_zome_workflow_inner<'env, Ribosome: RibosomeT>(
         }
     }
 
-    Ok(result)
+    // The chain was walked from the new head back
+    committed.reverse();
+    Ok(committed)
 }
 
 pub struct CallZomeWorkspace {
@@ -303,16 +612,20 @@ impl Workspace for CallZomeWorkspace {
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::core::state::source_chain::SourceChainBuf;
     use crate::core::{
         ribosome::MockRibosomeT,
         workflow::{error::WorkflowError, genesis_workflow::tests::fake_genesis},
     };
-    use crate::fixt::KeystoreSenderFixturator;
+    use crate::fixt::{curve::Zomes, KeystoreSenderFixturator, WasmRibosomeFixturator};
     use ::fixt::prelude::*;
     use holochain_p2p::HolochainP2pCellFixturator;
     use holochain_serialized_bytes::prelude::*;
     use holochain_state::{env::ReadManager, test_utils::test_cell_env};
-    use holochain_types::{observability, test_utils::fake_agent_pubkey_1};
+    use holochain_types::{
+        observability,
+        test_utils::{fake_agent_pubkey_1, fake_cell_id},
+    };
     use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::entry::Entry;
     use holochain_zome_types::ExternInput;
@@ -328,21 +641,19 @@ pub mod tests {
         workspace: CallZomeWorkspace,
         ribosome: Ribosome,
         invocation: ZomeCallInvocation,
-    ) -> WorkflowResult<ZomeCallInvocationResult> {
+    ) -> WorkflowResult<RibosomeResult<ZomeCallResponse>> {
         let keystore = fixt!(KeystoreSender);
         let network = fixt!(HolochainP2pCell);
-        let args = CallZomeWorkflowArgs {
-            invocation,
-            ribosome,
-        };
         call_zome_workflow_inner(
             workspace.into(),
             network,
             keystore,
             SignalBroadcaster::noop(),
-            args,
+            &ribosome,
+            invocation,
         )
         .await
+        .map(|(result, _)| result)
     }
 
     // 1.  Check if there is a Capability token secret in the parameters.
@@ -519,4 +830,256 @@ pub mod tests {
             .unwrap();
         // TODO: Check the workspace has changes
     }
+
+    fn create_entry_invocation(fn_name: &str) -> ZomeCallInvocation {
+        crate::core::ribosome::ZomeCallInvocationFixturator::new(
+            crate::core::ribosome::NamedInvocation(
+                fake_cell_id(1),
+                TestWasm::Create.into(),
+                fn_name.into(),
+                ExternInput::new(().try_into().unwrap()),
+            ),
+        )
+        .next()
+        .unwrap()
+    }
+
+    #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+    struct Post(String);
+
+    fn create_post_invocation(content: &str) -> ZomeCallInvocation {
+        crate::core::ribosome::ZomeCallInvocationFixturator::new(
+            crate::core::ribosome::NamedInvocation(
+                fake_cell_id(1),
+                TestWasm::PostCommitCreate.into(),
+                "create_post".into(),
+                ExternInput::new(Post(content.into()).try_into().unwrap()),
+            ),
+        )
+        .next()
+        .unwrap()
+    }
+
+    async fn run_call_zome_batch(
+        env: &EnvironmentWrite,
+        invocations: Vec<ZomeCallInvocation>,
+    ) -> ZomeCallBatchResult {
+        let ribosome =
+            WasmRibosomeFixturator::new(Zomes(vec![TestWasm::Create, TestWasm::PostCommitCreate]))
+                .next()
+                .unwrap();
+        let workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let (trigger_produce_dht_ops, _rx) = TriggerSender::new();
+        call_zome_batch_workflow(
+            workspace,
+            fixt!(HolochainP2pCell),
+            env.keystore().clone(),
+            SignalBroadcaster::noop(),
+            env.clone(),
+            CallZomeBatchWorkflowArgs {
+                ribosome,
+                invocations,
+            },
+            trigger_produce_dht_ops,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn run_call_zome_post_commit(
+        env: &EnvironmentWrite,
+        invocation: ZomeCallInvocation,
+    ) -> ZomeCallInvocationResult {
+        let ribosome = WasmRibosomeFixturator::new(Zomes(vec![TestWasm::PostCommitCreate]))
+            .next()
+            .unwrap();
+        let workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let (trigger_produce_dht_ops, _rx) = TriggerSender::new();
+        call_zome_workflow(
+            workspace,
+            fixt!(HolochainP2pCell),
+            env.keystore().clone(),
+            SignalBroadcaster::noop(),
+            env.clone(),
+            CallZomeWorkflowArgs {
+                ribosome,
+                invocation,
+            },
+            trigger_produce_dht_ops,
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn genesis_env() -> holochain_state::test_utils::TestEnvironment {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        env.guard()
+            .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+            .unwrap();
+        test_env
+    }
+
+    /// The contents of the posts on the chain, oldest first
+    fn chain_posts(env: &EnvironmentWrite) -> Vec<String> {
+        let chain = SourceChainBuf::new(env.clone().into()).unwrap();
+        (0..chain.len() as u32)
+            .filter_map(|i| chain.get_at_index(i).unwrap())
+            .filter_map(|element| element.entry().to_app_option::<Post>().unwrap())
+            .map(|Post(content)| content)
+            .collect()
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn post_commit_receives_the_committed_headers() {
+        observability::test_run().ok();
+        let test_env = genesis_env().await;
+        let env = test_env.env();
+
+        // The wasm fails with the headers it was given
+        let result = run_call_zome_post_commit(&env, create_post_invocation("fail")).await;
+        let chain = SourceChainBuf::new(env.clone().into()).unwrap();
+        let post_header = chain.chain_head().unwrap().clone();
+        assert_matches!(
+            result.unwrap().post_commit_error,
+            Some(RibosomeError::PostCommitFailed(zome_name, headers, reason))
+            if zome_name == TestWasm::PostCommitCreate.into()
+                && headers.0 == vec![post_header]
+                && reason == "asked to fail"
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn post_commit_commits_are_validated_and_kept() {
+        observability::test_run().ok();
+        let test_env = genesis_env().await;
+        let env = test_env.env();
+
+        let result = run_call_zome_post_commit(&env, create_post_invocation("hello")).await;
+        assert_matches!(
+            result,
+            Ok(ZomeCallInvocationResponse {
+                response: ZomeCallResponse::Ok(_),
+                post_commit_error: None,
+            })
+        );
+        assert_eq!(
+            chain_posts(&env),
+            vec!["hello".to_string(), "reply to hello".to_string()]
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn failing_post_commit_commits_are_discarded() {
+        observability::test_run().ok();
+        let test_env = genesis_env().await;
+        let env = test_env.env();
+
+        // The callback failed after committing a reply,
+        // which is reported alongside the call's response
+        let result = run_call_zome_post_commit(&env, create_post_invocation("fail")).await;
+        assert_matches!(
+            result,
+            Ok(ZomeCallInvocationResponse {
+                response: ZomeCallResponse::Ok(_),
+                post_commit_error: Some(RibosomeError::PostCommitFailed(_, _, _)),
+            })
+        );
+        assert_eq!(chain_posts(&env), vec!["fail".to_string()]);
+
+        // The callback succeeded but committed an invalid reply,
+        // which is reported to the caller instead of failing the workflow
+        let result = run_call_zome_post_commit(&env, create_post_invocation("reply invalid")).await;
+        assert_matches!(
+            result,
+            Ok(ZomeCallInvocationResponse {
+                response: ZomeCallResponse::Ok(_),
+                post_commit_error: Some(RibosomeError::SourceChainError(
+                    SourceChainError::InvalidCommit(_)
+                )),
+            })
+        );
+        assert_eq!(
+            chain_posts(&env),
+            vec!["fail".to_string(), "reply invalid".to_string()]
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn zome_call_batch_commits_all_or_nothing() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+            fake_genesis(&mut workspace.source_chain).await.unwrap();
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+                .unwrap();
+        }
+        let chain_len = || SourceChainBuf::new(env.clone().into()).unwrap().len();
+        assert_eq!(chain_len(), 3);
+
+        // The second call fails so the first call's commit is discarded
+        let result = run_call_zome_batch(
+            &env,
+            vec![
+                create_entry_invocation("create_entry"),
+                create_entry_invocation("no_such_function"),
+            ],
+        )
+        .await;
+        assert_matches!(result, Err(RibosomeError::ZomeFnNotExists(_, _)));
+        assert_eq!(chain_len(), 3);
+
+        // Every call succeeds so both commits are kept,
+        // the second one after the first on the chain
+        let responses = run_call_zome_batch(
+            &env,
+            vec![
+                create_entry_invocation("create_entry"),
+                create_entry_invocation("create_entry"),
+            ],
+        )
+        .await
+        .unwrap();
+        assert_eq!(responses.responses.len(), 2);
+        assert_matches!(responses.post_commit_error, None);
+        assert_eq!(chain_len(), 5);
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn zome_call_batch_keeps_commits_when_post_commit_fails() {
+        observability::test_run().ok();
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        {
+            let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+            fake_genesis(&mut workspace.source_chain).await.unwrap();
+            env.guard()
+                .with_commit(|writer| workspace.flush_to_txn_ref(writer))
+                .unwrap();
+        }
+        let chain_len = || SourceChainBuf::new(env.clone().into()).unwrap().len();
+
+        let response = run_call_zome_batch(
+            &env,
+            vec![
+                create_entry_invocation("create_entry"),
+                create_post_invocation("fail"),
+            ],
+        )
+        .await
+        .unwrap();
+        // Both calls responded and their commits were kept,
+        // but the reply the failing post_commit committed was discarded
+        assert_eq!(response.responses.len(), 2);
+        assert_matches!(
+            response.post_commit_error,
+            Some(RibosomeError::PostCommitFailed(_, _, _))
+        );
+        assert_eq!(chain_len(), 5);
+    }
 }
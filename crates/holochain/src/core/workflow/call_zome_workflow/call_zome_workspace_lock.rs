@@ -20,3 +20,9 @@ impl From<CallZomeWorkspace> for CallZomeWorkspaceLock {
         Self::new(w)
     }
 }
+
+impl From<Arc<RwLock<CallZomeWorkspace>>> for CallZomeWorkspaceLock {
+    fn from(w: Arc<RwLock<CallZomeWorkspace>>) -> Self {
+        Self(w)
+    }
+}
@@ -29,6 +29,9 @@ pub enum WorkflowError {
     #[error("Agent is invalid: {0:?}")]
     AgentInvalid(AgentPubKey),
 
+    #[error("The genesis_self_check callback of zome {0} rejected the agent: {1}")]
+    GenesisSelfCheckFailed(ZomeName, String),
+
     #[error("Conductor API error: {0}")]
     ConductorApi(#[from] Box<ConductorApiError>),
 
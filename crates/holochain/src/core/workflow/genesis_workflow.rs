@@ -11,6 +11,12 @@ use super::error::{WorkflowError, WorkflowResult};
 use crate::conductor::api::CellConductorApiT;
 use crate::core::{
     queue_consumer::OneshotWriter,
+    ribosome::{
+        guest_callback::genesis_self_check::{
+            GenesisSelfCheckHostAccess, GenesisSelfCheckInvocation, GenesisSelfCheckResult,
+        },
+        RibosomeT,
+    },
     state::{
         source_chain::SourceChainBuf,
         workspace::{Workspace, WorkspaceResult},
@@ -20,44 +26,52 @@ use derive_more::Constructor;
 use holochain_state::prelude::*;
 use holochain_types::dna::DnaFile;
 use holochain_types::prelude::*;
+use holochain_zome_types::genesis::GenesisSelfCheckData;
 use tracing::*;
 
 /// The struct which implements the genesis Workflow
 #[derive(Constructor, Debug)]
-pub struct GenesisWorkflowArgs {
+pub struct GenesisWorkflowArgs<Ribosome: RibosomeT> {
     dna_file: DnaFile,
     agent_pubkey: AgentPubKey,
     membrane_proof: Option<SerializedBytes>,
+    ribosome: Ribosome,
 }
 
-#[instrument(skip(workspace, writer, api))]
-pub async fn genesis_workflow<'env, Api: CellConductorApiT>(
+#[instrument(skip(workspace, writer, api, args))]
+pub async fn genesis_workflow<'env, Api: CellConductorApiT, Ribosome: RibosomeT>(
     mut workspace: GenesisWorkspace,
     writer: OneshotWriter,
     api: Api,
-    args: GenesisWorkflowArgs,
+    args: GenesisWorkflowArgs<Ribosome>,
 ) -> WorkflowResult<()> {
     genesis_workflow_inner(&mut workspace, args, api).await?;
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     Ok(())
 }
 
-async fn genesis_workflow_inner<Api: CellConductorApiT>(
+async fn genesis_workflow_inner<Api: CellConductorApiT, Ribosome: RibosomeT>(
     workspace: &mut GenesisWorkspace,
-    args: GenesisWorkflowArgs,
+    args: GenesisWorkflowArgs<Ribosome>,
     api: Api,
 ) -> WorkflowResult<()> {
     let GenesisWorkflowArgs {
         dna_file,
         agent_pubkey,
         membrane_proof,
+        ribosome,
     } = args;
 
+    // A chain imported from an archive already carries its genesis elements
+    if workspace.source_chain.has_genesis() {
+        return Ok(());
+    }
+
     // TODO: this is a placeholder for a real DPKI request to show intent
     if api
         .dpki_request("is_agent_pubkey_valid".into(), agent_pubkey.to_string())
@@ -68,6 +82,18 @@ async fn genesis_workflow_inner<Api: CellConductorApiT>(
         return Err(WorkflowError::AgentInvalid(agent_pubkey.clone()));
     }
 
+    // Let the DNA check the membrane proof before anything is committed,
+    // so an agent who can't join finds out at install time
+    let invocation = GenesisSelfCheckInvocation::new(GenesisSelfCheckData {
+        agent_key: agent_pubkey.clone(),
+        membrane_proof: membrane_proof.clone(),
+    });
+    if let GenesisSelfCheckResult::Invalid(zome_name, reason) =
+        ribosome.run_genesis_self_check(GenesisSelfCheckHostAccess, invocation)?
+    {
+        return Err(WorkflowError::GenesisSelfCheckFailed(zome_name, reason));
+    }
+
     workspace
         .source_chain
         .genesis(
@@ -109,7 +135,7 @@ pub mod tests {
 
     use crate::{
         conductor::api::MockCellConductorApi,
-        core::{state::source_chain::SourceChain, SourceChainResult},
+        core::{ribosome::MockRibosomeT, state::source_chain::SourceChain, SourceChainResult},
     };
     use fallible_iterator::FallibleIterator;
     use holochain_state::test_utils::test_cell_env;
@@ -141,10 +167,15 @@ pub mod tests {
             let mut api = MockCellConductorApi::new();
             api.expect_sync_dpki_request()
                 .returning(|_, _| Ok("mocked dpki request response".to_string()));
+            let mut ribosome = MockRibosomeT::new();
+            ribosome
+                .expect_run_genesis_self_check()
+                .returning(|_, _| Ok(GenesisSelfCheckResult::Valid));
             let args = GenesisWorkflowArgs {
                 dna_file: dna.clone(),
                 agent_pubkey: agent_pubkey.clone(),
                 membrane_proof: None,
+                ribosome,
             };
             let _: () = genesis_workflow(workspace, arc.clone().into(), api, args).await?;
         }
@@ -171,6 +202,40 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn genesis_self_check_failure_leaves_chain_empty() -> Result<(), anyhow::Error> {
+        observability::test_run()?;
+        let test_env = test_cell_env();
+        let arc = test_env.env();
+
+        let workspace = GenesisWorkspace::new(arc.clone().into()).await?;
+        let mut api = MockCellConductorApi::new();
+        api.expect_sync_dpki_request()
+            .returning(|_, _| Ok("mocked dpki request response".to_string()));
+        let mut ribosome = MockRibosomeT::new();
+        ribosome
+            .expect_run_genesis_self_check()
+            .returning(|_, invocation| {
+                assert_eq!(invocation.payload.membrane_proof, None);
+                Ok(GenesisSelfCheckResult::Invalid(
+                    "foo".into(),
+                    "no invite".to_string(),
+                ))
+            });
+        let args = GenesisWorkflowArgs {
+            dna_file: fake_dna_file("a"),
+            agent_pubkey: fake_agent_pubkey_1(),
+            membrane_proof: None,
+            ribosome,
+        };
+        let result = genesis_workflow(workspace, arc.clone().into(), api, args).await;
+        assert_matches!(result, Err(WorkflowError::GenesisSelfCheckFailed(_, _)));
+
+        let workspace = GenesisWorkspace::new(arc.clone().into()).await?;
+        assert!(!workspace.source_chain.has_genesis());
+        Ok(())
+    }
 }
 
 /* TODO: make doc-able
@@ -1,8 +1,10 @@
 //! The workflow and queue consumer for DhtOp integration
 
 use super::*;
+use crate::conductor::interface::SignalBroadcaster;
 use crate::core::{
     queue_consumer::{OneshotWriter, TriggerSender, WorkComplete},
+    signal::SystemSignal,
     state::{
         cascade::error::CascadeResult,
         cascade::Cascade,
@@ -31,6 +33,7 @@ use holochain_state::{
     prelude::*,
 };
 use holochain_types::{
+    cell::CellId,
     dht_op::{produce_op_lights_from_elements, DhtOp, DhtOpLight, UniqueForm},
     element::{Element, SignedHeaderHashed, SignedHeaderHashedExt},
     validate::ValidationStatus,
@@ -50,11 +53,13 @@ pub use disintegrate::*;
 mod disintegrate;
 mod tests;
 
-#[instrument(skip(workspace, writer, trigger_sys))]
+#[instrument(skip(workspace, writer, trigger_sys, signal_tx))]
 pub async fn integrate_dht_ops_workflow(
     mut workspace: IntegrateDhtOpsWorkspace,
     writer: OneshotWriter,
     trigger_sys: &mut TriggerSender,
+    cell_id: &CellId,
+    mut signal_tx: SignalBroadcaster,
 ) -> WorkflowResult<WorkComplete> {
     // one of many possible ways to access the env
     let env = workspace.elements.headers().env().clone();
@@ -82,6 +87,8 @@ pub async fn integrate_dht_ops_workflow(
     }
 
     let mut total_integrated: usize = 0;
+    // Ops from our own headers, which we let clients know the outcome of
+    let mut own_ops_integrated = Vec::new();
 
     // Try to process the queue over and over again, until we either exhaust
     // the queue, or we can no longer integrate anything in the queue.
@@ -98,10 +105,18 @@ pub async fn integrate_dht_ops_workflow(
                 value,
                 order,
             } = so.0;
+            let is_own_op = op.header().author() == cell_id.agent_pubkey();
             // Check validation status and put in correct dbs
             let outcome = integrate_single_dht_op(value.clone(), op, &mut workspace).await?;
             match outcome {
                 Outcome::Integrated(integrated) => {
+                    if is_own_op {
+                        own_ops_integrated.push((
+                            hash.clone(),
+                            integrated.op.header_hash().clone(),
+                            integrated.validation_status.clone(),
+                        ));
+                    }
                     // TODO We could create a prefix for the integrated ops db
                     // and separate rejected ops from valid ops.
                     // Currently you need to check the IntegratedDhtOpsValue for
@@ -144,7 +159,7 @@ pub async fn integrate_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
 
@@ -152,6 +167,24 @@ pub async fn integrate_dht_ops_workflow(
         trigger_sys.trigger();
     }
 
+    for (op_hash, header_hash, validation_status) in own_ops_integrated {
+        let cell_id = cell_id.clone();
+        let signal = match validation_status {
+            ValidationStatus::Valid => SystemSignal::OpValidated {
+                cell_id,
+                op_hash,
+                header_hash,
+            },
+            ValidationStatus::Rejected => SystemSignal::OpRejected {
+                cell_id,
+                op_hash,
+                header_hash,
+            },
+            ValidationStatus::Abandoned => continue,
+        };
+        signal_tx.send_system(signal);
+    }
+
     Ok(result)
 }
 
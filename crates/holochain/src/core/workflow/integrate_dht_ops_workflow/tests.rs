@@ -542,9 +542,15 @@ impl Db {
 async fn call_workflow<'env>(env: EnvironmentWrite) {
     let workspace = IntegrateDhtOpsWorkspace::new(env.clone().into()).unwrap();
     let (mut qt, _rx) = TriggerSender::new();
-    integrate_dht_ops_workflow(workspace, env.clone().into(), &mut qt)
-        .await
-        .unwrap();
+    integrate_dht_ops_workflow(
+        workspace,
+        env.clone().into(),
+        &mut qt,
+        &fixt!(CellId),
+        SignalBroadcaster::noop(),
+    )
+    .await
+    .unwrap();
 }
 
 // Need to clear the data from the previous test
@@ -1337,6 +1343,7 @@ mod slow_tests {
                 uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
                 properties: SerializedBytes::try_from(()).unwrap(),
                 zomes: vec![TestWasm::Create.into()].into(),
+                limits: Default::default(),
             },
             vec![TestWasm::Create.into()],
         )
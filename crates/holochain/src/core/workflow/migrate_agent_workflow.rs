@@ -0,0 +1,355 @@
+//! # Migrate Agent Workflow
+//!
+//! Moves an agent from one DNA to another. The chain on the old DNA is
+//! closed with a [CloseChain] header naming the new DNA and the chain on the
+//! new DNA is opened with an [OpenChain] header naming the old one.
+//! Every zome's `migrate_agent` callback in both DNAs must pass before either
+//! header is written, and nothing can be written to a chain after it is closed.
+//! The workflow only prepares a header; the conductor writes both of them.
+//!
+//! [CloseChain]: holochain_zome_types::header::CloseChain
+//! [OpenChain]: holochain_zome_types::header::OpenChain
+
+use super::{error::WorkflowResult, CallZomeWorkspace, CallZomeWorkspaceLock};
+use crate::core::{
+    ribosome::{
+        error::{RibosomeError, RibosomeResult},
+        guest_callback::migrate_agent::{
+            MigrateAgentHostAccess, MigrateAgentInvocation, MigrateAgentResult,
+        },
+        RibosomeT,
+    },
+    state::workspace::{Workspace, WorkspaceResult},
+    sys_validate_element,
+};
+use derive_more::Constructor;
+use holo_hash::{DnaHash, HeaderHash};
+use holochain_state::prelude::Writer;
+use holochain_zome_types::header::builder;
+use holochain_zome_types::migrate_agent::MigrateAgent;
+use std::sync::Arc;
+use tracing::*;
+
+#[derive(Constructor, Debug)]
+pub struct MigrateAgentWorkflowArgs<Ribosome: RibosomeT> {
+    pub ribosome: Ribosome,
+    pub migrate_agent: MigrateAgent,
+    /// The new DNA when closing the chain,
+    /// the previous DNA when opening it
+    pub dna_hash: DnaHash,
+}
+
+pub type MigrateAgentWorkspace = CallZomeWorkspace;
+
+/// A [CloseChain] or [OpenChain] header which every zome's `migrate_agent`
+/// callback has approved and which has passed sys validation against the
+/// chain head, but which hasn't been written to the environment yet.
+/// Flushing it writes the header, or fails with
+/// [SourceChainError::HeadMoved] if the chain moved in the meantime.
+///
+/// [CloseChain]: holochain_zome_types::header::CloseChain
+/// [OpenChain]: holochain_zome_types::header::OpenChain
+/// [SourceChainError::HeadMoved]: crate::core::state::source_chain::SourceChainError::HeadMoved
+pub struct PreparedMigration {
+    workspace: MigrateAgentWorkspace,
+    /// The hash of the migration header
+    pub header_hash: HeaderHash,
+}
+
+impl Workspace for PreparedMigration {
+    fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
+        self.workspace.flush_to_txn_ref(writer)
+    }
+}
+
+/// Run every zome's `migrate_agent` callback and put the migration header
+/// into the workspace without writing it, so both sides of a migration can be
+/// checked before either chain is changed.
+#[instrument(skip(workspace, args))]
+pub async fn prepare_migration<Ribosome: RibosomeT>(
+    workspace: MigrateAgentWorkspace,
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<PreparedMigration> {
+    let MigrateAgentWorkflowArgs {
+        ribosome,
+        migrate_agent,
+        dna_hash,
+    } = args;
+    let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+    run_migrate_agent_callbacks(&ribosome, workspace_lock.clone(), migrate_agent.clone())?;
+    let header_hash = put_migration_header(workspace_lock.clone(), migrate_agent, dna_hash).await?;
+    // The callbacks' host access has been dropped so this is the only handle
+    let workspace = Arc::try_unwrap(workspace_lock.into_inner())
+        .unwrap_or_else(|_| panic!("Nothing else holds the migration workspace"))
+        .into_inner();
+    Ok(PreparedMigration {
+        workspace,
+        header_hash,
+    })
+}
+
+/// Run the `migrate_agent` callback of every zome, failing with
+/// [RibosomeError::MigrateAgentFailed] if any of them refuses the migration
+pub fn run_migrate_agent_callbacks<Ribosome: RibosomeT>(
+    ribosome: &Ribosome,
+    workspace_lock: CallZomeWorkspaceLock,
+    migrate_agent: MigrateAgent,
+) -> RibosomeResult<()> {
+    let host_access = MigrateAgentHostAccess::new(workspace_lock);
+    let invocation = MigrateAgentInvocation::new(ribosome.dna_file().dna().clone(), migrate_agent);
+    match ribosome.run_migrate_agent(host_access, invocation)? {
+        MigrateAgentResult::Pass => Ok(()),
+        MigrateAgentResult::Fail(zome_name, reason) => {
+            Err(RibosomeError::MigrateAgentFailed(zome_name, reason))
+        }
+    }
+}
+
+/// Write the [CloseChain] or [OpenChain] header to the source chain.
+/// The header is sys validated against the chain head so a chain which is
+/// already closed can't be closed again.
+///
+/// [CloseChain]: holochain_zome_types::header::CloseChain
+/// [OpenChain]: holochain_zome_types::header::OpenChain
+pub async fn put_migration_header(
+    workspace_lock: CallZomeWorkspaceLock,
+    migrate_agent: MigrateAgent,
+    dna_hash: DnaHash,
+) -> RibosomeResult<HeaderHash> {
+    let mut guard = workspace_lock.write().await;
+    let source_chain = &mut guard.source_chain;
+    let header_hash = match migrate_agent {
+        MigrateAgent::Close => {
+            source_chain
+                .put(
+                    builder::CloseChain {
+                        new_dna_hash: dna_hash,
+                    },
+                    None,
+                )
+                .await?
+        }
+        MigrateAgent::Open => {
+            source_chain
+                .put(
+                    builder::OpenChain {
+                        prev_dna_hash: dna_hash,
+                    },
+                    None,
+                )
+                .await?
+        }
+    };
+    let element = source_chain
+        .get_element(&header_hash)?
+        .expect("Element we just put in SourceChain must be gettable");
+    let prev_element = match element.header().prev_header() {
+        Some(prev_header) => source_chain.get_element(prev_header)?,
+        None => None,
+    };
+    sys_validate_element(
+        &source_chain.agent_pubkey()?,
+        &element,
+        prev_element.as_ref(),
+    )
+    .await?;
+    Ok(header_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conductor::{
+        dna_store::MockDnaStore, error::ConductorError, CellError, ConductorHandle,
+    };
+    use crate::core::ribosome::MockRibosomeT;
+    use crate::core::state::source_chain::{SourceChainBuf, SourceChainError};
+    use crate::core::workflow::fake_genesis;
+    use crate::fixt::DnaFileFixturator;
+    use crate::test_utils::setup_app;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::DnaHashFixturator;
+    use holochain_serialized_bytes::SerializedBytes;
+    use holochain_state::test_utils::test_cell_env;
+    use holochain_types::{
+        app::InstalledCell,
+        cell::CellId,
+        dna::{DnaDef, DnaFile},
+        element::Element,
+        test_utils::fake_agent_pubkey_1,
+    };
+    use holochain_wasm_test_utils::TestWasm;
+    use holochain_zome_types::Header;
+    use matches::assert_matches;
+    use std::convert::TryFrom;
+
+    #[tokio::test(threaded_scheduler)]
+    async fn close_chain_blocks_later_headers() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+
+        let new_dna_hash = fixt!(DnaHash);
+        let header_hash = put_migration_header(
+            workspace_lock.clone(),
+            MigrateAgent::Close,
+            new_dna_hash.clone(),
+        )
+        .await
+        .unwrap();
+        assert_matches!(
+            workspace_lock
+                .read()
+                .await
+                .source_chain
+                .get_element(&header_hash)
+                .unwrap()
+                .unwrap()
+                .header(),
+            Header::CloseChain(c) if c.new_dna_hash == new_dna_hash
+        );
+
+        let result =
+            put_migration_header(workspace_lock.clone(), MigrateAgent::Close, new_dna_hash).await;
+        assert_matches!(
+            result,
+            Err(RibosomeError::SourceChainError(
+                SourceChainError::InvalidPreviousHeader(_)
+            ))
+        );
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn failed_callback_refuses_migration() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        let dna_file = fixt!(DnaFile);
+        let mut ribosome = MockRibosomeT::new();
+        ribosome.expect_dna_file().return_const(dna_file);
+        ribosome.expect_run_migrate_agent().returning(|_, _| {
+            Ok(MigrateAgentResult::Fail(
+                "foo".into(),
+                "not yet".to_string(),
+            ))
+        });
+
+        let result = run_migrate_agent_callbacks(
+            &ribosome,
+            CallZomeWorkspaceLock::new(workspace),
+            MigrateAgent::Close,
+        );
+        assert_matches!(result, Err(RibosomeError::MigrateAgentFailed(_, _)));
+    }
+
+    async fn migration_dna(uuid: &str, zome: TestWasm) -> DnaFile {
+        DnaFile::new(
+            DnaDef {
+                name: "migrate_agent_test".to_string(),
+                uuid: uuid.to_string(),
+                properties: SerializedBytes::try_from(()).unwrap(),
+                zomes: vec![zome.into()].into(),
+                limits: Default::default(),
+            },
+            vec![zome.into()],
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn chain_head(handle: &ConductorHandle, cell_id: &CellId) -> Element {
+        let env = handle.get_cell_env(cell_id).await.unwrap();
+        let source_chain = SourceChainBuf::new(env.into()).unwrap();
+        let head = source_chain.chain_head().unwrap().clone();
+        source_chain.get_element(&head).unwrap().unwrap()
+    }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn migration_is_refused_unless_both_dnas_pass() {
+        let from_dna = migration_dna("from", TestWasm::MigrateAgentPass).await;
+        let refusing_dna = migration_dna("refusing", TestWasm::MigrateAgentFail).await;
+        let to_dna = migration_dna("to", TestWasm::MigrateAgentPass).await;
+        let dnas = vec![from_dna.clone(), refusing_dna.clone(), to_dna.clone()];
+
+        let agent = fake_agent_pubkey_1();
+        let cell_id = |dna: &DnaFile| CellId::new(dna.dna_hash().clone(), agent.clone());
+        let (from_cell, refusing_cell, to_cell) =
+            (cell_id(&from_dna), cell_id(&refusing_dna), cell_id(&to_dna));
+
+        let mut dna_store = MockDnaStore::new();
+        dna_store
+            .expect_get()
+            .returning(move |hash| dnas.iter().find(|dna| dna.dna_hash() == hash).cloned());
+        dna_store.expect_add_dnas::<Vec<_>>().return_const(());
+        dna_store.expect_add_entry_defs::<Vec<_>>().return_const(());
+        dna_store.expect_get_entry_def().return_const(None);
+
+        let (_tmpdir, _app_api, handle) = setup_app(
+            vec![(
+                "migrate_app",
+                vec![
+                    (InstalledCell::new(from_cell.clone(), "from".into()), None),
+                    (
+                        InstalledCell::new(refusing_cell.clone(), "refusing".into()),
+                        None,
+                    ),
+                    (InstalledCell::new(to_cell.clone(), "to".into()), None),
+                ],
+            )],
+            dna_store,
+        )
+        .await;
+
+        // The target DNA refuses, so the old chain is left open
+        let from_head = chain_head(&handle, &from_cell).await;
+        let refusing_head = chain_head(&handle, &refusing_cell).await;
+        let result = handle
+            .migrate_agent(
+                agent.clone(),
+                from_dna.dna_hash().clone(),
+                refusing_dna.dna_hash().clone(),
+            )
+            .await;
+        assert_matches!(
+            result,
+            Err(ConductorError::InternalCellError(CellError::WorkflowError(
+                _
+            )))
+        );
+        assert_eq!(chain_head(&handle, &from_cell).await, from_head);
+        assert_eq!(chain_head(&handle, &refusing_cell).await, refusing_head);
+
+        // Both pass, so the chain is closed on one and opened on the other
+        let to_head = chain_head(&handle, &to_cell).await;
+        handle
+            .migrate_agent(
+                agent.clone(),
+                from_dna.dna_hash().clone(),
+                to_dna.dna_hash().clone(),
+            )
+            .await
+            .unwrap();
+        let close = chain_head(&handle, &from_cell).await;
+        assert_matches!(
+            close.header(),
+            Header::CloseChain(c)
+                if &c.new_dna_hash == to_dna.dna_hash()
+                    && c.prev_header == *from_head.header_address()
+                    && c.header_seq == from_head.header().header_seq() + 1
+        );
+        let open = chain_head(&handle, &to_cell).await;
+        assert_matches!(
+            open.header(),
+            Header::OpenChain(o)
+                if &o.prev_dna_hash == from_dna.dna_hash()
+                    && o.prev_header == *to_head.header_address()
+                    && o.header_seq == to_head.header().header_seq() + 1
+        );
+
+        let shutdown = handle.take_shutdown_handle().await.unwrap();
+        handle.shutdown().await;
+        shutdown.await.unwrap();
+    }
+}
@@ -26,7 +26,7 @@ pub async fn produce_dht_ops_workflow(
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
 
     // trigger other workflows
     trigger_publish.trigger();
@@ -151,6 +151,7 @@ mod tests {
         observability::test_run().ok();
         let test_env = test_cell_env();
         let env = test_env.env();
+        crate::test_utils::load_private_entry_key(&env).await;
         let env_ref = env.guard();
 
         // Setup the database and expected data
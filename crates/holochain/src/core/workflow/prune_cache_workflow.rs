@@ -0,0 +1,261 @@
+//! # Prune Cache Workflow
+//!
+//! The cascade caches every element and piece of metadata it fetches from
+//! the network, so on a long-running node the cache databases would grow
+//! without bound. This workflow keeps them within the [CacheConfig] limits.
+//!
+//! Everything cached about one basis hash (an entry, header or agent) is
+//! treated as a single item: the element data, the system, links and status
+//! metadata are always evicted together, so the cache never holds a status
+//! without the headers it was computed from.
+//!
+//! The [CACHE_AGE] database records when the cascade last used each item.
+//! Items unused for longer than the maximum age are evicted, then the least
+//! recently used until the cache fits its byte budget. An evicted item is
+//! simply fetched from the network again the next time it's needed.
+//! The authored and integrated vaults are never touched.
+
+use super::error::WorkflowResult;
+use crate::conductor::config::CacheConfig;
+use crate::core::state::{
+    cache_access,
+    metadata::{delete_prefixed, MetadataBuf},
+};
+use holo_hash::HOLO_HASH_SERIALIZED_LEN;
+use holochain_state::{
+    db::{
+        CACHE_AGE, CACHE_LINKS_META, CACHE_STATUS_META, CACHE_SYSTEM_META, ELEMENT_CACHE_ENTRIES,
+        ELEMENT_CACHE_HEADERS,
+    },
+    env::EnvironmentWrite,
+    error::{DatabaseError, DatabaseResult},
+    fresh_reader,
+    prelude::*,
+};
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::*;
+
+#[cfg(test)]
+mod tests;
+
+/// How many items are written in each transaction,
+/// so a pass never holds the write lock for long
+const PRUNE_BATCH_SIZE: usize = 256;
+
+/// What one pass of pruning did
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PruneCacheSummary {
+    /// Items left in the cache
+    pub kept: usize,
+    /// Bytes of keys and values left in the cache
+    pub kept_bytes: usize,
+    /// Items evicted from the cache
+    pub evicted: usize,
+}
+
+/// Prune a Cell's cache once, as of now
+#[instrument(skip(env))]
+pub async fn prune_cache_workflow(
+    env: EnvironmentWrite,
+    config: &CacheConfig,
+) -> WorkflowResult<PruneCacheSummary> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let summary = prune_cache(&env, config, now)?;
+    Ok(summary)
+}
+
+/// The databases which are pruned
+struct CacheDbs {
+    entries: SingleStore,
+    headers: SingleStore,
+    system_meta: MultiStore,
+    links_meta: SingleStore,
+    misc_meta: SingleStore,
+}
+
+impl CacheDbs {
+    fn new(env: &EnvironmentWrite) -> DatabaseResult<Self> {
+        Ok(Self {
+            entries: env.get_db(&*ELEMENT_CACHE_ENTRIES)?,
+            headers: env.get_db(&*ELEMENT_CACHE_HEADERS)?,
+            system_meta: env.get_db(&*CACHE_SYSTEM_META)?,
+            links_meta: env.get_db(&*CACHE_LINKS_META)?,
+            misc_meta: env.get_db(&*CACHE_STATUS_META)?,
+        })
+    }
+
+    /// Add up the size of the keys and values cached about each basis
+    fn measure<R: Readable>(&self, r: &R) -> DatabaseResult<HashMap<Vec<u8>, usize>> {
+        let mut sizes = HashMap::new();
+        let mut add = |basis: Option<Vec<u8>>, size: usize| {
+            if let Some(basis) = basis {
+                *sizes.entry(basis).or_insert(0) += size;
+            }
+        };
+        // These keys are all the integrated prefix followed by the basis
+        let prefixed_iters = vec![
+            self.entries.iter_start(r)?,
+            self.headers.iter_start(r)?,
+            self.system_meta.iter_start(r)?,
+            self.links_meta.iter_start(r)?,
+        ];
+        for iter in prefixed_iters {
+            for kv in iter {
+                let (k, v) = kv?;
+                add(prefixed_basis(k), k.len() + v.len());
+            }
+        }
+        for kv in self.misc_meta.iter_start(r)? {
+            let (k, v) = kv?;
+            add(
+                MetadataBuf::<IntegratedPrefix>::cache_misc_basis(k),
+                k.len() + v.len(),
+            );
+        }
+        Ok(sizes)
+    }
+
+    /// Delete everything cached about a basis
+    fn evict(&self, writer: &mut Writer, basis: &[u8]) -> DatabaseResult<()> {
+        let mut key = vec![IntegratedPrefix::PREFIX];
+        key.extend_from_slice(basis);
+        self.entries.delete(writer, &key)?;
+        self.headers.delete(writer, &key)?;
+        self.system_meta.delete_all(writer, &key)?;
+        delete_prefixed(self.links_meta, writer, &key)?;
+        MetadataBuf::<IntegratedPrefix>::evict_cache_misc(self.misc_meta, writer, basis)
+    }
+}
+
+fn prefixed_basis(key: &[u8]) -> Option<Vec<u8>> {
+    key.get(1..=HOLO_HASH_SERIALIZED_LEN).map(<[u8]>::to_vec)
+}
+
+/// Everything cached about one basis
+struct CacheItem {
+    basis: Vec<u8>,
+    /// When the cascade last used the item, in seconds since the epoch
+    last_used: u64,
+    /// The size of the keys and values cached about the basis
+    size: usize,
+    /// The last use is newer than the one in [CACHE_AGE]
+    touched: bool,
+}
+
+/// What a pass does to an item, or to the age of an item
+/// which has already left the cache
+enum PruneOp {
+    Evict(Vec<u8>),
+    Touch(Vec<u8>, u64),
+    Forget(Vec<u8>),
+}
+
+/// Run one pass of pruning as of `now`, in seconds since the epoch
+pub(crate) fn prune_cache(
+    env: &EnvironmentWrite,
+    config: &CacheConfig,
+    now: u64,
+) -> DatabaseResult<PruneCacheSummary> {
+    let used = cache_access::take(env);
+    prune_cache_with_uses(env, config, now, &used).map_err(|e| {
+        // Nothing is lost if the pass is retried with these uses
+        cache_access::restore(env, used);
+        e
+    })
+}
+
+fn prune_cache_with_uses(
+    env: &EnvironmentWrite,
+    config: &CacheConfig,
+    now: u64,
+    used: &HashMap<Vec<u8>, u64>,
+) -> DatabaseResult<PruneCacheSummary> {
+    let dbs = CacheDbs::new(env)?;
+    let ages = env.get_db(&*CACHE_AGE)?;
+
+    // Measure the cache in a read transaction so writers aren't blocked.
+    // Only the basis hashes and their sizes are held, never the cached values.
+    let (mut items, forgotten) = fresh_reader!(env, |r| {
+        let sizes = dbs.measure(&r)?;
+        // Ages of items which have left the cache since the last pass
+        let mut forgotten = Vec::new();
+        for kv in ages.iter_start(&r)? {
+            let (k, _) = kv?;
+            if !sizes.contains_key(k) {
+                forgotten.push(PruneOp::Forget(k.to_vec()));
+            }
+        }
+        let mut items = Vec::with_capacity(sizes.len());
+        for (basis, size) in sizes {
+            let stored = ages.get(&r, &basis)?.and_then(decode_age);
+            let last_used = match (stored, used.get(&basis)) {
+                (Some(stored), Some(&used)) => stored.max(used),
+                (Some(stored), None) => stored,
+                (None, Some(&used)) => used,
+                // Never used since it was cached, so its age starts now
+                (None, None) => now,
+            };
+            items.push(CacheItem {
+                touched: stored != Some(last_used),
+                basis,
+                last_used,
+                size,
+            });
+        }
+        DatabaseResult::Ok((items, forgotten))
+    })?;
+
+    // Least recently used first
+    items.sort_by_key(|item| item.last_used);
+    let mut summary = PruneCacheSummary {
+        kept_bytes: items.iter().map(|item| item.size).sum(),
+        ..Default::default()
+    };
+    let mut ops = forgotten;
+    for item in items {
+        let expired = now.saturating_sub(item.last_used) > config.max_age_secs;
+        if expired || summary.kept_bytes > config.max_bytes {
+            summary.kept_bytes -= item.size;
+            summary.evicted += 1;
+            ops.push(PruneOp::Evict(item.basis));
+        } else {
+            summary.kept += 1;
+            if item.touched {
+                ops.push(PruneOp::Touch(item.basis, item.last_used));
+            }
+        }
+    }
+
+    // Each item is evicted whole within one transaction
+    for batch in ops.chunks(PRUNE_BATCH_SIZE) {
+        env.with_commit::<DatabaseError, _, _>(|writer| {
+            for op in batch {
+                match op {
+                    PruneOp::Evict(basis) => {
+                        dbs.evict(writer, basis)?;
+                        ages.delete(writer, basis)?;
+                    }
+                    PruneOp::Touch(basis, last_used) => {
+                        ages.put(writer, basis, &last_used.to_be_bytes())?
+                    }
+                    PruneOp::Forget(basis) => ages.delete(writer, basis)?,
+                }
+            }
+            Ok(())
+        })?;
+    }
+    Ok(summary)
+}
+
+fn decode_age(bytes: &[u8]) -> Option<u64> {
+    let mut secs = [0; 8];
+    if bytes.len() != secs.len() {
+        return None;
+    }
+    secs.copy_from_slice(bytes);
+    Some(u64::from_be_bytes(secs))
+}
@@ -0,0 +1,167 @@
+use super::*;
+use crate::core::state::metadata::MetadataBufT;
+use crate::fixt::CreateFixturator;
+use ::fixt::prelude::*;
+use holo_hash::EntryHash;
+use holochain_state::{db::ELEMENT_VAULT_HEADERS, test_utils::test_cell_env};
+use holochain_types::{header::NewEntryHeader, metadata::EntryDhtStatus};
+
+fn basis(n: u8) -> Vec<u8> {
+    vec![n; HOLO_HASH_SERIALIZED_LEN]
+}
+
+fn cached_key(n: u8) -> Vec<u8> {
+    let mut key = vec![IntegratedPrefix::PREFIX];
+    key.extend(basis(n));
+    key
+}
+
+fn put_cached(env: &EnvironmentWrite, n: u8, value: &[u8]) -> DatabaseResult<()> {
+    let entries = env.get_db(&*ELEMENT_CACHE_ENTRIES)?;
+    env.with_commit(|writer| entries.put(writer, cached_key(n), value))
+}
+
+fn cached_keys(env: &EnvironmentWrite) -> DatabaseResult<Vec<Vec<u8>>> {
+    let entries = env.get_db(&*ELEMENT_CACHE_ENTRIES)?;
+    let g = env.guard();
+    let r = g.reader()?;
+    let keys = entries
+        .iter_start(&r)?
+        .map(|kv| kv.map(|(k, _)| k.to_vec()))
+        .collect::<DatabaseResult<_>>()?;
+    Ok(keys)
+}
+
+fn config(max_bytes: usize, max_age_secs: u64) -> CacheConfig {
+    CacheConfig {
+        max_bytes,
+        max_age_secs,
+        ..Default::default()
+    }
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn evicts_items_unused_for_max_age() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let config = config(1024, 50);
+
+    put_cached(&env, 1, b"value")?;
+    assert_eq!(prune_cache(&env, &config, 100)?.evicted, 0);
+
+    put_cached(&env, 2, b"value")?;
+    assert_eq!(prune_cache(&env, &config, 120)?.evicted, 0);
+
+    let summary = prune_cache(&env, &config, 160)?;
+    assert_eq!(
+        summary,
+        PruneCacheSummary {
+            kept: 1,
+            kept_bytes: cached_key(2).len() + "value".len(),
+            evicted: 1,
+        }
+    );
+    assert_eq!(cached_keys(&env)?, vec![cached_key(2)]);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn using_an_item_keeps_it() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let config = config(1024, 50);
+
+    put_cached(&env, 1, b"value")?;
+    prune_cache(&env, &config, 100)?;
+    cache_access::touch_at(&env, basis(1), 140);
+
+    assert_eq!(prune_cache(&env, &config, 160)?.evicted, 0);
+    assert_eq!(prune_cache(&env, &config, 191)?.evicted, 1);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn restored_uses_keep_an_item() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let config = config(1024, 50);
+
+    put_cached(&env, 1, b"value")?;
+    prune_cache(&env, &config, 100)?;
+    cache_access::touch_at(&env, basis(1), 140);
+    // As a failed pass would
+    let used = cache_access::take(&env);
+    cache_access::restore(&env, used);
+
+    assert_eq!(prune_cache(&env, &config, 160)?.evicted, 0);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn evicts_least_recently_used_over_budget() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let item_size = cached_key(0).len() + 63;
+
+    put_cached(&env, 1, &[0; 63])?;
+    prune_cache(&env, &config(1024, 1000), 100)?;
+    put_cached(&env, 2, &[0; 63])?;
+    prune_cache(&env, &config(1024, 1000), 110)?;
+    put_cached(&env, 3, &[0; 63])?;
+    // The oldest item was used most recently
+    cache_access::touch_at(&env, basis(1), 115);
+
+    let summary = prune_cache(&env, &config(item_size * 2, 1000), 120)?;
+    assert_eq!(summary.evicted, 1);
+    assert_eq!(summary.kept_bytes, item_size * 2);
+    assert_eq!(cached_keys(&env)?, vec![cached_key(1), cached_key(3)]);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn evicts_metadata_with_its_basis() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let create = fixt!(Create);
+    let entry_hash: EntryHash = create.entry_hash.clone();
+
+    let mut meta = MetadataBuf::cache(env.clone().into())?;
+    meta.register_header(NewEntryHeader::Create(create))?;
+    env.with_commit(|writer| meta.flush_to_txn_ref(writer))?;
+
+    let meta = MetadataBuf::cache(env.clone().into())?;
+    fresh_reader!(env, |r| {
+        assert_eq!(meta.get_dht_status(&r, &entry_hash)?, EntryDhtStatus::Live);
+        DatabaseResult::Ok(())
+    })?;
+
+    let summary = prune_cache(&env, &config(0, 1000), 100)?;
+    assert_eq!(summary.kept, 0);
+
+    // The status and the headers it came from are gone together
+    let dbs = CacheDbs::new(&env)?;
+    fresh_reader!(env, |r| {
+        assert!(dbs.measure(&r)?.is_empty());
+        assert_eq!(meta.get_headers(&r, entry_hash.clone())?.count()?, 0);
+        assert_ne!(meta.get_dht_status(&r, &entry_hash)?, EntryDhtStatus::Live);
+        DatabaseResult::Ok(())
+    })
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn leaves_vault_untouched() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let headers = env.get_db(&*ELEMENT_VAULT_HEADERS)?;
+    env.with_commit(|writer| headers.put(writer, cached_key(1), b"value"))?;
+    put_cached(&env, 1, b"value")?;
+
+    let summary = prune_cache(&env, &config(0, 0), 100)?;
+    assert_eq!(summary.evicted, 1);
+    assert_eq!(cached_keys(&env)?, Vec::<Vec<u8>>::new());
+
+    let g = env.guard();
+    let r = g.reader()?;
+    assert_eq!(headers.get(&r, cached_key(1))?, Some(&b"value"[..]));
+    Ok(())
+}
@@ -14,11 +14,16 @@ use super::{
     error::WorkflowResult,
     produce_dht_ops_workflow::dht_op_light::{error::DhtOpConvertError, light_to_op},
 };
+use crate::conductor::api::CellConductorApiT;
+use crate::conductor::entry_def_store::EntryDefBufferKey;
+use crate::conductor::interface::SignalBroadcaster;
 use crate::core::{
     queue_consumer::{OneshotWriter, WorkComplete},
+    signal::SystemSignal,
     state::{
-        dht_op_integration::AuthoredDhtOpsStore,
+        dht_op_integration::{AuthoredDhtOpsStore, AuthoredDhtOpsValue},
         element_buf::ElementBuf,
+        validation_receipts_db::ValidationReceiptsBuf,
         workspace::{Workspace, WorkspaceResult},
     },
 };
@@ -29,19 +34,25 @@ use holochain_p2p::HolochainP2pCellT;
 use holochain_state::{
     buffer::{BufferedStore, KvBufFresh},
     db::AUTHORED_DHT_OPS,
+    error::DatabaseResult,
     fresh_reader,
     prelude::*,
     transaction::Writer,
 };
-use holochain_types::{dht_op::DhtOp, Timestamp};
+use holochain_types::{
+    cell::CellId,
+    dht_op::{DhtOp, DhtOpLight},
+    Timestamp,
+};
+use holochain_zome_types::header::{AppEntryType, EntryType};
 use std::collections::HashMap;
 use std::time;
 use tracing::*;
 
-/// Default redundancy factor for validation receipts
-// TODO: Pull this from the wasm entry def and only use this if it's missing
+/// Default redundancy factor for validation receipts.
+/// Used for ops that aren't on an app entry, or when
+/// the entry def is missing from the entry def store
 // TODO: Put a default in the DnaBundle
-// TODO: build zome_types/entry_def map to get the (AppEntryType map to entry def)
 pub const DEFAULT_RECEIPT_BUNDLE_SIZE: u32 = 5;
 
 /// Don't publish a DhtOp more than once during this interval.
@@ -57,29 +68,48 @@ pub struct PublishDhtOpsWorkspace {
     elements: ElementBuf,
 }
 
-#[instrument(skip(workspace, writer, network))]
+#[instrument(skip(workspace, writer, network, signal_tx, conductor_api))]
 pub async fn publish_dht_ops_workflow(
     mut workspace: PublishDhtOpsWorkspace,
     writer: OneshotWriter,
     network: &mut HolochainP2pCell,
+    mut signal_tx: SignalBroadcaster,
+    conductor_api: &impl CellConductorApiT,
 ) -> WorkflowResult<WorkComplete> {
-    let to_publish = publish_dht_ops_workflow_inner(&mut workspace).await?;
+    let to_publish = publish_dht_ops_workflow_inner(&mut workspace, conductor_api).await?;
 
     // Commit to the network
+    let mut published = Vec::new();
     for (basis, ops) in to_publish {
+        let op_hashes = ops
+            .iter()
+            .map(|(op_hash, _)| op_hash.clone())
+            .collect::<Vec<_>>();
         network.publish(true, basis, ops, None).await?;
+        published.extend(op_hashes);
     }
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
     // commit the workspace
-    writer.with_writer(|writer| Ok(workspace.flush_to_txn(writer)?))?;
+    writer.with_writer(|writer| Ok(workspace.flush_to_txn_ref(writer)?))?;
+
+    // let clients know what was published
+    let cell_id = CellId::new(network.dna_hash(), network.from_agent());
+    for op_hash in published {
+        signal_tx.send_system(SystemSignal::OpPublished {
+            cell_id: cell_id.clone(),
+            op_hash,
+        });
+    }
 
     Ok(WorkComplete::Complete)
 }
 
-/// Read the authored for ops with receipt count < R
+/// Read the authored for ops with receipt count < R,
+/// where R is the required validations of the op's entry def
 pub async fn publish_dht_ops_workflow_inner(
     workspace: &mut PublishDhtOpsWorkspace,
+    conductor_api: &impl CellConductorApiT,
 ) -> WorkflowResult<HashMap<AnyDhtHash, Vec<(DhtOpHash, DhtOp)>>> {
     // TODO: PERF: We need to check all ops every time this runs
     // instead we could have a queue of ops where count < R and a kv for count > R.
@@ -96,31 +126,53 @@ pub async fn publish_dht_ops_workflow_inner(
     let values = fresh_reader!(env, |r| workspace
         .authored()
         .iter(&r)?
-        .filter_map(|(k, mut r)| {
-            Ok(if r.receipt_count < DEFAULT_RECEIPT_BUNDLE_SIZE {
-                let needs_publish = r
-                    .last_publish_time
-                    .map(|last| {
-                        let duration = now.signed_duration_since(last.into());
-                        duration > interval
-                    })
-                    .unwrap_or(true);
-                if needs_publish {
-                    r.last_publish_time = Some(now_ts);
-                    Some((DhtOpHash::with_pre_hashed(k.to_vec()), r))
-                } else {
-                    None
-                }
+        .filter_map(|(k, r)| {
+            let needs_publish = r
+                .last_publish_time
+                .map(|last| {
+                    let duration = now.signed_duration_since(last.into());
+                    duration > interval
+                })
+                .unwrap_or(true);
+            Ok(if needs_publish {
+                Some((DhtOpHash::with_pre_hashed(k.to_vec()), r))
             } else {
                 None
             })
         })
         .collect::<Vec<_>>())?;
 
+    // The receipt handler updates the stored count, but an update made while
+    // this workflow runs is overwritten when the workspace is flushed, so the
+    // count is read from the receipts themselves. Receipts are never removed,
+    // so a higher stored count is kept.
+    let receipts = ValidationReceiptsBuf::new(&env)?;
+    let values = fresh_reader!(env, |r| values
+        .into_iter()
+        .map(|(op_hash, mut value): (DhtOpHash, AuthoredDhtOpsValue)| {
+            let receipt_count = receipts.count_valid(&r, &op_hash)? as u32;
+            value.receipt_count = value.receipt_count.max(receipt_count);
+            Ok((op_hash, value))
+        })
+        .collect::<DatabaseResult<Vec<_>>>())?;
+
     // Ops to publish by basis
     let mut to_publish = HashMap::new();
-
-    for (op_hash, value) in values {
+    // Entry defs looked up during this run
+    let mut required = HashMap::new();
+
+    for (op_hash, mut value) in values {
+        let required_validations = required_validations(
+            &value.op,
+            workspace.elements(),
+            conductor_api,
+            &mut required,
+        )
+        .await?;
+        if value.receipt_count >= required_validations {
+            continue;
+        }
+        value.last_publish_time = Some(now_ts);
         // Insert updated values into database for items about to be published
         let op = value.op.clone();
         workspace.authored().put(op_hash.clone(), value)?;
@@ -141,6 +193,55 @@ pub async fn publish_dht_ops_workflow_inner(
     Ok(to_publish)
 }
 
+/// How many validation receipts an op needs before we stop publishing it.
+/// Ops which carry an app entry to its authorities use the required
+/// validations of the entry def from the entry def store, at least one.
+/// Every other op, including the agent activity and metadata ops of a header
+/// with an app entry, goes to authorities of a different basis which don't
+/// validate the entry, so it uses the default.
+async fn required_validations(
+    op: &DhtOpLight,
+    elements: &ElementBuf,
+    conductor_api: &impl CellConductorApiT,
+    required: &mut HashMap<AppEntryType, u32>,
+) -> WorkflowResult<u32> {
+    match op {
+        DhtOpLight::StoreElement(_, Some(_), _) | DhtOpLight::StoreEntry(_, _, _) => (),
+        _ => return Ok(DEFAULT_RECEIPT_BUNDLE_SIZE),
+    }
+    let app_entry_type = match elements
+        .get_header(op.header_hash())?
+        .as_ref()
+        .and_then(|h| h.header().entry_type())
+    {
+        Some(EntryType::App(app_entry_type)) => app_entry_type.clone(),
+        _ => return Ok(DEFAULT_RECEIPT_BUNDLE_SIZE),
+    };
+    if let Some(r) = required.get(&app_entry_type) {
+        return Ok(*r);
+    }
+    let zome = conductor_api.get_this_dna().await.and_then(|dna_file| {
+        dna_file
+            .dna()
+            .zomes
+            .get(u8::from(app_entry_type.zome_id()) as usize)
+            .map(|(_, zome)| zome.clone())
+    });
+    let entry_def = match zome {
+        Some(zome) => {
+            let key = EntryDefBufferKey::new(zome, app_entry_type.id());
+            conductor_api.get_entry_def(&key).await
+        }
+        None => None,
+    };
+    // An op needing no receipts would never be published at all
+    let r = entry_def
+        .map(|entry_def| (u8::from(entry_def.required_validations) as u32).max(1))
+        .unwrap_or(DEFAULT_RECEIPT_BUNDLE_SIZE);
+    required.insert(app_entry_type, r);
+    Ok(r)
+}
+
 impl Workspace for PublishDhtOpsWorkspace {
     fn flush_to_txn_ref(&mut self, writer: &mut Writer) -> WorkspaceResult<()> {
         self.authored_dht_ops.flush_to_txn_ref(writer)?;
@@ -173,6 +274,7 @@ impl PublishDhtOpsWorkspace {
 mod tests {
     use super::*;
     use crate::{
+        conductor::api::MockCellConductorApi,
         core::{
             queue_consumer::TriggerSender,
             state::{dht_op_integration::AuthoredDhtOpsValue, source_chain::SourceChain},
@@ -182,7 +284,7 @@ mod tests {
             },
             SourceChainError,
         },
-        fixt::{CreateLinkFixturator, EntryFixturator},
+        fixt::{CreateFixturator, CreateLinkFixturator, EntryDefFixturator, EntryFixturator},
     };
     use ::fixt::prelude::*;
     use futures::future::FutureExt;
@@ -192,6 +294,7 @@ mod tests {
         actor::{HolochainP2p, HolochainP2pRefToCell, HolochainP2pSender},
         spawn_holochain_p2p, HolochainP2pRef,
     };
+    use holochain_serialized_bytes::prelude::*;
     use holochain_state::{
         buffer::BufferedStore,
         env::{EnvironmentWrite, ReadManager, WriteManager},
@@ -200,9 +303,11 @@ mod tests {
     };
     use holochain_types::{
         dht_op::{DhtOp, DhtOpHashed, DhtOpLight},
+        dna::{DnaDef, DnaFile},
         fixt::{AppEntryTypeFixturator, SignatureFixturator},
         observability, HeaderHashed,
     };
+    use holochain_wasm_test_utils::TestWasm;
     use holochain_zome_types::entry_def::EntryVisibility;
     use holochain_zome_types::{
         element::SignedHeaderHashed,
@@ -211,7 +316,7 @@ mod tests {
     use matches::assert_matches;
     use std::{
         collections::HashMap,
-        convert::TryInto,
+        convert::{TryFrom, TryInto},
         sync::{
             atomic::{AtomicU32, Ordering},
             Arc,
@@ -335,9 +440,18 @@ mod tests {
     /// Call the workflow
     async fn call_workflow(env: EnvironmentWrite, mut cell_network: HolochainP2pCell) {
         let workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
-        publish_dht_ops_workflow(workspace, env.clone().into(), &mut cell_network)
-            .await
-            .unwrap();
+        // Without a dna every op needs the default number of receipts
+        let mut conductor_api = MockCellConductorApi::new();
+        conductor_api.expect_sync_get_this_dna().return_const(None);
+        publish_dht_ops_workflow(
+            workspace,
+            env.clone().into(),
+            &mut cell_network,
+            SignalBroadcaster::noop(),
+            &conductor_api,
+        )
+        .await
+        .unwrap();
     }
 
     /// There is a test that shows that network messages would be sent to all agents via broadcast.
@@ -493,6 +607,7 @@ mod tests {
                 // Create test env
                 let test_env = test_cell_env();
                 let env = test_env.env();
+                crate::test_utils::load_private_entry_key(&env).await;
                 let env_ref = env.guard();
 
                 // Setup data
@@ -750,5 +865,103 @@ mod tests {
         );
     }
 
+    fn required_validations_conductor_api(
+        dna_file: DnaFile,
+        required_validations: u8,
+    ) -> MockCellConductorApi {
+        let mut entry_def = fixt!(EntryDef);
+        entry_def.required_validations = required_validations.into();
+        let mut conductor_api = MockCellConductorApi::new();
+        conductor_api
+            .expect_sync_get_this_dna()
+            .return_const(Some(dna_file));
+        conductor_api
+            .expect_sync_get_entry_def()
+            .return_const(Some(entry_def));
+        conductor_api
+    }
+
+    /// Ops carrying an app entry need the required validations of its entry
+    /// def, at least one, and everything else needs the default
+    #[tokio::test(threaded_scheduler)]
+    async fn required_validations_from_entry_def() {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = PublishDhtOpsWorkspace::new(env.clone().into()).unwrap();
+
+        let dna_file = DnaFile::new(
+            DnaDef {
+                name: "required_validations_test".to_string(),
+                uuid: "0c6fbd2b-5d1a-4c36-9a4b-6a7f0f0b2c1e".to_string(),
+                properties: SerializedBytes::try_from(()).unwrap(),
+                zomes: vec![TestWasm::EntryDefs.into()].into(),
+                limits: Default::default(),
+            },
+            vec![TestWasm::EntryDefs.into()],
+        )
+        .await
+        .unwrap();
+
+        // Ops on a header with an app entry
+        let mut create = fixt!(Create);
+        create.entry_type = EntryType::App(AppEntryType::new(
+            0.into(),
+            0.into(),
+            EntryVisibility::Public,
+        ));
+        let entry_hash = create.entry_hash.clone();
+        let create = HeaderHashed::from_content_sync(create.into());
+        let store_entry_op = DhtOpLight::StoreEntry(
+            create.as_hash().clone(),
+            entry_hash.clone(),
+            entry_hash.clone().into(),
+        );
+        let store_element_op = DhtOpLight::StoreElement(
+            create.as_hash().clone(),
+            Some(entry_hash),
+            create.as_hash().clone().into(),
+        );
+        let activity_op =
+            DhtOpLight::RegisterAgentActivity(create.as_hash().clone(), fixt!(AgentPubKey).into());
+        // An op without an entry
+        let link_add = HeaderHashed::from_content_sync(fixt!(CreateLink).into());
+        let link_op =
+            DhtOpLight::RegisterAddLink(link_add.as_hash().clone(), fixt!(EntryHash).into());
+        for header in vec![create, link_add] {
+            let signed_header = SignedHeaderHashed::with_presigned(header, fixt!(Signature));
+            workspace.elements.put(signed_header, None).unwrap();
+        }
+
+        let conductor_api = required_validations_conductor_api(dna_file.clone(), 2);
+        let mut required = HashMap::new();
+        for (op, expected) in vec![
+            (&store_entry_op, 2),
+            (&store_element_op, 2),
+            (&activity_op, DEFAULT_RECEIPT_BUNDLE_SIZE),
+            (&link_op, DEFAULT_RECEIPT_BUNDLE_SIZE),
+        ] {
+            assert_eq!(
+                required_validations(op, workspace.elements(), &conductor_api, &mut required)
+                    .await
+                    .unwrap(),
+                expected
+            );
+        }
+
+        // An entry def requiring no validations still needs one receipt
+        let conductor_api = required_validations_conductor_api(dna_file, 0);
+        assert_eq!(
+            required_validations(
+                &store_entry_op,
+                workspace.elements(),
+                &conductor_api,
+                &mut HashMap::new()
+            )
+            .await
+            .unwrap(),
+            1
+        );
+    }
+
     // TODO: COVERAGE: Test public ops do publish
 }
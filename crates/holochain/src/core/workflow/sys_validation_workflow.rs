@@ -11,7 +11,7 @@ use crate::{
             cascade::DbPairMut,
             dht_op_integration::{IntegrationLimboStore, IntegrationLimboValue},
             element_buf::ElementBuf,
-            metadata::MetadataBuf,
+            metadata::{MetadataBuf, MetadataBufT},
             validation_db::{ValidationLimboStatus, ValidationLimboStore, ValidationLimboValue},
             workspace::{Workspace, WorkspaceResult},
         },
@@ -31,7 +31,7 @@ use holochain_state::{
 };
 use holochain_types::{
     dht_op::DhtOp, header::NewEntryHeaderRef, test_utils::which_agent, validate::ValidationStatus,
-    Entry, Timestamp,
+    warrant::Warrant, Entry, Timestamp,
 };
 use holochain_zome_types::signature::Signature;
 use holochain_zome_types::{
@@ -208,6 +208,7 @@ async fn validate_op(
     conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: IncomingDhtOpSender,
 ) -> WorkflowResult<Outcome> {
+    let network_for_warrant = network.clone();
     match validate_op_inner(
         op,
         workspace,
@@ -232,12 +233,29 @@ async fn validate_op(
                 error = ?e,
                 error_msg = %e
             );
+            if let ValidationOutcome::ChainFork(warrant) = &e {
+                hold_warrant(warrant.as_ref().clone(), workspace, network_for_warrant).await?;
+            }
             Ok(handle_failed(e))
         }
         Err(e) => Err(e.into()),
     }
 }
 
+/// Hold a warrant we created in the meta vault so the author's later
+/// ops are rejected, and publish it to the author's other authorities.
+async fn hold_warrant(
+    warrant: Warrant,
+    workspace: &mut SysValidationWorkspace,
+    mut network: HolochainP2pCell,
+) -> WorkflowResult<()> {
+    workspace.meta_vault.register_warrant(warrant.clone())?;
+    if let Err(e) = network.publish_warrant(warrant, None).await {
+        warn!(msg = "Failed to publish warrant", ?e);
+    }
+    Ok(())
+}
+
 /// For now errors result in an outcome but in the future
 /// we might find it useful to include the reason something
 /// was rejected etc.
@@ -245,12 +263,15 @@ async fn validate_op(
 fn handle_failed(error: ValidationOutcome) -> Outcome {
     use Outcome::*;
     match error {
+        ValidationOutcome::AuthorWarranted(_) => Rejected,
+        ValidationOutcome::ChainFork(_) => Rejected,
         ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
         ValidationOutcome::EntryDefId(_) => Rejected,
         ValidationOutcome::EntryHash => Rejected,
         ValidationOutcome::EntryTooLarge(_, _) => Rejected,
         ValidationOutcome::EntryType => Rejected,
         ValidationOutcome::EntryVisibility(_) => Rejected,
+        ValidationOutcome::RateLimitExceeded(_, _) => Rejected,
         ValidationOutcome::TagTooLarge(_, _) => Rejected,
         ValidationOutcome::NotCreateLink(_) => Rejected,
         ValidationOutcome::NotNewEntry(_) => Rejected,
@@ -273,6 +294,7 @@ async fn validate_op_inner(
     conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: IncomingDhtOpSender,
 ) -> SysValidationResult<()> {
+    check_not_warranted(&op.header(), &workspace.meta_vault).await?;
     match op {
         DhtOp::StoreElement(_, header, entry) => {
             store_element(header, workspace, network.clone()).await?;
@@ -304,9 +326,16 @@ async fn validate_op_inner(
             store_element(&header, workspace, network).await?;
             Ok(())
         }
-        DhtOp::RegisterAgentActivity(_, header) => {
-            register_agent_activity(header, workspace, network.clone(), incoming_dht_ops_sender)
-                .await?;
+        DhtOp::RegisterAgentActivity(signature, header) => {
+            register_agent_activity(
+                signature,
+                header,
+                workspace,
+                network.clone(),
+                conductor_api,
+                incoming_dht_ops_sender,
+            )
+            .await?;
             store_element(header, workspace, network).await?;
             Ok(())
         }
@@ -336,7 +365,14 @@ async fn validate_op_inner(
             Ok(())
         }
         DhtOp::RegisterAddLink(_, header) => {
-            register_add_link(header, workspace, network, incoming_dht_ops_sender).await?;
+            register_add_link(
+                header,
+                workspace,
+                network,
+                conductor_api,
+                incoming_dht_ops_sender,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterRemoveLink(_, header) => {
@@ -357,13 +393,16 @@ pub async fn counterfeit_check(
 }
 
 async fn register_agent_activity(
+    signature: &Signature,
     header: &Header,
     workspace: &mut SysValidationWorkspace,
     network: HolochainP2pCell,
+    conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: IncomingDhtOpSender,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
     let prev_header_hash = header.prev_header();
+    let rate_limit = get_dna_def(conductor_api).await?.limits.rate_limit;
 
     // Checks
     check_prev_header(&header)?;
@@ -378,7 +417,8 @@ async fn register_agent_activity(
         )
         .await?;
     }
-    check_chain_rollback(&header, &workspace).await?;
+    check_chain_rollback(signature, &header, &workspace).await?;
+    check_spam(&header, rate_limit, &workspace).await?;
     Ok(())
 }
 
@@ -400,6 +440,7 @@ async fn store_element(
             .ok_or_else(|| ValidationOutcome::DepMissingFromDht(prev_header_hash.clone().into()))?;
         check_prev_timestamp(&header, prev_header.header())?;
         check_prev_seq(&header, prev_header.header())?;
+        check_prev_not_closed(prev_header.header())?;
     }
     Ok(())
 }
@@ -420,9 +461,10 @@ async fn store_entry(
     if let EntryType::App(app_entry_type) = entry_type {
         let entry_def = check_app_entry_type(app_entry_type, conductor_api).await?;
         check_not_private(&entry_def)?;
+        let dna_def = get_dna_def(conductor_api).await?;
+        check_entry_size(entry, dna_def.max_entry_size(&entry_def))?;
     }
     check_entry_hash(entry_hash, entry).await?;
-    check_entry_size(entry)?;
 
     // Additional checks if this is an Update
     if let NewEntryHeaderRef::Update(entry_update) = header {
@@ -514,6 +556,7 @@ async fn register_add_link(
     link_add: &CreateLink,
     workspace: &mut SysValidationWorkspace,
     network: HolochainP2pCell,
+    conductor_api: &impl CellConductorApiT,
     incoming_dht_ops_sender: IncomingDhtOpSender,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
@@ -536,7 +579,8 @@ async fn register_add_link(
         .await?
         .ok_or_else(|| ValidationOutcome::DepMissingFromDht(target_entry_address.clone().into()))?;
 
-    check_tag_size(&link_add.tag)?;
+    let dna_def = get_dna_def(conductor_api).await?;
+    check_tag_size(&link_add.tag, dna_def.limits.max_tag_size)?;
     Ok(())
 }
 
@@ -712,6 +756,8 @@ impl Workspace for SysValidationWorkspace {
 
         self.element_pending.flush_to_txn_ref(writer)?;
         self.meta_pending.flush_to_txn_ref(writer)?;
+        // Warrants are the only thing written to the vault here
+        self.meta_vault.flush_to_txn_ref(writer)?;
         Ok(())
     }
 }
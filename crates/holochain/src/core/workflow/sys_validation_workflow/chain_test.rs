@@ -17,14 +17,11 @@ use crate::{
     test_utils::wait_for_integration,
 };
 use ::fixt::prelude::*;
+use holochain_state::fresh_reader_test;
 use holochain_zome_types::fixt::*;
 
-/// Unfortunately this test doesn't do anything yet because
-/// failing a chain validation is just a log error so the only way to
-/// verify this works is to run this with logging and check it outputs
-/// use `RUST_LOG=[agent_activity]=warn`
+/// A valid chain is accepted and a fork in it produces a warrant
 #[tokio::test(threaded_scheduler)]
-#[ignore]
 async fn sys_validation_agent_activity_test() {
     observability::test_run().ok();
 
@@ -34,6 +31,7 @@ async fn sys_validation_agent_activity_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -139,8 +137,16 @@ async fn run_test(alice_cell_id: CellId, handle: ConductorHandle) {
 
     wait_for_integration(&alice_env, 7 + 2, 100, Duration::from_millis(100)).await;
 
-    // Check you don't see any warning output
-    // TODO: When we add invalid chains put a real check here
+    // A valid chain doesn't produce any warrants
+    let meta_vault = MetadataBuf::vault(alice_env.clone().into()).unwrap();
+    let warrant_count = fresh_reader_test!(alice_env, |r| {
+        meta_vault
+            .get_warrants(&r, alice_cell_id.agent_pubkey().clone())
+            .unwrap()
+            .count()
+            .unwrap()
+    });
+    assert_eq!(warrant_count, 0);
 
     // set valid prev header chain
     let last_hash = HeaderHash::with_data_sync(&Header::Create(h2.clone()));
@@ -190,6 +196,16 @@ async fn run_test(alice_cell_id: CellId, handle: ConductorHandle) {
 
     wait_for_integration(&alice_env, 9 + 2, 100, Duration::from_millis(100)).await;
 
-    // Check you **do** see any warning output
-    // TODO: When we add invalid chains put a real check here
+    // The fork is held as a warrant against alice
+    let meta_vault = MetadataBuf::vault(alice_env.clone().into()).unwrap();
+    let warrants: Vec<_> = fresh_reader_test!(alice_env, |r| {
+        meta_vault
+            .get_warrants(&r, alice_cell_id.agent_pubkey().clone())
+            .unwrap()
+            .collect()
+            .unwrap()
+    });
+    assert_eq!(warrants.len(), 1);
+    assert_eq!(warrants[0].author(), alice_cell_id.agent_pubkey());
+    assert_eq!(warrants[0].header_seq(), 5);
 }
@@ -2,8 +2,12 @@ pub mod curve;
 
 use crate::conductor::interface::SignalBroadcaster;
 use crate::core::ribosome::guest_callback::entry_defs::EntryDefsInvocation;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckHostAccess;
+use crate::core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckInvocation;
 use crate::core::ribosome::guest_callback::init::InitHostAccess;
 use crate::core::ribosome::guest_callback::init::InitInvocation;
+use crate::core::ribosome::guest_callback::merge::MergeHostAccess;
+use crate::core::ribosome::guest_callback::merge::MergeInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitHostAccess;
@@ -44,6 +48,8 @@ pub use holochain_types::fixt::*;
 use holochain_types::test_utils::fake_dna_zomes;
 use holochain_wasm_test_utils::strum::IntoEnumIterator;
 use holochain_wasm_test_utils::TestWasm;
+use holochain_zome_types::crdt::MergeData;
+use holochain_zome_types::genesis::GenesisSelfCheckData;
 use holochain_zome_types::header::HeaderHashes;
 use holochain_zome_types::link::LinkTag;
 use holochain_zome_types::zome::ZomeName;
@@ -311,6 +317,26 @@ fixturator!(
     constructor fn new();
 );
 
+fixturator!(
+    GenesisSelfCheckInvocation;
+    constructor fn new(GenesisSelfCheckData);
+);
+
+fixturator!(
+    GenesisSelfCheckHostAccess;
+    constructor fn new();
+);
+
+fixturator!(
+    MergeInvocation;
+    constructor fn new(ZomeName, String, MergeData);
+);
+
+fixturator!(
+    MergeHostAccess;
+    constructor fn new();
+);
+
 fixturator!(
     InitInvocation;
     constructor fn new(DnaDef);
@@ -338,7 +364,7 @@ fixturator!(
 
 fixturator!(
     PostCommitHostAccess;
-    constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell);
+    constructor fn new(CallZomeWorkspaceLock, KeystoreSender, HolochainP2pCell, SignalBroadcaster, CellId);
 );
 
 fixturator!(
@@ -411,6 +437,8 @@ fixturator!(
         Validate(ValidateHostAccess)
         Init(InitHostAccess)
         EntryDefs(EntryDefsHostAccess)
+        GenesisSelfCheck(GenesisSelfCheckHostAccess)
+        Merge(MergeHostAccess)
         MigrateAgent(MigrateAgentHostAccess)
         ValidationPackage(ValidationPackageHostAccess)
         PostCommit(PostCommitHostAccess)
@@ -7,7 +7,10 @@ use crate::{
         dna_store::MockDnaStore,
         ConductorBuilder, ConductorHandle,
     },
-    core::workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
+    core::{
+        state::private_entries::PrivateEntryKey,
+        workflow::incoming_dht_ops_workflow::IncomingDhtOpsWorkspace,
+    },
 };
 use ::fixt::prelude::*;
 use fallible_iterator::FallibleIterator;
@@ -99,6 +102,14 @@ macro_rules! meta_mock {
     }};
 }
 
+/// Load the [PrivateEntryKey] of a test environment's cell, as a cell does
+/// when it starts up, so private entries can be read and written
+pub async fn load_private_entry_key(env: &EnvironmentWrite) {
+    PrivateEntryKey::for_env(env)
+        .await
+        .expect("Couldn't load the cell's private entry key");
+}
+
 /// Create a fake SignedHeaderHashed and EntryHashed pair with random content
 pub async fn fake_unique_element(
     keystore: &KeystoreSender,
@@ -182,7 +193,7 @@ pub async fn setup_app(
     let conductor_handle = ConductorBuilder::with_mock_dna_store(dna_store)
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::websocket(0),
             }]),
             ..Default::default()
         })
@@ -6,9 +6,11 @@ use crate::{
     core::{
         ribosome::{host_fn, wasm_ribosome::WasmRibosome, CallContext, ZomeCallHostAccess},
         state::{metadata::LinkMetaKey, workspace::Workspace},
-        workflow::{CallZomeWorkspace, CallZomeWorkspaceLock},
+        workflow::{fake_genesis, CallZomeWorkspace, CallZomeWorkspaceLock},
     },
+    fixt::{CallContextFixturator, ZomeCallHostAccessFixturator},
 };
+use ::fixt::prelude::*;
 use hdk3::prelude::EntryError;
 use holo_hash::{AnyDhtHash, EntryHash, HeaderHash};
 use holochain_keystore::KeystoreSender;
@@ -20,8 +22,16 @@ use holochain_serialized_bytes::prelude::*;
 use holochain_state::{
     env::{EnvironmentRead, EnvironmentWrite},
     prelude::{GetDb, WriteManager},
+    test_utils::{test_cell_env, TestEnvironment},
 };
-use holochain_types::{cell::CellId, dna::DnaFile, element::Element, Entry};
+use holochain_types::{
+    cell::CellId,
+    dna::{DnaFile, DnaLimits},
+    element::Element,
+    test_utils::fake_dna_zomes,
+    Entry,
+};
+use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::{
     entry_def,
     header::*,
@@ -124,6 +134,57 @@ impl CallData {
     }
 }
 
+/// A call context for one test wasm in a dna with the given limits,
+/// on a source chain that has been through genesis.
+/// Useful for checking that host functions enforce the dna limits.
+pub struct LimitedCallData {
+    pub ribosome: Arc<WasmRibosome>,
+    pub call_context: Arc<CallContext>,
+    pub workspace_lock: CallZomeWorkspaceLock,
+    chain_head: HeaderHash,
+    _test_env: TestEnvironment,
+}
+
+impl LimitedCallData {
+    pub async fn new(test_wasm: TestWasm, limits: DnaLimits) -> Self {
+        let test_env = test_cell_env();
+        let env = test_env.env();
+        let mut workspace = CallZomeWorkspace::new(env.clone().into()).unwrap();
+        fake_genesis(&mut workspace.source_chain).await.unwrap();
+        let chain_head = workspace.source_chain.chain_head().unwrap().clone();
+        let workspace_lock = CallZomeWorkspaceLock::new(workspace);
+
+        let mut dna_file = fake_dna_zomes("limited", vec![(test_wasm.into(), test_wasm.into())]);
+        dna_file.dna.limits = limits;
+        let ribosome = WasmRibosome::new(dna_file);
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.zome_name = test_wasm.into();
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.workspace = workspace_lock.clone();
+        call_context.host_access = host_access.into();
+        LimitedCallData {
+            ribosome: Arc::new(ribosome),
+            call_context: Arc::new(call_context),
+            workspace_lock,
+            chain_head,
+            _test_env: test_env,
+        }
+    }
+
+    /// Panics if anything has been put on the source chain since genesis
+    pub async fn assert_chain_unchanged(&self) {
+        assert_eq!(
+            self.workspace_lock
+                .read()
+                .await
+                .source_chain
+                .chain_head()
+                .unwrap(),
+            &self.chain_head
+        );
+    }
+}
+
 pub async fn commit_entry<'env, E: Into<entry_def::EntryDefId>>(
     env: &EnvironmentWrite,
     call_data: CallData,
@@ -34,6 +34,7 @@ async fn gossip_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Anchor.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::Anchor.into()],
     )
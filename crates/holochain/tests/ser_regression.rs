@@ -57,6 +57,7 @@ async fn ser_regression_test() {
             uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::SerRegression.into()].into(),
+            limits: Default::default(),
         },
         vec![TestWasm::SerRegression.into()],
     )
@@ -144,7 +145,12 @@ async fn ser_regression_test() {
         _ => unreachable!(),
     };
 
-    let output = handle.call_zome(invocation).await.unwrap().unwrap();
+    let output = handle
+        .call_zome(invocation)
+        .await
+        .unwrap()
+        .unwrap()
+        .response;
 
     let channel_hash = match output {
         ZomeCallResponse::Ok(guest_output) => {
@@ -181,7 +187,12 @@ async fn ser_regression_test() {
         _ => unreachable!(),
     };
 
-    let output = handle.call_zome(invocation).await.unwrap().unwrap();
+    let output = handle
+        .call_zome(invocation)
+        .await
+        .unwrap()
+        .unwrap()
+        .response;
 
     match output {
         ZomeCallResponse::Ok(guest_output) => {
@@ -66,7 +66,7 @@ pub async fn check_started(holochain: &mut Child) {
 fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::websocket(port),
         }]),
         environment_path: environment_path.into(),
         network: None,
@@ -78,6 +78,8 @@ fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
             passphrase: "password".into(),
         }),
         use_dangerous_test_keystore: true,
+        durability: Default::default(),
+        cache: Default::default(),
     }
 }
 
@@ -246,7 +248,12 @@ pub async fn call_zome_fn<SB: TryInto<SerializedBytes, Error = SerializedBytesEr
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, holochain: &mut Child) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port: None };
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        binding: Default::default(),
+        options: Default::default(),
+        unix_socket: None,
+    };
     let response = client.request(request);
     let response = check_timeout(holochain, response, 1000).await;
     match response {
@@ -446,7 +453,7 @@ async fn emit_signals() {
     let sig2: SerializedBytes = unwrap_to::unwrap_to!(msg2 => WebsocketMessage::Signal).clone();
 
     assert_eq!(
-        Signal::App(cell_id, ().try_into().unwrap()),
+        Signal::App(cell_id, TestWasm::EmitSignal.into(), ().try_into().unwrap()),
         Signal::try_from(sig1.clone()).unwrap(),
     );
     assert_eq!(sig1, sig2);
@@ -487,6 +494,49 @@ async fn conductor_admin_interface_runs_from_config() -> Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+#[tokio::test(threaded_scheduler)]
+async fn conductor_admin_interface_runs_on_unix_socket() -> Result<()> {
+    observability::test_run().ok();
+    let tmp_dir = TempDir::new("conductor_cfg").unwrap();
+    let environment_path = tmp_dir.path().to_path_buf();
+    let socket_path = tmp_dir.path().join("admin.sock");
+    let mut config = create_config(0, environment_path);
+    config.admin_interfaces = Some(vec![AdminInterfaceConfig {
+        driver: InterfaceDriver::UnixSocket {
+            path: socket_path.clone(),
+        },
+    }]);
+    let conductor_handle = Conductor::builder().config(config).build().await?;
+    let (mut client, _) =
+        websocket_connect_unix(&socket_path, Arc::new(WebsocketConfig::default())).await?;
+
+    // The admin interface can attach an app interface on a unix socket too
+    let app_socket_path = tmp_dir.path().join("app.sock");
+    let request = AdminRequest::AttachAppInterface {
+        port: None,
+        binding: Default::default(),
+        options: Default::default(),
+        unix_socket: Some(app_socket_path.clone()),
+    };
+    let response = client.request(request).await;
+    assert_matches!(
+        response,
+        Ok(AdminResponse::AppInterfaceAttached { port: 0 })
+    );
+
+    let (mut app_client, _) =
+        websocket_connect_unix(&app_socket_path, Arc::new(WebsocketConfig::default())).await?;
+    let request = AppRequest::AppInfo {
+        app_id: "not installed".to_string(),
+    };
+    let response = app_client.request(request).await;
+    assert_matches!(response, Ok(AppResponse::AppInfo(None)));
+    conductor_handle.shutdown().await;
+
+    Ok(())
+}
+
 #[tokio::test(threaded_scheduler)]
 async fn conductor_admin_interface_ends_with_shutdown() -> Result<()> {
     observability::test_run().ok();
@@ -3,6 +3,7 @@
 
 use holo_hash::*;
 use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::query::{AgentActivity, ChainQueryFilter};
 use holochain_zome_types::zome::FunctionName;
 use holochain_zome_types::{capability::CapSecret, zome::ZomeName};
 use std::sync::Arc;
@@ -58,6 +59,13 @@ pub trait HolochainP2pCellT {
         timeout_ms: Option<u64>,
     ) -> actor::HolochainP2pResult<()>;
 
+    /// Publish a warrant to the authorities for the agent it accuses.
+    async fn publish_warrant(
+        &mut self,
+        warrant: holochain_types::warrant::Warrant,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()>;
+
     /// Request a validation package.
     async fn get_validation_package(&mut self) -> actor::HolochainP2pResult<()>;
 
@@ -82,6 +90,14 @@ pub trait HolochainP2pCellT {
         options: actor::GetLinksOptions,
     ) -> actor::HolochainP2pResult<Vec<GetLinksResponse>>;
 
+    /// Get an agent's source chain activity from its authorities.
+    async fn get_agent_activity(
+        &mut self,
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: actor::GetActivityOptions,
+    ) -> actor::HolochainP2pResult<Vec<AgentActivity>>;
+
     /// Send a validation receipt to a remote node.
     async fn send_validation_receipt(
         &mut self,
@@ -167,6 +183,22 @@ impl HolochainP2pCellT for HolochainP2pCell {
             .await
     }
 
+    /// Publish a warrant to the authorities for the agent it accuses.
+    async fn publish_warrant(
+        &mut self,
+        warrant: holochain_types::warrant::Warrant,
+        timeout_ms: Option<u64>,
+    ) -> actor::HolochainP2pResult<()> {
+        self.sender
+            .publish_warrant(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                warrant,
+                timeout_ms,
+            )
+            .await
+    }
+
     /// Request a validation package.
     async fn get_validation_package(&mut self) -> actor::HolochainP2pResult<()> {
         self.sender
@@ -226,6 +258,24 @@ impl HolochainP2pCellT for HolochainP2pCell {
             .await
     }
 
+    /// Get an agent's source chain activity from its authorities.
+    async fn get_agent_activity(
+        &mut self,
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: actor::GetActivityOptions,
+    ) -> actor::HolochainP2pResult<Vec<AgentActivity>> {
+        self.sender
+            .get_agent_activity(
+                (*self.dna_hash).clone(),
+                (*self.from_agent).clone(),
+                agent,
+                query,
+                options,
+            )
+            .await
+    }
+
     /// Send a validation receipt to a remote node.
     async fn send_validation_receipt(
         &mut self,
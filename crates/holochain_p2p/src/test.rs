@@ -315,4 +315,55 @@ mod tests {
         p2p.ghost_actor_shutdown().await.unwrap();
         r_task.await.unwrap();
     }
+
+    #[tokio::test(threaded_scheduler)]
+    async fn test_get_agent_activity_workflow() {
+        let (dna, a1, a2, a3) = test_setup();
+
+        let (p2p, mut evt) = spawn_holochain_p2p().await.unwrap();
+
+        let test_1 = AgentActivity {
+            headers: vec![],
+            status: holochain_zome_types::query::ChainStatus::Forked,
+            highest_observed: Some(3),
+        };
+
+        let test_1_clone = test_1.clone();
+        let r_task = tokio::task::spawn(async move {
+            use tokio::stream::StreamExt;
+            while let Some(evt) = evt.next().await {
+                let test_1_clone = test_1_clone.clone();
+                use crate::types::event::HolochainP2pEvent::*;
+                match evt {
+                    GetAgentActivity { respond, .. } => {
+                        respond.r(Ok(async move { Ok(test_1_clone) }.boxed().into()));
+                    }
+                    _ => (),
+                }
+            }
+        });
+
+        p2p.join(dna.clone(), a1.clone()).await.unwrap();
+        p2p.join(dna.clone(), a2.clone()).await.unwrap();
+
+        let res = p2p
+            .get_agent_activity(
+                dna,
+                a1,
+                a3,
+                ChainQueryFilter::new(),
+                actor::GetActivityOptions::default(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(1, res.len());
+
+        for r in res {
+            assert_eq!(r, test_1);
+        }
+
+        p2p.ghost_actor_shutdown().await.unwrap();
+        r_task.await.unwrap();
+    }
 }
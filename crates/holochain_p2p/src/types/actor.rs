@@ -140,6 +140,26 @@ impl Default for GetLinksOptions {
     }
 }
 
+#[derive(Debug, Clone)]
+/// Get an agent's activity from the DHT.
+/// Fields tagged with `[Network]` are network-level controls.
+/// Fields tagged with `[Remote]` are controls that will be forwarded to the
+/// remote agent processing this `GetAgentActivity` request.
+pub struct GetActivityOptions {
+    /// [Network]
+    /// Timeout to await responses for aggregation.
+    /// Set to `None` for a default "best-effort".
+    /// Note - if all requests time-out you will receive an empty result,
+    /// not a timeout error.
+    pub timeout_ms: Option<u64>,
+}
+
+impl Default for GetActivityOptions {
+    fn default() -> Self {
+        Self { timeout_ms: None }
+    }
+}
+
 ghost_actor::ghost_chan! {
     /// The HolochainP2pSender struct allows controlling the HolochainP2p
     /// actor instance.
@@ -171,6 +191,14 @@ ghost_actor::ghost_chan! {
             timeout_ms: Option<u64>,
         ) -> ();
 
+        /// Publish a warrant to the authorities for the agent it accuses.
+        fn publish_warrant(
+            dna_hash: DnaHash,
+            from_agent: AgentPubKey,
+            warrant: holochain_types::warrant::Warrant,
+            timeout_ms: Option<u64>,
+        ) -> ();
+
         /// Request a validation package.
         fn get_validation_package(input: GetValidationPackage) -> (); // TODO - proper return type
 
@@ -198,6 +226,15 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> Vec<GetLinksResponse>;
 
+        /// Get an agent's source chain activity from its authorities.
+        fn get_agent_activity(
+            dna_hash: DnaHash,
+            from_agent: AgentPubKey,
+            agent: AgentPubKey,
+            query: ChainQueryFilter,
+            options: GetActivityOptions,
+        ) -> Vec<AgentActivity>;
+
         /// Send a validation receipt to a remote node.
         fn send_validation_receipt(dna_hash: DnaHash, to_agent: AgentPubKey, from_agent: AgentPubKey, receipt: SerializedBytes) -> ();
     }
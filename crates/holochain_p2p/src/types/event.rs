@@ -44,6 +44,16 @@ impl From<&actor::GetLinksOptions> for GetLinksOptions {
     }
 }
 
+/// GetActivity options help control how the get is processed at various levels.
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct GetActivityOptions {}
+
+impl From<&actor::GetActivityOptions> for GetActivityOptions {
+    fn from(_a: &actor::GetActivityOptions) -> Self {
+        Self {}
+    }
+}
+
 ghost_actor::ghost_chan! {
     /// The HolochainP2pEvent stream allows handling events generated from
     /// the HolochainP2p actor.
@@ -69,6 +79,15 @@ ghost_actor::ghost_chan! {
             ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
         ) -> ();
 
+        /// A remote node is publishing a warrant against an agent
+        /// whose activity we claim to be holding.
+        fn publish_warrant(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            from_agent: AgentPubKey,
+            warrant: holochain_types::warrant::Warrant,
+        ) -> ();
+
         /// A remote node is requesting a validation package.
         fn get_validation_package(
             // The dna_hash / space_hash context.
@@ -102,6 +121,15 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> GetLinksResponse;
 
+        /// A remote node is requesting agent activity from us.
+        fn get_agent_activity(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            agent: AgentPubKey,
+            query: ChainQueryFilter,
+            options: GetActivityOptions,
+        ) -> AgentActivity;
+
         /// A remote node has sent us a validation receipt.
         fn validation_receipt_received(
             dna_hash: DnaHash,
@@ -143,10 +171,12 @@ macro_rules! match_p2p_evt {
         match $h {
             HolochainP2pEvent::CallRemote { $i, .. } => { $($t)* }
             HolochainP2pEvent::Publish { $i, .. } => { $($t)* }
+            HolochainP2pEvent::PublishWarrant { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetValidationPackage { $i, .. } => { $($t)* }
             HolochainP2pEvent::Get { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetMeta { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
+            HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
             HolochainP2pEvent::FetchOpHashesForConstraints { $i, .. } => { $($t)* }
             HolochainP2pEvent::FetchOpHashData { $i, .. } => { $($t)* }
@@ -34,6 +34,9 @@ pub(crate) enum WireMessage {
         dht_hash: holo_hash::AnyDhtHash,
         ops: Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>,
     },
+    PublishWarrant {
+        warrant: holochain_types::warrant::Warrant,
+    },
     ValidationReceipt {
         #[serde(with = "serde_bytes")]
         receipt: Vec<u8>,
@@ -50,6 +53,11 @@ pub(crate) enum WireMessage {
         link_key: WireLinkMetaKey,
         options: event::GetLinksOptions,
     },
+    GetAgentActivity {
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: event::GetActivityOptions,
+    },
 }
 
 impl WireMessage {
@@ -88,6 +96,10 @@ impl WireMessage {
         }
     }
 
+    pub fn publish_warrant(warrant: holochain_types::warrant::Warrant) -> WireMessage {
+        Self::PublishWarrant { warrant }
+    }
+
     pub fn validation_receipt(receipt: SerializedBytes) -> WireMessage {
         Self::ValidationReceipt {
             receipt: UnsafeBytes::from(receipt).into(),
@@ -108,4 +120,16 @@ impl WireMessage {
     pub fn get_links(link_key: WireLinkMetaKey, options: event::GetLinksOptions) -> WireMessage {
         Self::GetLinks { link_key, options }
     }
+
+    pub fn get_agent_activity(
+        agent: AgentPubKey,
+        query: ChainQueryFilter,
+        options: event::GetActivityOptions,
+    ) -> WireMessage {
+        Self::GetAgentActivity {
+            agent,
+            query,
+            options,
+        }
+    }
 }
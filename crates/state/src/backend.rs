@@ -0,0 +1,263 @@
+//! The storage backends which an environment's databases live in.
+//!
+//! Every buffered store reads and writes through the byte-level transactions
+//! defined here, so nothing above this module depends on LMDB. Transactions
+//! are handed out as trait objects rather than as associated types of the
+//! backend, since their lifetimes depend on the environment lock guard which
+//! created them. LMDB is the backend of every persisted environment, and the
+//! in-memory backend lets tests run without touching the filesystem.
+
+use crate::{
+    db::DbKind,
+    env::EnvironmentStats,
+    error::DatabaseResult,
+    transaction::{Readable, Writer},
+};
+use std::path::Path;
+
+mod lmdb;
+mod memory;
+
+#[cfg(test)]
+mod tests;
+
+pub use self::lmdb::LmdbBackend;
+pub use memory::MemoryBackend;
+
+/// Identifies one database of an environment to that environment's backend.
+/// A Table is only meaningful to the backend which handed it out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Table(usize);
+
+impl Table {
+    /// Construct a handle, for use by backend implementations
+    pub fn new(index: usize) -> Self {
+        Self(index)
+    }
+
+    /// The index the backend gave this table
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// Key-value pairs in the order a backend iterates them
+pub type RawIter<'t> = Box<dyn Iterator<Item = DatabaseResult<(&'t [u8], &'t [u8])>> + 't>;
+
+/// The storage underneath an environment
+pub trait StorageBackend: Send + Sync {
+    /// Open a database which already exists, returning None if it doesn't
+    fn open_table(&self, name: &str, kind: &DbKind) -> DatabaseResult<Option<Table>>;
+
+    /// Open a database, creating it if it doesn't exist
+    fn create_table(&self, name: &str, kind: &DbKind) -> DatabaseResult<Table>;
+
+    /// Begin a read-only transaction, which sees a consistent snapshot
+    /// of every database
+    fn read_txn(&self) -> DatabaseResult<Box<dyn ReadTxn + '_>>;
+
+    /// Begin a read-write transaction. Only one can be open at a time,
+    /// so this blocks until any other one is committed or dropped.
+    fn write_txn(&self) -> DatabaseResult<Box<dyn WriteTxn + '_>>;
+
+    /// Flush all committed data to durable storage
+    fn sync(&self) -> DatabaseResult<()>;
+
+    /// Size and usage statistics
+    fn stats(&self) -> DatabaseResult<EnvironmentStats>;
+
+    /// Set how much data can be stored before writes fail with "map full".
+    /// This must only be called while no transaction is open.
+    fn resize(&self, map_size: usize) -> DatabaseResult<()>;
+
+    /// Write a consistent copy of every database into the directory `dest`,
+    /// without blocking writers while the copy is made.
+    fn copy_to(&self, dest: &Path) -> DatabaseResult<()>;
+}
+
+/// A read-only transaction
+pub trait ReadTxn {
+    /// The value of a key in a single-value database
+    fn get(&self, table: Table, key: &[u8]) -> DatabaseResult<Option<&[u8]>>;
+
+    /// Every value of a key in a multi-value database, in order
+    fn get_multi(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>>;
+
+    /// Every key-value pair in a database, from the first key
+    fn iter_start(&self, table: Table) -> DatabaseResult<RawIter<'_>>;
+
+    /// Every key-value pair in a database from `key` onwards
+    fn iter_from(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>>;
+
+    /// Every key-value pair in a database in reverse, from the last key
+    fn iter_end(&self, table: Table) -> DatabaseResult<RawIter<'_>>;
+}
+
+/// A read-write transaction. Nothing it writes is visible to other
+/// transactions until it is committed, and dropping it aborts it.
+pub trait WriteTxn: ReadTxn {
+    /// This transaction as a [ReadTxn]
+    fn as_read(&self) -> &dyn ReadTxn;
+
+    /// Set the value of a key in a single-value database
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> DatabaseResult<()>;
+
+    /// Add a value to a key in a multi-value database.
+    /// With `no_dup_data` set, a value already stored under the key is skipped.
+    fn put_multi(
+        &mut self,
+        table: Table,
+        key: &[u8],
+        value: &[u8],
+        no_dup_data: bool,
+    ) -> DatabaseResult<()>;
+
+    /// Delete a key, and all of its values in a multi-value database.
+    /// Deleting a missing key is not an error.
+    fn delete(&mut self, table: Table, key: &[u8]) -> DatabaseResult<()>;
+
+    /// Delete one value of a key in a multi-value database.
+    /// Deleting a missing value is not an error.
+    fn delete_multi(&mut self, table: Table, key: &[u8], value: &[u8]) -> DatabaseResult<()>;
+
+    /// Delete everything in a database
+    fn clear(&mut self, table: Table) -> DatabaseResult<()>;
+
+    /// Commit every write made in this transaction
+    fn commit(self: Box<Self>) -> DatabaseResult<()>;
+}
+
+/// A single-value database with arbitrary keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SingleStore(Table);
+
+/// A single-value database with [IntKey](crate::key::IntKey) keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq, shrinkwraprs::Shrinkwrap)]
+pub struct IntegerStore(SingleStore);
+
+/// A multi-value database with arbitrary keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MultiStore(Table);
+
+impl SingleStore {
+    /// Wrap a table of a single-value database
+    pub fn new(table: Table) -> Self {
+        Self(table)
+    }
+
+    /// The backend's handle for this database
+    pub fn table(&self) -> Table {
+        self.0
+    }
+
+    /// Get the value of a key
+    pub fn get<'t, R: Readable, K: AsRef<[u8]>>(
+        &self,
+        reader: &'t R,
+        k: K,
+    ) -> DatabaseResult<Option<&'t [u8]>> {
+        reader.txn().get(self.0, k.as_ref())
+    }
+
+    /// Set the value of a key
+    pub fn put<K: AsRef<[u8]>>(&self, writer: &mut Writer, k: K, v: &[u8]) -> DatabaseResult<()> {
+        writer.write(|txn| txn.put(self.0, k.as_ref(), v))
+    }
+
+    /// Delete a key, if it exists
+    pub fn delete<K: AsRef<[u8]>>(&self, writer: &mut Writer, k: K) -> DatabaseResult<()> {
+        writer.write(|txn| txn.delete(self.0, k.as_ref()))
+    }
+
+    /// Iterate over every key-value pair, from the first key
+    pub fn iter_start<'t, R: Readable>(&self, reader: &'t R) -> DatabaseResult<RawIter<'t>> {
+        reader.txn().iter_start(self.0)
+    }
+
+    /// Iterate over every key-value pair from a key onwards
+    pub fn iter_from<'t, R: Readable, K: AsRef<[u8]>>(
+        &self,
+        reader: &'t R,
+        k: K,
+    ) -> DatabaseResult<RawIter<'t>> {
+        reader.txn().iter_from(self.0, k.as_ref())
+    }
+
+    /// Iterate over every key-value pair in reverse, from the last key
+    pub fn iter_end<'t, R: Readable>(&self, reader: &'t R) -> DatabaseResult<RawIter<'t>> {
+        reader.txn().iter_end(self.0)
+    }
+
+    /// Delete every key
+    pub fn clear(&self, writer: &mut Writer) -> DatabaseResult<()> {
+        writer.write(|txn| txn.clear(self.0))
+    }
+}
+
+impl IntegerStore {
+    /// Wrap a table of an integer-keyed database
+    pub fn new(table: Table) -> Self {
+        Self(SingleStore(table))
+    }
+}
+
+impl MultiStore {
+    /// Wrap a table of a multi-value database
+    pub fn new(table: Table) -> Self {
+        Self(table)
+    }
+
+    /// The backend's handle for this database
+    pub fn table(&self) -> Table {
+        self.0
+    }
+
+    /// Get every value of a key
+    pub fn get<'t, R: Readable, K: AsRef<[u8]>>(
+        &self,
+        reader: &'t R,
+        k: K,
+    ) -> DatabaseResult<RawIter<'t>> {
+        reader.txn().get_multi(self.0, k.as_ref())
+    }
+
+    /// Add a value to a key
+    pub fn put<K: AsRef<[u8]>>(&self, writer: &mut Writer, k: K, v: &[u8]) -> DatabaseResult<()> {
+        writer.write(|txn| txn.put_multi(self.0, k.as_ref(), v, false))
+    }
+
+    /// Add a value to a key, unless the key already has that value
+    pub fn put_no_dup<K: AsRef<[u8]>>(
+        &self,
+        writer: &mut Writer,
+        k: K,
+        v: &[u8],
+    ) -> DatabaseResult<()> {
+        writer.write(|txn| txn.put_multi(self.0, k.as_ref(), v, true))
+    }
+
+    /// Delete one value of a key, if it exists
+    pub fn delete<K: AsRef<[u8]>>(
+        &self,
+        writer: &mut Writer,
+        k: K,
+        v: &[u8],
+    ) -> DatabaseResult<()> {
+        writer.write(|txn| txn.delete_multi(self.0, k.as_ref(), v))
+    }
+
+    /// Delete a key and all of its values, if it exists
+    pub fn delete_all<K: AsRef<[u8]>>(&self, writer: &mut Writer, k: K) -> DatabaseResult<()> {
+        writer.write(|txn| txn.delete(self.0, k.as_ref()))
+    }
+
+    /// Iterate over every key-value pair, from the first key
+    pub fn iter_start<'t, R: Readable>(&self, reader: &'t R) -> DatabaseResult<RawIter<'t>> {
+        reader.txn().iter_start(self.0)
+    }
+
+    /// Delete every key
+    pub fn clear(&self, writer: &mut Writer) -> DatabaseResult<()> {
+        writer.write(|txn| txn.clear(self.0))
+    }
+}
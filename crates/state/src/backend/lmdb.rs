@@ -0,0 +1,329 @@
+//! The LMDB backend, by way of rkv
+
+use super::{RawIter, ReadTxn, StorageBackend, Table, WriteTxn};
+use crate::{
+    db::DbKind,
+    env::{EnvironmentStats, DATA_FILE},
+    error::{DatabaseError, DatabaseResult},
+};
+use lmdb::Transaction;
+use parking_lot::RwLock;
+use rkv::{DatabaseFlags, Rkv, StoreError, StoreOptions, Value, WriteFlags};
+use std::{ffi::CString, path::Path};
+
+/// The directory under a copy's destination that LMDB writes the copy into
+/// before it is moved into place
+const PARTIAL_COPY_DIR: &str = "partial";
+
+#[derive(Clone, Copy)]
+enum LmdbTable {
+    Single(rkv::SingleStore),
+    Multi(rkv::MultiStore),
+}
+
+/// A backend storing every database in one LMDB environment on disk
+pub struct LmdbBackend {
+    rkv: Rkv,
+    tables: RwLock<Vec<(String, LmdbTable)>>,
+}
+
+impl LmdbBackend {
+    /// Wrap an open rkv environment
+    pub fn new(rkv: Rkv) -> Self {
+        Self {
+            rkv,
+            tables: RwLock::new(Vec::new()),
+        }
+    }
+
+    fn table(&self, table: Table) -> DatabaseResult<LmdbTable> {
+        self.tables
+            .read()
+            .get(table.index())
+            .map(|(_, t)| *t)
+            .ok_or(DatabaseError::ForeignTable)
+    }
+
+    fn open(&self, name: &str, kind: &DbKind, create: bool) -> DatabaseResult<Option<Table>> {
+        let mut tables = self.tables.write();
+        if let Some(i) = tables.iter().position(|(n, _)| n == name) {
+            return Ok(Some(Table::new(i)));
+        }
+        let mut opts = if create {
+            StoreOptions::create()
+        } else {
+            StoreOptions::default()
+        };
+        let opened = match kind {
+            DbKind::Single => self.rkv.open_single(name, opts).map(LmdbTable::Single),
+            DbKind::SingleInt => {
+                // This is the flag rkv sets on its own IntegerStore, whose keys
+                // are encoded exactly like the bytes of an IntKey
+                opts.flags.set(DatabaseFlags::INTEGER_KEY, true);
+                self.rkv.open_single(name, opts).map(LmdbTable::Single)
+            }
+            DbKind::Multi => {
+                // This is needed for the optional put flag NO_DUP_DATA on KvvBufUsed.
+                // As far as I can tell, if we are not using NO_DUP_DATA, it will
+                // only affect the sorting of the values in case there are dups,
+                // which should be ok for our usage.
+                //
+                // NOTE - see:
+                // https://github.com/mozilla/rkv/blob/0.10.4/src/env.rs#L122-L131
+                //
+                // Aparently RKV already sets this flag, but it's not mentioned
+                // in the docs anywhere. We're going to set it too, just in case
+                // it is removed out from under us at some point in the future.
+                opts.flags.set(DatabaseFlags::DUP_SORT, true);
+                self.rkv.open_multi(name, opts).map(LmdbTable::Multi)
+            }
+        };
+        match opened {
+            Ok(table) => {
+                tables.push((name.to_string(), table));
+                Ok(Some(Table::new(tables.len() - 1)))
+            }
+            Err(StoreError::LmdbError(lmdb::Error::NotFound)) if !create => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl StorageBackend for LmdbBackend {
+    fn open_table(&self, name: &str, kind: &DbKind) -> DatabaseResult<Option<Table>> {
+        self.open(name, kind, false)
+    }
+
+    fn create_table(&self, name: &str, kind: &DbKind) -> DatabaseResult<Table> {
+        Ok(self
+            .open(name, kind, true)?
+            .expect("LMDB creates missing databases"))
+    }
+
+    fn read_txn(&self) -> DatabaseResult<Box<dyn ReadTxn + '_>> {
+        Ok(Box::new(LmdbTxn {
+            txn: self.rkv.read()?,
+            backend: self,
+        }))
+    }
+
+    fn write_txn(&self) -> DatabaseResult<Box<dyn WriteTxn + '_>> {
+        Ok(Box::new(LmdbTxn {
+            txn: self.rkv.write()?,
+            backend: self,
+        }))
+    }
+
+    fn sync(&self) -> DatabaseResult<()> {
+        self.rkv.sync(true)?;
+        Ok(())
+    }
+
+    fn stats(&self) -> DatabaseResult<EnvironmentStats> {
+        let info = self.rkv.info()?;
+        let page_size = self.rkv.stat()?.page_size() as usize;
+        let disk_size = std::fs::metadata(self.rkv.path().join(DATA_FILE))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        Ok(EnvironmentStats {
+            map_size: info.map_size(),
+            used_size: (info.last_pgno() + 1) * page_size,
+            page_size,
+            disk_size,
+        })
+    }
+
+    fn resize(&self, map_size: usize) -> DatabaseResult<()> {
+        self.rkv.set_map_size(map_size)?;
+        Ok(())
+    }
+
+    fn copy_to(&self, dest: &Path) -> DatabaseResult<()> {
+        let partial = dest.join(PARTIAL_COPY_DIR);
+        let copy_error = |e| DatabaseError::EnvironmentCopy(self.rkv.path().to_owned(), e);
+        if partial.exists() {
+            std::fs::remove_dir_all(&partial).map_err(copy_error)?;
+        }
+        std::fs::create_dir_all(&partial).map_err(copy_error)?;
+        let partial_path = partial
+            .to_str()
+            .and_then(|p| CString::new(p).ok())
+            .ok_or_else(|| {
+                copy_error(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    format!("unsupported backup path {:?}", partial),
+                ))
+            })?;
+
+        // rkv doesn't expose the LMDB environment, but the transaction of a
+        // reader knows it. The reader is dropped before copying because
+        // LMDB copies from a read transaction of its own, which lets writers
+        // carry on while the copy is made.
+        let env = {
+            let reader = self.rkv.read()?;
+            // SAFETY: the reader's transaction is live for this call, and
+            // `mdb_txn_env` only reads the environment pointer out of it
+            unsafe { lmdb_sys::mdb_txn_env(reader.0.txn()) }
+        };
+        // SAFETY: `env` belongs to `self.rkv`, which is borrowed for the whole
+        // of this function, so the environment can't be closed during the copy.
+        // `mdb_env_copy2` may be called from any thread while the environment
+        // is open, and `partial_path` is a nul terminated string that outlives it.
+        let rc = unsafe { lmdb_sys::mdb_env_copy2(env, partial_path.as_ptr(), 0) };
+        if rc != lmdb_sys::MDB_SUCCESS {
+            return Err(StoreError::LmdbError(lmdb::Error::from_err_code(rc)).into());
+        }
+
+        // Move the finished copy into place, so an interrupted copy never
+        // looks like a complete one
+        std::fs::rename(partial.join(DATA_FILE), dest.join(DATA_FILE)).map_err(copy_error)?;
+        std::fs::remove_dir_all(&partial).map_err(copy_error)
+    }
+}
+
+/// An rkv Reader or Writer
+struct LmdbTxn<'e, T> {
+    txn: T,
+    backend: &'e LmdbBackend,
+}
+
+fn blob(value: Option<Value<'_>>) -> DatabaseResult<Option<&[u8]>> {
+    match value {
+        Some(Value::Blob(buf)) => Ok(Some(buf)),
+        None => Ok(None),
+        Some(_) => Err(DatabaseError::InvalidValue),
+    }
+}
+
+fn raw_iter<'t>(
+    iter: impl Iterator<Item = Result<(&'t [u8], Option<Value<'t>>), StoreError>> + 't,
+) -> RawIter<'t> {
+    Box::new(iter.filter_map(|item| match item {
+        Ok((k, v)) => blob(v).transpose().map(|v| v.map(|v| (k, v))),
+        Err(e) => Some(Err(e.into())),
+    }))
+}
+
+/// Deleting something which isn't there is not an error for a [WriteTxn]
+fn ignore_not_found(result: Result<(), StoreError>) -> DatabaseResult<()> {
+    match result {
+        Err(StoreError::LmdbError(lmdb::Error::NotFound)) => Ok(()),
+        r => Ok(r?),
+    }
+}
+
+impl<'e, T: rkv::Readable> ReadTxn for LmdbTxn<'e, T> {
+    fn get(&self, table: Table, key: &[u8]) -> DatabaseResult<Option<&[u8]>> {
+        match self.backend.table(table)? {
+            LmdbTable::Single(db) => blob(db.get(&self.txn, key)?),
+            LmdbTable::Multi(db) => blob(db.get_first(&self.txn, key)?),
+        }
+    }
+
+    fn get_multi(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        match self.backend.table(table)? {
+            LmdbTable::Multi(db) => match db.get(&self.txn, key) {
+                Ok(iter) => Ok(raw_iter(iter)),
+                // rkv reports a missing key as an error rather than no values
+                Err(StoreError::LmdbError(lmdb::Error::NotFound)) => {
+                    Ok(Box::new(std::iter::empty()))
+                }
+                Err(e) => Err(e.into()),
+            },
+            LmdbTable::Single(_) => Err(DatabaseError::WrongStoreKind),
+        }
+    }
+
+    fn iter_start(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        match self.backend.table(table)? {
+            LmdbTable::Single(db) => Ok(raw_iter(db.iter_start(&self.txn)?)),
+            LmdbTable::Multi(db) => Ok(raw_iter(db.iter_start(&self.txn)?)),
+        }
+    }
+
+    fn iter_from(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        match self.backend.table(table)? {
+            LmdbTable::Single(db) => Ok(raw_iter(db.iter_from(&self.txn, key)?)),
+            LmdbTable::Multi(_) => Err(DatabaseError::WrongStoreKind),
+        }
+    }
+
+    fn iter_end(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        match self.backend.table(table)? {
+            LmdbTable::Single(db) => Ok(raw_iter(db.iter_end(&self.txn)?)),
+            LmdbTable::Multi(_) => Err(DatabaseError::WrongStoreKind),
+        }
+    }
+}
+
+impl<'e> WriteTxn for LmdbTxn<'e, rkv::Writer<'e>> {
+    fn as_read(&self) -> &dyn ReadTxn {
+        self
+    }
+
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> DatabaseResult<()> {
+        match self.backend.table(table)? {
+            LmdbTable::Single(db) => Ok(db.put(&mut self.txn, key, &Value::Blob(value))?),
+            LmdbTable::Multi(_) => Err(DatabaseError::WrongStoreKind),
+        }
+    }
+
+    fn put_multi(
+        &mut self,
+        table: Table,
+        key: &[u8],
+        value: &[u8],
+        no_dup_data: bool,
+    ) -> DatabaseResult<()> {
+        let db = match self.backend.table(table)? {
+            LmdbTable::Multi(db) => db,
+            LmdbTable::Single(_) => return Err(DatabaseError::WrongStoreKind),
+        };
+        if !no_dup_data {
+            return Ok(db.put(&mut self.txn, key, &Value::Blob(value))?);
+        }
+        match db.put_with_flags(
+            &mut self.txn,
+            key,
+            &Value::Blob(value),
+            WriteFlags::NO_DUP_DATA,
+        ) {
+            // This error is a little misleading...
+            // In a MultiStore with NO_DUP_DATA, it is
+            // actually returned if there is a duplicate
+            // value... which we want to ignore.
+            Err(StoreError::LmdbError(lmdb::Error::KeyExist)) => Ok(()),
+            r => Ok(r?),
+        }
+    }
+
+    fn delete(&mut self, table: Table, key: &[u8]) -> DatabaseResult<()> {
+        ignore_not_found(match self.backend.table(table)? {
+            LmdbTable::Single(db) => db.delete(&mut self.txn, key),
+            LmdbTable::Multi(db) => db.delete_all(&mut self.txn, key),
+        })
+    }
+
+    fn delete_multi(&mut self, table: Table, key: &[u8], value: &[u8]) -> DatabaseResult<()> {
+        match self.backend.table(table)? {
+            LmdbTable::Multi(db) => {
+                ignore_not_found(db.delete(&mut self.txn, key, &Value::Blob(value)))
+            }
+            LmdbTable::Single(_) => Err(DatabaseError::WrongStoreKind),
+        }
+    }
+
+    fn clear(&mut self, table: Table) -> DatabaseResult<()> {
+        match self.backend.table(table)? {
+            LmdbTable::Single(db) => db.clear(&mut self.txn)?,
+            LmdbTable::Multi(db) => db.clear(&mut self.txn)?,
+        }
+        Ok(())
+    }
+
+    fn commit(self: Box<Self>) -> DatabaseResult<()> {
+        let this = *self;
+        this.txn.commit()?;
+        Ok(())
+    }
+}
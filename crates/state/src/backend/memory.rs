@@ -0,0 +1,378 @@
+//! A backend which keeps every database in memory, for tests
+
+use super::{RawIter, ReadTxn, StorageBackend, Table, WriteTxn};
+use crate::{
+    db::DbKind,
+    env::EnvironmentStats,
+    error::{DatabaseError, DatabaseResult},
+};
+use parking_lot::{Mutex, MutexGuard, RwLock};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    sync::Arc,
+};
+
+/// A key, ordered like LMDB orders the keys of its database
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct RowKey {
+    /// The native-endian value of keys of integer-keyed databases,
+    /// which LMDB orders numerically rather than by their bytes
+    int: Option<u32>,
+    bytes: Vec<u8>,
+}
+
+#[derive(Clone)]
+struct MemoryTable {
+    name: String,
+    multi: bool,
+    int: bool,
+    /// Single-value databases have exactly one value per key
+    rows: BTreeMap<RowKey, BTreeSet<Vec<u8>>>,
+}
+
+impl MemoryTable {
+    fn key(&self, bytes: &[u8]) -> RowKey {
+        let mut int = [0; 4];
+        let int = if self.int && bytes.len() == int.len() {
+            int.copy_from_slice(bytes);
+            Some(u32::from_ne_bytes(int))
+        } else {
+            None
+        };
+        RowKey {
+            int,
+            bytes: bytes.to_vec(),
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct Tables(Vec<MemoryTable>);
+
+/// A backend whose databases live in memory and disappear with it.
+///
+/// Each read transaction sees the snapshot committed when it began.
+/// A write transaction works on a copy of every database, which replaces the
+/// snapshot when committed, so this is only suitable for small amounts of data.
+#[derive(Default)]
+pub struct MemoryBackend {
+    committed: RwLock<Arc<Tables>>,
+    /// Held for the life of each write transaction
+    writer: Mutex<()>,
+}
+
+impl MemoryBackend {
+    /// An empty backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Tables are created outside of any write transaction, so this doesn't
+    /// wait for the writer. An open write transaction picks up the new table
+    /// when it first uses it, and keeps it when it commits.
+    fn open(&self, name: &str, kind: &DbKind, create: bool) -> Option<Table> {
+        let mut committed = self.committed.write();
+        if let Some(i) = committed.0.iter().position(|t| t.name == name) {
+            return Some(Table::new(i));
+        }
+        if !create {
+            return None;
+        }
+        let mut tables = Tables::clone(&committed);
+        tables.0.push(MemoryTable {
+            name: name.to_string(),
+            multi: matches!(kind, DbKind::Multi),
+            int: matches!(kind, DbKind::SingleInt),
+            rows: BTreeMap::new(),
+        });
+        *committed = Arc::new(tables);
+        Some(Table::new(committed.0.len() - 1))
+    }
+}
+
+impl StorageBackend for MemoryBackend {
+    fn open_table(&self, name: &str, kind: &DbKind) -> DatabaseResult<Option<Table>> {
+        Ok(self.open(name, kind, false))
+    }
+
+    fn create_table(&self, name: &str, kind: &DbKind) -> DatabaseResult<Table> {
+        Ok(self
+            .open(name, kind, true)
+            .expect("missing tables are created"))
+    }
+
+    fn read_txn(&self) -> DatabaseResult<Box<dyn ReadTxn + '_>> {
+        Ok(Box::new(MemoryReadTxn(self.committed.read().clone())))
+    }
+
+    fn write_txn(&self) -> DatabaseResult<Box<dyn WriteTxn + '_>> {
+        let lock = self.writer.lock();
+        let tables = Tables::clone(&self.committed.read());
+        Ok(Box::new(MemoryWriteTxn {
+            backend: self,
+            _lock: lock,
+            tables,
+        }))
+    }
+
+    fn sync(&self) -> DatabaseResult<()> {
+        Ok(())
+    }
+
+    fn stats(&self) -> DatabaseResult<EnvironmentStats> {
+        let used_size = self
+            .committed
+            .read()
+            .0
+            .iter()
+            .flat_map(|t| t.rows.iter())
+            .map(|(k, vs)| vs.iter().map(|v| k.bytes.len() + v.len()).sum::<usize>())
+            .sum();
+        Ok(EnvironmentStats {
+            map_size: used_size,
+            used_size,
+            page_size: 0,
+            disk_size: 0,
+        })
+    }
+
+    fn resize(&self, _map_size: usize) -> DatabaseResult<()> {
+        // The map never fills up
+        Ok(())
+    }
+
+    fn copy_to(&self, _dest: &Path) -> DatabaseResult<()> {
+        Err(DatabaseError::BackendUnsupported("in-memory", "copying"))
+    }
+}
+
+impl Tables {
+    /// Add the tables which were created after this copy was taken.
+    /// Tables are never removed, so they are the ones past the end of it.
+    fn take_new(&mut self, committed: &Tables) {
+        if let Some(new) = committed.0.get(self.0.len()..) {
+            self.0.extend_from_slice(new);
+        }
+    }
+
+    fn table(&self, table: Table) -> DatabaseResult<&MemoryTable> {
+        self.0.get(table.index()).ok_or(DatabaseError::ForeignTable)
+    }
+
+    fn table_mut(&mut self, table: Table) -> DatabaseResult<&mut MemoryTable> {
+        self.0
+            .get_mut(table.index())
+            .ok_or(DatabaseError::ForeignTable)
+    }
+
+    fn get(&self, table: Table, key: &[u8]) -> DatabaseResult<Option<&[u8]>> {
+        let table = self.table(table)?;
+        Ok(table
+            .rows
+            .get(&table.key(key))
+            .and_then(|vs| vs.iter().next())
+            .map(|v| &v[..]))
+    }
+
+    fn get_multi(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        let table = self.table(table)?;
+        if !table.multi {
+            return Err(DatabaseError::WrongStoreKind);
+        }
+        Ok(match table.rows.get_key_value(&table.key(key)) {
+            Some((k, vs)) => Box::new(vs.iter().map(move |v| Ok((&k.bytes[..], &v[..])))),
+            None => Box::new(std::iter::empty()),
+        })
+    }
+
+    fn iter_start(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        Ok(Box::new(self.table(table)?.rows.iter().flat_map(
+            |(k, vs)| vs.iter().map(move |v| Ok((&k.bytes[..], &v[..]))),
+        )))
+    }
+
+    fn iter_from(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        let table = self.table(table)?;
+        if table.multi {
+            return Err(DatabaseError::WrongStoreKind);
+        }
+        Ok(Box::new(table.rows.range(table.key(key)..).flat_map(
+            |(k, vs)| vs.iter().map(move |v| Ok((&k.bytes[..], &v[..]))),
+        )))
+    }
+
+    fn iter_end(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        let table = self.table(table)?;
+        if table.multi {
+            return Err(DatabaseError::WrongStoreKind);
+        }
+        Ok(Box::new(table.rows.iter().rev().flat_map(|(k, vs)| {
+            vs.iter().rev().map(move |v| Ok((&k.bytes[..], &v[..])))
+        })))
+    }
+}
+
+struct MemoryReadTxn(Arc<Tables>);
+
+struct MemoryWriteTxn<'e> {
+    backend: &'e MemoryBackend,
+    _lock: MutexGuard<'e, ()>,
+    tables: Tables,
+}
+
+impl ReadTxn for MemoryReadTxn {
+    fn get(&self, table: Table, key: &[u8]) -> DatabaseResult<Option<&[u8]>> {
+        self.0.get(table, key)
+    }
+
+    fn get_multi(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        self.0.get_multi(table, key)
+    }
+
+    fn iter_start(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        self.0.iter_start(table)
+    }
+
+    fn iter_from(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        self.0.iter_from(table, key)
+    }
+
+    fn iter_end(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        self.0.iter_end(table)
+    }
+}
+
+impl<'e> MemoryWriteTxn<'e> {
+    /// Whether the table was created since this transaction began and hasn't
+    /// been written to by it, so it is still empty
+    fn is_new(&self, table: Table) -> bool {
+        table.index() >= self.tables.0.len()
+            && table.index() < self.backend.committed.read().0.len()
+    }
+
+    fn table_mut(&mut self, table: Table) -> DatabaseResult<&mut MemoryTable> {
+        if self.is_new(table) {
+            self.tables.take_new(&self.backend.committed.read());
+        }
+        self.tables.table_mut(table)
+    }
+}
+
+impl<'e> ReadTxn for MemoryWriteTxn<'e> {
+    fn get(&self, table: Table, key: &[u8]) -> DatabaseResult<Option<&[u8]>> {
+        if self.is_new(table) {
+            return Ok(None);
+        }
+        self.tables.get(table, key)
+    }
+
+    fn get_multi(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        if self.is_new(table) {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        self.tables.get_multi(table, key)
+    }
+
+    fn iter_start(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        if self.is_new(table) {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        self.tables.iter_start(table)
+    }
+
+    fn iter_from(&self, table: Table, key: &[u8]) -> DatabaseResult<RawIter<'_>> {
+        if self.is_new(table) {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        self.tables.iter_from(table, key)
+    }
+
+    fn iter_end(&self, table: Table) -> DatabaseResult<RawIter<'_>> {
+        if self.is_new(table) {
+            return Ok(Box::new(std::iter::empty()));
+        }
+        self.tables.iter_end(table)
+    }
+}
+
+impl<'e> WriteTxn for MemoryWriteTxn<'e> {
+    fn as_read(&self) -> &dyn ReadTxn {
+        self
+    }
+
+    fn put(&mut self, table: Table, key: &[u8], value: &[u8]) -> DatabaseResult<()> {
+        let table = self.table_mut(table)?;
+        if table.multi {
+            return Err(DatabaseError::WrongStoreKind);
+        }
+        let key = table.key(key);
+        let mut values = BTreeSet::new();
+        values.insert(value.to_vec());
+        table.rows.insert(key, values);
+        Ok(())
+    }
+
+    fn put_multi(
+        &mut self,
+        table: Table,
+        key: &[u8],
+        value: &[u8],
+        no_dup_data: bool,
+    ) -> DatabaseResult<()> {
+        let table = self.table_mut(table)?;
+        if !table.multi {
+            return Err(DatabaseError::WrongStoreKind);
+        }
+        let key = table.key(key);
+        let values = table.rows.entry(key).or_default();
+        if no_dup_data && values.contains(value) {
+            // LMDB refuses the duplicate with KeyExist, which is ignored
+            return Ok(());
+        }
+        // Without NO_DUP_DATA, LMDB with sorted duplicates still never
+        // stores the same value twice under a key
+        values.insert(value.to_vec());
+        Ok(())
+    }
+
+    fn delete(&mut self, table: Table, key: &[u8]) -> DatabaseResult<()> {
+        let table = self.table_mut(table)?;
+        let key = table.key(key);
+        table.rows.remove(&key);
+        Ok(())
+    }
+
+    fn delete_multi(&mut self, table: Table, key: &[u8], value: &[u8]) -> DatabaseResult<()> {
+        let table = self.table_mut(table)?;
+        if !table.multi {
+            return Err(DatabaseError::WrongStoreKind);
+        }
+        let key = table.key(key);
+        if let Some(values) = table.rows.get_mut(&key) {
+            values.remove(value);
+            if values.is_empty() {
+                table.rows.remove(&key);
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self, table: Table) -> DatabaseResult<()> {
+        self.table_mut(table)?.rows.clear();
+        Ok(())
+    }
+
+    fn commit(self: Box<Self>) -> DatabaseResult<()> {
+        let MemoryWriteTxn {
+            backend,
+            mut tables,
+            ..
+        } = *self;
+        let mut committed = backend.committed.write();
+        // Keep the tables created while this transaction was open
+        tables.take_new(&committed);
+        *committed = Arc::new(tables);
+        Ok(())
+    }
+}
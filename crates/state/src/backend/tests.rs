@@ -0,0 +1,143 @@
+use super::*;
+use crate::{
+    env::{ReadManager, WriteManager},
+    error::DatabaseError,
+    key::{BufKey, IntKey},
+    test_utils::{test_cell_envs, test_in_memory_cell_env, TestEnvironment},
+};
+
+type Pairs = Vec<(Vec<u8>, Vec<u8>)>;
+
+fn collect(iter: RawIter) -> DatabaseResult<Pairs> {
+    iter.map(|kv| kv.map(|(k, v)| (k.to_vec(), v.to_vec())))
+        .collect()
+}
+
+/// Everything a backend reads back after the same writes
+#[derive(Debug, PartialEq)]
+struct Reads {
+    multi_a: Pairs,
+    multi_missing: Pairs,
+    multi_all: Pairs,
+    single_from_b: Pairs,
+    single_reversed: Pairs,
+    single_deleted: Option<Vec<u8>>,
+    int_ordered: Vec<u32>,
+    wrong_kind: bool,
+}
+
+fn write_and_read(test_env: &TestEnvironment) -> DatabaseResult<Reads> {
+    let env = test_env.guard();
+    let multi = env.open_multi("multi")?;
+    let single = env.open_single("single")?;
+    let int = env.open_integer("int")?;
+
+    env.with_commit::<DatabaseError, _, _>(|writer| {
+        multi.put(writer, "a", b"2")?;
+        multi.put(writer, "a", b"1")?;
+        // Duplicates are stored once, with or without NO_DUP_DATA
+        multi.put(writer, "a", b"1")?;
+        multi.put_no_dup(writer, "a", b"2")?;
+        multi.put_no_dup(writer, "b", b"x")?;
+        multi.put(writer, "c", b"y")?;
+        multi.delete_all(writer, "c")?;
+        // Deleting what isn't there is not an error
+        multi.delete(writer, "b", b"missing")?;
+        multi.delete_all(writer, "missing")?;
+
+        single.put(writer, "c", b"3")?;
+        single.put(writer, "a", b"1")?;
+        single.put(writer, "b", b"2")?;
+        single.put(writer, "a", b"overwritten")?;
+        single.put(writer, "d", b"4")?;
+        single.delete(writer, "d")?;
+        single.delete(writer, "missing")?;
+
+        for i in &[256u32, 1, 65536, 2] {
+            int.put(writer, IntKey::from(*i), b"")?;
+        }
+        Ok(())
+    })?;
+
+    let reader = env.reader()?;
+    Ok(Reads {
+        multi_a: collect(multi.get(&reader, "a")?)?,
+        multi_missing: collect(multi.get(&reader, "missing")?)?,
+        multi_all: collect(multi.iter_start(&reader)?)?,
+        single_from_b: collect(single.iter_from(&reader, "b")?)?,
+        single_reversed: collect(single.iter_end(&reader)?)?,
+        single_deleted: single.get(&reader, "d")?.map(<[u8]>::to_vec),
+        int_ordered: collect(int.iter_start(&reader)?)?
+            .into_iter()
+            .map(|(k, _)| IntKey::from_key_bytes_or_friendly_panic(&k).into())
+            .collect(),
+        wrong_kind: matches!(
+            reader.txn().get_multi(single.table(), b"a"),
+            Err(DatabaseError::WrongStoreKind)
+        ),
+    })
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn backends_read_back_the_same() -> DatabaseResult<()> {
+    let reads = test_cell_envs()
+        .iter()
+        .map(write_and_read)
+        .collect::<DatabaseResult<Vec<_>>>()?;
+    let pair = |k: &str, v: &str| (k.as_bytes().to_vec(), v.as_bytes().to_vec());
+    assert_eq!(
+        reads[0],
+        Reads {
+            multi_a: vec![pair("a", "1"), pair("a", "2")],
+            multi_missing: vec![],
+            multi_all: vec![pair("a", "1"), pair("a", "2"), pair("b", "x")],
+            single_from_b: vec![pair("b", "2"), pair("c", "3")],
+            single_reversed: vec![pair("c", "3"), pair("b", "2"), pair("a", "overwritten")],
+            single_deleted: None,
+            int_ordered: vec![1, 2, 256, 65536],
+            wrong_kind: true,
+        }
+    );
+    assert_eq!(reads[0], reads[1]);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn memory_backend_opens_tables_during_a_write() -> DatabaseResult<()> {
+    let arc = test_in_memory_cell_env();
+    let env = arc.guard();
+    let before = env.open_single("before")?;
+    env.with_commit::<DatabaseError, _, _>(|writer| {
+        before.put(writer, "k", b"v")?;
+        let written = env.open_single("written")?;
+        let unwritten = env.open_multi("unwritten")?;
+        assert_eq!(written.get(writer, "k")?, None);
+        assert_eq!(collect(unwritten.get(writer, "k")?)?, vec![]);
+        written.put(writer, "k", b"v")?;
+        assert_eq!(written.get(writer, "k")?, Some(&b"v"[..]));
+        Ok(())
+    })?;
+
+    // Tables created during the write outlive its commit
+    let reader = env.reader()?;
+    let written = env.open_single("written")?;
+    let unwritten = env.open_multi("unwritten")?;
+    assert_eq!(before.get(&reader, "k")?, Some(&b"v"[..]));
+    assert_eq!(written.get(&reader, "k")?, Some(&b"v"[..]));
+    assert_eq!(collect(unwritten.get(&reader, "k")?)?, vec![]);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn memory_backend_aborts_dropped_writes() -> DatabaseResult<()> {
+    let arc = test_in_memory_cell_env();
+    let env = arc.guard();
+    let db = env.open_single("db")?;
+    {
+        let mut writer = env.writer_unmanaged()?;
+        db.put(&mut writer, "k", b"v")?;
+    }
+    let reader = env.reader()?;
+    assert_eq!(db.get(&reader, "k")?, None);
+    Ok(())
+}
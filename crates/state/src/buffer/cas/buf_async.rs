@@ -31,7 +31,7 @@ where
     P: PrefixType,
 {
     /// Create a new CasBufUsedAsync
-    pub fn new(db: rkv::SingleStore) -> Self {
+    pub fn new(db: SingleStore) -> Self {
         Self(KvBufUsed::new(db))
     }
 
@@ -144,7 +144,7 @@ where
     P: PrefixType,
 {
     /// Create a new CasBufFreshAsync
-    pub fn new(env: EnvironmentRead, db: rkv::SingleStore) -> Self {
+    pub fn new(env: EnvironmentRead, db: SingleStore) -> Self {
         Self {
             env,
             inner: CasBufUsedAsync::new(db),
@@ -5,11 +5,10 @@ use crate::{
     env::{ReadManager, WriteManager},
     error::DatabaseError,
     prelude::*,
-    test_utils::{test_cell_env, DbString},
+    test_utils::{test_cell_envs, DbString},
 };
 use ::fixt::prelude::*;
 use fallible_iterator::{DoubleEndedFallibleIterator, FallibleIterator};
-use rkv::StoreOptions;
 use std::collections::BTreeMap;
 use tracing::*;
 
@@ -17,54 +16,51 @@ pub(super) type Store = KvBufUsed<DbString, V>;
 
 #[tokio::test(threaded_scheduler)]
 async fn kv_iter_from_partial() {
-    let test_env = test_cell_env();
-    let arc = test_env.env();
-    let env = arc.guard();
-    let db = env
-        .inner()
-        .open_single("kv", StoreOptions::create())
-        .unwrap();
+    for arc in test_cell_envs() {
+        let env = arc.guard();
+        let db = env.open_single("kv").unwrap();
 
-    {
-        let mut buf: Store = KvBufUsed::new(db);
+        {
+            let mut buf: Store = KvBufUsed::new(db);
 
-        buf.put("a".into(), V(101)).unwrap();
-        buf.put("b".into(), V(102)).unwrap();
-        buf.put("dogs_likes_7".into(), V(1)).unwrap();
-        buf.put("dogs_likes_79".into(), V(2)).unwrap();
-        buf.put("dogs_likes_3".into(), V(3)).unwrap();
-        buf.put("dogs_likes_88".into(), V(4)).unwrap();
-        buf.put("dogs_likes_f".into(), V(5)).unwrap();
-        buf.put("d".into(), V(103)).unwrap();
-        buf.put("e".into(), V(104)).unwrap();
-        buf.put("aaaaaaaaaaaaaaaaaaaa".into(), V(105)).unwrap();
-        buf.put("eeeeeeeeeeeeeeeeeeee".into(), V(106)).unwrap();
+            buf.put("a".into(), V(101)).unwrap();
+            buf.put("b".into(), V(102)).unwrap();
+            buf.put("dogs_likes_7".into(), V(1)).unwrap();
+            buf.put("dogs_likes_79".into(), V(2)).unwrap();
+            buf.put("dogs_likes_3".into(), V(3)).unwrap();
+            buf.put("dogs_likes_88".into(), V(4)).unwrap();
+            buf.put("dogs_likes_f".into(), V(5)).unwrap();
+            buf.put("d".into(), V(103)).unwrap();
+            buf.put("e".into(), V(104)).unwrap();
+            buf.put("aaaaaaaaaaaaaaaaaaaa".into(), V(105)).unwrap();
+            buf.put("eeeeeeeeeeeeeeeeeeee".into(), V(106)).unwrap();
 
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-    }
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+        }
 
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let buf: Store = KvBufUsed::new(db);
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let buf: Store = KvBufUsed::new(db);
 
-        let iter = buf.store().iter_from(&reader, "dogs_likes".into()).unwrap();
-        let results = iter.collect::<Vec<_>>().unwrap();
-        assert_eq!(
-            results,
-            vec![
-                (&b"dogs_likes_3"[..], V(3)),
-                (&b"dogs_likes_7"[..], V(1)),
-                (&b"dogs_likes_79"[..], V(2)),
-                (&b"dogs_likes_88"[..], V(4)),
-                (&b"dogs_likes_f"[..], V(5)),
-                (&b"e"[..], V(104)),
-                (&b"eeeeeeeeeeeeeeeeeeee"[..], V(106)),
-            ]
-        );
+            let iter = buf.store().iter_from(&reader, "dogs_likes".into()).unwrap();
+            let results = iter.collect::<Vec<_>>().unwrap();
+            assert_eq!(
+                results,
+                vec![
+                    (&b"dogs_likes_3"[..], V(3)),
+                    (&b"dogs_likes_7"[..], V(1)),
+                    (&b"dogs_likes_79"[..], V(2)),
+                    (&b"dogs_likes_88"[..], V(4)),
+                    (&b"dogs_likes_f"[..], V(5)),
+                    (&b"e"[..], V(104)),
+                    (&b"eeeeeeeeeeeeeeeeeeee"[..], V(106)),
+                ]
+            );
 
-        Ok(())
-    })
-    .unwrap();
+            Ok(())
+        })
+        .unwrap();
+    }
 }
 
 enum TestData {
@@ -240,125 +236,122 @@ fn re_do_test<R: Readable>(
 async fn kv_single_iter() {
     holochain_types::observability::test_run().ok();
     let mut rng = rand::thread_rng();
-    let test_env = test_cell_env();
-    let arc = test_env.env();
-    let env = arc.guard();
-    let db = env
-        .inner()
-        .open_single("kv", StoreOptions::create())
-        .unwrap();
-    let td = StringFixturator::new(Unpredictable)
-        .zip(VFixturator::new(Unpredictable))
-        .filter(|(k, _)| k.len() > 0)
-        .map(|(k, v)| (DbString::from(k), v))
-        .take(300)
-        .collect::<BTreeMap<_, _>>();
-    let td_vec = td.into_iter().collect::<Vec<_>>();
-    let mut puts = rng
-        .sample_iter(rand::distributions::Uniform::new(0, td_vec.len()))
-        .map(|i| td_vec[i].clone());
-    let from_key = puts.next().unwrap().0;
-    let mut dels = rng
-        .sample_iter(rand::distributions::Uniform::new(0, td_vec.len()))
-        .map(|i| td_vec[i].0.clone());
-    let puts_dels = puts
-        .map(|p| {
-            if rng.gen() {
-                TestData::Put(p.into())
-            } else {
-                TestData::Del(dels.next().unwrap())
-            }
-        })
-        .take(1000)
-        .collect::<Vec<_>>();
-    let mut puts_dels = puts_dels.into_iter();
-    let mut expected_state: BTreeMap<DbString, V> = BTreeMap::new();
+    for arc in test_cell_envs() {
+        let env = arc.guard();
+        let db = env.open_single("kv").unwrap();
+        let td = StringFixturator::new(Unpredictable)
+            .zip(VFixturator::new(Unpredictable))
+            .filter(|(k, _)| k.len() > 0)
+            .map(|(k, v)| (DbString::from(k), v))
+            .take(300)
+            .collect::<BTreeMap<_, _>>();
+        let td_vec = td.into_iter().collect::<Vec<_>>();
+        let mut puts = rng
+            .sample_iter(rand::distributions::Uniform::new(0, td_vec.len()))
+            .map(|i| td_vec[i].clone());
+        let from_key = puts.next().unwrap().0;
+        let mut dels = rng
+            .sample_iter(rand::distributions::Uniform::new(0, td_vec.len()))
+            .map(|i| td_vec[i].0.clone());
+        let puts_dels = puts
+            .map(|p| {
+                if rng.gen() {
+                    TestData::Put(p.into())
+                } else {
+                    TestData::Del(dels.next().unwrap())
+                }
+            })
+            .take(1000)
+            .collect::<Vec<_>>();
+        let mut puts_dels = puts_dels.into_iter();
+        let mut expected_state: BTreeMap<DbString, V> = BTreeMap::new();
 
-    let span = trace_span!("kv_single_iter");
-    let _g = span.enter();
+        let span = trace_span!("kv_single_iter");
+        let _g = span.enter();
 
-    let mut runs = vec!["Start | ".into()];
-    let mut reproduce = vec!["\nReproduce:\n".into()];
+        let mut runs = vec!["Start | ".into()];
+        let mut reproduce = vec!["\nReproduce:\n".into()];
 
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        let span = trace_span!("in_scratch");
-        let _g = span.enter();
-        runs.push(format!(
-            "{} | ",
-            span.metadata().map(|m| m.name()).unwrap_or("")
-        ));
-        reproduce.push(format!(
-            "let {} = vec![",
-            span.metadata().map(|f| f.name()).unwrap_or("")
-        ));
-        do_test(
-            &reader,
-            &mut buf,
-            &mut puts_dels,
-            &mut expected_state,
-            &mut runs,
-            &mut reproduce,
-            &from_key,
-        );
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            let span = trace_span!("in_scratch");
+            let _g = span.enter();
+            runs.push(format!(
+                "{} | ",
+                span.metadata().map(|m| m.name()).unwrap_or("")
+            ));
+            reproduce.push(format!(
+                "let {} = vec![",
+                span.metadata().map(|f| f.name()).unwrap_or("")
+            ));
+            do_test(
+                &reader,
+                &mut buf,
+                &mut puts_dels,
+                &mut expected_state,
+                &mut runs,
+                &mut reproduce,
+                &from_key,
+            );
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
+        .unwrap();
 
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        let span = trace_span!("in_db_first");
-        let _g = span.enter();
-        runs.push(format!(
-            "{} | ",
-            span.metadata().map(|m| m.name()).unwrap_or("")
-        ));
-        reproduce.push(format!(
-            "]; \n\nlet {} = vec![",
-            span.metadata().map(|f| f.name()).unwrap_or("")
-        ));
-        do_test(
-            &reader,
-            &mut buf,
-            &mut puts_dels,
-            &mut expected_state,
-            &mut runs,
-            &mut reproduce,
-            &from_key,
-        );
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        let span = trace_span!("in_db_second");
-        let _g = span.enter();
-        runs.push(format!(
-            "{} | ",
-            span.metadata().map(|m| m.name()).unwrap_or("")
-        ));
-        reproduce.push(format!(
-            "]; \n\nlet {} = vec![",
-            span.metadata().map(|f| f.name()).unwrap_or("")
-        ));
-        do_test(
-            &reader,
-            &mut buf,
-            &mut puts_dels,
-            &mut expected_state,
-            &mut runs,
-            &mut reproduce,
-            &from_key,
-        );
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            let span = trace_span!("in_db_first");
+            let _g = span.enter();
+            runs.push(format!(
+                "{} | ",
+                span.metadata().map(|m| m.name()).unwrap_or("")
+            ));
+            reproduce.push(format!(
+                "]; \n\nlet {} = vec![",
+                span.metadata().map(|f| f.name()).unwrap_or("")
+            ));
+            do_test(
+                &reader,
+                &mut buf,
+                &mut puts_dels,
+                &mut expected_state,
+                &mut runs,
+                &mut reproduce,
+                &from_key,
+            );
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
+        .unwrap();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            let span = trace_span!("in_db_second");
+            let _g = span.enter();
+            runs.push(format!(
+                "{} | ",
+                span.metadata().map(|m| m.name()).unwrap_or("")
+            ));
+            reproduce.push(format!(
+                "]; \n\nlet {} = vec![",
+                span.metadata().map(|f| f.name()).unwrap_or("")
+            ));
+            do_test(
+                &reader,
+                &mut buf,
+                &mut puts_dels,
+                &mut expected_state,
+                &mut runs,
+                &mut reproduce,
+                &from_key,
+            );
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
+        .unwrap();
+    }
 }
 
 #[tokio::test(threaded_scheduler)]
@@ -486,55 +479,73 @@ async fn kv_single_iter_found_4() {
 
 #[tokio::test(threaded_scheduler)]
 async fn exhaust_both_ends() {
-    let test_env = test_cell_env();
-    let arc = test_env.env();
-    let env = arc.guard();
-    let db = env
-        .inner()
-        .open_single("kv", StoreOptions::create())
+    for arc in test_cell_envs() {
+        let env = arc.guard();
+        let db = env.open_single("kv").unwrap();
+        let values = (b'a'..=b'z')
+            .map(|a| DbString::from_key_bytes_or_friendly_panic(&[a]))
+            .zip((0..).into_iter().map(V))
+            .collect::<Vec<_>>();
+        let expected = [
+            (b"a", V(0)),
+            (b"z", V(25)),
+            (b"b", V(1)),
+            (b"y", V(24)),
+            (b"c", V(2)),
+            (b"x", V(23)),
+            (b"d", V(3)),
+            (b"w", V(22)),
+            (b"e", V(4)),
+            (b"v", V(21)),
+            (b"f", V(5)),
+            (b"u", V(20)),
+            (b"g", V(6)),
+            (b"t", V(19)),
+            (b"h", V(7)),
+            (b"s", V(18)),
+            (b"i", V(8)),
+            (b"r", V(17)),
+            (b"j", V(9)),
+            (b"q", V(16)),
+            (b"k", V(10)),
+            (b"p", V(15)),
+            (b"l", V(11)),
+            (b"o", V(14)),
+            (b"m", V(12)),
+            (b"n", V(13)),
+        ];
+        let expected = expected
+            .iter()
+            .map(|(k, v)| ([k[0]], v.clone()))
+            .collect::<Vec<_>>();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            for (k, v) in values {
+                buf.put(k, v).unwrap();
+            }
+            {
+                let mut i = buf.iter(&reader).unwrap().map(|(k, v)| Ok(([k[0]], v)));
+                let mut result = Vec::new();
+                loop {
+                    match (i.next().unwrap(), i.next_back().unwrap()) {
+                        (Some(f), Some(b)) => {
+                            result.push(f);
+                            result.push(b);
+                        }
+                        (Some(f), None) => result.push(f),
+                        (None, Some(b)) => result.push(b),
+                        (None, None) => break,
+                    }
+                }
+                assert_eq!(result, expected);
+            }
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
         .unwrap();
-    let values = (b'a'..=b'z')
-        .map(|a| DbString::from_key_bytes_or_friendly_panic(&[a]))
-        .zip((0..).into_iter().map(V))
-        .collect::<Vec<_>>();
-    let expected = [
-        (b"a", V(0)),
-        (b"z", V(25)),
-        (b"b", V(1)),
-        (b"y", V(24)),
-        (b"c", V(2)),
-        (b"x", V(23)),
-        (b"d", V(3)),
-        (b"w", V(22)),
-        (b"e", V(4)),
-        (b"v", V(21)),
-        (b"f", V(5)),
-        (b"u", V(20)),
-        (b"g", V(6)),
-        (b"t", V(19)),
-        (b"h", V(7)),
-        (b"s", V(18)),
-        (b"i", V(8)),
-        (b"r", V(17)),
-        (b"j", V(9)),
-        (b"q", V(16)),
-        (b"k", V(10)),
-        (b"p", V(15)),
-        (b"l", V(11)),
-        (b"o", V(14)),
-        (b"m", V(12)),
-        (b"n", V(13)),
-    ];
-    let expected = expected
-        .iter()
-        .map(|(k, v)| ([k[0]], v.clone()))
-        .collect::<Vec<_>>();
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        for (k, v) in values {
-            buf.put(k, v).unwrap();
-        }
-        {
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let buf: Store = KvBufUsed::new(db);
             let mut i = buf.iter(&reader).unwrap().map(|(k, v)| Ok(([k[0]], v)));
             let mut result = Vec::new();
             loop {
@@ -549,31 +560,10 @@ async fn exhaust_both_ends() {
                 }
             }
             assert_eq!(result, expected);
-        }
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let buf: Store = KvBufUsed::new(db);
-        let mut i = buf.iter(&reader).unwrap().map(|(k, v)| Ok(([k[0]], v)));
-        let mut result = Vec::new();
-        loop {
-            match (i.next().unwrap(), i.next_back().unwrap()) {
-                (Some(f), Some(b)) => {
-                    result.push(f);
-                    result.push(b);
-                }
-                (Some(f), None) => result.push(f),
-                (None, Some(b)) => result.push(b),
-                (None, None) => break,
-            }
-        }
-        assert_eq!(result, expected);
-        Ok(())
-    })
-    .unwrap();
+            Ok(())
+        })
+        .unwrap();
+    }
 }
 
 async fn kv_single_iter_runner(
@@ -582,80 +572,77 @@ async fn kv_single_iter_runner(
     in_db_second: impl Iterator<Item = TestData> + Send,
     from_key: DbString,
 ) {
-    let test_env = test_cell_env();
-    let arc = test_env.env();
-    let env = arc.guard();
-    let db = env
-        .inner()
-        .open_single("kv", StoreOptions::create())
-        .unwrap();
+    for arc in test_cell_envs() {
+        let env = arc.guard();
+        let db = env.open_single("kv").unwrap();
 
-    let mut runs = vec!["Start | ".into()];
-    let mut expected_state: BTreeMap<DbString, V> = BTreeMap::new();
+        let mut runs = vec!["Start | ".into()];
+        let mut expected_state: BTreeMap<DbString, V> = BTreeMap::new();
 
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        let span = trace_span!("in_scratch");
-        let _g = span.enter();
-        runs.push(format!(
-            "{} | ",
-            span.metadata().map(|m| m.name()).unwrap_or("in_scratch")
-        ));
-        re_do_test(
-            &reader,
-            &mut buf,
-            &mut in_scratch.into_iter(),
-            &mut expected_state,
-            &mut runs,
-            &from_key,
-        );
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            let span = trace_span!("in_scratch");
+            let _g = span.enter();
+            runs.push(format!(
+                "{} | ",
+                span.metadata().map(|m| m.name()).unwrap_or("in_scratch")
+            ));
+            re_do_test(
+                &reader,
+                &mut buf,
+                &mut in_scratch.into_iter(),
+                &mut expected_state,
+                &mut runs,
+                &from_key,
+            );
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
+        .unwrap();
 
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        let span = trace_span!("in_db_first");
-        let _g = span.enter();
-        runs.push(format!(
-            "{} | ",
-            span.metadata().map(|m| m.name()).unwrap_or("in_db_first")
-        ));
-        re_do_test(
-            &reader,
-            &mut buf,
-            &mut in_db_first.into_iter(),
-            &mut expected_state,
-            &mut runs,
-            &from_key,
-        );
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            let span = trace_span!("in_db_first");
+            let _g = span.enter();
+            runs.push(format!(
+                "{} | ",
+                span.metadata().map(|m| m.name()).unwrap_or("in_db_first")
+            ));
+            re_do_test(
+                &reader,
+                &mut buf,
+                &mut in_db_first.into_iter(),
+                &mut expected_state,
+                &mut runs,
+                &from_key,
+            );
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
+        .unwrap();
 
-    env.with_reader::<DatabaseError, _, _>(|reader| {
-        let mut buf: Store = KvBufUsed::new(db);
-        let span = trace_span!("in_db_second");
-        let _g = span.enter();
-        runs.push(format!(
-            "{} | ",
-            span.metadata().map(|m| m.name()).unwrap_or("in_db_second")
-        ));
-        re_do_test(
-            &reader,
-            &mut buf,
-            &mut in_db_second.into_iter(),
-            &mut expected_state,
-            &mut runs,
-            &from_key,
-        );
-        env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
-            .unwrap();
-        Ok(())
-    })
-    .unwrap();
+        env.with_reader::<DatabaseError, _, _>(|reader| {
+            let mut buf: Store = KvBufUsed::new(db);
+            let span = trace_span!("in_db_second");
+            let _g = span.enter();
+            runs.push(format!(
+                "{} | ",
+                span.metadata().map(|m| m.name()).unwrap_or("in_db_second")
+            ));
+            re_do_test(
+                &reader,
+                &mut buf,
+                &mut in_db_second.into_iter(),
+                &mut expected_state,
+                &mut runs,
+                &from_key,
+            );
+            env.with_commit(|mut writer| buf.flush_to_txn(&mut writer))
+                .unwrap();
+            Ok(())
+        })
+        .unwrap();
+    }
 }
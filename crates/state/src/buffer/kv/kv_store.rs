@@ -1,13 +1,9 @@
 use super::KvStoreT;
 use crate::buffer::{check_empty_key, iter::SingleIterRaw};
-use crate::{
-    error::{DatabaseError, DatabaseResult},
-    prelude::*,
-};
+use crate::{error::DatabaseResult, prelude::*};
 use fallible_iterator::FallibleIterator;
-use rkv::SingleStore;
 
-/// Wrapper around an rkv SingleStore which provides strongly typed values
+/// Wrapper around a SingleStore which provides strongly typed values
 pub struct KvStore<K, V>
 where
     K: BufKey,
@@ -29,11 +25,7 @@ where
         k: &K,
     ) -> DatabaseResult<Option<&'env [u8]>> {
         check_empty_key(k)?;
-        match self.db.get(reader, k)? {
-            Some(rkv::Value::Blob(buf)) => Ok(Some(buf)),
-            None => Ok(None),
-            Some(_) => Err(DatabaseError::InvalidValue),
-        }
+        self.db.get(reader, k)
     }
 
     /// Fetch data from DB, deserialize into V type
@@ -48,14 +40,12 @@ where
     /// Put V into DB as serialized data
     fn put(&self, writer: &mut Writer, k: &K, v: &V) -> DatabaseResult<()> {
         let buf = holochain_serialized_bytes::encode(v)?;
-        let encoded = rkv::Value::Blob(&buf);
-        self.db.put(writer, k, &encoded)?;
-        Ok(())
+        self.db.put(writer, k, &buf)
     }
 
     /// Delete value from DB
     fn delete(&self, writer: &mut Writer, k: &K) -> DatabaseResult<()> {
-        Ok(self.db.delete(writer, k)?)
+        self.db.delete(writer, k)
     }
 
     /// Iterate over the underlying persisted data
@@ -101,7 +91,7 @@ where
         }
     }
 
-    /// Accessor for raw Rkv DB
+    /// Accessor for the raw DB handle
     pub fn db(&self) -> SingleStore {
         self.db
     }
@@ -109,6 +99,6 @@ where
     // TODO: This should be cfg test but can't because it's in a different crate
     /// Clear db, useful for tests
     pub fn delete_all(&mut self, writer: &mut Writer) -> DatabaseResult<()> {
-        Ok(self.db.clear(writer)?)
+        self.db.clear(writer)
     }
 }
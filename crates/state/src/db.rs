@@ -1,17 +1,15 @@
 //! Functionality for safely accessing LMDB database references.
 
 use crate::{
+    backend::{IntegerStore, MultiStore, SingleStore, StorageBackend},
     env::EnvironmentKind,
     error::{DatabaseError, DatabaseResult},
-    exports::IntegerStore,
-    prelude::IntKey,
 };
 use derive_more::Display;
 use holochain_keystore::KeystoreSender;
 use holochain_types::universal_map::{Key as UmKey, UniversalMap};
 use lazy_static::lazy_static;
 use parking_lot::RwLock;
-use rkv::{MultiStore, Rkv, SingleStore, StoreOptions};
 use std::collections::{hash_map, HashMap};
 use std::path::{Path, PathBuf};
 
@@ -23,6 +21,8 @@ pub enum DbName {
     ElementVaultPublicEntries,
     /// Vault database: KV store of chain entries, keyed by address
     ElementVaultPrivateEntries,
+    /// Vault database: records that the private entries have been encrypted
+    ElementVaultPrivateEntriesMigrated,
     /// Vault database: KV store of chain headers, keyed by address
     ElementVaultHeaders,
     /// Vault database: KVV store of chain metadata, storing relationships
@@ -44,6 +44,9 @@ pub enum DbName {
     MetaCacheLinks,
     /// Vault database: Kv store of entry dht status
     MetaCacheStatus,
+    /// Cache database: KV store of when each key of the other cache
+    /// databases was first seen, for pruning the cache
+    CacheAge,
     /// database which stores a single key-value pair, encoding the
     /// mutable state for the entire Conductor
     ConductorState,
@@ -73,6 +76,7 @@ impl DbName {
         match self {
             ElementVaultPublicEntries => Single,
             ElementVaultPrivateEntries => Single,
+            ElementVaultPrivateEntriesMigrated => Single,
             ElementVaultHeaders => Single,
             MetaVaultSys => Multi,
             MetaVaultLinks => Single,
@@ -83,6 +87,7 @@ impl DbName {
             MetaCacheSys => Multi,
             MetaCacheLinks => Single,
             MetaCacheStatus => Single,
+            CacheAge => Single,
             ConductorState => Single,
             Wasm => Single,
             DnaDef => Single,
@@ -108,8 +113,8 @@ pub enum DbKind {
 }
 
 /// A UniversalMap key used to access persisted database references.
-/// The key type is DbName, the value can be one of the various `rkv`
-/// database types
+/// The key type is DbName, the value can be one of the various
+/// [backend](crate::backend) database types
 pub type DbKey<V> = UmKey<DbName, V>;
 
 type DbMap = UniversalMap<DbName>;
@@ -121,6 +126,9 @@ lazy_static! {
     /// The key to access the PrivateChainEntries database
     pub static ref ELEMENT_VAULT_PRIVATE_ENTRIES: DbKey<SingleStore> =
     DbKey::<SingleStore>::new(DbName::ElementVaultPrivateEntries);
+    /// The key to access the record of the private entries being encrypted
+    pub static ref ELEMENT_VAULT_PRIVATE_ENTRIES_MIGRATED: DbKey<SingleStore> =
+    DbKey::<SingleStore>::new(DbName::ElementVaultPrivateEntriesMigrated);
     /// The key to access the ChainHeaders database
     pub static ref ELEMENT_VAULT_HEADERS: DbKey<SingleStore> =
     DbKey::<SingleStore>::new(DbName::ElementVaultHeaders);
@@ -144,6 +152,8 @@ lazy_static! {
     pub static ref CACHE_LINKS_META: DbKey<SingleStore> = DbKey::new(DbName::MetaCacheLinks);
     /// The key to access the status database of the Cache
     pub static ref CACHE_STATUS_META: DbKey<SingleStore> = DbKey::new(DbName::MetaCacheStatus);
+    /// The key to access the age database of the Cache
+    pub static ref CACHE_AGE: DbKey<SingleStore> = DbKey::new(DbName::CacheAge);
     /// The key to access the ConductorState database
     pub static ref CONDUCTOR_STATE: DbKey<SingleStore> = DbKey::new(DbName::ConductorState);
     /// The key to access the Wasm database
@@ -168,24 +178,36 @@ lazy_static! {
     static ref DB_MAP_MAP: RwLock<HashMap<PathBuf, DbMap>> = RwLock::new(HashMap::new());
 }
 
-/// Get access to the singleton database manager ([GetDb]),
-/// in order to access individual LMDB databases
-pub(super) fn initialize_databases(rkv: &Rkv, kind: &EnvironmentKind) -> DatabaseResult<()> {
+/// Open and register every database of an environment of the given kind.
+/// Unless `create` is set, which a read-only environment can't do, databases
+/// missing from the environment are left unregistered.
+pub(super) fn initialize_databases(
+    backend: &dyn StorageBackend,
+    path: &Path,
+    kind: &EnvironmentKind,
+    create: bool,
+) -> DatabaseResult<()> {
     let mut dbmap = DB_MAP_MAP.write();
-    let path = rkv.path().to_owned();
+    let path = path.to_owned();
     match dbmap.entry(path.clone()) {
         hash_map::Entry::Occupied(_) => {
             return Err(DatabaseError::EnvironmentDoubleInitialized(path))
         }
         hash_map::Entry::Vacant(e) => e.insert({
             let mut um = UniversalMap::new();
-            register_databases(&rkv, kind, &mut um)?;
+            register_databases(backend, kind, create, &mut um)?;
             um
         }),
     };
     Ok(())
 }
 
+/// Forget the databases of an environment which is gone,
+/// so another one can be initialized at the same path
+pub(super) fn forget_databases(path: &Path) {
+    DB_MAP_MAP.write().remove(path);
+}
+
 pub(super) fn get_db<V: 'static + Copy + Send + Sync>(
     path: &Path,
     key: &'static DbKey<V>,
@@ -200,77 +222,67 @@ pub(super) fn get_db<V: 'static + Copy + Send + Sync>(
     Ok(db)
 }
 
-fn register_databases(env: &Rkv, kind: &EnvironmentKind, um: &mut DbMap) -> DatabaseResult<()> {
+fn register_databases(
+    env: &dyn StorageBackend,
+    kind: &EnvironmentKind,
+    create: bool,
+    um: &mut DbMap,
+) -> DatabaseResult<()> {
     match kind {
         EnvironmentKind::Cell(_) => {
-            register_db(env, um, &*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
-            register_db(env, um, &*ELEMENT_VAULT_PRIVATE_ENTRIES)?;
-            register_db(env, um, &*ELEMENT_VAULT_HEADERS)?;
-            register_db(env, um, &*META_VAULT_SYS)?;
-            register_db(env, um, &*META_VAULT_LINKS)?;
-            register_db(env, um, &*META_VAULT_MISC)?;
-            register_db(env, um, &*CHAIN_SEQUENCE)?;
-            register_db(env, um, &*ELEMENT_CACHE_ENTRIES)?;
-            register_db(env, um, &*ELEMENT_CACHE_HEADERS)?;
-            register_db(env, um, &*CACHE_SYSTEM_META)?;
-            register_db(env, um, &*CACHE_LINKS_META)?;
-            register_db(env, um, &*CACHE_STATUS_META)?;
-            register_db(env, um, &*AUTHORED_DHT_OPS)?;
-            register_db(env, um, &*INTEGRATED_DHT_OPS)?;
-            register_db(env, um, &*INTEGRATION_LIMBO)?;
-            register_db(env, um, &*VALIDATION_LIMBO)?;
-            register_db(env, um, &*VALIDATION_RECEIPTS)?;
+            register_db(env, um, create, &*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
+            register_db(env, um, create, &*ELEMENT_VAULT_PRIVATE_ENTRIES)?;
+            register_db(env, um, create, &*ELEMENT_VAULT_PRIVATE_ENTRIES_MIGRATED)?;
+            register_db(env, um, create, &*ELEMENT_VAULT_HEADERS)?;
+            register_db(env, um, create, &*META_VAULT_SYS)?;
+            register_db(env, um, create, &*META_VAULT_LINKS)?;
+            register_db(env, um, create, &*META_VAULT_MISC)?;
+            register_db(env, um, create, &*CHAIN_SEQUENCE)?;
+            register_db(env, um, create, &*ELEMENT_CACHE_ENTRIES)?;
+            register_db(env, um, create, &*ELEMENT_CACHE_HEADERS)?;
+            register_db(env, um, create, &*CACHE_SYSTEM_META)?;
+            register_db(env, um, create, &*CACHE_LINKS_META)?;
+            register_db(env, um, create, &*CACHE_STATUS_META)?;
+            register_db(env, um, create, &*CACHE_AGE)?;
+            register_db(env, um, create, &*AUTHORED_DHT_OPS)?;
+            register_db(env, um, create, &*INTEGRATED_DHT_OPS)?;
+            register_db(env, um, create, &*INTEGRATION_LIMBO)?;
+            register_db(env, um, create, &*VALIDATION_LIMBO)?;
+            register_db(env, um, create, &*VALIDATION_RECEIPTS)?;
         }
         EnvironmentKind::Conductor => {
-            register_db(env, um, &*CONDUCTOR_STATE)?;
+            register_db(env, um, create, &*CONDUCTOR_STATE)?;
         }
         EnvironmentKind::Wasm => {
-            register_db(env, um, &*WASM)?;
-            register_db(env, um, &*DNA_DEF)?;
-            register_db(env, um, &*ENTRY_DEF)?;
+            register_db(env, um, create, &*WASM)?;
+            register_db(env, um, create, &*DNA_DEF)?;
+            register_db(env, um, create, &*ENTRY_DEF)?;
         }
     }
     Ok(())
 }
 
 fn register_db<V: 'static + Send + Sync>(
-    env: &Rkv,
+    env: &dyn StorageBackend,
     um: &mut DbMap,
+    create: bool,
     key: &DbKey<V>,
 ) -> DatabaseResult<()> {
     let db_name = key.key();
     let db_str = format!("{}", db_name);
-    let _ = match db_name.kind() {
-        DbKind::Single => um.insert(
-            key.with_value_type(),
-            env.open_single(db_str.as_str(), StoreOptions::create())?,
-        ),
-        DbKind::SingleInt => um.insert(
-            key.with_value_type(),
-            env.open_integer::<&str, IntKey>(db_str.as_str(), StoreOptions::create())?,
-        ),
-        DbKind::Multi => {
-            let mut opts = StoreOptions::create();
-
-            // This is needed for the optional put flag NO_DUP_DATA on KvvBufUsed.
-            // As far as I can tell, if we are not using NO_DUP_DATA, it will
-            // only affect the sorting of the values in case there are dups,
-            // which should be ok for our usage.
-            //
-            // NOTE - see:
-            // https://github.com/mozilla/rkv/blob/0.10.4/src/env.rs#L122-L131
-            //
-            // Aparently RKV already sets this flag, but it's not mentioned
-            // in the docs anywhere. We're going to set it too, just in case
-            // it is removed out from under us at some point in the future.
-            opts.flags.set(rkv::DatabaseFlags::DUP_SORT, true);
-
-            um.insert(
-                key.with_value_type(),
-                env.open_multi(db_str.as_str(), opts)?,
-            )
-        }
+    let kind = db_name.kind();
+    let table = if create {
+        Some(env.create_table(db_str.as_str(), &kind)?)
+    } else {
+        env.open_table(db_str.as_str(), &kind)?
     };
+    if let Some(table) = table {
+        match kind {
+            DbKind::Single => um.insert(key.with_value_type(), SingleStore::new(table)),
+            DbKind::SingleInt => um.insert(key.with_value_type(), IntegerStore::new(table)),
+            DbKind::Multi => um.insert(key.with_value_type(), MultiStore::new(table)),
+        };
+    }
     Ok(())
 }
 
@@ -1,7 +1,9 @@
-//! Functions dealing with obtaining and referencing singleton LMDB environments
+//! Functions dealing with obtaining and referencing singleton LMDB environments,
+//! and environments kept in memory for tests
 
 use crate::{
-    db::{get_db, initialize_databases, DbKey, GetDb},
+    backend::{IntegerStore, LmdbBackend, MemoryBackend, MultiStore, SingleStore, StorageBackend},
+    db::{forget_databases, get_db, initialize_databases, DbKey, DbKind, GetDb},
     error::{DatabaseError, DatabaseResult},
     transaction::{Reader, Writer},
 };
@@ -9,17 +11,32 @@ use derive_more::Into;
 use holochain_keystore::KeystoreSender;
 use holochain_types::cell::CellId;
 use lazy_static::lazy_static;
-use parking_lot::{RwLock, RwLockReadGuard};
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use rkv::{EnvironmentFlags, Rkv};
 use shrinkwraprs::Shrinkwrap;
 use std::{
     collections::{hash_map, HashMap},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    time::Duration,
 };
 
+#[cfg(test)]
+mod tests;
+
 const DEFAULT_INITIAL_MAP_SIZE: usize = 100 * 1024 * 1024; // 100MB
 const MAX_DBS: u32 = 32;
+/// How many times a write transaction which filled the map is retried,
+/// doubling the map size each time
+const MAX_MAP_GROWTHS: usize = 4;
+/// How long to wait for in-flight transactions to finish before giving up
+/// on an operation which needs exclusive access, like growing the map
+const EXCLUSIVE_LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+/// The file in an environment directory holding all of its data
+pub(crate) const DATA_FILE: &str = "data.mdb";
 
 lazy_static! {
     static ref ENVIRONMENTS: RwLock<HashMap<PathBuf, EnvironmentWrite>> = {
@@ -46,12 +63,116 @@ lazy_static! {
 
         RwLock::new(HashMap::new())
     };
+    static ref READ_ONLY_ENVIRONMENTS: RwLock<HashMap<PathBuf, EnvironmentRead>> =
+        RwLock::new(HashMap::new());
+}
+
+/// How an environment trades write speed against the risk of losing
+/// recent commits if the process or machine crashes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DurabilityMode {
+    /// Commits are written back to disk by the OS in its own time.
+    /// This is the fastest mode, but a crash can lose recent commits.
+    Async,
+    /// Each commit is synced to disk before it completes
+    Sync,
+    /// Commits are written back asynchronously, but the whole environment
+    /// is also synced to disk at a fixed interval, which bounds how much
+    /// can be lost in a crash
+    Periodic {
+        /// Milliseconds between syncs
+        interval_ms: u64,
+    },
+}
+
+impl Default for DurabilityMode {
+    fn default() -> Self {
+        DurabilityMode::Async
+    }
+}
+
+impl DurabilityMode {
+    fn flags(&self) -> EnvironmentFlags {
+        match self {
+            // The flags WRITE_MAP and MAP_ASYNC make writes waaaaay faster by async writing to disk rather than blocking
+            // There is some loss of data integrity guarantees that comes with this.
+            DurabilityMode::Async | DurabilityMode::Periodic { .. } => {
+                EnvironmentFlags::WRITE_MAP | EnvironmentFlags::MAP_ASYNC
+            }
+            // Without MAP_ASYNC, each commit waits for the map to be synced
+            DurabilityMode::Sync => EnvironmentFlags::WRITE_MAP,
+        }
+    }
+}
+
+/// The backend of an environment. The lock is taken exclusively by operations
+/// which must wait for every transaction to finish, like growing the LMDB map.
+type SharedBackend = Arc<RwLock<Box<dyn StorageBackend>>>;
+
+/// The thread which syncs an environment opened with [DurabilityMode::Periodic]
+/// to disk at a fixed interval. It runs until the environment is closed, or
+/// until every clone of the environment has been dropped.
+struct PeriodicSync {
+    /// None unless the environment was opened with [DurabilityMode::Periodic]
+    interval: Option<Duration>,
+    /// Dropping this stops the thread
+    stop: Mutex<Option<mpsc::Sender<()>>>,
 }
 
-fn default_flags() -> EnvironmentFlags {
-    // The flags WRITE_MAP and MAP_ASYNC make writes waaaaay faster by async writing to disk rather than blocking
-    // There is some loss of data integrity guarantees that comes with this.
-    EnvironmentFlags::WRITE_MAP | EnvironmentFlags::MAP_ASYNC
+impl PeriodicSync {
+    fn new(durability: DurabilityMode) -> Self {
+        let interval = match durability {
+            DurabilityMode::Periodic { interval_ms } => {
+                Some(Duration::from_millis(interval_ms.max(1)))
+            }
+            _ => None,
+        };
+        Self {
+            interval,
+            stop: Mutex::new(None),
+        }
+    }
+
+    /// Spawn the thread, unless it is already running or isn't needed
+    fn start(&self, backend: &SharedBackend, path: &Path) {
+        let interval = match self.interval {
+            Some(interval) => interval,
+            None => return,
+        };
+        let mut stop = self.stop.lock();
+        if stop.is_some() {
+            return;
+        }
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let backend = Arc::downgrade(backend);
+        let path = path.to_owned();
+        let spawned = std::thread::Builder::new()
+            .name("lmdb-periodic-sync".into())
+            .spawn(move || loop {
+                match stop_rx.recv_timeout(interval) {
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    // The environment was closed or dropped
+                    _ => break,
+                }
+                match backend.upgrade() {
+                    Some(backend) => {
+                        if let Err(e) = backend.read().sync() {
+                            tracing::error!(?e, ?path, "Failed to sync LMDB environment to disk");
+                        }
+                    }
+                    None => break,
+                }
+            });
+        match spawned {
+            Ok(_) => *stop = Some(stop_tx),
+            Err(e) => tracing::error!(?e, "Failed to spawn the periodic LMDB sync thread"),
+        }
+    }
+
+    fn stop(&self) {
+        self.stop.lock().take();
+    }
 }
 
 #[cfg(feature = "lmdb_no_tls")]
@@ -73,33 +194,89 @@ fn rkv_builder(
     move |path: &Path| {
         let mut env_builder = Rkv::environment_builder();
         env_builder
-            // max size of memory map, can be changed later
+            // initial size of memory map, grown when a write fills it
             .set_map_size(initial_map_size.unwrap_or(DEFAULT_INITIAL_MAP_SIZE))
             // max number of DBs in this environment
             .set_max_dbs(MAX_DBS)
-            .set_flags(flags.unwrap_or_else(default_flags) | required_flags());
+            .set_flags(
+                flags.unwrap_or_else(|| DurabilityMode::default().flags()) | required_flags(),
+            );
         Rkv::from_env(path, env_builder)
     }
 }
 
+/// Forgets the databases of an in-memory environment once the environment
+/// and all of its clones are dropped, since nothing can open it again
+struct InMemoryDbs(PathBuf);
+
+impl Drop for InMemoryDbs {
+    fn drop(&mut self) {
+        forget_databases(&self.0);
+    }
+}
+
 /// A read-only version of [EnvironmentWrite].
 /// This environment can only generate read-only transactions, never read-write.
 #[derive(Clone)]
 pub struct EnvironmentRead {
-    arc: Arc<RwLock<Rkv>>,
+    backend: SharedBackend,
     kind: EnvironmentKind,
     path: PathBuf,
     keystore: KeystoreSender,
+    /// Only set for in-memory environments
+    _in_memory: Option<Arc<InMemoryDbs>>,
+    periodic_sync: Arc<PeriodicSync>,
 }
 
 impl EnvironmentRead {
+    /// Open an existing environment without write access, so that it can be
+    /// inspected safely while another process, like a running conductor,
+    /// is writing to it. Databases missing from the environment are not
+    /// created, and getting them fails with [DatabaseError::StoreNotInitialized].
+    ///
+    /// If this process already has the environment open for writing,
+    /// that environment is returned instead.
+    pub fn open_read_only(
+        path_prefix: &Path,
+        kind: EnvironmentKind,
+        keystore: KeystoreSender,
+    ) -> DatabaseResult<EnvironmentRead> {
+        let path = path_prefix.join(kind.path());
+        if let Some(env) = ENVIRONMENTS.read().get(&path) {
+            return Ok(env.0.clone());
+        }
+        if !path.is_dir() {
+            return Err(DatabaseError::EnvironmentMissing(path));
+        }
+        let mut map = READ_ONLY_ENVIRONMENTS.write();
+        let env = match map.entry(path.clone()) {
+            hash_map::Entry::Occupied(e) => e.get().clone(),
+            hash_map::Entry::Vacant(e) => e
+                .insert({
+                    let rkv = rkv_builder(None, Some(EnvironmentFlags::READ_ONLY))(&path)?;
+                    let backend = LmdbBackend::new(rkv);
+                    initialize_databases(&backend, &path, &kind, false)?;
+                    EnvironmentRead {
+                        backend: Arc::new(RwLock::new(Box::new(backend))),
+                        kind,
+                        keystore,
+                        path,
+                        _in_memory: None,
+                        periodic_sync: Arc::new(PeriodicSync::new(DurabilityMode::Async)),
+                    }
+                })
+                .clone(),
+        };
+        Ok(env)
+    }
+
     /// Get a read-only lock on the EnvironmentWrite. The most typical use case is
     /// to get a lock in order to create a read-only transaction. The lock guard
     /// must outlive the transaction, so it has to be returned here and managed
     /// explicitly.
     pub fn guard(&self) -> EnvironmentReadRef<'_> {
         EnvironmentReadRef {
-            rkv: self.arc.read(),
+            backend: self.backend.read(),
         }
     }
 
@@ -117,6 +294,47 @@ impl EnvironmentRead {
     pub fn path(&self) -> &PathBuf {
         &self.path
     }
+
+    /// Flush all committed data to disk, whatever the [DurabilityMode]
+    pub fn sync(&self) -> DatabaseResult<()> {
+        self.backend.read().sync()
+    }
+
+    /// Write a consistent snapshot of this environment into a directory of
+    /// the same name under `backup_root`, returning that directory.
+    ///
+    /// The backend copies from a read transaction, so the snapshot is
+    /// consistent while reads and writes carry on.
+    pub fn backup_into(&self, backup_root: &Path) -> DatabaseResult<PathBuf> {
+        let dest = backup_root.join(
+            self.path
+                .file_name()
+                .ok_or_else(|| DatabaseError::EnvironmentMissing(self.path.clone()))?,
+        );
+        self.backend.read().copy_to(&dest)?;
+        Ok(dest)
+    }
+
+    /// Size and usage statistics for this environment
+    pub fn stats(&self) -> DatabaseResult<EnvironmentStats> {
+        self.backend.read().stats()
+    }
+}
+
+/// Size and usage of the LMDB map behind an environment.
+/// An in-memory environment reports the size of its data as both the map
+/// size and the used size, and has no pages or data file.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EnvironmentStats {
+    /// The current size of the memory map, i.e. how much can be stored
+    /// before the map must grow again
+    pub map_size: usize,
+    /// The bytes of the map in use by committed data
+    pub used_size: usize,
+    /// The LMDB page size
+    pub page_size: usize,
+    /// The size of the data file on disk
+    pub disk_size: u64,
 }
 
 impl GetDb for EnvironmentWrite {
@@ -151,6 +369,17 @@ impl EnvironmentWrite {
         path_prefix: &Path,
         kind: EnvironmentKind,
         keystore: KeystoreSender,
+    ) -> DatabaseResult<EnvironmentWrite> {
+        Self::new_with_durability(path_prefix, kind, keystore, DurabilityMode::default())
+    }
+
+    /// Create an environment with the given [DurabilityMode].
+    /// If the environment is already open, it keeps the mode it was opened with.
+    pub fn new_with_durability(
+        path_prefix: &Path,
+        kind: EnvironmentKind,
+        keystore: KeystoreSender,
+        durability: DurabilityMode,
     ) -> DatabaseResult<EnvironmentWrite> {
         let mut map = ENVIRONMENTS.write();
         let path = path_prefix.join(kind.path());
@@ -162,21 +391,53 @@ impl EnvironmentWrite {
             hash_map::Entry::Occupied(e) => e.get().clone(),
             hash_map::Entry::Vacant(e) => e
                 .insert({
-                    let rkv = rkv_builder(None, None)(&path)?;
+                    let rkv = rkv_builder(None, Some(durability.flags()))(&path)?;
+                    let backend = LmdbBackend::new(rkv);
                     tracing::debug!("Initializing databases for path {:?}", path);
-                    initialize_databases(&rkv, &kind)?;
+                    initialize_databases(&backend, &path, &kind, true)?;
                     EnvironmentWrite(EnvironmentRead {
-                        arc: Arc::new(RwLock::new(rkv)),
+                        backend: Arc::new(RwLock::new(Box::new(backend))),
                         kind,
                         keystore,
                         path,
+                        _in_memory: None,
+                        periodic_sync: Arc::new(PeriodicSync::new(durability)),
                     })
                 })
                 .clone(),
         };
+        // This resumes the sync of an environment which was closed
+        env.0.periodic_sync.start(&env.0.backend, &env.0.path);
         Ok(env)
     }
 
+    /// Create an environment which keeps its databases in memory and writes
+    /// nothing to disk, for tests. Every call creates a new, empty environment,
+    /// which is gone once it and all of its clones are dropped.
+    ///
+    /// The environment's path is only a unique name: it does not exist.
+    pub fn new_in_memory(
+        kind: EnvironmentKind,
+        keystore: KeystoreSender,
+    ) -> DatabaseResult<EnvironmentWrite> {
+        static IN_MEMORY_COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = PathBuf::from(format!(
+            "in-memory-{}",
+            IN_MEMORY_COUNT.fetch_add(1, Ordering::SeqCst)
+        ))
+        .join(kind.path());
+        let backend = MemoryBackend::new();
+        initialize_databases(&backend, &path, &kind, true)?;
+        Ok(EnvironmentWrite(EnvironmentRead {
+            backend: Arc::new(RwLock::new(Box::new(backend))),
+            kind,
+            keystore,
+            _in_memory: Some(Arc::new(InMemoryDbs(path.clone()))),
+            periodic_sync: Arc::new(PeriodicSync::new(DurabilityMode::Async)),
+            path,
+        }))
+    }
+
     /// Create a Cell environment (slight shorthand)
     pub fn new_cell(
         path_prefix: &Path,
@@ -192,17 +453,157 @@ impl EnvironmentWrite {
         EnvironmentWriteRef(self.0.guard())
     }
 
+    /// Run a closure in a fresh read-write transaction and commit it.
+    /// Every write to an environment outside of tests should go through here.
+    ///
+    /// If the transaction fails because the LMDB map is full, the map is grown
+    /// and the closure is run again in a new transaction. Since the failed
+    /// transaction was aborted, it is valid for the closure to flush the same
+    /// data again. If the map can't be grown right away, the write fails with
+    /// [DatabaseError::MapFull] and can be retried once it has grown.
+    pub fn with_commit<E, R, F>(&self, mut f: F) -> Result<R, E>
+    where
+        E: From<DatabaseError>,
+        F: FnMut(&mut Writer) -> Result<R, E>,
+    {
+        let mut growths = 0;
+        loop {
+            let (result, map_full) = {
+                let guard = self.guard();
+                let mut writer = guard.writer_unmanaged()?;
+                match f(&mut writer) {
+                    Ok(r) => match writer.commit() {
+                        Ok(()) => return Ok(r),
+                        Err(e) => {
+                            let map_full = matches!(e, DatabaseError::MapFull);
+                            (Err(e.into()), map_full)
+                        }
+                    },
+                    // The writer is dropped, aborting the transaction
+                    Err(e) => (Err(e), writer.map_full()),
+                }
+            };
+            if map_full && growths < MAX_MAP_GROWTHS {
+                growths += 1;
+                if self.grow_map()? {
+                    continue;
+                }
+            }
+            return result;
+        }
+    }
+
+    /// Double the size of the LMDB map.
+    /// LMDB only allows resizing while this process has no transaction open,
+    /// so this needs the write lock, which waits for every guard to drop.
+    /// Writes are made from async tasks, so this doesn't wait for the lock:
+    /// if a guard is held, the map is grown on a background thread and
+    /// false is returned.
+    fn grow_map(&self) -> DatabaseResult<bool> {
+        let map_size = match self.0.backend.try_read() {
+            Some(backend) => backend.stats()?.map_size,
+            // Someone is already waiting to grow the map
+            None => return Ok(false),
+        };
+        if let Some(backend) = self.0.backend.try_write() {
+            resize_map(&**backend, &self.0.path, map_size)?;
+            return Ok(true);
+        }
+        let backend = self.0.backend.clone();
+        let path = self.0.path.clone();
+        std::thread::spawn(
+            move || match backend.try_write_for(EXCLUSIVE_LOCK_TIMEOUT) {
+                Some(backend) => {
+                    if let Err(e) = resize_map(&**backend, &path, map_size) {
+                        tracing::error!(?e, "Failed to grow the full LMDB map at {:?}", path);
+                    }
+                }
+                None => tracing::warn!("Timed out waiting to grow the full LMDB map at {:?}", path),
+            },
+        );
+        Ok(false)
+    }
+
+    /// Stop syncing the environment to disk in the background, and flush it
+    /// to disk one last time. The environment can still be used, and opening
+    /// it again with [EnvironmentWrite::new_with_durability] resumes the sync.
+    pub fn close(&self) -> DatabaseResult<()> {
+        self.0.periodic_sync.stop();
+        self.0.sync()
+    }
+
     /// Remove the db and directory
     pub async fn remove(self) -> DatabaseResult<()> {
+        self.0.periodic_sync.stop();
         let mut map = ENVIRONMENTS.write();
         map.remove(&self.0.path);
-        // TODO remove this db from the DB_MAP_MAP?
-        // remove the directory
-        std::fs::remove_dir_all(&self.0.path)?;
+        forget_databases(&self.0.path);
+        // remove the directory, which in-memory environments don't have
+        if self.0.path.is_dir() {
+            std::fs::remove_dir_all(&self.0.path)?;
+        }
         Ok(())
     }
 }
 
+/// Double the size of an LMDB map which was `map_size` bytes when it filled.
+/// Several writers may find the map full at once, so it is only grown if
+/// no one else has grown it since.
+fn resize_map(backend: &dyn StorageBackend, path: &Path, map_size: usize) -> DatabaseResult<()> {
+    if backend.stats()?.map_size > map_size {
+        return Ok(());
+    }
+    let new_map_size = map_size.saturating_mul(2);
+    tracing::info!(
+        "Growing the LMDB map at {:?} from {} to {} bytes",
+        path,
+        map_size,
+        new_map_size
+    );
+    backend.resize(new_map_size)
+}
+
+/// Restore environments written by [EnvironmentRead::backup_into] under
+/// `backup_root` into `environment_root`, returning the restored directories.
+///
+/// Only environments missing from `environment_root` are restored, so existing
+/// data is never overwritten. This must run before any environment under
+/// `environment_root` is opened.
+pub fn restore_environments(
+    backup_root: &Path,
+    environment_root: &Path,
+) -> DatabaseResult<Vec<PathBuf>> {
+    let copy_err = |e| DatabaseError::EnvironmentCopy(backup_root.to_owned(), e);
+    let mut restored = Vec::new();
+    for entry in std::fs::read_dir(backup_root).map_err(copy_err)? {
+        let backup = entry.map_err(copy_err)?.path();
+        if !backup.join(DATA_FILE).is_file() {
+            continue;
+        }
+        let dest = environment_root.join(backup.file_name().expect("read_dir entries have names"));
+        if dest.join(DATA_FILE).exists() {
+            continue;
+        }
+        copy_data_file(&backup, &dest)?;
+        tracing::info!("Restored environment {:?} from {:?}", dest, backup);
+        restored.push(dest);
+    }
+    Ok(restored)
+}
+
+/// Copy the data file of the environment directory `src` into `dest`.
+/// The copy is written under a temporary name and renamed into place,
+/// so an interrupted copy never looks like a complete one.
+fn copy_data_file(src: &Path, dest: &Path) -> DatabaseResult<()> {
+    let copy = || -> std::io::Result<()> {
+        std::fs::create_dir_all(dest)?;
+        let tmp = dest.join(format!("{}.partial", DATA_FILE));
+        std::fs::copy(src.join(DATA_FILE), &tmp)?;
+        std::fs::rename(&tmp, dest.join(DATA_FILE))
+    };
+    copy().map_err(|e| DatabaseError::EnvironmentCopy(src.to_owned(), e))
+}
+
 /// The various types of LMDB environment, used to specify the list of databases to initialize
 #[derive(Clone)]
 pub enum EnvironmentKind {
@@ -228,13 +629,13 @@ impl EnvironmentKind {
 /// This has the distinction of being unable to create a read-write transaction,
 /// because unlike [EnvironmentWriteRef], this does not implement WriteManager
 pub struct EnvironmentReadRef<'e> {
-    rkv: RwLockReadGuard<'e, Rkv>,
+    backend: RwLockReadGuard<'e, Box<dyn StorageBackend>>,
 }
 
 impl<'e> EnvironmentReadRef<'e> {
-    /// Access the wrapped Rkv
-    pub fn rkv(&self) -> &Rkv {
-        &self.rkv
+    /// Access the environment's storage backend
+    pub fn backend(&self) -> &dyn StorageBackend {
+        &**self.backend
     }
 }
 
@@ -254,8 +655,10 @@ pub trait ReadManager<'e> {
 pub trait WriteManager<'e> {
     /// Run a closure, passing in a mutable reference to a read-write
     /// transaction, and commit the transaction after the closure has run.
-    /// If there is a LMDB error, recover from it and re-run the closure.
-    // FIXME: B-01566: implement write failure detection
+    ///
+    /// The guard this is called on keeps the LMDB map from growing, so a write
+    /// which fills the map fails. Outside of tests, use
+    /// [EnvironmentWrite::with_commit], which grows the map and retries.
     fn with_commit<E, R, F: Send>(&self, f: F) -> Result<R, E>
     where
         E: From<DatabaseError>,
@@ -264,7 +667,7 @@ pub trait WriteManager<'e> {
 
 impl<'e> ReadManager<'e> for EnvironmentReadRef<'e> {
     fn reader(&'e self) -> DatabaseResult<Reader<'e>> {
-        let reader = Reader::from(self.rkv.read()?);
+        let reader = Reader::from(self.backend.read_txn()?);
         Ok(reader)
     }
 
@@ -283,7 +686,7 @@ impl<'e> WriteManager<'e> for EnvironmentWriteRef<'e> {
         E: From<DatabaseError>,
         F: FnOnce(&mut Writer) -> Result<R, E>,
     {
-        let mut writer = Writer::from(self.rkv.write().map_err(Into::into)?);
+        let mut writer = Writer::from(self.backend.write_txn()?);
         let result = f(&mut writer)?;
         writer.commit().map_err(Into::into)?;
         Ok(result)
@@ -291,17 +694,34 @@ impl<'e> WriteManager<'e> for EnvironmentWriteRef<'e> {
 }
 
 impl<'e> EnvironmentWriteRef<'e> {
-    /// Access the underlying Rkv lock guard
-    #[cfg(test)]
-    pub(crate) fn inner(&'e self) -> &Rkv {
-        &self.rkv
+    /// Open a single-value database by name, creating it if needed.
+    /// This is for tests: the databases Holochain uses are opened along
+    /// with the environment and accessed with [GetDb].
+    pub fn open_single(&self, name: &str) -> DatabaseResult<SingleStore> {
+        Ok(SingleStore::new(
+            self.backend.create_table(name, &DbKind::Single)?,
+        ))
+    }
+
+    /// Open an integer-keyed database by name, creating it if needed
+    pub fn open_integer(&self, name: &str) -> DatabaseResult<IntegerStore> {
+        Ok(IntegerStore::new(
+            self.backend.create_table(name, &DbKind::SingleInt)?,
+        ))
+    }
+
+    /// Open a multi-value database by name, creating it if needed
+    pub fn open_multi(&self, name: &str) -> DatabaseResult<MultiStore> {
+        Ok(MultiStore::new(
+            self.backend.create_table(name, &DbKind::Multi)?,
+        ))
     }
 
     /// Get a raw read-write transaction for this environment.
     /// It is preferable to use WriterManager::with_commit for database writes,
     /// which can properly recover from and manage write failures
     pub fn writer_unmanaged(&'e self) -> DatabaseResult<Writer<'e>> {
-        let writer = Writer::from(self.rkv.write()?);
+        let writer = Writer::from(self.backend.write_txn()?);
         Ok(writer)
     }
 }
@@ -0,0 +1,295 @@
+use super::*;
+use crate::{db::ELEMENT_VAULT_PUBLIC_ENTRIES, test_utils::test_cell_env};
+
+#[tokio::test(threaded_scheduler)]
+async fn full_map_is_grown_and_write_retried() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    // Shrink the map so that a modest write will fill it
+    let small_map_size = 1024 * 1024;
+    env.0.backend.write().resize(small_map_size)?;
+    assert_eq!(env.stats()?.map_size, small_map_size);
+
+    let db = env.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
+    let value = vec![0u8; 64 * 1024];
+    let mut attempts = 0;
+    env.with_commit(|writer| {
+        attempts += 1;
+        for i in 0..32u32 {
+            db.put(writer, i.to_be_bytes(), &value)?;
+        }
+        DatabaseResult::Ok(())
+    })?;
+    assert!(attempts > 1);
+
+    let stats = env.stats()?;
+    assert!(stats.map_size > small_map_size);
+    assert!(stats.used_size > 32 * 64 * 1024);
+    assert!(stats.used_size <= stats.map_size);
+
+    // Everything was written by the retried transaction
+    let guard = env.guard();
+    let reader = guard.reader()?;
+    for i in 0..32u32 {
+        assert!(db.get(&reader, i.to_be_bytes())?.is_some());
+    }
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn full_map_is_grown_whatever_the_error_type() -> DatabaseResult<()> {
+    /// An error which hides the [DatabaseError] it came from
+    #[derive(Debug)]
+    struct Opaque;
+    impl From<DatabaseError> for Opaque {
+        fn from(_: DatabaseError) -> Self {
+            Opaque
+        }
+    }
+
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let small_map_size = 1024 * 1024;
+    env.0.backend.write().resize(small_map_size)?;
+    let db = env.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
+    let value = vec![0u8; 64 * 1024];
+
+    // A guard can't grow the map, so its commit fails
+    let full = env.guard().with_commit(|writer| {
+        for i in 0..32u32 {
+            db.put(writer, i.to_be_bytes(), &value)?;
+        }
+        DatabaseResult::Ok(())
+    });
+    assert_eq!(full, Err(DatabaseError::MapFull));
+
+    let mut attempts = 0;
+    env.with_commit::<Opaque, _, _>(|writer| {
+        attempts += 1;
+        for i in 0..32u32 {
+            db.put(writer, i.to_be_bytes(), &value)?;
+        }
+        Ok(())
+    })
+    .unwrap();
+    assert!(attempts > 1);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn full_map_is_grown_in_the_background_while_a_guard_is_held() -> DatabaseResult<()> {
+    let test_env = test_cell_env();
+    let env = test_env.env();
+    let small_map_size = 1024 * 1024;
+    env.0.backend.write().resize(small_map_size)?;
+    let db = env.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
+    let value = vec![0u8; 64 * 1024];
+    let write = || {
+        env.with_commit(|writer| {
+            for i in 0..32u32 {
+                db.put(writer, i.to_be_bytes(), &value)?;
+            }
+            DatabaseResult::Ok(())
+        })
+    };
+
+    // The held guard keeps the map from growing, so the write fails
+    // straight away instead of waiting for the guard to drop
+    let start = std::time::Instant::now();
+    {
+        let _guard = env.guard();
+        assert_eq!(write(), Err(DatabaseError::MapFull));
+    }
+    assert!(start.elapsed() < EXCLUSIVE_LOCK_TIMEOUT);
+
+    // Once the guard is dropped the map grows and the write can be retried
+    while env.stats()?.map_size == small_map_size {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+    write()?;
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn environments_open_and_sync_in_every_durability_mode() -> DatabaseResult<()> {
+    let modes = vec![
+        DurabilityMode::Async,
+        DurabilityMode::Sync,
+        DurabilityMode::Periodic { interval_ms: 10 },
+    ];
+    for durability in modes {
+        let tmpdir = tempdir::TempDir::new("holochain-test-environments").unwrap();
+        let env = EnvironmentWrite::new_with_durability(
+            tmpdir.path(),
+            EnvironmentKind::Wasm,
+            crate::test_utils::test_keystore(),
+            durability,
+        )?;
+        let db = env.get_db(&*crate::db::WASM)?;
+        env.with_commit(|writer| {
+            db.put(writer, "key", b"value")?;
+            DatabaseResult::Ok(())
+        })?;
+        env.sync()?;
+        let guard = env.guard();
+        let reader = guard.reader()?;
+        assert!(db.get(&reader, "key")?.is_some());
+    }
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn closing_stops_the_periodic_sync_until_reopened() -> DatabaseResult<()> {
+    let tmpdir = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    let open = || {
+        EnvironmentWrite::new_with_durability(
+            tmpdir.path(),
+            EnvironmentKind::Wasm,
+            crate::test_utils::test_keystore(),
+            DurabilityMode::Periodic { interval_ms: 10 },
+        )
+    };
+    let running = |env: &EnvironmentWrite| env.0.periodic_sync.stop.lock().is_some();
+
+    let env = open()?;
+    assert!(running(&env));
+    env.close()?;
+    assert!(!running(&env));
+
+    // The environment is still usable once closed
+    let db = env.get_db(&*crate::db::WASM)?;
+    env.with_commit(|writer| db.put(writer, "key", b"value"))?;
+
+    let reopened = open()?;
+    assert!(running(&reopened));
+    assert!(running(&env));
+    reopened.close()?;
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn backup_and_restore_environment() -> DatabaseResult<()> {
+    let keystore = crate::test_utils::test_keystore();
+    let env_root = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    let env = EnvironmentWrite::new(env_root.path(), EnvironmentKind::Wasm, keystore.clone())?;
+    let db = env.get_db(&*crate::db::WASM)?;
+    env.with_commit(|writer| {
+        db.put(writer, "key", b"value")?;
+        DatabaseResult::Ok(())
+    })?;
+
+    let backup_root = tempdir::TempDir::new("holochain-test-backup").unwrap();
+    let backup = env.backup_into(backup_root.path())?;
+    assert_eq!(backup, backup_root.path().join("wasm"));
+
+    // Restoring only fills in missing environments
+    assert!(restore_environments(backup_root.path(), env_root.path())?.is_empty());
+
+    let restore_root = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    let restored = restore_environments(backup_root.path(), restore_root.path())?;
+    assert_eq!(restored, vec![restore_root.path().join("wasm")]);
+
+    let env = EnvironmentWrite::new(restore_root.path(), EnvironmentKind::Wasm, keystore)?;
+    let db = env.get_db(&*crate::db::WASM)?;
+    let guard = env.guard();
+    let reader = guard.reader()?;
+    assert_eq!(db.get(&reader, "key")?, Some(&b"value"[..]));
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn backup_is_taken_while_the_environment_is_in_use() -> DatabaseResult<()> {
+    let keystore = crate::test_utils::test_keystore();
+    let env_root = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    let env = EnvironmentWrite::new(env_root.path(), EnvironmentKind::Wasm, keystore.clone())?;
+    let db = env.get_db(&*crate::db::WASM)?;
+    env.with_commit(|writer| {
+        db.put(writer, "key", b"value")?;
+        DatabaseResult::Ok(())
+    })?;
+
+    // An open reader doesn't hold the backup up, and writes carry on
+    // after the backup without ending up in it
+    let backup_root = tempdir::TempDir::new("holochain-test-backup").unwrap();
+    {
+        let guard = env.guard();
+        let _reader = guard.reader()?;
+        env.backup_into(backup_root.path())?;
+    }
+    env.with_commit(|writer| {
+        db.put(writer, "later", b"value")?;
+        DatabaseResult::Ok(())
+    })?;
+
+    let restore_root = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    restore_environments(backup_root.path(), restore_root.path())?;
+    let env = EnvironmentWrite::new(restore_root.path(), EnvironmentKind::Wasm, keystore)?;
+    let db = env.get_db(&*crate::db::WASM)?;
+    let guard = env.guard();
+    let reader = guard.reader()?;
+    assert_eq!(db.get(&reader, "key")?, Some(&b"value"[..]));
+    assert_eq!(db.get(&reader, "later")?, None);
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn read_only_environment_reads_existing_data() -> DatabaseResult<()> {
+    let keystore = crate::test_utils::test_keystore();
+    let env_root = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    let env = EnvironmentWrite::new(env_root.path(), EnvironmentKind::Wasm, keystore.clone())?;
+    let db = env.get_db(&*crate::db::WASM)?;
+    env.with_commit(|writer| {
+        db.put(writer, "key", b"value")?;
+        DatabaseResult::Ok(())
+    })?;
+
+    // Copy the environment somewhere this process has never opened for writing
+    let backup_root = tempdir::TempDir::new("holochain-test-backup").unwrap();
+    env.backup_into(backup_root.path())?;
+    let copy_root = tempdir::TempDir::new("holochain-test-environments").unwrap();
+    restore_environments(backup_root.path(), copy_root.path())?;
+
+    let env = EnvironmentRead::open_read_only(copy_root.path(), EnvironmentKind::Wasm, keystore)?;
+    let db = env.get_db(&*crate::db::WASM)?;
+    let guard = env.guard();
+    let reader = guard.reader()?;
+    assert_eq!(db.get(&reader, "key")?, Some(&b"value"[..]));
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn in_memory_environment_isolates_transactions() -> DatabaseResult<()> {
+    let env = crate::test_utils::test_in_memory_cell_env();
+    let db = env.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
+    let guard = env.guard();
+    let before = guard.reader()?;
+    guard.with_commit(|writer| {
+        db.put(writer, "key", b"value")?;
+        // The write is visible within its own transaction
+        assert_eq!(db.get(writer, "key")?, Some(&b"value"[..]));
+        DatabaseResult::Ok(())
+    })?;
+
+    // A reader only sees what was committed before it began
+    assert_eq!(db.get(&before, "key")?, None);
+    let after = guard.reader()?;
+    assert_eq!(db.get(&after, "key")?, Some(&b"value"[..]));
+    assert!(!env.path().exists());
+    Ok(())
+}
+
+#[tokio::test(threaded_scheduler)]
+async fn in_memory_databases_are_forgotten_once_dropped() -> DatabaseResult<()> {
+    let env = crate::test_utils::test_in_memory_cell_env();
+    let path = env.path().clone();
+    let clone = env.clone();
+    drop(env);
+    clone.get_db(&*ELEMENT_VAULT_PUBLIC_ENTRIES)?;
+
+    drop(clone);
+    assert!(matches!(
+        get_db(&path, &*ELEMENT_VAULT_PUBLIC_ENTRIES),
+        Err(DatabaseError::EnvironmentMissing(_))
+    ));
+    Ok(())
+}
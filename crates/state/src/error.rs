@@ -23,12 +23,33 @@ pub enum DatabaseError {
     #[error("LMDB environment directory does not exist at configured path: {0}")]
     EnvironmentMissing(PathBuf),
 
+    #[error("Failed to copy the LMDB environment at {0}: {1}")]
+    EnvironmentCopy(PathBuf, std::io::Error),
+
+    #[error("The {0} storage backend does not support {1}")]
+    BackendUnsupported(&'static str, &'static str),
+
+    #[error("The LMDB map is full, so it must grow before anything more can be written")]
+    MapFull,
+
+    #[error("A database handle was used with an environment it does not belong to")]
+    ForeignTable,
+
+    #[error("A database was used as the wrong kind of store")]
+    WrongStoreKind,
+
     #[error("There is an unexpected value in an LMDB database (TODO: more info)")]
     InvalidValue,
 
     #[error("Attempted to access a private entry in a context where no private database is specified: {0}")]
     NoPrivateDb(String),
 
+    #[error("Failed to encrypt or decrypt a private entry: {0}")]
+    PrivateEntryCrypto(String),
+
+    #[error("The cell's private entry key must be loaded before its private entries are used")]
+    PrivateEntryKeyNotLoaded,
+
     // TODO: the following is necessary for actual backtraces, and would be ideal,
     // but requires the unstable "backtrace" feature, so we are doing without for now.
     //
@@ -105,7 +126,10 @@ pub type DatabaseResult<T> = Result<T, DatabaseError>;
 // }
 impl From<rkv::StoreError> for DatabaseError {
     fn from(e: rkv::StoreError) -> DatabaseError {
-        DatabaseError::LmdbStoreError(e.compat())
+        match e {
+            rkv::StoreError::LmdbError(lmdb::Error::MapFull) => DatabaseError::MapFull,
+            e => DatabaseError::LmdbStoreError(e.compat()),
+        }
     }
 }
 
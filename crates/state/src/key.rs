@@ -26,9 +26,9 @@ pub trait BufKey: Sized + Ord + Eq + AsRef<[u8]> + Send + Sync {
     fn from_key_bytes_or_friendly_panic(bytes: &[u8]) -> Self;
 }
 
-/// Trait alias for the combination of constraints needed for keys in [KvIntStore](kv_int::KvIntStore)
-pub trait BufIntKey: Ord + Eq + rkv::store::integer::PrimitiveInt + Send + Sync {}
-impl<T> BufIntKey for T where T: Ord + Eq + rkv::store::integer::PrimitiveInt + Send + Sync {}
+/// Marks the key types which can be used in a [KvIntStore](kv_int::KvIntStore),
+/// which are stored as integers by backends that can tell the difference
+pub trait BufIntKey: BufKey + Copy {}
 
 /// Trait alias for the combination of constraints needed for values in [KvStore](kv::KvStore) and [KvIntStore](kv_int::KvIntStore)
 pub trait BufVal: Clone + Serialize + DeserializeOwned + std::fmt::Debug + Send + Sync {}
@@ -40,12 +40,12 @@ impl<T> BufMultiVal for T where T: Ord + Eq + Clone + Serialize + DeserializeOwn
 
 /// Used for keys into integer-keyed LMDB stores.
 ///
-/// This strange type is constrained by both rkv's interface, and our own
+/// This strange type is constrained by both LMDB's integer keys, and our own
 /// database abstractions
 #[derive(Copy, PartialOrd, Ord, PartialEq, Eq, Clone, Serialize, serde::Deserialize)]
 pub struct IntKey([u8; 4]);
 
-impl rkv::store::integer::PrimitiveInt for IntKey {}
+impl BufIntKey for IntKey {}
 
 impl BufKey for IntKey {
     fn from_key_bytes_or_friendly_panic(vec: &[u8]) -> Self {
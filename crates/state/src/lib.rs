@@ -1,8 +1,8 @@
 //! # Building blocks for persisted Holochain state
 //!
-//! ## Backend: LMDB
+//! ## Backends
 //!
-//! Persistence is not generalized for different backends: it is targeted specifically for LMDB. In the future, if we have to change backends, or if we have to support something like IndexedDb, we will generalize the interface just enough to cover both. The interface provided by `rkv` is already somewhat generalized, with the abstract notions of Readers, Writers, and Stores.
+//! Persistence is targeted at LMDB, but every environment reads and writes through a [StorageBackend](backend::StorageBackend), which hands out byte-level read-only and read-write transactions. LMDB (via `rkv`) is the backend of every persisted environment. An in-memory backend lets tests use environments without a temp directory, see [EnvironmentWrite::new_in_memory](env::EnvironmentWrite::new_in_memory). Another backend, like SQLite or IndexedDb, only needs to implement the traits in the [backend] module.
 //!
 //! ## Buffered Stores
 //!
@@ -32,13 +32,13 @@
 //!
 //! ## Building blocks
 //!
-//! The rkv crate provides a few abstractions for working with LMDB stores. The ones we use are:
+//! The [backend] module provides a few abstractions for working with databases, modeled on those of the `rkv` crate:
 //!
 //! - SingleStore: a key-value store with arbitrary key and one value per key
 //! - IntegerStore: a key-value store with integer key and one value per key
 //! - MultiStore: a key-value store with arbitrary key and multiple values per key
 //!
-//! On top of these abstractions, the `holochain_state` crate provides three buffered store abstractions to wrap each of the rkv store types, as well as a simple CAS abstraction:
+//! On top of these abstractions, the `holochain_state` crate provides three buffered store abstractions to wrap each of the store types, as well as a simple CAS abstraction:
 //!
 //! - [KvBuffer]: a SingleStore with a scratch space
 //! - [KvIntBuffer]: an IntegerStore with a scratch space
@@ -51,6 +51,7 @@
 
 #![deny(missing_docs)]
 
+pub mod backend;
 pub mod buffer;
 pub mod db;
 pub mod env;
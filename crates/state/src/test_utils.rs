@@ -25,6 +25,30 @@ pub fn test_wasm_env() -> TestEnvironment {
     test_env(EnvironmentKind::Wasm)
 }
 
+/// Create an [EnvironmentWrite] of [EnvironmentKind::Cell] which keeps its
+/// data in memory, for tests which don't need LMDB
+pub fn test_in_memory_cell_env() -> EnvironmentWrite {
+    let cell_id = fake_cell_id(1);
+    test_in_memory_env(EnvironmentKind::Cell(cell_id))
+}
+
+/// Create an [EnvironmentWrite] of any kind which keeps its data in memory
+pub fn test_in_memory_env(kind: EnvironmentKind) -> EnvironmentWrite {
+    EnvironmentWrite::new_in_memory(kind, test_keystore())
+        .expect("Couldn't create in-memory test environment")
+}
+
+/// Create a [TestEnvironment] of [EnvironmentKind::Cell] on each storage
+/// backend, LMDB first, for tests which must pass on both.
+/// The in-memory environment's temp directory is left empty.
+pub fn test_cell_envs() -> Vec<TestEnvironment> {
+    let in_memory = TestEnvironment {
+        env: test_in_memory_cell_env(),
+        tmpdir: Arc::new(TempDir::new("holochain-test-environments").unwrap()),
+    };
+    vec![test_cell_env(), in_memory]
+}
+
 /// Generate a test keystore pre-populated with a couple test keypairs.
 pub fn test_keystore() -> holochain_keystore::KeystoreSender {
     use holochain_keystore::KeystoreSenderExt;
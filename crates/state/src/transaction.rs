@@ -1,21 +1,22 @@
-//! This module is just wrappers around the rkv transaction representations.
+//! Wrappers around the transactions of a [StorageBackend](crate::backend::StorageBackend).
 //! They are necessary/useful for a few reasons:
+//! - Holochain code can be written against one Reader and one Writer type,
+//!     whichever backend the environment uses
 //! - Reader is not marked Send + Sync in rkv, but we must mark it such to make
 //!     use of the threadsafe read-only transactions provided by the MDB_NOTLS flag
-//! - We can upgrade some error types from rkv::StoreError, which does not implement
-//!     std::error::Error, into error types that do
 
-use crate::error::DatabaseError;
+use crate::{
+    backend::{ReadTxn, WriteTxn},
+    error::{DatabaseError, DatabaseResult},
+};
 use chrono::{offset::Local, DateTime};
-use derive_more::From;
-use rkv::{Database, RoCursor, StoreError, Value};
-use shrinkwraprs::Shrinkwrap;
 
-/// Just a trait alias for rkv::Readable
-/// It's important because it lets us use either a Reader or a Writer
-/// for read-only operations
-pub trait Readable: rkv::Readable {}
-impl<T: rkv::Readable> Readable for T {}
+/// Implemented by both [Reader] and [Writer], so that read-only operations
+/// can be done in either kind of transaction
+pub trait Readable {
+    /// The backend transaction to read from
+    fn txn(&self) -> &dyn ReadTxn;
+}
 
 struct ReaderSpanInfo {
     // Using a chrono timestamp here because we need duration operations
@@ -41,9 +42,8 @@ impl Drop for ReaderSpanInfo {
     }
 }
 
-/// Wrapper around `rkv::Reader`, so it can be marked as threadsafe
-#[derive(Shrinkwrap)]
-pub struct Reader<'env>(#[shrinkwrap(main_field)] rkv::Reader<'env>, ReaderSpanInfo);
+/// A read-only transaction of any backend, which can be marked as threadsafe
+pub struct Reader<'env>(Box<dyn ReadTxn + 'env>, ReaderSpanInfo);
 
 /// If MDB_NOTLS env flag is set, then read-only transactions are threadsafe
 /// and we can mark them as such
@@ -55,42 +55,67 @@ unsafe impl<'env> Send for Reader<'env> {}
 #[cfg(feature = "lmdb_no_tls")]
 unsafe impl<'env> Sync for Reader<'env> {}
 
-impl<'env> rkv::Readable for Reader<'env> {
-    fn get<K: AsRef<[u8]>>(&self, db: Database, k: &K) -> Result<Option<Value>, StoreError> {
-        self.0.get(db, k)
-    }
-
-    fn open_ro_cursor(&self, db: Database) -> Result<RoCursor, StoreError> {
-        self.0.open_ro_cursor(db)
+impl<'env> Readable for Reader<'env> {
+    fn txn(&self) -> &dyn ReadTxn {
+        &*self.0
     }
 }
 
-impl<'env> From<rkv::Reader<'env>> for Reader<'env> {
-    fn from(r: rkv::Reader<'env>) -> Self {
-        Self(r, ReaderSpanInfo::new())
+impl<'env> From<Box<dyn ReadTxn + 'env>> for Reader<'env> {
+    fn from(txn: Box<dyn ReadTxn + 'env>) -> Self {
+        Self(txn, ReaderSpanInfo::new())
     }
 }
 
-/// Wrapper around `rkv::Writer`, which lifts some of the return values to types recognized by this crate,
-/// rather than the rkv-specific values
-#[derive(From, Shrinkwrap)]
-#[shrinkwrap(mutable, unsafe_ignore_visibility)]
-pub struct Writer<'env>(rkv::Writer<'env>);
+/// A read-write transaction of any backend
+pub struct Writer<'env> {
+    txn: Box<dyn WriteTxn + 'env>,
+    /// Set once a write fails with [DatabaseError::MapFull], after which
+    /// the transaction can't be committed
+    map_full: bool,
+}
 
-impl<'env> rkv::Readable for Writer<'env> {
-    fn get<K: AsRef<[u8]>>(&self, db: Database, k: &K) -> Result<Option<Value>, StoreError> {
-        self.0.get(db, k)
+impl<'env> From<Box<dyn WriteTxn + 'env>> for Writer<'env> {
+    fn from(txn: Box<dyn WriteTxn + 'env>) -> Self {
+        Self {
+            txn,
+            map_full: false,
+        }
     }
+}
 
-    fn open_ro_cursor(&self, db: Database) -> Result<RoCursor, StoreError> {
-        self.0.open_ro_cursor(db)
+impl<'env> Readable for Writer<'env> {
+    fn txn(&self) -> &dyn ReadTxn {
+        self.txn.as_read()
     }
 }
 
 impl<'env> Writer<'env> {
-    /// This override exists solely to raise the Error from the rkv::StoreError,
-    /// which does not implement std::error::Error, into a DatabaseError, which does.
-    pub fn commit(self) -> Result<(), DatabaseError> {
-        self.0.commit().map_err(DatabaseError::from)
+    /// Make a write with the backend transaction
+    pub(crate) fn write<R>(
+        &mut self,
+        f: impl FnOnce(&mut (dyn WriteTxn + 'env)) -> DatabaseResult<R>,
+    ) -> DatabaseResult<R> {
+        let result = f(&mut *self.txn);
+        if let Err(DatabaseError::MapFull) = result {
+            self.map_full = true;
+        }
+        result
+    }
+
+    /// Whether a write failed because the LMDB map is full. The error may have
+    /// been wrapped in any other error type by the time the transaction is
+    /// given up, so this is how it's known that the map must grow.
+    pub fn map_full(&self) -> bool {
+        self.map_full
+    }
+
+    /// Commit every write made in this transaction
+    pub fn commit(self) -> DatabaseResult<()> {
+        if self.map_full {
+            // LMDB would refuse to commit it too
+            return Err(DatabaseError::MapFull);
+        }
+        self.txn.commit()
     }
 }
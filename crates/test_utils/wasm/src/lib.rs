@@ -15,6 +15,7 @@ pub enum TestWasm {
     Capability,
     Create,
     Crd,
+    Crdt,
     Crud,
     Debug,
     EntryDefs,
@@ -26,9 +27,11 @@ pub enum TestWasm {
     InitFail,
     InitPass,
     Link,
+    Membrane,
     MigrateAgentFail,
     MigrateAgentPass,
     MultipleCalls,
+    PostCommitCreate,
     PostCommitFail,
     PostCommitSuccess,
     Query,
@@ -57,6 +60,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::Capability => "capability",
             TestWasm::Create => "create_entry",
             TestWasm::Crd => "crd",
+            TestWasm::Crdt => "crdt",
             TestWasm::Crud => "crud",
             TestWasm::Debug => "debug",
             TestWasm::EntryDefs => "entry_defs",
@@ -68,9 +72,11 @@ impl From<TestWasm> for ZomeName {
             TestWasm::InitFail => "init_fail",
             TestWasm::InitPass => "init_pass",
             TestWasm::Link => "link",
+            TestWasm::Membrane => "membrane",
             TestWasm::MigrateAgentFail => "migrate_agent_fail",
             TestWasm::MigrateAgentPass => "migrate_agent_pass",
             TestWasm::MultipleCalls => "multiple_calls",
+            TestWasm::PostCommitCreate => "post_commit_create",
             TestWasm::PostCommitFail => "post_commit_fail",
             TestWasm::PostCommitSuccess => "post_commit_success",
             TestWasm::Query => "query",
@@ -107,6 +113,7 @@ impl From<TestWasm> for DnaWasm {
                 get_code("wasm32-unknown-unknown/release/test_wasm_create_entry.wasm")
             }
             TestWasm::Crd => get_code("wasm32-unknown-unknown/release/test_wasm_crd.wasm"),
+            TestWasm::Crdt => get_code("wasm32-unknown-unknown/release/test_wasm_crdt.wasm"),
             TestWasm::Crud => get_code("wasm32-unknown-unknown/release/test_wasm_crud.wasm"),
             TestWasm::Debug => get_code("wasm32-unknown-unknown/release/test_wasm_debug.wasm"),
             TestWasm::EntryDefs => {
@@ -130,6 +137,9 @@ impl From<TestWasm> for DnaWasm {
                 get_code("wasm32-unknown-unknown/release/test_wasm_init_pass.wasm")
             }
             TestWasm::Link => get_code("wasm32-unknown-unknown/release/test_wasm_link.wasm"),
+            TestWasm::Membrane => {
+                get_code("wasm32-unknown-unknown/release/test_wasm_membrane.wasm")
+            }
             TestWasm::MigrateAgentFail => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_migrate_agent_fail.wasm")
             }
@@ -139,6 +149,9 @@ impl From<TestWasm> for DnaWasm {
             TestWasm::MultipleCalls => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_multiple_calls.wasm")
             }
+            TestWasm::PostCommitCreate => {
+                get_code("wasm32-unknown-unknown/release/test_wasm_post_commit_create.wasm")
+            }
             TestWasm::PostCommitFail => {
                 get_code("wasm32-unknown-unknown/release/test_wasm_post_commit_fail.wasm")
             }
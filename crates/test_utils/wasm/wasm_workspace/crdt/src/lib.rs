@@ -0,0 +1,25 @@
+use hdk3::prelude::*;
+
+#[hdk_entry(id = "note", crdt_type = "last_write_wins")]
+struct Note(String);
+
+entry_defs![Note::entry_def()];
+
+/// Replace the note created by a header with a new one
+#[derive(Serialize, Deserialize, SerializedBytes)]
+struct UpdateNote(HeaderHash, Note);
+
+#[hdk_extern]
+fn create(note: Note) -> ExternResult<HeaderHash> {
+    Ok(create_entry!(note)?)
+}
+
+#[hdk_extern]
+fn update(UpdateNote(header_hash, note): UpdateNote) -> ExternResult<HeaderHash> {
+    Ok(update_entry!(header_hash, note)?)
+}
+
+#[hdk_extern]
+fn read(entry_hash: EntryHash) -> ExternResult<GetOutput> {
+    Ok(GetOutput::new(get!(entry_hash)?))
+}
@@ -0,0 +1,24 @@
+use hdk3::prelude::*;
+
+/// Agents can only join with this membrane proof
+const MEMBRANE_PROOF: &[u8] = b"let me in";
+
+fn check_membrane_proof(membrane_proof: Option<SerializedBytes>) -> ValidateCallbackResult {
+    match membrane_proof {
+        Some(proof) if proof.bytes().as_slice() == MEMBRANE_PROOF => ValidateCallbackResult::Valid,
+        _ => ValidateCallbackResult::Invalid("not a valid membrane proof".to_string()),
+    }
+}
+
+#[hdk_extern]
+fn genesis_self_check(data: GenesisSelfCheckData) -> ExternResult<ValidateCallbackResult> {
+    Ok(check_membrane_proof(data.membrane_proof))
+}
+
+#[hdk_extern]
+fn validate_agent_validation_pkg(element: Element) -> ExternResult<ValidateCallbackResult> {
+    Ok(match element.header() {
+        Header::AgentValidationPkg(pkg) => check_membrane_proof(pkg.membrane_proof.clone()),
+        _ => ValidateCallbackResult::Valid,
+    })
+}
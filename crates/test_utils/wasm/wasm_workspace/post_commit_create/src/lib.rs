@@ -0,0 +1,55 @@
+use hdk3::prelude::*;
+
+#[hdk_entry(id = "post")]
+struct Post(String);
+
+entry_defs![Post::entry_def()];
+
+/// Commit a post. Its content tells post_commit what to commit in reply:
+/// "fail" commits a reply then fails, "reply invalid" commits a reply which
+/// doesn't validate, and anything else commits a valid reply.
+#[hdk_extern]
+fn create_post(post: Post) -> ExternResult<HeaderHash> {
+    Ok(create_entry!(post)?)
+}
+
+#[hdk_extern]
+fn validate(element: Element) -> ExternResult<ValidateCallbackResult> {
+    Ok(match element.entry().to_app_option::<Post>()? {
+        Some(Post(content)) if content == "invalid" => {
+            ValidateCallbackResult::Invalid("invalid post".to_string())
+        }
+        _ => ValidateCallbackResult::Valid,
+    })
+}
+
+#[hdk_extern]
+fn post_commit(headers: HeaderHashes) -> ExternResult<PostCommitCallbackResult> {
+    // The committed posts, read from the local chain
+    let mut posts = vec![];
+    for element in query!(QueryFilter::new())?.0 {
+        if headers.0.contains(element.header_address()) {
+            if let Some(Post(content)) = element.entry().to_app_option()? {
+                posts.push(content);
+            }
+        }
+    }
+    for content in posts {
+        match content.as_str() {
+            "fail" => {
+                create_entry!(Post("reply".into()))?;
+                return Ok(PostCommitCallbackResult::Fail(
+                    headers,
+                    "asked to fail".into(),
+                ));
+            }
+            "reply invalid" => {
+                create_entry!(Post("invalid".into()))?;
+            }
+            _ => {
+                create_entry!(Post(format!("reply to {}", content)))?;
+            }
+        }
+    }
+    Ok(PostCommitCallbackResult::Success)
+}
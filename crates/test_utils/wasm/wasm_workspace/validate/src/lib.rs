@@ -20,7 +20,7 @@ impl From<&ThisWasmEntry> for EntryDefId {
 
 impl From<&ThisWasmEntry> for CrdtType {
     fn from(_: &ThisWasmEntry) -> Self {
-        Self
+        Self::default()
     }
 }
 
@@ -42,6 +42,7 @@ impl From<&ThisWasmEntry> for EntryDef {
             id: entry.into(),
             crdt_type: entry.into(),
             required_validations: entry.into(),
+            max_size: None,
             visibility: entry.into(),
         }
     }
@@ -50,6 +50,40 @@ impl InstallAppDnaPayload {
 /// App-specific payload for proving membership in the membrane of the app
 pub type MembraneProof = SerializedBytes;
 
+/// Information needed to clone an already installed Cell into a new Cell
+/// with a different DnaHash, as part of the same App
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct CreateCloneCellPayload {
+    /// The App to add the new Cell to
+    pub app_id: AppId,
+    /// The CellNick of the installed Cell whose Dna will be cloned
+    pub cell_nick: CellNick,
+    /// The new UUID to give to the cloned Dna
+    pub uuid: Option<String>,
+    /// Properties to override in the cloned Dna
+    pub properties: Option<JsonProperties>,
+    /// App-specific proof-of-membrane-membership for the cloned Dna, if required
+    pub membrane_proof: Option<MembraneProof>,
+}
+
+impl CreateCloneCellPayload {
+    /// The CellNick which will be given to the `index`th clone of a Cell
+    pub fn clone_nick(&self, index: usize) -> CellNick {
+        format!("{}.{}", self.cell_nick, index)
+    }
+
+    /// The index of a clone of the Cell, if `nick` is the nick of one.
+    /// Clones of those clones have nicks with more suffixes, so aren't counted.
+    pub fn clone_index(&self, nick: &str) -> Option<usize> {
+        let prefix = format!("{}.", self.cell_nick);
+        if nick.starts_with(&prefix) {
+            nick[prefix.len()..].parse().ok()
+        } else {
+            None
+        }
+    }
+}
+
 /// Data about an installed Cell
 #[derive(Clone, Debug, Into, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct InstalledCell(CellId, CellNick);
@@ -11,12 +11,72 @@ use derive_more::From;
 pub use error::DnaError;
 use holo_hash::impl_hashable_content;
 pub use holo_hash::*;
-use holochain_zome_types::zome::ZomeName;
+use holochain_zome_types::{entry_def::EntryDef, zome::ZomeName};
 use std::collections::BTreeMap;
 
 /// Zomes need to be an ordered map from ZomeName to a Zome
 pub type Zomes = Vec<(ZomeName, zome::Zome)>;
 
+/// 16mb limit on Entries due to websocket limits.
+/// Consider splitting large entries up.
+pub const MAX_ENTRY_SIZE: usize = 16_000_000;
+
+/// 400b limit on LinkTags.
+/// Tags are used as keys to the database to allow
+/// fast lookup so they need to be small.
+pub const MAX_TAG_SIZE: usize = 400;
+
+/// Limits on the data of a DNA. Since they are part of the [DnaDef],
+/// every node running the DNA enforces the same limits.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DnaLimits {
+    /// The largest an app entry may be in bytes,
+    /// unless its [EntryDef] sets its own limit
+    #[serde(default = "default_max_entry_size")]
+    pub max_entry_size: usize,
+    /// The largest a link tag may be in bytes
+    #[serde(default = "default_max_tag_size")]
+    pub max_tag_size: usize,
+    /// How fast an author may publish headers.
+    /// There is no limit if this is `None`
+    #[serde(default)]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// The most headers an author may publish within a window of time
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    /// The most headers allowed in any one window
+    pub max_headers: u32,
+    /// The length of the window in milliseconds
+    pub window_ms: u64,
+}
+
+fn default_max_entry_size() -> usize {
+    MAX_ENTRY_SIZE
+}
+
+fn default_max_tag_size() -> usize {
+    MAX_TAG_SIZE
+}
+
+impl DnaLimits {
+    /// Are these the limits a DNA has when it doesn't set any
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl Default for DnaLimits {
+    fn default() -> Self {
+        Self {
+            max_entry_size: MAX_ENTRY_SIZE,
+            max_tag_size: MAX_TAG_SIZE,
+            rate_limit: None,
+        }
+    }
+}
+
 /// A type to allow json values to be used as [SerializedBytes]
 #[derive(Debug, Clone, From, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct JsonProperties(serde_json::Value);
@@ -43,6 +103,13 @@ pub struct DnaDef {
 
     /// An array of zomes associated with your holochain application.
     pub zomes: Zomes,
+
+    /// Size limits on entries and link tags.
+    /// If omitted, the [MAX_ENTRY_SIZE] and [MAX_TAG_SIZE] defaults apply.
+    /// The default limits aren't serialized, so a DNA which doesn't set any
+    /// keeps the hash it had before limits existed.
+    #[serde(default, skip_serializing_if = "DnaLimits::is_default")]
+    pub limits: DnaLimits,
 }
 
 impl DnaDef {
@@ -59,6 +126,11 @@ impl DnaDef {
             .map(|(_, zome)| zome)
             .ok_or_else(|| DnaError::ZomeNotFound(format!("Zome '{}' not found", &zome_name,)))
     }
+
+    /// The largest an app entry of the given entry def may be in bytes
+    pub fn max_entry_size(&self, entry_def: &EntryDef) -> usize {
+        entry_def.max_size.unwrap_or(self.limits.max_entry_size)
+    }
 }
 
 /// A DnaDef paired with its DnaHash
@@ -188,3 +260,59 @@ impl std::fmt::Debug for DnaFile {
         f.write_fmt(format_args!("DnaFile(dna_hash = {})", self.dna_hash))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A DnaDef as it was serialized before it had limits
+    #[derive(Serialize, Deserialize, SerializedBytes)]
+    struct DnaDefWithoutLimits {
+        name: String,
+        uuid: String,
+        properties: SerializedBytes,
+        zomes: Zomes,
+    }
+
+    fn dna_def(limits: DnaLimits) -> DnaDef {
+        DnaDef {
+            name: "limits".to_string(),
+            uuid: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
+            properties: SerializedBytes::try_from(()).unwrap(),
+            zomes: vec![],
+            limits,
+        }
+    }
+
+    #[test]
+    fn default_limits_keep_the_old_hash() {
+        let dna = dna_def(DnaLimits::default());
+        let old_bytes = SerializedBytes::try_from(DnaDefWithoutLimits {
+            name: dna.name.clone(),
+            uuid: dna.uuid.clone(),
+            properties: dna.properties.clone(),
+            zomes: dna.zomes.clone(),
+        })
+        .unwrap();
+        assert_eq!(SerializedBytes::try_from(dna.clone()).unwrap(), old_bytes);
+
+        // An old DnaDef decodes with the default limits
+        assert_eq!(DnaDef::try_from(old_bytes).unwrap(), dna);
+    }
+
+    #[test]
+    fn custom_limits_change_the_hash() {
+        let limits = DnaLimits {
+            max_tag_size: 10,
+            ..Default::default()
+        };
+        let dna = dna_def(limits);
+        let default_dna = dna_def(DnaLimits::default());
+        assert_ne!(
+            SerializedBytes::try_from(dna.clone()).unwrap(),
+            SerializedBytes::try_from(default_dna).unwrap()
+        );
+        let bytes = SerializedBytes::try_from(dna.clone()).unwrap();
+        assert_eq!(DnaDef::try_from(bytes).unwrap().limits, limits);
+    }
+}
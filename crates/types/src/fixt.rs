@@ -28,6 +28,7 @@ use holochain_zome_types::capability::GrantedFunctions;
 use holochain_zome_types::capability::ZomeCallCapGrant;
 use holochain_zome_types::capability::CAP_SECRET_BYTES;
 use holochain_zome_types::crdt::CrdtType;
+use holochain_zome_types::crdt::MergeData;
 use holochain_zome_types::entry::AppEntryBytes;
 use holochain_zome_types::entry_def::EntryDef;
 use holochain_zome_types::entry_def::EntryDefId;
@@ -320,6 +321,22 @@ fixturator!(
     };
 );
 
+fixturator!(
+    MergeData;
+    curve Empty MergeData {
+        original: ElementFixturator::new(Empty).next().unwrap(),
+        updates: vec![],
+    };
+    curve Unpredictable MergeData {
+        original: ElementFixturator::new(Unpredictable).next().unwrap(),
+        updates: ElementFixturator::new(Unpredictable).take(3).collect(),
+    };
+    curve Predictable MergeData {
+        original: ElementFixturator::new_indexed(Predictable, self.0.index).next().unwrap(),
+        updates: ElementFixturator::new_indexed(Predictable, self.0.index + 1).take(3).collect(),
+    };
+);
+
 fn new_entry_element(entry: Entry, header_type: HeaderType, index: usize) -> Element {
     let et = match entry {
         Entry::App(_) => EntryType::App(
@@ -390,9 +407,7 @@ fixturator!(
 
 fixturator!(
     CrdtType;
-    curve Empty CrdtType;
-    curve Unpredictable CrdtType;
-    curve Predictable CrdtType;
+    unit variants [ GrowOnlySet SingleAuthorLatestWins LastWriteWins AppMerge ] empty GrowOnlySet;
 );
 
 fixturator!(
@@ -462,6 +477,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Empty, self.0.index)
             .next()
             .unwrap(),
+        limits: Default::default(),
     };
 
     curve Unpredictable DnaDef {
@@ -477,6 +493,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Unpredictable, self.0.index)
             .next()
             .unwrap(),
+        limits: Default::default(),
     };
 
     curve Predictable DnaDef {
@@ -492,6 +509,7 @@ fixturator!(
         zomes: ZomesFixturator::new_indexed(Predictable, self.0.index)
             .next()
             .unwrap(),
+        limits: Default::default(),
     };
 );
 
@@ -21,6 +21,7 @@ pub mod prelude;
 pub mod timestamp;
 pub mod universal_map;
 pub mod validate;
+pub mod warrant;
 
 // #[cfg(test)]
 pub mod test_utils;
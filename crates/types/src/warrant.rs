@@ -0,0 +1,55 @@
+//! Proofs that an agent has broken the rules every DNA shares.
+//!
+//! A [Warrant] carries everything needed to check it, so any node can
+//! verify it with nothing but the accused agent's public key.
+
+use holo_hash::{AgentPubKey, HeaderHash};
+use holochain_serialized_bytes::prelude::*;
+use holochain_zome_types::element::SignedHeader;
+
+/// A proof that an agent misbehaved
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq, Eq)]
+pub enum Warrant {
+    /// The agent signed two different headers at the same
+    /// position of their source chain
+    ChainFork(SignedHeader, SignedHeader),
+}
+
+impl Warrant {
+    /// Create a warrant for a fork between two headers.
+    /// The headers are ordered by hash so every node that sees
+    /// the same fork builds the same warrant.
+    pub fn chain_fork(a: SignedHeader, b: SignedHeader) -> Self {
+        if HeaderHash::with_data_sync(a.header()) <= HeaderHash::with_data_sync(b.header()) {
+            Warrant::ChainFork(a, b)
+        } else {
+            Warrant::ChainFork(b, a)
+        }
+    }
+
+    /// The agent this warrant is against
+    pub fn author(&self) -> &AgentPubKey {
+        match self {
+            Warrant::ChainFork(a, _) => a.header().author(),
+        }
+    }
+
+    /// The position in the agent's chain where they misbehaved
+    pub fn header_seq(&self) -> u32 {
+        match self {
+            Warrant::ChainFork(a, _) => a.header().header_seq(),
+        }
+    }
+
+    /// Check the warrant makes a claim at all: both headers are
+    /// by the same author, at the same position and are different.
+    /// This does not check the signatures.
+    pub fn is_well_formed(&self) -> bool {
+        match self {
+            Warrant::ChainFork(a, b) => {
+                let (a, b) = (a.header(), b.header());
+                a.author() == b.author() && a.header_seq() == b.header_seq() && a != b
+            }
+        }
+    }
+}
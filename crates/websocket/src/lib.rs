@@ -14,6 +14,10 @@
 //! [WebsocketReceiver](struct.WebsocketReceiver.html)
 //! ).
 //!
+//! On unix, the same websocket framing can be carried over a unix domain
+//! socket instead of tcp with [websocket_bind_unix](fn.websocket_bind_unix.html)
+//! and [websocket_connect_unix](fn.websocket_connect_unix.html).
+//!
 //! # Example
 //!
 //! ```
@@ -170,4 +174,107 @@ mod tests {
 
         assert_eq!("echo: test", &rsp.0,);
     }
+
+    #[tokio::test]
+    async fn rejects_disallowed_origin() {
+        holochain_types::observability::test_run().ok();
+        let mut server = websocket_bind(
+            url2!("ws://127.0.0.1:0"),
+            Arc::new(
+                WebsocketConfig::default().allowed_origins(vec!["http://localhost:8888".into()]),
+            ),
+        )
+        .await
+        .unwrap();
+
+        let binding = server.local_addr().clone();
+
+        tokio::task::spawn(async move { while let Some(_) = server.next().await {} });
+
+        let request = |origin: &str| {
+            tungstenite::http::Request::builder()
+                .uri(binding.as_str())
+                .header("Origin", origin)
+                .body(())
+                .unwrap()
+        };
+
+        let result = tokio_tungstenite::connect_async(request("http://evil.example")).await;
+        assert!(result.is_err());
+
+        let result = tokio_tungstenite::connect_async(request("http://localhost:8888")).await;
+        assert!(result.is_ok());
+
+        // clients without an Origin header are unaffected
+        let result = websocket_connect(binding, Arc::new(WebsocketConfig::default())).await;
+        assert!(result.is_ok());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_test() {
+        holochain_types::observability::test_run().ok();
+        let dir = tempdir::TempDir::new("websocket_unix").unwrap();
+        let path = dir.path().join("test.sock");
+        let mut server = websocket_bind_unix(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+
+        tokio::task::spawn(async move {
+            while let Some(maybe_con) = server.next().await {
+                let (_send, mut recv) = maybe_con.unwrap();
+
+                tokio::task::spawn(async move {
+                    if let Some(WebsocketMessage::Request(data, respond)) = recv.next().await {
+                        let msg: TestMessage = data.try_into().unwrap();
+                        let msg = TestMessage(format!("echo: {}", msg.0));
+                        respond(msg.try_into().unwrap()).await.unwrap();
+                    }
+                });
+            }
+        });
+
+        let (mut send, _recv) = websocket_connect_unix(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+
+        let msg = TestMessage("test".to_string());
+        let rsp: TestMessage = send.request(msg).await.unwrap();
+
+        assert_eq!("echo: test", &rsp.0,);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn unix_socket_bind_only_replaces_sockets() {
+        holochain_types::observability::test_run().ok();
+        let dir = tempdir::TempDir::new("websocket_unix").unwrap();
+        let config = Arc::new(WebsocketConfig::default());
+
+        // a regular file is left alone
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"data").unwrap();
+        assert!(websocket_bind_unix(&file, config.clone()).await.is_err());
+        assert_eq!(std::fs::read(&file).unwrap(), b"data");
+
+        // so is a symlink, even to a socket
+        let path = dir.path().join("test.sock");
+        let server = websocket_bind_unix(&path, config.clone()).await.unwrap();
+        let link = dir.path().join("link.sock");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+        assert!(websocket_bind_unix(&link, config.clone()).await.is_err());
+        assert!(std::fs::symlink_metadata(&link).is_ok());
+
+        // a socket that is still listened on is left alone
+        let err = websocket_bind_unix(&path, config.clone())
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), ErrorKind::AddrInUse);
+
+        // a stale socket is replaced
+        drop(server);
+        assert!(path.exists());
+        assert!(websocket_bind_unix(&path, config).await.is_ok());
+    }
 }
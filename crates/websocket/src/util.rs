@@ -1,6 +1,9 @@
 //! internal websocket utility types and code
 
 use crate::*;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite};
 
 /// Implements both sides of TryFrom SerializedBytes for the passed in item.
 /// See holochain_serialized_bytes::holochain_serial! macro.
@@ -60,8 +63,69 @@ pub(crate) fn init_tracing() {
     holochain_types::observability::test_run().unwrap();
 }
 
+/// internal byte stream underlying a websocket
+pub(crate) enum RawStream {
+    /// plain tcp
+    Plain(tokio::net::TcpStream),
+    /// tcp wrapped in tls
+    Tls(tokio_tls::TlsStream<tokio::net::TcpStream>),
+    /// unix domain socket
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+}
+
+impl AsyncRead for RawStream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context, buf: &mut [u8]) -> Poll<Result<usize>> {
+        match self.get_mut() {
+            RawStream::Plain(s) => Pin::new(s).poll_read(cx, buf),
+            RawStream::Tls(s) => Pin::new(s).poll_read(cx, buf),
+            #[cfg(unix)]
+            RawStream::Unix(s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for RawStream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context, buf: &[u8]) -> Poll<Result<usize>> {
+        match self.get_mut() {
+            RawStream::Plain(s) => Pin::new(s).poll_write(cx, buf),
+            RawStream::Tls(s) => Pin::new(s).poll_write(cx, buf),
+            #[cfg(unix)]
+            RawStream::Unix(s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        match self.get_mut() {
+            RawStream::Plain(s) => Pin::new(s).poll_flush(cx),
+            RawStream::Tls(s) => Pin::new(s).poll_flush(cx),
+            #[cfg(unix)]
+            RawStream::Unix(s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Result<()>> {
+        match self.get_mut() {
+            RawStream::Plain(s) => Pin::new(s).poll_shutdown(cx),
+            RawStream::Tls(s) => Pin::new(s).poll_shutdown(cx),
+            #[cfg(unix)]
+            RawStream::Unix(s) => Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
+
 /// internal socket type
-pub(crate) type RawSocket = tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>;
+pub(crate) type RawSocket = tokio_tungstenite::WebSocketStream<RawStream>;
+
+/// The url scheme used to identify unix domain socket addresses
+#[cfg(unix)]
+pub(crate) const UNIX_SCHEME: &str = "unix";
+
+/// internal helper to convert unix socket paths to urls
+#[cfg(unix)]
+pub(crate) fn path_to_url(path: &std::path::Path) -> Url2 {
+    url2!("{}://{}", UNIX_SCHEME, path.display())
+}
 
 /// internal helper to convert addrs to urls
 pub(crate) fn addr_to_url(a: SocketAddr, scheme: &str) -> Url2 {
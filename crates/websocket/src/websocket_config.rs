@@ -26,6 +26,20 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// Certificate and private key used by a listener to serve "wss"
+    /// connections. [default = None]
+    pub tls_identity: Option<TlsIdentity>,
+
+    /// PEM encoded certificate which outgoing "wss" connections trust
+    /// along with the system's root certificates, e.g. the self-signed
+    /// certificate of a listener. [default = None]
+    pub tls_root_certificate: Option<Vec<u8>>,
+
+    /// Origins from which a listener accepts connections. Connections which
+    /// don't send an Origin header (i.e. non-browser clients) are always
+    /// accepted. [default = None, accept any origin]
+    pub allowed_origins: Option<Vec<String>>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +52,9 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            tls_identity: None,
+            tls_root_certificate: None,
+            allowed_origins: None,
         }
     }
 }
@@ -78,6 +95,54 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter. Also switches the scheme to "wss".
+    pub fn tls_identity(mut self, identity: TlsIdentity) -> Self {
+        self.scheme = "wss";
+        self.tls_identity = Some(identity);
+        self
+    }
+
+    /// Builder-style setter. Also switches the scheme to "wss".
+    pub fn tls_root_certificate(mut self, cert_pem: Vec<u8>) -> Self {
+        self.scheme = "wss";
+        self.tls_root_certificate = Some(cert_pem);
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn allowed_origins(mut self, origins: Vec<String>) -> Self {
+        self.allowed_origins = Some(origins);
+        self
+    }
+}
+
+/// A PEM encoded certificate chain and PKCS #8 private key
+/// for serving websockets over TLS.
+#[derive(Clone)]
+pub struct TlsIdentity {
+    /// PEM encoded certificate chain
+    pub cert_pem: Vec<u8>,
+    /// PEM encoded PKCS #8 private key
+    pub key_pem: Vec<u8>,
+}
+
+impl std::fmt::Debug for TlsIdentity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // never print the private key
+        f.write_str("TlsIdentity { .. }")
+    }
+}
+
+impl TlsIdentity {
+    /// internal helper to build a tls acceptor from this identity
+    pub(crate) fn to_acceptor(&self) -> std::io::Result<tokio_tls::TlsAcceptor> {
+        let other = |e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e);
+        let identity =
+            native_tls::Identity::from_pkcs8(&self.cert_pem, &self.key_pem).map_err(other)?;
+        let acceptor = native_tls::TlsAcceptor::new(identity).map_err(other)?;
+        Ok(acceptor.into())
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
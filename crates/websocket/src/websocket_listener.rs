@@ -2,6 +2,7 @@
 
 use crate::*;
 use futures::stream::{BoxStream, StreamExt};
+use tungstenite::handshake::server::{ErrorResponse, Request, Response};
 
 /// Websocket listening / server socket. This struct is an async Stream -
 /// calling `.next().await` will give you a Future that will in turn resolve
@@ -44,6 +45,16 @@ impl tokio::stream::Stream for WebsocketListener {
 /// Returns a [WebsocketListener](struct.WebsocketListener.html) instance.
 pub async fn websocket_bind(addr: Url2, config: Arc<WebsocketConfig>) -> Result<WebsocketListener> {
     let addr = url_to_addr(&addr, config.scheme).await?;
+    let tls_acceptor = match &config.tls_identity {
+        Some(identity) => Some(identity.to_acceptor()?),
+        None if config.scheme == "wss" => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "binding a 'wss' listener requires a tls identity",
+            ))
+        }
+        None => None,
+    };
     let socket = match &addr {
         SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
         SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
@@ -58,7 +69,66 @@ pub async fn websocket_bind(addr: Url2, config: Arc<WebsocketConfig>) -> Result<
     let socket = socket
         .map({
             let config = config.clone();
-            move |socket_result| connect(config.clone(), socket_result)
+            move |socket_result| connect(config.clone(), tls_acceptor.clone(), socket_result)
+        })
+        .buffer_unordered(config.max_pending_connections)
+        .boxed();
+
+    tracing::info!(
+        message = "bind",
+        local_addr = %local_addr,
+    );
+    Ok(WebsocketListener {
+        config,
+        local_addr,
+        socket,
+    })
+}
+
+/// Bind a new websocket listening socket to a unix domain socket at `path`,
+/// and begin awaiting incoming connections. Access to the listener is
+/// governed by the filesystem permissions of the socket file.
+/// A stale socket file left at `path` by a previous listener is replaced,
+/// but a socket that still accepts connections, or anything else at `path`,
+/// including a symlink, is an error.
+/// Returns a [WebsocketListener](struct.WebsocketListener.html) instance.
+#[cfg(unix)]
+pub async fn websocket_bind_unix(
+    path: impl AsRef<std::path::Path>,
+    config: Arc<WebsocketConfig>,
+) -> Result<WebsocketListener> {
+    use std::os::unix::fs::FileTypeExt;
+    let path = path.as_ref();
+    // don't follow symlinks, so only a socket itself is ever removed
+    if let Ok(metadata) = tokio::fs::symlink_metadata(path).await {
+        if !metadata.file_type().is_socket() {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("'{}' exists and is not a unix socket", path.display()),
+            ));
+        }
+        // a socket something is still listening on isn't stale
+        match tokio::net::UnixStream::connect(path).await {
+            Ok(_) => {
+                return Err(Error::new(
+                    ErrorKind::AddrInUse,
+                    format!("'{}' is in use by another listener", path.display()),
+                ))
+            }
+            Err(e) if e.kind() == ErrorKind::ConnectionRefused => {
+                tokio::fs::remove_file(path).await?;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    let socket = tokio::net::UnixListener::bind(path)?;
+
+    let local_addr = path_to_url(path);
+    let socket = socket
+        .map({
+            let config = config.clone();
+            let local_addr = local_addr.clone();
+            move |socket_result| connect_unix(config.clone(), local_addr.clone(), socket_result)
         })
         .buffer_unordered(config.max_pending_connections)
         .boxed();
@@ -77,6 +147,7 @@ pub async fn websocket_bind(addr: Url2, config: Arc<WebsocketConfig>) -> Result<
 /// Connects the new listener
 async fn connect(
     config: Arc<WebsocketConfig>,
+    tls_acceptor: Option<tokio_tls::TlsAcceptor>,
     socket_result: std::io::Result<tokio::net::TcpStream>,
 ) -> Result<(WebsocketSender, WebsocketReceiver)> {
     match socket_result {
@@ -84,22 +155,87 @@ async fn connect(
             socket.set_keepalive(Some(std::time::Duration::from_secs(
                 config.tcp_keepalive_s as u64,
             )))?;
+            let peer_addr = socket.peer_addr()?;
             tracing::debug!(
                 message = "accepted incoming raw socket",
-                remote_addr = %socket.peer_addr()?,
+                remote_addr = %peer_addr,
             );
-            let socket = tokio_tungstenite::accept_async_with_config(
-                socket,
-                Some(tungstenite::protocol::WebSocketConfig {
-                    max_send_queue: Some(config.max_send_queue),
-                    max_message_size: Some(config.max_message_size),
-                    max_frame_size: Some(config.max_frame_size),
-                }),
-            )
-            .await
-            .map_err(|e| Error::new(ErrorKind::Other, e))?;
-            build_websocket_pair(config, socket)
+            let socket = match tls_acceptor {
+                Some(tls_acceptor) => RawStream::Tls(
+                    tls_acceptor
+                        .accept(socket)
+                        .await
+                        .map_err(|e| Error::new(ErrorKind::Other, e))?,
+                ),
+                None => RawStream::Plain(socket),
+            };
+            let remote_addr = addr_to_url(peer_addr, config.scheme);
+            handshake(config, remote_addr, socket).await
         }
         Err(e) => Err(Error::new(ErrorKind::Other, e)),
     }
 }
+
+/// Connects the new unix socket listener
+#[cfg(unix)]
+async fn connect_unix(
+    config: Arc<WebsocketConfig>,
+    local_addr: Url2,
+    socket_result: std::io::Result<tokio::net::UnixStream>,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    match socket_result {
+        Ok(socket) => {
+            // unix socket peers are unnamed, so identify them by our own path
+            tracing::debug!(
+                message = "accepted incoming raw unix socket",
+                local_addr = %local_addr,
+            );
+            handshake(config, local_addr, RawStream::Unix(socket)).await
+        }
+        Err(e) => Err(Error::new(ErrorKind::Other, e)),
+    }
+}
+
+/// Performs the server side of the websocket handshake on an accepted stream
+async fn handshake(
+    config: Arc<WebsocketConfig>,
+    remote_addr: Url2,
+    socket: RawStream,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    let allowed_origins = config.allowed_origins.clone();
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
+        socket,
+        move |request: &Request, response: Response| {
+            check_origin(allowed_origins.as_ref(), request, response)
+        },
+        Some(config.to_tungstenite()),
+    )
+    .await
+    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    build_websocket_pair(config, remote_addr, socket)
+}
+
+/// Reject the handshake if the request comes from an origin
+/// which is not in the allowed origins
+fn check_origin(
+    allowed_origins: Option<&Vec<String>>,
+    request: &Request,
+    response: Response,
+) -> std::result::Result<Response, ErrorResponse> {
+    let origin = request
+        .headers()
+        .get("Origin")
+        .and_then(|origin| origin.to_str().ok());
+    match (allowed_origins, origin) {
+        (Some(allowed_origins), Some(origin)) if !allowed_origins.iter().any(|o| o == origin) => {
+            tracing::warn!(
+                message = "rejected connection from disallowed origin",
+                %origin,
+            );
+            let mut error = ErrorResponse::new(Some(format!("Origin '{}' is not allowed", origin)));
+            *error.status_mut() = tungstenite::http::StatusCode::FORBIDDEN;
+            Err(error)
+        }
+        _ => Ok(response),
+    }
+}
@@ -110,6 +110,28 @@ pub async fn websocket_connect(
     socket.set_keepalive(Some(std::time::Duration::from_secs(
         config.tcp_keepalive_s as u64,
     )))?;
+    let peer_addr = socket.peer_addr()?;
+    let socket = if config.scheme == "wss" {
+        let mut builder = native_tls::TlsConnector::builder();
+        if let Some(cert_pem) = &config.tls_root_certificate {
+            builder.add_root_certificate(
+                native_tls::Certificate::from_pem(cert_pem)
+                    .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?,
+            );
+        }
+        let tls_connector: tokio_tls::TlsConnector = builder
+            .build()
+            .map_err(|e| Error::new(ErrorKind::Other, e))?
+            .into();
+        RawStream::Tls(
+            tls_connector
+                .connect(url.host_str().unwrap_or_default(), socket)
+                .await
+                .map_err(|e| Error::new(ErrorKind::Other, e))?,
+        )
+    } else {
+        RawStream::Plain(socket)
+    };
     let (socket, _) = tokio_tungstenite::client_async_with_config(
         url.as_str(),
         socket,
@@ -117,20 +139,41 @@ pub async fn websocket_connect(
     )
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
-    build_websocket_pair(config, socket)
+    let remote_addr = addr_to_url(peer_addr, config.scheme);
+    build_websocket_pair(config, remote_addr, socket)
+}
+
+/// Establish a new outgoing websocket connection over the unix domain socket
+/// at `path`. Returns a split websocket connection pair: (
+/// [WebsocketSender](struct.WebsocketSender.html),
+/// [WebsocketReceiver](struct.WebsocketReceiver.html)
+/// ).
+#[cfg(unix)]
+pub async fn websocket_connect_unix(
+    path: impl AsRef<std::path::Path>,
+    config: Arc<WebsocketConfig>,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    let path = path.as_ref();
+    let socket = tokio::net::UnixStream::connect(path).await?;
+    // the handshake needs an http host, which is meaningless for unix sockets
+    let (socket, _) = tokio_tungstenite::client_async_with_config(
+        "ws://localhost/",
+        RawStream::Unix(socket),
+        Some(config.to_tungstenite()),
+    )
+    .await
+    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    build_websocket_pair(config, path_to_url(path), socket)
 }
 
 /// internal set up the tokio tasks that keep a websocket running
 /// and produce the public (WebsocketSender, WebsocketReceiver) pair.
 pub(crate) fn build_websocket_pair(
     config: Arc<WebsocketConfig>,
+    remote_addr: Url2,
     socket: RawSocket,
 ) -> Result<(WebsocketSender, WebsocketReceiver)> {
-    let remote_addr = url2!(
-        "{}#{}",
-        addr_to_url(socket.get_ref().peer_addr()?, config.scheme),
-        nanoid::nanoid!(),
-    );
+    let remote_addr = url2!("{}#{}", remote_addr, nanoid::nanoid!());
 
     // split the sink and stream so we can handle them simultaneously
     use futures::stream::StreamExt;
@@ -68,6 +68,47 @@ async fn integration_test() {
     assert_eq!("None", &format!("{:?}", recv.next().await),);
 }
 
+#[tokio::test]
+async fn tls_round_trip() {
+    holochain_types::observability::test_run().ok();
+
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let identity = TlsIdentity {
+        cert_pem: cert.serialize_pem().unwrap().into_bytes(),
+        key_pem: cert.serialize_private_key_pem().into_bytes(),
+    };
+    let server = websocket_bind(
+        url2!("wss://127.0.0.1:0"),
+        std::sync::Arc::new(WebsocketConfig::default().tls_identity(identity.clone())),
+    )
+    .await
+    .unwrap();
+    assert_eq!("wss", server.local_addr().scheme());
+    let url = url2!("wss://localhost:{}", server.local_addr().port().unwrap());
+
+    spawn_listener_loop(server);
+
+    // A self-signed certificate isn't trusted unless the client is told to
+    assert!(websocket_connect(
+        url.clone(),
+        std::sync::Arc::new(WebsocketConfig::default().scheme("wss")),
+    )
+    .await
+    .is_err());
+
+    let (mut send, recv) = websocket_connect(
+        url,
+        std::sync::Arc::new(WebsocketConfig::default().tls_root_certificate(identity.cert_pem)),
+    )
+    .await
+    .unwrap();
+    assert_eq!("wss", recv.remote_addr().scheme());
+
+    let msg = TestMessage("test-request".to_string());
+    let rsp: TestMessage = send.request(msg).await.unwrap();
+    assert_eq!("echo: test-request", &rsp.0,);
+}
+
 #[tokio::test]
 #[ignore]
 async fn channels_properly_close() {
@@ -77,7 +118,14 @@ async fn channels_properly_close() {
 fn spawn_listener_loop(mut server: WebsocketListener) -> tokio::task::JoinHandle<()> {
     tokio::task::spawn(async move {
         while let Some(maybe_con) = server.next().await {
-            let (_send, mut recv) = maybe_con.unwrap();
+            let (_send, mut recv) = match maybe_con {
+                Ok(con) => con,
+                Err(error) => {
+                    // e.g. a client which doesn't trust our certificate
+                    tracing::warn!(test = "failed incoming connection", ?error);
+                    continue;
+                }
+            };
             tracing::info!(
                 test = "incoming connection",
                 remote_addr = %recv.remote_addr(),
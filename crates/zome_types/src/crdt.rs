@@ -1,2 +1,210 @@
-#[derive(Default, Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
-pub struct CrdtType;
+//! Conflict resolution for entries that have more than one [Update](crate::header::Update).
+//! The [CrdtType] of an entry def decides which element a `get` on the
+//! original entry hash resolves to.
+
+use crate::element::Element;
+use crate::zome_io::ExternOutput;
+use crate::CallbackResult;
+use holo_hash::HeaderHash;
+use holochain_serialized_bytes::prelude::*;
+
+/// How a `get` resolves an entry that has been updated.
+/// Every variant is deterministic so all agents holding the same updates
+/// see the same element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+pub enum CrdtType {
+    /// Updates never replace the original.
+    /// `get` returns the original and every update is a member of the set,
+    /// available through `get_details`.
+    GrowOnlySet,
+    /// Only updates authored by the author of the original count.
+    /// The latest of them wins.
+    SingleAuthorLatestWins,
+    /// Updates from any author count.
+    /// The latest wins, with ties broken by the highest header hash.
+    LastWriteWins,
+    /// The `merge` callback of the zome that defines the entry picks the winner.
+    AppMerge,
+}
+
+impl Default for CrdtType {
+    fn default() -> Self {
+        Self::GrowOnlySet
+    }
+}
+
+/// Entry defs stored before [CrdtType] had variants hold a unit in its place,
+/// which means [CrdtType::GrowOnlySet]
+impl<'de> Deserialize<'de> for CrdtType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "CrdtType")]
+        enum Stored {
+            GrowOnlySet,
+            SingleAuthorLatestWins,
+            LastWriteWins,
+            AppMerge,
+        }
+        Ok(match Option::<Stored>::deserialize(deserializer)? {
+            None | Some(Stored::GrowOnlySet) => Self::GrowOnlySet,
+            Some(Stored::SingleAuthorLatestWins) => Self::SingleAuthorLatestWins,
+            Some(Stored::LastWriteWins) => Self::LastWriteWins,
+            Some(Stored::AppMerge) => Self::AppMerge,
+        })
+    }
+}
+
+impl CrdtType {
+    /// Pick the element a `get` on the original should return from the live updates.
+    /// Returns `None` for [CrdtType::AppMerge] because only the app can decide.
+    pub fn resolve<'a>(
+        &self,
+        original: &'a Element,
+        updates: &'a [Element],
+    ) -> Option<&'a Element> {
+        let latest = |candidates: Vec<&'a Element>| {
+            candidates
+                .into_iter()
+                .max_by_key(|e| (e.header().timestamp(), e.header_address().clone()))
+        };
+        match self {
+            Self::GrowOnlySet => Some(original),
+            Self::SingleAuthorLatestWins => {
+                let author = original.header().author();
+                let mut candidates: Vec<_> = updates
+                    .iter()
+                    .filter(|e| e.header().author() == author)
+                    .collect();
+                candidates.push(original);
+                latest(candidates)
+            }
+            Self::LastWriteWins => {
+                let mut candidates: Vec<_> = updates.iter().collect();
+                candidates.push(original);
+                latest(candidates)
+            }
+            Self::AppMerge => None,
+        }
+    }
+}
+
+/// The data passed to the `merge` callback of an entry def with [CrdtType::AppMerge].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct MergeData {
+    /// The element that was updated
+    pub original: Element,
+    /// Every live update to the original, and the updates to those
+    /// updates in turn, oldest first
+    pub updates: Vec<Element>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum MergeCallbackResult {
+    /// The header hash of the original or one of the updates
+    Resolved(HeaderHash),
+    Fail(String),
+}
+
+impl From<ExternOutput> for MergeCallbackResult {
+    fn from(guest_output: ExternOutput) -> Self {
+        match guest_output.into_inner().try_into() {
+            Ok(v) => v,
+            Err(e) => Self::Fail(format!("{:?}", e)),
+        }
+    }
+}
+
+impl CallbackResult for MergeCallbackResult {
+    fn is_definitive(&self) -> bool {
+        match self {
+            MergeCallbackResult::Fail(_) => true,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "fixturators")]
+mod tests {
+    use super::CrdtType;
+    use crate::element::{Element, SignedHeaderHashed};
+    use crate::fixt::*;
+    use crate::header::HeaderHashed;
+    use crate::timestamp::Timestamp;
+    use crate::Header;
+    use ::fixt::prelude::*;
+    use holo_hash::fixt::AgentPubKeyFixturator;
+    use holo_hash::AgentPubKey;
+
+    fn element(header: Header) -> Element {
+        Element::new(
+            SignedHeaderHashed::with_presigned(
+                HeaderHashed::from_content_sync(header),
+                vec![0; 64].into(),
+            ),
+            None,
+        )
+    }
+
+    fn update(author: &AgentPubKey, secs: i64) -> Element {
+        let mut u = fixt!(Update);
+        u.author = author.clone();
+        u.timestamp = Timestamp(secs, 0);
+        element(u.into())
+    }
+
+    #[test]
+    fn resolve_updates() {
+        let alice = fixt!(AgentPubKey);
+        let bob = fixt!(AgentPubKey);
+
+        let mut c = fixt!(Create);
+        c.author = alice.clone();
+        c.timestamp = Timestamp(0, 0);
+        let original = element(c.into());
+
+        let updates = vec![update(&alice, 1), update(&bob, 3), update(&alice, 2)];
+
+        assert_eq!(
+            CrdtType::GrowOnlySet.resolve(&original, &updates),
+            Some(&original)
+        );
+        assert_eq!(
+            CrdtType::SingleAuthorLatestWins.resolve(&original, &updates),
+            Some(&updates[2])
+        );
+        assert_eq!(
+            CrdtType::LastWriteWins.resolve(&original, &updates),
+            Some(&updates[1])
+        );
+        assert_eq!(CrdtType::AppMerge.resolve(&original, &updates), None);
+
+        // No updates resolves to the original for every builtin type
+        assert_eq!(
+            CrdtType::LastWriteWins.resolve(&original, &[]),
+            Some(&original)
+        );
+    }
+
+    #[test]
+    fn crdt_type_reads_the_old_unit_form() {
+        /// What CrdtType was before it had variants
+        #[derive(serde::Serialize)]
+        struct OldCrdtType;
+
+        let old = holochain_serialized_bytes::encode(&OldCrdtType).unwrap();
+        let crdt_type: CrdtType = holochain_serialized_bytes::decode(&old).unwrap();
+        assert_eq!(crdt_type, CrdtType::GrowOnlySet);
+
+        for crdt_type in vec![
+            CrdtType::GrowOnlySet,
+            CrdtType::SingleAuthorLatestWins,
+            CrdtType::LastWriteWins,
+            CrdtType::AppMerge,
+        ] {
+            let bytes = holochain_serialized_bytes::encode(&crdt_type).unwrap();
+            let decoded: CrdtType = holochain_serialized_bytes::decode(&bytes).unwrap();
+            assert_eq!(decoded, crdt_type);
+        }
+    }
+}
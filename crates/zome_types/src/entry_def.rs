@@ -70,10 +70,14 @@ pub struct EntryDef {
     pub id: EntryDefId,
     /// Public or Private
     pub visibility: EntryVisibility,
-    /// TBD -- Special types of conflict resolution support from Holochain (e.g. Single-Author, )
+    /// How a get resolves an entry of this type that has been updated
     pub crdt_type: CrdtType,
     /// how many validations to receive before considered "network saturated" (MAX value of 50?)
     pub required_validations: RequiredValidations,
+    /// The largest an entry of this type may be in bytes, overriding
+    /// the limit set by the DNA
+    #[serde(default)]
+    pub max_size: Option<usize>,
 }
 
 impl EntryDef {
@@ -88,6 +92,7 @@ impl EntryDef {
             visibility,
             crdt_type,
             required_validations,
+            max_size: None,
         }
     }
 }
@@ -170,8 +175,9 @@ mod tests {
             vec![EntryDef {
                 id: "bar".into(),
                 visibility: EntryVisibility::Public,
-                crdt_type: CrdtType,
+                crdt_type: CrdtType::GrowOnlySet,
                 required_validations: 5.into(),
+                max_size: None,
             }]
             .into(),
         );
@@ -5,6 +5,7 @@ use holo_hash::EntryHash;
 use holochain_serialized_bytes::prelude::SerializedBytes;
 
 use crate::entry_def::EntryVisibility;
+use crate::genesis::GenesisSelfCheckData;
 use crate::header::*;
 use crate::link::LinkTag;
 use crate::timestamp::Timestamp;
@@ -135,6 +136,30 @@ fixturator!(
     LinkTag; from Bytes;
 );
 
+fixturator!(
+    GenesisSelfCheckData;
+    curve Empty (
+        GenesisSelfCheckData {
+            agent_key: AgentPubKeyFixturator::new(Empty).next().unwrap(),
+            membrane_proof: MaybeSerializedBytesFixturator::new(Empty).next().unwrap(),
+        }
+    );
+    curve Unpredictable (
+        GenesisSelfCheckData {
+            agent_key: AgentPubKeyFixturator::new(Unpredictable).next().unwrap(),
+            membrane_proof: MaybeSerializedBytesFixturator::new(Unpredictable).next().unwrap(),
+        }
+    );
+    curve Predictable (
+        GenesisSelfCheckData {
+            agent_key: AgentPubKeyFixturator::new_indexed(Predictable, self.0.index).next().unwrap(),
+            membrane_proof: MaybeSerializedBytesFixturator::new_indexed(Predictable, self.0.index)
+                .next()
+                .unwrap(),
+        }
+    );
+);
+
 pub struct KnownCreateLink {
     pub base_address: EntryHash,
     pub target_address: EntryHash,
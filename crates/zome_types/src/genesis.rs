@@ -0,0 +1,18 @@
+//! Types for the `genesis_self_check` callback, which lets a DNA check an
+//! agent can join it before their source chain is created.
+
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+
+/// The data passed to every zome's `genesis_self_check` callback.
+/// The callback returns a [ValidateCallbackResult](crate::validate::ValidateCallbackResult)
+/// and can't depend on anything from the network, so an agent with a bad
+/// membrane proof finds out before joining.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct GenesisSelfCheckData {
+    /// The agent joining the DNA
+    pub agent_key: AgentPubKey,
+    /// The membrane proof the agent was installed with, which will be
+    /// written to their `AgentValidationPkg` header
+    pub membrane_proof: Option<SerializedBytes>,
+}
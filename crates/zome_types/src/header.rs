@@ -297,7 +297,7 @@ pub struct DeleteLink {
 }
 
 /// When migrating to a new version of a DNA, this header is committed to the
-/// new chain to declare the migration path taken. **Currently unused**
+/// new chain to declare the migration path taken.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
 pub struct OpenChain {
     pub author: AgentPubKey,
@@ -309,7 +309,8 @@ pub struct OpenChain {
 }
 
 /// When migrating to a new version of a DNA, this header is committed to the
-/// old chain to declare the migration path taken. **Currently unused**
+/// old chain to declare the migration path taken. Nothing can be committed
+/// after it.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, SerializedBytes)]
 pub struct CloseChain {
     pub author: AgentPubKey,
@@ -1,7 +1,10 @@
 //! Types for source chain queries
 
+use crate::element::SignedHeaderHashed;
 use crate::header::{EntryType, Header, HeaderType};
+use crate::timestamp::Timestamp;
 pub use holochain_serialized_bytes::prelude::*;
+use std::ops::{Bound, RangeBounds};
 
 /// Query arguments
 #[derive(
@@ -10,15 +13,27 @@ pub use holochain_serialized_bytes::prelude::*;
 #[non_exhaustive]
 pub struct ChainQueryFilter {
     /// The range of source chain sequence numbers to match.
-    /// Inclusive start, exclusive end.
-    // TODO: can we generalize this over RangeBounds to allow unbounded ranges?
-    pub sequence_range: Option<std::ops::Range<u32>>,
-    /// Filter by EntryType
-    pub entry_type: Option<EntryType>,
-    /// Filter by HeaderType
-    pub header_type: Option<HeaderType>,
+    /// Either end may be unbounded.
+    pub sequence_range: Option<(Bound<u32>, Bound<u32>)>,
+    /// The range of header timestamps to match.
+    /// Either end may be unbounded.
+    pub timestamp_range: Option<(Bound<Timestamp>, Bound<Timestamp>)>,
+    /// Filter by EntryType, matching any of the types.
+    /// Headers without an entry are not filtered out.
+    pub entry_types: Option<Vec<EntryType>>,
+    /// Filter by HeaderType, matching any of the types
+    pub header_types: Option<Vec<HeaderType>>,
     /// Include the entries in the elements
     pub include_entries: bool,
+    /// Also include private entries when entries are included.
+    /// Only the author can see them because a query only reads its own chain.
+    pub include_private_entries: bool,
+    /// Return the newest elements first instead of in chain order
+    pub descending: bool,
+    /// Skip this many matching elements, for pagination
+    pub offset: u32,
+    /// Return at most this many elements
+    pub limit: Option<u32>,
 }
 
 impl ChainQueryFilter {
@@ -30,21 +45,45 @@ impl ChainQueryFilter {
         }
     }
 
-    /// Filter on sequence range
-    pub fn sequence_range(mut self, sequence_range: std::ops::Range<u32>) -> Self {
-        self.sequence_range = Some(sequence_range);
+    /// Filter on sequence range, e.g. `2..`, `..=5` or `3..7`
+    pub fn sequence_range(mut self, sequence_range: impl RangeBounds<u32>) -> Self {
+        self.sequence_range = Some(bounds(&sequence_range));
         self
     }
 
-    /// Filter on entry type
+    /// Filter on timestamp range
+    pub fn timestamp_range(mut self, timestamp_range: impl RangeBounds<Timestamp>) -> Self {
+        self.timestamp_range = Some(bounds(&timestamp_range));
+        self
+    }
+
+    /// Filter on entry type.
+    /// Can be called more than once to match any of several types.
     pub fn entry_type(mut self, entry_type: EntryType) -> Self {
-        self.entry_type = Some(entry_type);
+        self.entry_types
+            .get_or_insert_with(Vec::new)
+            .push(entry_type);
+        self
+    }
+
+    /// Filter on any of these entry types
+    pub fn entry_types(mut self, entry_types: impl IntoIterator<Item = EntryType>) -> Self {
+        self.entry_types = Some(entry_types.into_iter().collect());
         self
     }
 
-    /// Filter on header type
+    /// Filter on header type.
+    /// Can be called more than once to match any of several types.
     pub fn header_type(mut self, header_type: HeaderType) -> Self {
-        self.header_type = Some(header_type);
+        self.header_types
+            .get_or_insert_with(Vec::new)
+            .push(header_type);
+        self
+    }
+
+    /// Filter on any of these header types
+    pub fn header_types(mut self, header_types: impl IntoIterator<Item = HeaderType>) -> Self {
+        self.header_types = Some(header_types.into_iter().collect());
         self
     }
 
@@ -54,37 +93,125 @@ impl ChainQueryFilter {
         self
     }
 
-    /// Perform the boolean check which this filter represents
+    /// Include private entries as well, if entries are included
+    pub fn include_private_entries(mut self, include_private_entries: bool) -> Self {
+        self.include_private_entries = include_private_entries;
+        self
+    }
+
+    /// Return the newest elements first
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
+    /// Skip this many matching elements
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Return at most this many elements
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Perform the boolean check which this filter represents.
+    /// Ordering, offset and limit are applied by whoever walks the chain.
     pub fn check(&self, header: &Header) -> bool {
         let check_range = self
             .sequence_range
             .as_ref()
             .map(|range| range.contains(&header.header_seq()))
             .unwrap_or(true);
+        let check_timestamp = self
+            .timestamp_range
+            .as_ref()
+            .map(|range| range.contains(&header.timestamp()))
+            .unwrap_or(true);
         let check_header_type = self
-            .header_type
+            .header_types
             .as_ref()
-            .map(|header_type| header.header_type() == *header_type)
+            .map(|header_types| header_types.contains(&header.header_type()))
             .unwrap_or(true);
         let check_entry_type = self
-            .entry_type
+            .entry_types
             .as_ref()
-            .map(|entry_type| {
+            .map(|entry_types| {
                 header
                     .entry_type()
-                    .map(|header_entry_type| *header_entry_type == *entry_type)
+                    .map(|header_entry_type| entry_types.contains(header_entry_type))
                     .unwrap_or(true)
             })
             .unwrap_or(true);
-        check_range && check_header_type && check_entry_type
+        check_range && check_timestamp && check_header_type && check_entry_type
+    }
+
+    /// Apply the order, offset and limit to the items that passed
+    /// [ChainQueryFilter::check], given in chain order
+    pub fn paginate<T>(&self, mut items: Vec<T>) -> Vec<T> {
+        if self.descending {
+            items.reverse();
+        }
+        items
+            .into_iter()
+            .skip(self.offset as usize)
+            .take(self.limit.map(|limit| limit as usize).unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
+// Bound::cloned is not stable yet
+fn bounds<T: Clone>(range: &impl RangeBounds<T>) -> (Bound<T>, Bound<T>) {
+    let cloned = |bound: Bound<&T>| match bound {
+        Bound::Included(t) => Bound::Included(t.clone()),
+        Bound::Excluded(t) => Bound::Excluded(t.clone()),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (cloned(range.start_bound()), cloned(range.end_bound()))
+}
+
+/// The state of an agent's source chain as seen by the
+/// authorities holding its activity
+#[derive(serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ChainStatus {
+    /// No activity has been seen for this agent
+    Empty,
+    /// All the activity seen for this agent is valid
+    Valid,
+    /// The agent has published two headers at the same sequence number
+    Forked,
+    /// Some of the agent's activity has been rejected
+    Invalid,
+}
+
+impl Default for ChainStatus {
+    fn default() -> Self {
+        ChainStatus::Empty
     }
 }
 
+/// An agent's source chain activity as returned by `get_agent_activity`
+#[derive(
+    serde::Serialize, serde::Deserialize, SerializedBytes, Default, PartialEq, Clone, Debug,
+)]
+pub struct AgentActivity {
+    /// The valid headers on the agent's chain that matched the query,
+    /// ordered by sequence number and paginated as the query asks
+    pub headers: Vec<SignedHeaderHashed>,
+    /// The status of the whole chain, regardless of the query
+    pub status: ChainStatus,
+    /// The highest sequence number observed on the chain, valid or not
+    pub highest_observed: Option<u32>,
+}
+
 #[cfg(test)]
 #[cfg(feature = "fixturators")]
 mod tests {
     use crate::fixt::AppEntryTypeFixturator;
     use crate::header::EntryType;
+    use crate::timestamp::Timestamp;
     use crate::{fixt::*, Header};
     use ::fixt::prelude::*;
 
@@ -201,6 +328,96 @@ mod tests {
         );
     }
 
+    #[test]
+    fn filter_by_unbounded_chain_sequence() {
+        let headers = fixtures();
+
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(4..), &headers),
+            [false, false, false, false, true, true].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(..=1), &headers),
+            [true, true, false, false, false, false].to_vec()
+        );
+        assert_eq!(
+            map_query(&ChainQueryFilter::new().sequence_range(..), &headers),
+            [true, true, true, true, true, true].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_by_many_types() {
+        let headers = fixtures();
+
+        let query_1 = ChainQueryFilter::new()
+            .header_type(headers[0].header_type())
+            .header_type(headers[2].header_type());
+        let query_2 = ChainQueryFilter::new().entry_types(vec![
+            headers[0].entry_type().unwrap().clone(),
+            headers[1].entry_type().unwrap().clone(),
+        ]);
+
+        assert_eq!(
+            map_query(&query_1, &headers),
+            [true, false, true, true, false, true].to_vec()
+        );
+        assert_eq!(
+            map_query(&query_2, &headers),
+            [true, true, true, true, true, true].to_vec()
+        );
+    }
+
+    #[test]
+    fn filter_by_timestamp() {
+        let headers: Vec<Header> = fixtures()
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, mut h)| {
+                match &mut h {
+                    Header::Create(c) => c.timestamp = Timestamp(i as i64, 0),
+                    Header::Update(u) => u.timestamp = Timestamp(i as i64, 0),
+                    Header::CreateLink(l) => l.timestamp = Timestamp(i as i64, 0),
+                    _ => unreachable!(),
+                }
+                h
+            })
+            .collect();
+
+        let query = ChainQueryFilter::new().timestamp_range(Timestamp(1, 0)..Timestamp(3, 0));
+
+        assert_eq!(
+            map_query(&query, &headers),
+            [false, true, true, false, false, false].to_vec()
+        );
+    }
+
+    #[test]
+    fn paginate() {
+        let items: Vec<u32> = (0..6).collect();
+
+        assert_eq!(ChainQueryFilter::new().paginate(items.clone()), items);
+        assert_eq!(
+            ChainQueryFilter::new()
+                .offset(1)
+                .limit(2)
+                .paginate(items.clone()),
+            vec![1, 2]
+        );
+        assert_eq!(
+            ChainQueryFilter::new()
+                .descending(true)
+                .limit(3)
+                .paginate(items.clone()),
+            vec![5, 4, 3]
+        );
+        assert_eq!(
+            ChainQueryFilter::new().offset(10).paginate(items),
+            Vec::<u32>::new()
+        );
+    }
+
     #[test]
     fn filter_by_multi() {
         let headers = fixtures();
@@ -105,6 +105,14 @@ wasm_io_types!(
     // @todo
     pub struct DeleteInput(holo_hash::HeaderHash);
     pub struct DeleteOutput(holo_hash::HeaderHash);
+    // Close the chain for migration to the DNA with this hash.
+    pub struct CloseChainInput(holo_hash::DnaHash);
+    // Header hash of the CloseChain element.
+    pub struct CloseChainOutput(holo_hash::HeaderHash);
+    // Open the chain as a migration from the DNA with this hash.
+    pub struct OpenChainInput(holo_hash::DnaHash);
+    // Header hash of the OpenChain element.
+    pub struct OpenChainOutput(holo_hash::HeaderHash);
     // Create a link between two entries.
     pub struct CreateLinkInput(
         (
@@ -124,6 +132,9 @@ wasm_io_types!(
     pub struct GetOutput(Option<crate::element::Element>);
     pub struct GetDetailsInput((holo_hash::AnyDhtHash, crate::entry::GetOptions));
     pub struct GetDetailsOutput(Option<crate::metadata::Details>);
+    // Get an agent's source chain activity from its authorities.
+    pub struct GetAgentActivityInput((holo_hash::AgentPubKey, crate::query::ChainQueryFilter));
+    pub struct GetAgentActivityOutput(crate::query::AgentActivity);
     // @todo
     pub struct EntryTypePropertiesInput(());
     pub struct EntryTypePropertiesOutput(());